@@ -4,7 +4,7 @@ import { AxiosHeaders } from "axios";
 const CAPTCHA_KEY = "synerunify-captcha-secret-key-12345678";
 
 const apis = {
-  getData: "/captcha/public/get-data", // 获取验证码数据
+  getData: "/system/system_auth/captcha", // 获取验证码数据,经系统服务限流
   checkData: "/captcha/public/check-data", // 校验验证码数据
   checkStatus: "/captcha/public/check-status", // 获取校验结果
 };
@@ -34,9 +34,7 @@ export interface CaptchaCheckRequest {
 }
 
 export const getData = (id: string): Promise<CaptchaDataResponse> => {
-  const headers = new AxiosHeaders();
-  headers.set("X-API-Key", CAPTCHA_KEY);
-  return api.get<CaptchaDataResponse>(apis.getData, { id }, { headers });
+  return api.get<CaptchaDataResponse>(apis.getData, { id });
 };
 
 export const checkData = (request: CaptchaCheckRequest): Promise<string> => {
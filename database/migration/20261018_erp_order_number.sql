@@ -0,0 +1,46 @@
+-- ----------------------------
+-- 单据编号由雪花id改为按规则生成的字符串,如 PO-20261018-0001
+-- 已有的编号原样转为字符串,同一租户内编号唯一
+-- 执行前确认各表没有重复编号,如:
+-- SELECT tenant_id, order_number, COUNT(*) FROM erp_purchase_order GROUP BY tenant_id, order_number HAVING COUNT(*) > 1;
+-- ----------------------------
+
+ALTER TABLE `erp_inbound_order`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_inventory_check`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_inventory_transfer`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_outbound_order`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_payment`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_purchase_order`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_purchase_return`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_receipt`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_sales_order`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
+
+ALTER TABLE `erp_sales_return`
+  MODIFY COLUMN `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
+  ADD UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE;
@@ -0,0 +1,9 @@
+-- ----------------------------
+-- 分布式锁保护的写入校验fencing token,记录每个锁已写入的最大token
+-- ----------------------------
+CREATE TABLE IF NOT EXISTS `system_lock_fence`  (
+  `name` varchar(128) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '锁名称',
+  `fencing_token` bigint NOT NULL COMMENT '已写入的最大fencing token',
+  `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`name`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '分布式锁fencing token表' ROW_FORMAT = DYNAMIC;
@@ -25,15 +25,16 @@ CREATE TABLE `erp_customer`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '客户ID',
   `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '客户名称',
   `contact_person` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '联系人',
-  `phone` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '电话',
+  `phone` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '电话,加密保存',
   `email` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '邮箱',
   `address` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '地址',
   `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态',
   `sort` int NOT NULL DEFAULT 0 COMMENT '排序',
-  `tax_id` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号',
+  `tax_id` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号,加密保存',
+  `tax_id_index` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号盲索引',
   `tax_rate` int NULL DEFAULT NULL COMMENT '税率,精确到万分位',
   `bank_name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户行',
-  `bank_account` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号',
+  `bank_account` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号,加密保存',
   `bank_address` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户地址',
   `remarks` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
   `department_code` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT '' COMMENT '部门编码',
@@ -44,13 +45,14 @@ CREATE TABLE `erp_customer`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_tax_id_index`(`tax_id_index` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '客户信息表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_customer
 -- ----------------------------
-INSERT INTO `erp_customer` VALUES (1, '测试客户', '测试客户', '18888888777', '123@q.com', '高新区', 0, 0, 'x12334', 0, '中国银行', '12345678', '高新区', '测试', '0000', 1, 1, '2025-06-11 12:47:19', 1, '2025-06-11 12:49:07', b'0', 1);
+INSERT INTO `erp_customer` VALUES (1, '测试客户', '测试客户', '18888888777', '123@q.com', '高新区', 0, 0, 'x12334', NULL, 0, '中国银行', '12345678', '高新区', '测试', '0000', 1, 1, '2025-06-11 12:47:19', 1, '2025-06-11 12:49:07', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_financial_record
@@ -85,7 +87,7 @@ CREATE TABLE `erp_financial_record`  (
 DROP TABLE IF EXISTS `erp_inbound_order`;
 CREATE TABLE `erp_inbound_order`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '入库订单ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `purchase_id` bigint NULL DEFAULT NULL COMMENT '采购订单ID',
   `supplier_id` bigint NOT NULL COMMENT '供应商ID',
   `user_id` bigint NOT NULL COMMENT '用户ID',
@@ -102,14 +104,15 @@ CREATE TABLE `erp_inbound_order`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 5 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '入库订单表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_inbound_order
 -- ----------------------------
-INSERT INTO `erp_inbound_order` VALUES (1, 62349514941403136, 6, 1, 1, '2025-06-22 00:00:00', '入库1', 1, 100, 1, '0000', 1, 1, '2025-06-22 01:14:45', 1, '2025-06-22 01:14:45', b'0', 1);
-INSERT INTO `erp_inbound_order` VALUES (4, 67450420758319104, NULL, 1, 1, '2025-07-07 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-07-06 03:03:54', 1, '2025-07-06 03:03:54', b'0', 1);
+INSERT INTO `erp_inbound_order` VALUES (1, '62349514941403136', 6, 1, 1, '2025-06-22 00:00:00', '入库1', 1, 100, 1, '0000', 1, 1, '2025-06-22 01:14:45', 1, '2025-06-22 01:14:45', b'0', 1);
+INSERT INTO `erp_inbound_order` VALUES (4, '67450420758319104', NULL, 1, 1, '2025-07-07 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-07-06 03:03:54', 1, '2025-07-06 03:03:54', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_inbound_order_attachment
@@ -177,7 +180,7 @@ INSERT INTO `erp_inbound_order_detail` VALUES (3, 4, NULL, 1, 1, 1, 0, 0, 0, '',
 DROP TABLE IF EXISTS `erp_inventory_check`;
 CREATE TABLE `erp_inventory_check`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '盘点记录ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `check_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '盘点日期',
   `remarks` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
   `department_code` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT '' COMMENT '部门编码',
@@ -188,13 +191,14 @@ CREATE TABLE `erp_inventory_check`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '库存盘点表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_inventory_check
 -- ----------------------------
-INSERT INTO `erp_inventory_check` VALUES (1, 64335513670127616, '2025-06-27 00:00:00', '测试盘点', '0000', 1, 1, '2025-06-27 12:46:21', 1, '2025-06-27 12:46:21', b'0', 1);
+INSERT INTO `erp_inventory_check` VALUES (1, '64335513670127616', '2025-06-27 00:00:00', '测试盘点', '0000', 1, 1, '2025-06-27 12:46:21', 1, '2025-06-27 12:46:21', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_inventory_check_attachment
@@ -289,7 +293,7 @@ INSERT INTO `erp_inventory_record` VALUES (8, 1, 1, 1, 3, '2025-07-07 00:00:00',
 DROP TABLE IF EXISTS `erp_inventory_transfer`;
 CREATE TABLE `erp_inventory_transfer`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '调拨记录ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `transfer_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '调拨日期',
   `remarks` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
   `department_code` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT '' COMMENT '部门编码',
@@ -300,13 +304,14 @@ CREATE TABLE `erp_inventory_transfer`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 5 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '库存调拨表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_inventory_transfer
 -- ----------------------------
-INSERT INTO `erp_inventory_transfer` VALUES (4, 64333444120842240, '2025-06-27 00:00:00', '测试调拨', '0000', 1, 1, '2025-06-27 12:38:07', 1, '2025-06-27 12:38:07', b'0', 1);
+INSERT INTO `erp_inventory_transfer` VALUES (4, '64333444120842240', '2025-06-27 00:00:00', '测试调拨', '0000', 1, 1, '2025-06-27 12:38:07', 1, '2025-06-27 12:38:07', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_inventory_transfer_attachment
@@ -367,7 +372,7 @@ INSERT INTO `erp_inventory_transfer_detail` VALUES (1, 4, 1, 2, 1, 5, '调拨',
 DROP TABLE IF EXISTS `erp_outbound_order`;
 CREATE TABLE `erp_outbound_order`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '出库订单ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `sale_id` bigint NULL DEFAULT NULL COMMENT '销售订单ID',
   `customer_id` bigint NOT NULL COMMENT '客户ID',
   `user_id` bigint NOT NULL COMMENT '用户ID',
@@ -384,14 +389,15 @@ CREATE TABLE `erp_outbound_order`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 3 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '出库订单表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_outbound_order
 -- ----------------------------
-INSERT INTO `erp_outbound_order` VALUES (1, 63637073734668288, 1, 1, 1, '2025-06-26 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-06-25 14:31:05', 1, '2025-06-25 14:31:05', b'0', 1);
-INSERT INTO `erp_outbound_order` VALUES (2, 67459316675776512, NULL, 1, 1, '2025-07-07 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-07-06 03:39:15', 1, '2025-07-06 03:39:15', b'0', 1);
+INSERT INTO `erp_outbound_order` VALUES (1, '63637073734668288', 1, 1, 1, '2025-06-26 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-06-25 14:31:05', 1, '2025-06-25 14:31:05', b'0', 1);
+INSERT INTO `erp_outbound_order` VALUES (2, '67459316675776512', NULL, 1, 1, '2025-07-07 00:00:00', '测试', 1, 100, 1, '0000', 1, 1, '2025-07-06 03:39:15', 1, '2025-07-06 03:39:15', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_outbound_order_attachment
@@ -459,7 +465,7 @@ INSERT INTO `erp_outbound_order_detail` VALUES (3, 2, NULL, 1, 1, 1, 0, 0, 0, '
 DROP TABLE IF EXISTS `erp_payment`;
 CREATE TABLE `erp_payment`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '付款ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `supplier_id` bigint NOT NULL COMMENT '供应商ID',
   `user_id` bigint NOT NULL COMMENT '关联用户ID',
   `settlement_account_id` bigint NULL DEFAULT NULL COMMENT '结算账户ID',
@@ -477,13 +483,14 @@ CREATE TABLE `erp_payment`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '付款表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_payment
 -- ----------------------------
-INSERT INTO `erp_payment` VALUES (1, 64655266720780288, 1, 1, 1, 100, 1, '2025-06-28 00:00:00', '', 0, '测试', '0000', 1, 1, '2025-06-28 09:56:57', 1, '2025-06-28 09:56:57', b'0', 1);
+INSERT INTO `erp_payment` VALUES (1, '64655266720780288', 1, 1, 1, 100, 1, '2025-06-28 00:00:00', '', 0, '测试', '0000', 1, 1, '2025-06-28 09:56:57', 1, '2025-06-28 09:56:57', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_payment_attachment
@@ -615,7 +622,8 @@ CREATE TABLE `erp_product_inventory`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_product_warehouse`(`tenant_id` ASC, `product_id` ASC, `warehouse_id` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 3 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '产品库存表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
@@ -655,7 +663,7 @@ INSERT INTO `erp_product_unit` VALUES (2, 'g', 0, 0, '重量', 1, '2025-06-10 06
 DROP TABLE IF EXISTS `erp_purchase_order`;
 CREATE TABLE `erp_purchase_order`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '采购订单ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `supplier_id` bigint NOT NULL COMMENT '供应商ID',
   `user_id` bigint NOT NULL COMMENT '用户ID',
   `purchase_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '采购日期',
@@ -674,17 +682,17 @@ CREATE TABLE `erp_purchase_order`  (
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
   PRIMARY KEY (`id`) USING BTREE,
-  UNIQUE INDEX `order_number`(`order_number` ASC) USING BTREE
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 7 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '采购订单表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_purchase_order
 -- ----------------------------
-INSERT INTO `erp_purchase_order` VALUES (1, 59962819939930112, 1, 1, '2025-06-15 00:00:00', 100, 0, 1, 1, 10, '测试', '0000', 1, 1, '2025-06-15 11:10:53', 1, '2025-06-19 13:58:41', b'1', 1);
-INSERT INTO `erp_purchase_order` VALUES (2, 59964807209553920, 1, 1, '2025-06-16 00:00:00', 100, 0, 1, 1, 5, '测试', '0000', 1, 1, '2025-06-15 11:18:47', 1, '2025-06-19 13:58:45', b'1', 1);
-INSERT INTO `erp_purchase_order` VALUES (3, 59965337910644736, 1, 1, '2025-06-16 00:00:00', 1000, 0, 1, 1, 1000, '测试', '0000', 1, 1, '2025-06-15 11:20:53', 1, '2025-06-19 13:58:48', b'1', 1);
-INSERT INTO `erp_purchase_order` VALUES (4, 59966267620069376, 1, 1, '2025-06-15 00:00:00', 16643, 3, 1, 1, 12, '1', '0000', 1, 1, '2025-06-15 11:24:35', 1, '2025-06-19 14:30:48', b'0', 1);
-INSERT INTO `erp_purchase_order` VALUES (6, 59969975170895872, 1, 1, '2025-06-16 00:00:00', 1232, 1, 1, 1, 1234, '测试', '0000', 1, 1, '2025-06-15 11:39:19', 1, '2025-06-19 14:30:43', b'0', 1);
+INSERT INTO `erp_purchase_order` VALUES (1, '59962819939930112', 1, 1, '2025-06-15 00:00:00', 100, 0, 1, 1, 10, '测试', '0000', 1, 1, '2025-06-15 11:10:53', 1, '2025-06-19 13:58:41', b'1', 1);
+INSERT INTO `erp_purchase_order` VALUES (2, '59964807209553920', 1, 1, '2025-06-16 00:00:00', 100, 0, 1, 1, 5, '测试', '0000', 1, 1, '2025-06-15 11:18:47', 1, '2025-06-19 13:58:45', b'1', 1);
+INSERT INTO `erp_purchase_order` VALUES (3, '59965337910644736', 1, 1, '2025-06-16 00:00:00', 1000, 0, 1, 1, 1000, '测试', '0000', 1, 1, '2025-06-15 11:20:53', 1, '2025-06-19 13:58:48', b'1', 1);
+INSERT INTO `erp_purchase_order` VALUES (4, '59966267620069376', 1, 1, '2025-06-15 00:00:00', 16643, 3, 1, 1, 12, '1', '0000', 1, 1, '2025-06-15 11:24:35', 1, '2025-06-19 14:30:48', b'0', 1);
+INSERT INTO `erp_purchase_order` VALUES (6, '59969975170895872', 1, 1, '2025-06-16 00:00:00', 1232, 1, 1, 1, 1234, '测试', '0000', 1, 1, '2025-06-15 11:39:19', 1, '2025-06-19 14:30:43', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_purchase_order_attachment
@@ -747,7 +755,7 @@ INSERT INTO `erp_purchase_order_detail` VALUES (3, 6, 1, 20, 50, 1000, 2, '测
 DROP TABLE IF EXISTS `erp_purchase_return`;
 CREATE TABLE `erp_purchase_return`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '退货ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `purchase_order_id` bigint NOT NULL COMMENT '采购订单ID',
   `supplier_id` bigint NOT NULL COMMENT '供应商ID',
   `return_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '退货日期',
@@ -766,13 +774,13 @@ CREATE TABLE `erp_purchase_return`  (
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
   PRIMARY KEY (`id`) USING BTREE,
-  UNIQUE INDEX `order_number`(`order_number` ASC) USING BTREE
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '采购退货表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_purchase_return
 -- ----------------------------
-INSERT INTO `erp_purchase_return` VALUES (1, 63611174519836672, 6, 1, '2025-06-26 00:00:00', 100, 0, 1, 1, 0, '测试退货', '0000', 1, 1, '2025-06-25 12:48:10', 1, '2025-06-25 12:48:10', b'0', 1);
+INSERT INTO `erp_purchase_return` VALUES (1, '63611174519836672', 6, 1, '2025-06-26 00:00:00', 100, 0, 1, 1, 0, '测试退货', '0000', 1, 1, '2025-06-25 12:48:10', 1, '2025-06-25 12:48:10', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_purchase_return_attachment
@@ -838,7 +846,7 @@ INSERT INTO `erp_purchase_return_detail` VALUES (2, 1, 3, 1, 1, 20, 50, 1000, 2,
 DROP TABLE IF EXISTS `erp_receipt`;
 CREATE TABLE `erp_receipt`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '收款ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `customer_id` bigint NOT NULL COMMENT '客户ID',
   `user_id` bigint NOT NULL COMMENT '关联用户ID',
   `settlement_account_id` bigint NULL DEFAULT NULL COMMENT '结算账户ID',
@@ -856,13 +864,14 @@ CREATE TABLE `erp_receipt`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '收款表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_receipt
 -- ----------------------------
-INSERT INTO `erp_receipt` VALUES (1, 64700150106296320, 1, 1, 1, 100, 1, '2025-06-28 00:00:00', '', 0, '测试', '0000', 1, 1, '2025-06-28 12:55:18', 1, '2025-06-28 12:55:18', b'0', 1);
+INSERT INTO `erp_receipt` VALUES (1, '64700150106296320', 1, 1, 1, 100, 1, '2025-06-28 00:00:00', '', 0, '测试', '0000', 1, 1, '2025-06-28 12:55:18', 1, '2025-06-28 12:55:18', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_receipt_attachment
@@ -922,7 +931,7 @@ INSERT INTO `erp_receipt_detail` VALUES (1, 1, 1, NULL, 1, '测试', '0000', 1,
 DROP TABLE IF EXISTS `erp_sales_order`;
 CREATE TABLE `erp_sales_order`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '订单ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `customer_id` bigint NOT NULL COMMENT '客户ID',
   `user_id` bigint NOT NULL COMMENT '用户ID',
   `order_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '订单日期',
@@ -941,13 +950,13 @@ CREATE TABLE `erp_sales_order`  (
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
   PRIMARY KEY (`id`) USING BTREE,
-  UNIQUE INDEX `order_number`(`order_number` ASC) USING BTREE
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '销售订单表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_sales_order
 -- ----------------------------
-INSERT INTO `erp_sales_order` VALUES (1, 62553167430160384, 1, 1, '2025-06-23 00:00:00', 100, 0, 1, 1, 10, '测试销售', '0000', 1, 1, '2025-06-22 14:44:01', 1, '2025-06-22 14:44:01', b'0', 1);
+INSERT INTO `erp_sales_order` VALUES (1, '62553167430160384', 1, 1, '2025-06-23 00:00:00', 100, 0, 1, 1, 10, '测试销售', '0000', 1, 1, '2025-06-22 14:44:01', 1, '2025-06-22 14:44:01', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_sales_order_attachment
@@ -1010,7 +1019,7 @@ INSERT INTO `erp_sales_order_detail` VALUES (2, 1, 1, 1, 80, 80, 1, '', '0000',
 DROP TABLE IF EXISTS `erp_sales_return`;
 CREATE TABLE `erp_sales_return`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '退货ID',
-  `order_number` bigint NOT NULL COMMENT '订单编号',
+  `order_number` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '订单编号',
   `sales_order_id` bigint NOT NULL COMMENT '销售订单ID',
   `customer_id` bigint NOT NULL COMMENT '客户ID',
   `return_date` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '退货日期',
@@ -1029,13 +1038,13 @@ CREATE TABLE `erp_sales_return`  (
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
   PRIMARY KEY (`id`) USING BTREE,
-  UNIQUE INDEX `order_number`(`order_number` ASC) USING BTREE
+  UNIQUE INDEX `uk_tenant_order_number`(`tenant_id` ASC, `order_number` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '销售退货表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_sales_return
 -- ----------------------------
-INSERT INTO `erp_sales_return` VALUES (1, 63635320976969728, 1, 1, '2025-06-26 00:00:00', 100, 0, 1, 1, 0, '测试', '0000', 1, 1, '2025-06-25 14:24:07', 1, '2025-06-25 14:24:07', b'0', 1);
+INSERT INTO `erp_sales_return` VALUES (1, '63635320976969728', 1, 1, '2025-06-26 00:00:00', 100, 0, 1, 1, 0, '测试', '0000', 1, 1, '2025-06-25 14:24:07', 1, '2025-06-25 14:24:07', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_sales_return_attachment
@@ -1103,7 +1112,7 @@ CREATE TABLE `erp_settlement_account`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '账户ID',
   `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '账户名称',
   `bank_name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户行',
-  `bank_account` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号',
+  `bank_account` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号,加密保存',
   `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态',
   `sort` int NOT NULL DEFAULT 0 COMMENT '排序',
   `remarks` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
@@ -1129,14 +1138,15 @@ CREATE TABLE `erp_supplier`  (
   `id` bigint NOT NULL AUTO_INCREMENT COMMENT '供应商ID',
   `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '供应商名称',
   `contact_person` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '联系人',
-  `phone` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '电话',
+  `phone` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '电话,加密保存',
   `email` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '邮箱',
   `address` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '地址',
   `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态',
-  `tax_id` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号',
+  `tax_id` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号,加密保存',
+  `tax_id_index` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '纳税人识别号盲索引',
   `tax_rate` int NULL DEFAULT NULL COMMENT '税率,精确到万分位',
   `bank_name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户行',
-  `bank_account` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号',
+  `bank_account` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行账号,加密保存',
   `bank_address` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户地址',
   `remarks` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
   `sort` int NOT NULL DEFAULT 0 COMMENT '排序',
@@ -1146,13 +1156,14 @@ CREATE TABLE `erp_supplier`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_tax_id_index`(`tax_id_index` ASC) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 2 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '供应商信息表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of erp_supplier
 -- ----------------------------
-INSERT INTO `erp_supplier` VALUES (1, '测试供应商', '测试', '18888888777', '123@q.com', '高新区', 0, 'x12334', 13, '中国银行', '12345678', '高新区', '测试', 0, 1, '2025-06-10 14:48:11', 1, '2025-06-10 14:50:04', b'0', 1);
+INSERT INTO `erp_supplier` VALUES (1, '测试供应商', '测试', '18888888777', '123@q.com', '高新区', 0, 'x12334', NULL, 13, '中国银行', '12345678', '高新区', '测试', 0, 1, '2025-06-10 14:48:11', 1, '2025-06-10 14:50:04', b'0', 1);
 
 -- ----------------------------
 -- Table structure for erp_warehouse
@@ -2495,7 +2506,7 @@ CREATE TABLE `mall_trade_brokerage_withdraw`  (
   `total_price` int NOT NULL DEFAULT 0 COMMENT '当前总佣金',
   `type` tinyint NOT NULL DEFAULT 0 COMMENT '提现类型',
   `user_name` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '真实姓名',
-  `user_account` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '账号',
+  `user_account` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '账号,加密保存',
   `bank_name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '银行名称',
   `bank_address` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '开户地址',
   `qr_code_url` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '收款码',
@@ -2885,6 +2896,85 @@ CREATE TABLE `mall_trade_statistics`  (
 -- Records of mall_trade_statistics
 -- ----------------------------
 
+-- ----------------------------
+-- Table structure for system_background_job
+-- ----------------------------
+DROP TABLE IF EXISTS `system_background_job`;
+CREATE TABLE `system_background_job`  (
+  `id` bigint NOT NULL AUTO_INCREMENT COMMENT '作业id',
+  `job_type` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '作业类型',
+  `payload` text CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '作业参数',
+  `priority` int NOT NULL DEFAULT 0 COMMENT '优先级,大的先执行',
+  `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态（0等待执行 1执行中 2成功 3失败 4已取消）',
+  `progress` int NOT NULL DEFAULT 0 COMMENT '进度（0-100）',
+  `progress_message` varchar(500) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '进度说明',
+  `attempts` int NOT NULL DEFAULT 0 COMMENT '已执行次数',
+  `cancel_requested` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否请求取消',
+  `run_at` datetime(3) NOT NULL COMMENT '可执行时间,失败重试时延后',
+  `worker` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '执行实例',
+  `start_time` datetime(3) NULL DEFAULT NULL COMMENT '开始时间',
+  `heartbeat_time` datetime(3) NULL DEFAULT NULL COMMENT '心跳时间',
+  `finish_time` datetime(3) NULL DEFAULT NULL COMMENT '结束时间',
+  `artifact_file_id` bigint NULL DEFAULT NULL COMMENT '结果文件id',
+  `error` varchar(2000) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '错误信息',
+  `department_code` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT '' COMMENT '部门编码',
+  `department_id` bigint NOT NULL DEFAULT 0 COMMENT '部门ID',
+  `creator` bigint NULL DEFAULT NULL COMMENT '创建者id',
+  `create_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_status_run_at`(`status` ASC, `run_at` ASC) USING BTREE,
+  INDEX `idx_tenant_status`(`tenant_id` ASC, `status` ASC) USING BTREE
+) ENGINE = InnoDB AUTO_INCREMENT = 1 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '后台作业表' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_background_job
+-- ----------------------------
+
+-- ----------------------------
+-- Table structure for system_config
+-- ----------------------------
+DROP TABLE IF EXISTS `system_config`;
+CREATE TABLE `system_config`  (
+  `id` bigint NOT NULL AUTO_INCREMENT COMMENT 'id',
+  `config_key` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '参数键',
+  `config_value` varchar(2000) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '参数值',
+  `value_type` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT 'string' COMMENT '值类型（string integer float boolean json）',
+  `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '参数名称',
+  `remark` varchar(500) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
+  `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态（0正常 1停用）',
+  `creator` bigint NULL DEFAULT NULL COMMENT '创建者id',
+  `create_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updater` bigint NULL DEFAULT NULL COMMENT '更新者id',
+  `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
+  `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_tenant_key`(`tenant_id` ASC, `config_key` ASC) USING BTREE
+) ENGINE = InnoDB AUTO_INCREMENT = 18 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '系统参数表' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_config
+-- ----------------------------
+INSERT INTO `system_config` VALUES (1, 'system.jwt.access_token_ttl', '900', 'integer', 'access token有效期(秒)', NULL, 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (2, 'system.jwt.refresh_token_ttl', '604800', 'integer', 'refresh token有效期(秒)', NULL, 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (3, 'system.task.tenant_expire_cron', '0 5 0 * * *', 'string', '租户过期检查cron', NULL, 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (4, 'file.upload.max_size', '104857600', 'integer', '上传文件最大字节数', '不能超过服务配置的上限', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (5, 'common.document_number.purchase_order', '{"prefix": "PO", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '采购订单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (6, 'common.document_number.purchase_return', '{"prefix": "PR", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '采购退货编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (7, 'common.document_number.sales_order', '{"prefix": "SO", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '销售订单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (8, 'common.document_number.sales_return', '{"prefix": "SR", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '销售退货编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (9, 'common.document_number.inbound_order', '{"prefix": "IN", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '入库单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (10, 'common.document_number.outbound_order', '{"prefix": "OUT", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '出库单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (11, 'common.document_number.inventory_transfer', '{"prefix": "TR", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '调拨单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (12, 'common.document_number.inventory_check', '{"prefix": "IC", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '盘点单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (13, 'common.document_number.receipt', '{"prefix": "RC", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '收款单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (14, 'common.document_number.payment', '{"prefix": "PM", "separator": "-", "date_pattern": "%Y%m%d", "reset": "daily", "sequence_length": 4, "check_digit": false}', 'json', '付款单编号规则', '重置周期 reset: daily每天 monthly每月 never不重置', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (15, 'logger.retention_days', '180', 'integer', '日志保留天数', '只影响之后写入的日志', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (16, 'security.business_hours', '08:00-20:00', 'string', '工作时间', '非工作时间的登录记为安全事件,为空时不检测,跨零点如 22:00-06:00', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+INSERT INTO `system_config` VALUES (17, 'security.business_days', '1,2,3,4,5', 'string', '工作日', '1-7表示周一到周日,逗号分隔', 0, 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0', 1);
+
 -- ----------------------------
 -- Table structure for system_data_scope_rule
 -- ----------------------------
@@ -3051,6 +3141,27 @@ INSERT INTO `system_dict_type` VALUES (12, '配送方式', 'delivery_type', 0, '
 INSERT INTO `system_dict_type` VALUES (13, '计费方式', 'charge_type', 0, '计费方式', 1, '2025-06-25 12:54:19', 1, '2025-06-25 12:54:19', b'0');
 INSERT INTO `system_dict_type` VALUES (14, '店铺状态', 'store_status', 0, '店铺状态', 1, '2025-06-25 12:54:19', 1, '2025-06-25 12:54:19', b'0');
 
+-- ----------------------------
+-- Table structure for system_event_outbox
+-- ----------------------------
+DROP TABLE IF EXISTS `system_event_outbox`;
+CREATE TABLE `system_event_outbox`  (
+  `id` bigint NOT NULL AUTO_INCREMENT COMMENT '事件id',
+  `event_type` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '事件类型',
+  `payload` text CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '事件内容',
+  `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态（0待投递 1已投递）',
+  `create_time` datetime(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3) COMMENT '创建时间',
+  `publish_time` datetime NULL DEFAULT NULL COMMENT '投递时间',
+  `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_status_id`(`status` ASC, `id` ASC) USING BTREE,
+  INDEX `idx_status_publish_time`(`status` ASC, `publish_time` ASC) USING BTREE
+) ENGINE = InnoDB AUTO_INCREMENT = 1 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '领域事件发件箱' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_event_outbox
+-- ----------------------------
+
 -- ----------------------------
 -- Table structure for system_file
 -- ----------------------------
@@ -3277,6 +3388,73 @@ INSERT INTO `system_file` VALUES (198, 'pms_1761032449.06259669.png', 'image/png
 INSERT INTO `system_file` VALUES (199, 'pms_1761032449.06259669.png', 'image/png', 114147, '2025/11/11/113987597402902528_pms_1761032449.06259669.png', 1, '0000', 1, 1, '2025-11-11 13:06:12', 1, '2025-11-11 13:06:12', b'0', 1);
 INSERT INTO `system_file` VALUES (200, 'su7_1_20241226.jpg', 'image/jpeg', 1671768, '2025/11/11/113987598631833600_su7_1_20241226.jpg', 1, '0000', 1, 1, '2025-11-11 13:06:12', 1, '2025-11-11 13:06:12', b'0', 1);
 
+-- ----------------------------
+-- Table structure for system_job
+-- ----------------------------
+DROP TABLE IF EXISTS `system_job`;
+CREATE TABLE `system_job`  (
+  `id` bigint NOT NULL AUTO_INCREMENT COMMENT 'id',
+  `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '任务名称',
+  `cron_expression` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'cron表达式',
+  `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态（0正常 1暂停）',
+  `misfire_policy` tinyint NOT NULL DEFAULT 0 COMMENT '错过执行策略（0补执行一次 1忽略 2全部补执行）',
+  `last_fire_time` datetime(3) NULL DEFAULT NULL COMMENT '上次计划执行时间',
+  `next_fire_time` datetime(3) NULL DEFAULT NULL COMMENT '下次计划执行时间',
+  `remark` varchar(500) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
+  `creator` bigint NULL DEFAULT NULL COMMENT '创建者id',
+  `create_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updater` bigint NULL DEFAULT NULL COMMENT '更新者id',
+  `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
+  `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
+  PRIMARY KEY (`id`) USING BTREE,
+  UNIQUE INDEX `uk_name`(`name` ASC) USING BTREE
+) ENGINE = InnoDB AUTO_INCREMENT = 1 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '定时任务表' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_job
+-- ----------------------------
+
+-- ----------------------------
+-- Table structure for system_job_log
+-- ----------------------------
+DROP TABLE IF EXISTS `system_job_log`;
+CREATE TABLE `system_job_log`  (
+  `id` bigint NOT NULL AUTO_INCREMENT COMMENT 'id',
+  `job_name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '任务名称',
+  `trigger_type` tinyint NOT NULL DEFAULT 0 COMMENT '触发方式（0计划 1手动 2错过补执行 3重试）',
+  `attempt` int NOT NULL DEFAULT 1 COMMENT '第几次执行',
+  `status` tinyint NOT NULL DEFAULT 0 COMMENT '状态（0执行中 1成功 2失败）',
+  `instance` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '执行实例',
+  `start_time` datetime(3) NOT NULL COMMENT '开始时间',
+  `end_time` datetime(3) NULL DEFAULT NULL COMMENT '结束时间',
+  `duration` bigint NULL DEFAULT NULL COMMENT '耗时（毫秒）',
+  `error` varchar(2000) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '错误信息',
+  `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_job_name_start_time`(`job_name` ASC, `start_time` ASC) USING BTREE,
+  INDEX `idx_start_time`(`start_time` ASC) USING BTREE
+) ENGINE = InnoDB AUTO_INCREMENT = 1 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '定时任务执行记录表' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_job_log
+-- ----------------------------
+
+-- ----------------------------
+-- Table structure for system_lock_fence
+-- ----------------------------
+DROP TABLE IF EXISTS `system_lock_fence`;
+CREATE TABLE `system_lock_fence`  (
+  `name` varchar(128) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '锁名称',
+  `fencing_token` bigint NOT NULL COMMENT '已写入的最大fencing token',
+  `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`name`) USING BTREE
+) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '分布式锁fencing token表' ROW_FORMAT = DYNAMIC;
+
+-- ----------------------------
+-- Records of system_lock_fence
+-- ----------------------------
+
 -- ----------------------------
 -- Table structure for system_menu
 -- ----------------------------
@@ -3303,7 +3481,7 @@ CREATE TABLE `system_menu`  (
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   PRIMARY KEY (`id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 425 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '菜单权限表' ROW_FORMAT = DYNAMIC;
+) ENGINE = InnoDB AUTO_INCREMENT = 444 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '菜单权限表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
 -- Records of system_menu
@@ -3718,6 +3896,25 @@ INSERT INTO `system_menu` VALUES (421, '暂停营业', 'mall:store:list:pause',
 INSERT INTO `system_menu` VALUES (422, '审核通过', 'mall:store:list:accept', 3, 6, 410, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-10 02:13:36', 1, '2025-06-10 02:13:36', b'0');
 INSERT INTO `system_menu` VALUES (423, '审核拒绝', 'mall:store:list:reject', 3, 7, 410, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-10 02:13:54', 1, '2025-06-10 02:13:54', b'0');
 INSERT INTO `system_menu` VALUES (424, '永久关闭', 'mall:store:list:close', 3, 8, 410, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-10 02:13:54', 1, '2025-06-10 02:13:54', b'0');
+INSERT INTO `system_menu` VALUES (425, '参数设置', 'config', 2, 203, 3, '/config/config', 'config', 'pages/config/ConfigManage', 'ConfigManage', 'global.menu.system.config', 0, b'1', b'1', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (426, '查看', 'system:config:get', 3, 0, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (427, '新增', 'system:config:add', 3, 1, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (428, '修改', 'system:config:edit', 3, 2, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (429, '删除', 'system:config:delete', 3, 3, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (430, '启用', 'system:config:enable', 3, 4, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (431, '禁用', 'system:config:disable', 3, 5, 425, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-06-20 00:00:00', 1, '2025-06-20 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (432, '定时任务', 'job', 2, 204, 3, '/config/job', 'job', 'pages/config/JobManage', 'JobManage', 'global.menu.system.job', 0, b'1', b'1', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (433, '查看', 'system:job:get', 3, 0, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (434, '修改', 'system:job:edit', 3, 1, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (435, '暂停', 'system:job:pause', 3, 2, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (436, '恢复', 'system:job:resume', 3, 3, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (437, '立即执行', 'system:job:trigger', 3, 4, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (438, '执行记录', 'system:job:log', 3, 5, 432, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (439, '查看敏感信息', 'system:sensitive:view', 3, 4, 7, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (440, '修改日志级别', 'system:log:level', 3, 99, 2, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (441, '导出', 'audit:operation:export', 3, 82, 1, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (442, '导出', 'audit:login:export', 3, 83, 1, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
+INSERT INTO `system_menu` VALUES (443, '安全分析', 'audit:security', 3, 83, 2, '', '', '', '', '', 0, b'1', b'0', b'1', 1, '2025-07-01 00:00:00', 1, '2025-07-01 00:00:00', b'0');
 
 -- ----------------------------
 -- Table structure for system_notice
@@ -3729,13 +3926,15 @@ CREATE TABLE `system_notice`  (
   `content` text CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '公告内容',
   `type` tinyint NOT NULL COMMENT '公告类型（1通知 2公告）',
   `status` tinyint NOT NULL DEFAULT 0 COMMENT '公告状态（0正常 1关闭）',
+  `permission` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '可见权限,为空时租户内所有用户可见',
   `creator` bigint NULL DEFAULT NULL COMMENT '创建者id',
   `create_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `updater` bigint NULL DEFAULT NULL COMMENT '更新者id',
   `update_time` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `deleted` bit(1) NOT NULL DEFAULT b'0' COMMENT '是否删除',
   `tenant_id` bigint NOT NULL DEFAULT 0 COMMENT '租户编号',
-  PRIMARY KEY (`id`) USING BTREE
+  PRIMARY KEY (`id`) USING BTREE,
+  INDEX `idx_tenant_permission`(`tenant_id`, `permission`) USING BTREE
 ) ENGINE = InnoDB AUTO_INCREMENT = 1 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '通知公告表' ROW_FORMAT = DYNAMIC;
 
 -- ----------------------------
@@ -4351,7 +4550,7 @@ CREATE TABLE `system_user`  (
   `nickname` varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '用户昵称',
   `remark` varchar(500) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
   `email` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT '' COMMENT '用户邮箱',
-  `mobile` varchar(11) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT '' COMMENT '手机号码',
+  `mobile` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT '' COMMENT '手机号码,加密保存',
   `sex` tinyint NULL DEFAULT 0 COMMENT '用户性别',
   `avatar` varchar(512) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT '' COMMENT '头像地址',
   `status` tinyint NOT NULL DEFAULT 0 COMMENT '帐号状态（0正常 1停用）',
@@ -23,6 +23,9 @@ pub struct LoginLogger {
     #[serde(skip_serializing_if = "String::is_empty")]
     pub result: String, // 登陆结果
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>, // 是否登录成功,旧版本的日志为空,只记录了成功的登录
+
     #[serde(skip_serializing_if = "String::is_empty")]
     pub user_ip: String, // 用户 IP
 
@@ -61,6 +64,70 @@ impl LoginLogger {
     }
 }
 
+/// 登录安全事件,由logger-server分析登录日志产生,同一条登录日志的同类事件只记录一次
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SecurityEvent {
+
+    pub login_log_id: String, // 登录日志的_id
+
+    pub event_type: String, // 事件类型,见 SECURITY_EVENT_*
+
+    pub level: i8, // 风险等级,见 SECURITY_LEVEL_*
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<i64>, // 用户编号
+
+    pub username: String, // 用户账号
+
+    pub user_ip: String, // 用户 IP
+
+    pub user_agent: String, // 浏览器 UA
+
+    pub location: String, // IP所在地
+
+    pub detail: String, // 说明
+
+    pub operate_time: i64, // 登录时间(秒)
+
+    pub create_time: i64, // 发现时间(秒)
+
+    pub tenant_id: i64, // 租户编号
+
+}
+
+/// 用户的登录画像,记录常用的IP、设备和上次登录的位置
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LoginProfile {
+
+    #[serde(rename = "_id")]
+    pub id: String, // 租户编号:用户编号
+
+    pub tenant_id: i64, // 租户编号
+
+    pub user_id: i64, // 用户编号
+
+    pub ips: Vec<String>, // 最近使用的IP,最近的在前
+
+    pub devices: Vec<String>, // 最近使用的设备UA,最近的在前
+
+    pub last_login_time: i64, // 上次登录时间(秒)
+
+    pub last_ip: String, // 上次登录IP
+
+    pub last_location: String, // 上次登录IP所在地
+
+    pub last_latitude: Option<f64>, // 上次登录IP纬度
+
+    pub last_longitude: Option<f64>, // 上次登录IP经度
+
+}
+
+impl LoginProfile {
+    pub fn profile_id(tenant_id: i64, user_id: i64) -> String {
+        format!("{}:{}", tenant_id, user_id)
+    }
+}
+
 /// 操作日志
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 pub struct OperationLogger {
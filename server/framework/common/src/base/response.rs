@@ -5,6 +5,9 @@ use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 use utoipa::ToSchema;
 
+/// 成功的业务状态码
+pub const RESULT_CODE_SUCCESS: i32 = 200;
+
 /// 系统统一返回
 #[derive(Debug, Serialize, Default, ToSchema)]
 pub struct CommonResult<T> {
@@ -49,7 +52,7 @@ impl<T: Serialize> CommonResult<T> {
 
     pub fn with_none() -> Self {
         Self {
-            code: Some(200),
+            code: Some(RESULT_CODE_SUCCESS),
             data: None,
             message: Some("success".to_string()),
         }
@@ -57,7 +60,7 @@ impl<T: Serialize> CommonResult<T> {
 
     pub fn with_data(data: T) -> Self {
         Self {
-            code: Some(200),
+            code: Some(RESULT_CODE_SUCCESS),
             data: Some(data),
             message: Some("success".to_string()),
         }
@@ -71,7 +74,7 @@ impl<T: Serialize> CommonResult<T> {
     }
     pub fn with_msg(message: &str) -> Self {
         Self {
-            code: Some(200),
+            code: Some(RESULT_CODE_SUCCESS),
             data: None,
             message: Some(message.to_string()),
         }
@@ -79,7 +82,7 @@ impl<T: Serialize> CommonResult<T> {
     #[allow(dead_code)]
     pub fn with_data_msg(data: T, message: &str) -> Self {
         Self {
-            code: Some(200),
+            code: Some(RESULT_CODE_SUCCESS),
             data: Some(data),
             message: Some(message.to_string()),
         }
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::error;
+use crate::database::redis_constants::{REDIS_KEY_CACHE_PREFIX, REDIS_KEY_CACHE_TAG_PREFIX};
+use crate::database::redis_pool::AsyncRedisManager;
+
+/// 不存在的数据默认缓存时间,防止缓存穿透
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+/// 正在加载的key,同一进程内相同key只加载一次
+static LOADING: Lazy<DashMap<String, Arc<Mutex<()>>>> = Lazy::new(|| {
+    DashMap::new()
+});
+
+/// 缓存选项
+#[derive(Clone, Debug)]
+pub struct CacheOptions {
+    pub ttl: Duration, // 缓存时间
+    pub negative_ttl: Duration, // 数据不存在时的缓存时间,为0时不缓存
+    pub tags: Vec<String>, // 标签,按标签批量失效
+}
+
+impl CacheOptions {
+    pub fn new(ttl: Duration) -> Self {
+        CacheOptions {
+            ttl,
+            negative_ttl: DEFAULT_NEGATIVE_TTL.min(ttl),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+}
+
+/// 缓存内容,value为None表示数据不存在
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    value: Option<T>,
+}
+
+/// 读取缓存,未命中时调用loader加载并写入缓存
+/// redis异常时直接调用loader,不影响业务
+pub async fn get_or_load<T, F, Fut>(key: &str, options: &CacheOptions, loader: F) -> Result<Option<T>>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let redis_key = cache_key(key);
+    if let Some(entry) = read::<T>(&redis_key).await {
+        return Ok(entry.value);
+    }
+
+    // 相同key并发未命中时只有一个请求加载,其他请求等待后读缓存
+    let loading = lock_loading(vec![redis_key.clone()]).await;
+    let result = match read::<T>(&redis_key).await {
+        Some(entry) => Ok(entry.value),
+        None => {
+            let value = loader().await;
+            if let Ok(value) = &value {
+                write(&redis_key, value, options).await;
+            }
+            value
+        }
+    };
+    drop(loading);
+    result
+}
+
+/// 批量读取缓存,未命中的id一次性调用loader加载,loader未返回的id按不存在缓存
+/// 未命中的key与get_or_load共用加载锁,并发请求中只有一个加载,其他请求等待后读缓存
+pub async fn get_many_or_load<K, T, F, Fut>(prefix: &str, ids: &[K], options: &CacheOptions, loader: F) -> Result<HashMap<K, T>>
+where
+    K: Display + Eq + Hash + Clone,
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(Vec<K>) -> Fut,
+    Fut: Future<Output = Result<HashMap<K, T>>>,
+{
+    let mut result = HashMap::with_capacity(ids.len());
+    if ids.is_empty() {
+        return Ok(result);
+    }
+    let cached = read_many(prefix, ids).await;
+    let missing = partition(ids.to_vec(), cached, &mut result);
+    if missing.is_empty() {
+        return Ok(result);
+    }
+
+    let loading = lock_loading(missing.iter().map(|id| item_key(prefix, id)).collect()).await;
+    // 等待加载锁期间其他请求可能已写入缓存
+    let cached = read_many(prefix, &missing).await;
+    let missing = partition(missing, cached, &mut result);
+    if missing.is_empty() {
+        return Ok(result);
+    }
+
+    let mut loaded = loader(missing.clone()).await?;
+    for id in missing {
+        let value = loaded.remove(&id);
+        write(&item_key(prefix, &id), &value, options).await;
+        if let Some(value) = value {
+            result.insert(id, value);
+        }
+    }
+    drop(loading);
+    Ok(result)
+}
+
+/// 删除缓存
+pub async fn invalidate(key: &str) {
+    if let Err(e) = AsyncRedisManager::delete(cache_key(key)).await {
+        error!("cache invalidate error, key: {}, {}", key, e);
+    }
+}
+
+/// 删除标签下的所有缓存
+pub async fn invalidate_tag(tag: &str) {
+    let tag_key = tag_key(tag);
+    let keys: Vec<String> = match AsyncRedisManager::get_set_members(&tag_key).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            error!("cache invalidate tag error, tag: {}, {}", tag, e);
+            return;
+        }
+    };
+    // 集群模式下key不在同一个slot,逐个删除
+    let mut pipeline = redis::pipe();
+    for key in &keys {
+        pipeline.del(key).ignore();
+    }
+    pipeline.del(&tag_key).ignore();
+    if let Err(e) = AsyncRedisManager::query_pipeline::<()>(&pipeline).await {
+        error!("cache invalidate tag error, tag: {}, {}", tag, e);
+    }
+}
+
+fn cache_key(key: &str) -> String {
+    format!("{}{}", REDIS_KEY_CACHE_PREFIX, key)
+}
+
+fn item_key<K: Display>(prefix: &str, id: &K) -> String {
+    cache_key(&format!("{}{}", prefix, id))
+}
+
+fn tag_key(tag: &str) -> String {
+    format!("{}{}", REDIS_KEY_CACHE_TAG_PREFIX, tag)
+}
+
+/// 持有一组key的加载锁,drop时释放并清理没有等待者的锁
+struct LoadingGuard {
+    keys: Vec<String>,
+    guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl Drop for LoadingGuard {
+    fn drop(&mut self) {
+        self.guards.clear();
+        for key in &self.keys {
+            LOADING.remove_if(key, |_, loading| Arc::strong_count(loading) == 1);
+        }
+    }
+}
+
+/// 按key排序后依次加锁,批量加载之间不会互相等待形成死锁
+async fn lock_loading(mut keys: Vec<String>) -> LoadingGuard {
+    keys.sort();
+    keys.dedup();
+    let mut guards = Vec::with_capacity(keys.len());
+    for key in &keys {
+        let loading = LOADING.entry(key.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        guards.push(loading.lock_owned().await);
+    }
+    LoadingGuard { keys, guards }
+}
+
+/// 批量读取缓存,redis异常时按全部未命中处理
+async fn read_many<K: Display>(prefix: &str, ids: &[K]) -> Vec<Option<String>> {
+    let mut pipeline = redis::pipe();
+    for id in ids {
+        pipeline.get(item_key(prefix, id));
+    }
+    match AsyncRedisManager::query_pipeline(&pipeline).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            error!("cache read error, prefix: {}, {}", prefix, e);
+            vec![None; ids.len()]
+        }
+    }
+}
+
+/// 命中的数据放入result,缓存为不存在的id跳过,返回未命中的id(去重)
+fn partition<K, T>(ids: Vec<K>, cached: Vec<Option<String>>, result: &mut HashMap<K, T>) -> Vec<K>
+where
+    K: Eq + Hash + Clone,
+    T: DeserializeOwned,
+{
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    for (id, json) in ids.into_iter().zip(cached) {
+        match json.and_then(|json| serde_json::from_str::<CacheEntry<T>>(&json).ok()) {
+            Some(CacheEntry { value: Some(value) }) => { result.insert(id, value); }
+            Some(CacheEntry { value: None }) => {}
+            None => {
+                if seen.insert(id.clone()) {
+                    missing.push(id);
+                }
+            }
+        }
+    }
+    missing
+}
+
+async fn read<T: DeserializeOwned>(redis_key: &str) -> Option<CacheEntry<T>> {
+    match AsyncRedisManager::get::<_, String>(redis_key).await {
+        Ok(Some(json)) => serde_json::from_str(&json).ok(),
+        Ok(None) => None,
+        Err(e) => {
+            error!("cache read error, key: {}, {}", redis_key, e);
+            None
+        }
+    }
+}
+
+/// 缓存时间,数据不存在时使用negative_ttl,为0时不缓存
+fn entry_ttl<T>(value: &Option<T>, options: &CacheOptions) -> Option<Duration> {
+    let ttl = if value.is_some() { options.ttl } else { options.negative_ttl };
+    (!ttl.is_zero()).then_some(ttl)
+}
+
+async fn write<T: Serialize>(redis_key: &str, value: &Option<T>, options: &CacheOptions) {
+    let ttl = match entry_ttl(value, options) {
+        Some(ttl) => ttl,
+        None => return,
+    };
+    let json = match serde_json::to_string(&CacheEntry { value: value.as_ref() }) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("cache serialize error, key: {}, {}", redis_key, e);
+            return;
+        }
+    };
+    let mut pipeline = redis::pipe();
+    pipeline.set_ex(redis_key, json, ttl.as_secs().max(1)).ignore();
+    for tag in &options.tags {
+        // 标签集合的过期时间不短于缓存时间
+        let tag_key = tag_key(tag);
+        pipeline.sadd(&tag_key, redis_key).ignore();
+        pipeline.expire(&tag_key, options.ttl.as_secs().max(1) as i64).ignore();
+    }
+    if let Err(e) = AsyncRedisManager::query_pipeline::<()>(&pipeline).await {
+        error!("cache write error, key: {}, {}", redis_key, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn entry<T: Serialize>(value: Option<T>) -> Option<String> {
+        Some(serde_json::to_string(&CacheEntry { value }).unwrap())
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut result: HashMap<i64, String> = HashMap::new();
+        let cached = vec![
+            entry(Some("a".to_string())), // 命中
+            entry::<String>(None), // 缓存为不存在
+            None, // 未命中
+            Some("{broken".to_string()), // 无法解析按未命中
+            None, // 重复的id
+        ];
+        let missing = partition(vec![1, 2, 3, 4, 3], cached, &mut result);
+        assert_eq!(missing, vec![3, 4]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.get(&1).map(String::as_str), Some("a"));
+        assert!(!result.contains_key(&2));
+    }
+
+    #[test]
+    fn test_negative_entry() {
+        let json = serde_json::to_string(&CacheEntry::<&String> { value: None }).unwrap();
+        assert_eq!(json, r#"{"value":null}"#);
+        let entry: CacheEntry<String> = serde_json::from_str(&json).unwrap();
+        assert!(entry.value.is_none());
+    }
+
+    #[test]
+    fn test_entry_ttl() {
+        let options = CacheOptions::new(Duration::from_secs(600));
+        assert_eq!(entry_ttl(&Some(1), &options), Some(Duration::from_secs(600)));
+        assert_eq!(entry_ttl::<i32>(&None, &options), Some(DEFAULT_NEGATIVE_TTL));
+        // 不存在的数据缓存时间不超过缓存时间
+        let options = CacheOptions::new(Duration::from_secs(10));
+        assert_eq!(entry_ttl::<i32>(&None, &options), Some(Duration::from_secs(10)));
+        let options = options.with_negative_ttl(Duration::ZERO);
+        assert_eq!(entry_ttl::<i32>(&None, &options), None);
+    }
+
+    #[test]
+    fn test_item_key() {
+        assert_eq!(item_key("user:", &1), format!("{}user:1", REDIS_KEY_CACHE_PREFIX));
+    }
+
+    #[tokio::test]
+    async fn test_lock_loading_single_flight() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let mut tasks = Vec::new();
+        for i in 0..8 {
+            let running = running.clone();
+            // 重叠的key以不同顺序加锁,不会死锁
+            let keys = if i % 2 == 0 {
+                vec!["test_loading:a".to_string(), "test_loading:b".to_string()]
+            } else {
+                vec!["test_loading:b".to_string(), "test_loading:a".to_string(), "test_loading:a".to_string()]
+            };
+            tasks.push(tokio::spawn(async move {
+                let _loading = lock_loading(keys).await;
+                assert_eq!(running.fetch_add(1, Ordering::SeqCst), 0);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        let all = futures_util::future::join_all(tasks);
+        for task in tokio::time::timeout(Duration::from_secs(5), all).await.unwrap() {
+            task.unwrap();
+        }
+        // 释放后清理加载锁
+        assert!(!LOADING.contains_key("test_loading:a"));
+        assert!(!LOADING.contains_key("test_loading:b"));
+    }
+
+    #[tokio::test]
+    async fn test_lock_loading_disjoint_keys() {
+        let first = lock_loading(vec!["test_loading:c".to_string()]).await;
+        // 不同的key不互相等待
+        let second = tokio::time::timeout(Duration::from_secs(1), lock_loading(vec!["test_loading:d".to_string()])).await;
+        assert!(second.is_ok());
+        drop(first);
+    }
+
+    /// 需要本地redis和配置
+    #[tokio::test]
+    #[ignore]
+    async fn test_get_many_or_load() {
+        let prefix = "test_cache_aside:";
+        let options = CacheOptions::new(Duration::from_secs(60));
+        for id in [1, 2, 3] {
+            invalidate(&format!("{}{}", prefix, id)).await;
+        }
+        let loads = Arc::new(AtomicUsize::new(0));
+        let load = |ids: Vec<i64>| {
+            let loads = loads.clone();
+            async move {
+                loads.fetch_add(ids.len(), Ordering::SeqCst);
+                // id为3的数据不存在
+                let loaded: HashMap<i64, String> = ids.into_iter().filter(|id| *id != 3).map(|id| (id, format!("v{}", id))).collect();
+                Ok::<_, anyhow::Error>(loaded)
+            }
+        };
+
+        // 未命中时加载
+        let result = get_many_or_load(prefix, &[1, 2, 3], &options, load).await.unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+        // 命中和不存在的缓存都不再加载
+        let result = get_many_or_load(prefix, &[1, 2, 3], &options, load).await.unwrap();
+        assert_eq!(result.get(&2).map(String::as_str), Some("v2"));
+        assert!(!result.contains_key(&3));
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+        let value: Option<String> = get_or_load(&format!("{}3", prefix), &options, || async { Ok(Some("v3".to_string())) }).await.unwrap();
+        assert!(value.is_none());
+
+        for id in [1, 2, 3] {
+            invalidate(&format!("{}{}", prefix, id)).await;
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use redis::Script;
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{error, warn};
+use crate::database::redis_constants::REDIS_KEY_LOCK_PREFIX;
+use crate::database::redis_pool::AsyncRedisManager;
+
+/// 默认租期
+const DEFAULT_LEASE: Duration = Duration::from_secs(30);
+/// 等待锁时的重试间隔
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 进程内单调时钟的起点,租约的有效期以相对该起点的毫秒数保存
+static CLOCK_BASE: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// 当前的单调时钟(毫秒),与 LockGuard::valid_until 比较
+pub fn monotonic_millis() -> u64 {
+    CLOCK_BASE.elapsed().as_millis() as u64
+}
+
+/// 加锁成功后递增fencing token,两个key使用相同的hash tag保证在集群的同一个slot
+/// token不小于redis当前时间(微秒),redis数据丢失后计数器重建,token仍然大于之前发放的
+static ACQUIRE_SCRIPT: Lazy<Script> = Lazy::new(|| Script::new(r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+    local time = redis.call('TIME')
+    local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+    local token = math.max(tonumber(redis.call('GET', KEYS[2]) or '0') + 1, now)
+    redis.call('SET', KEYS[2], string.format('%d', token))
+    return token
+end
+return 0
+"#));
+
+/// 续期,只续期自己持有的锁
+static RENEW_SCRIPT: Lazy<Script> = Lazy::new(|| Script::new(r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#));
+
+/// 基于redis的分布式锁
+/// 持有期间后台按租期的1/3自动续期,进程崩溃时锁在租期后自动释放
+/// 每次加锁返回递增的fencing token,受保护的写入通过 LockGuard::check_db_fence / check_fence 拒绝过期持有者
+#[derive(Clone, Debug)]
+pub struct DistributedLock {
+    name: String,
+    lease: Duration, // 租期
+    wait: Duration, // 最长等待时间,为0时不等待
+}
+
+impl DistributedLock {
+    pub fn new(name: impl Into<String>) -> Self {
+        DistributedLock {
+            name: name.into(),
+            lease: DEFAULT_LEASE,
+            wait: Duration::ZERO,
+        }
+    }
+
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease.max(Duration::from_millis(100));
+        self
+    }
+
+    pub fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    fn lock_key(&self) -> String {
+        format!("{}{{{}}}", REDIS_KEY_LOCK_PREFIX, self.name)
+    }
+
+    fn fence_key(&self) -> String {
+        format!("{}{{{}}}:fence", REDIS_KEY_LOCK_PREFIX, self.name)
+    }
+
+    /// 尝试加锁一次,锁被占用时返回None
+    pub async fn try_acquire(&self) -> Result<Option<LockGuard>> {
+        let key = self.lock_key();
+        let token = format!("{:032x}", rand::random::<u128>());
+        let started = monotonic_millis();
+        let fencing_token: i64 = AsyncRedisManager::invoke_script(
+            &ACQUIRE_SCRIPT,
+            &[key.clone(), self.fence_key()],
+            &[token.clone(), self.lease.as_millis().to_string()],
+        ).await?;
+        if fencing_token == 0 {
+            return Ok(None);
+        }
+
+        let lease = Arc::new(LeaseState::new(started + valid_millis(self.lease)));
+        let renewal = tokio::spawn(renew(key.clone(), token.clone(), self.lease, lease.clone()));
+        Ok(Some(LockGuard {
+            name: self.name.clone(),
+            key,
+            fence_key: self.fence_key(),
+            token,
+            fencing_token,
+            lease,
+            renewal: Some(renewal),
+        }))
+    }
+
+    /// 加锁,在等待时间内重试,超时返回错误
+    pub async fn acquire(&self) -> Result<LockGuard> {
+        let deadline = Instant::now() + self.wait;
+        loop {
+            if let Some(guard) = self.try_acquire().await? {
+                return Ok(guard);
+            }
+            if Instant::now() + RETRY_INTERVAL > deadline {
+                return Err(anyhow!("获取锁超时: {}", self.name));
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    /// 在锁内执行,执行完成后释放锁,参数为fencing token
+    pub async fn run<F, Fut, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(i64) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let guard = self.acquire().await?;
+        let result = f(guard.fencing_token()).await;
+        if !guard.release().await? {
+            warn!("lock {} was lost before release", self.name);
+        }
+        result
+    }
+}
+
+/// 租约状态,续期任务和持有者共享
+struct LeaseState {
+    held: AtomicBool,
+    valid_until: AtomicU64, // 有效期(monotonic_millis),已扣除安全余量
+}
+
+impl LeaseState {
+    fn new(valid_until: u64) -> Self {
+        LeaseState { held: AtomicBool::new(true), valid_until: AtomicU64::new(valid_until) }
+    }
+
+    fn is_held(&self) -> bool {
+        self.held.load(Ordering::SeqCst) && monotonic_millis() < self.valid_until.load(Ordering::SeqCst)
+    }
+
+    fn lose(&self) {
+        self.held.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 续期成功后仍可使用的时长: 租期减去一个续期间隔作为安全余量
+/// 续期超时或失败时,持有者在redis中的key过期前就停止使用锁,避免与下一个持有者重叠
+fn valid_millis(lease: Duration) -> u64 {
+    (lease - lease / 3).as_millis() as u64
+}
+
+async fn renew(key: String, token: String, lease: Duration, state: Arc<LeaseState>) {
+    let interval = lease / 3;
+    loop {
+        tokio::time::sleep(interval).await;
+        let started = monotonic_millis();
+        let result = tokio::time::timeout(interval, AsyncRedisManager::invoke_script::<i64>(
+            &RENEW_SCRIPT,
+            &[key.clone()],
+            &[token.clone(), lease.as_millis().to_string()],
+        )).await;
+        match result {
+            Ok(Ok(1)) => state.valid_until.store(started + valid_millis(lease), Ordering::SeqCst),
+            Ok(Ok(_)) => {
+                warn!("lock {} lost, renewal stopped", key);
+                state.lose();
+                return;
+            }
+            Ok(Err(e)) => error!("lock {} renew error: {}", key, e),
+            Err(_) => error!("lock {} renew timeout", key),
+        }
+        // 续期失败后在有效期内继续重试,超过后视为丢失
+        if !state.is_held() {
+            warn!("lock {} lease expired without renewal", key);
+            state.lose();
+            return;
+        }
+    }
+}
+
+/// 锁的持有凭证,drop时自动释放
+pub struct LockGuard {
+    name: String,
+    key: String,
+    fence_key: String,
+    token: String,
+    fencing_token: i64,
+    lease: Arc<LeaseState>,
+    renewal: Option<JoinHandle<()>>,
+}
+
+impl LockGuard {
+    /// 本次加锁的fencing token,单调递增
+    pub fn fencing_token(&self) -> i64 {
+        self.fencing_token
+    }
+
+    /// 锁是否仍然被持有,被其他持有者获取或超过有效期没有续期成功后返回false
+    pub fn is_held(&self) -> bool {
+        self.lease.is_held()
+    }
+
+    /// 租约有效期(monotonic_millis),超过后不能再使用锁保护的资源
+    pub fn valid_until(&self) -> u64 {
+        if self.lease.held.load(Ordering::SeqCst) {
+            self.lease.valid_until.load(Ordering::SeqCst)
+        } else {
+            0
+        }
+    }
+
+    /// 在写入的数据库事务中校验fencing token,提交前调用
+    /// system_lock_fence 记录每个锁写入过的最大token,更大时说明锁已被其他持有者获取,返回错误回滚本次写入
+    /// 记录时加行锁,同一个锁的写入事务串行执行,校验和写入之间不会被其他持有者插入
+    pub async fn check_db_fence<C: ConnectionTrait>(&self, db: &C) -> Result<()> {
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::MySql,
+            "INSERT INTO system_lock_fence (name, fencing_token) VALUES (?, ?) \
+             ON DUPLICATE KEY UPDATE fencing_token = GREATEST(fencing_token, VALUES(fencing_token))",
+            [self.name.as_str().into(), self.fencing_token.into()],
+        );
+        db.execute(stmt).await?;
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::MySql,
+            "SELECT fencing_token FROM system_lock_fence WHERE name = ?",
+            [self.name.as_str().into()],
+        );
+        let latest: i64 = match db.query_one(stmt).await? {
+            Some(row) => row.try_get("", "fencing_token")?,
+            None => bail!("锁的fencing token不存在: {}", self.name),
+        };
+        if latest > self.fencing_token {
+            bail!("锁已被新的持有者获取: {}, fencing token {} < {}", self.name, self.fencing_token, latest);
+        }
+        Ok(())
+    }
+
+    /// 校验锁没有被其他持有者重新获取,写入redis、mongo等无法在事务中校验的资源前调用
+    pub async fn check_fence(&self) -> Result<()> {
+        let latest = AsyncRedisManager::get::<_, i64>(&self.fence_key).await?;
+        if let Some(latest) = latest.filter(|latest| *latest > self.fencing_token) {
+            bail!("锁已被新的持有者获取: {}, fencing token {} < {}", self.name, self.fencing_token, latest);
+        }
+        Ok(())
+    }
+
+    /// 释放锁,锁已过期或被其他持有者获取时返回false
+    pub async fn release(mut self) -> Result<bool> {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        self.lease.lose();
+        Ok(AsyncRedisManager::unlock(&self.key, &self.token).await?)
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let renewal = match self.renewal.take() {
+            Some(renewal) => renewal,
+            None => return, // 已经释放
+        };
+        renewal.abort();
+        self.lease.lose();
+        let (key, token) = (self.key.clone(), self.token.clone());
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = AsyncRedisManager::unlock(&key, &token).await {
+                    error!("lock {} release error: {}", key, e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(lease: Arc<LeaseState>) -> LockGuard {
+        LockGuard {
+            name: "test".to_string(),
+            key: "test".to_string(),
+            fence_key: "test:fence".to_string(),
+            token: "token".to_string(),
+            fencing_token: 1,
+            lease,
+            renewal: None,
+        }
+    }
+
+    #[test]
+    fn test_keys_share_hash_tag() {
+        let lock = DistributedLock::new("job:1");
+        assert_eq!(lock.lock_key(), format!("{}{{job:1}}", REDIS_KEY_LOCK_PREFIX));
+        assert_eq!(lock.fence_key(), format!("{}{{job:1}}:fence", REDIS_KEY_LOCK_PREFIX));
+    }
+
+    #[test]
+    fn test_min_lease() {
+        let lock = DistributedLock::new("test").with_lease(Duration::from_millis(10));
+        assert_eq!(lock.lease, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_valid_millis() {
+        // 扣除一个续期间隔
+        assert_eq!(valid_millis(Duration::from_secs(30)), 20_000);
+        assert_eq!(valid_millis(Duration::from_millis(300)), 200);
+    }
+
+    #[test]
+    fn test_lease_state() {
+        let now = monotonic_millis();
+        let lease = Arc::new(LeaseState::new(now + 60_000));
+        let guard = guard(lease.clone());
+        assert!(guard.is_held());
+        assert_eq!(guard.valid_until(), now + 60_000);
+
+        lease.lose();
+        assert!(!guard.is_held());
+        assert_eq!(guard.valid_until(), 0);
+    }
+
+    #[test]
+    fn test_lease_expired() {
+        let lease = Arc::new(LeaseState::new(monotonic_millis()));
+        // 超过有效期没有续期成功时不再持有
+        assert!(!guard(lease).is_held());
+    }
+
+    async fn acquire(conn: &mut redis::aio::MultiplexedConnection, key: &str, fence_key: &str, token: &str) -> i64 {
+        ACQUIRE_SCRIPT.key(key).key(fence_key).arg(token).arg(1000).invoke_async(conn).await.unwrap()
+    }
+
+    /// 需要本地redis
+    #[tokio::test]
+    #[ignore]
+    async fn test_acquire_release_fencing() {
+        let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        let lock = DistributedLock::new("test_distributed_lock");
+        let (key, fence_key) = (lock.lock_key(), lock.fence_key());
+        let _: () = redis::cmd("DEL").arg(&key).arg(&fence_key).query_async(&mut conn).await.unwrap();
+
+        let first = acquire(&mut conn, &key, &fence_key, "a").await;
+        assert!(first > 0);
+        // 被占用时不能加锁
+        assert_eq!(acquire(&mut conn, &key, &fence_key, "b").await, 0);
+        // 只续期自己持有的锁
+        let renewed: i64 = RENEW_SCRIPT.key(&key).arg("b").arg(1000).invoke_async(&mut conn).await.unwrap();
+        assert_eq!(renewed, 0);
+        let renewed: i64 = RENEW_SCRIPT.key(&key).arg("a").arg(1000).invoke_async(&mut conn).await.unwrap();
+        assert_eq!(renewed, 1);
+
+        let _: () = redis::cmd("DEL").arg(&key).query_async(&mut conn).await.unwrap();
+        let second = acquire(&mut conn, &key, &fence_key, "b").await;
+        assert!(second > first);
+        // 计数器丢失后token仍然递增
+        let _: () = redis::cmd("DEL").arg(&key).arg(&fence_key).query_async(&mut conn).await.unwrap();
+        let third = acquire(&mut conn, &key, &fence_key, "c").await;
+        assert!(third > second);
+
+        let _: () = redis::cmd("DEL").arg(&key).arg(&fence_key).query_async(&mut conn).await.unwrap();
+    }
+}
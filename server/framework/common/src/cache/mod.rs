@@ -0,0 +1,2 @@
+pub mod cache_aside;
+pub mod distributed_lock;
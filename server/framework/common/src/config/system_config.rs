@@ -0,0 +1,228 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use serde::de::DeserializeOwned;
+use tokio::sync::watch;
+use tracing::{error, info};
+use crate::constants::enum_constants::{ROOT_TENANT_ID, STATUS_ENABLE};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::database::redis_constants::REDIS_CHANNEL_SYSTEM_CONFIG;
+
+/// 系统参数key,根租户的值为全局默认值,其他租户可以单独配置覆盖
+pub const CONFIG_KEY_ACCESS_TOKEN_TTL: &str = "system.jwt.access_token_ttl"; // access token有效期(秒)
+pub const CONFIG_KEY_REFRESH_TOKEN_TTL: &str = "system.jwt.refresh_token_ttl"; // refresh token有效期(秒)
+pub const CONFIG_KEY_TENANT_EXPIRE_CRON: &str = "system.task.tenant_expire_cron"; // 租户过期检查
+pub const CONFIG_KEY_UPLOAD_MAX_SIZE: &str = "file.upload.max_size"; // 上传文件最大字节数
+pub const CONFIG_KEY_LOGGER_RETENTION_DAYS: &str = "logger.retention_days"; // 日志保留天数,只影响之后写入的日志
+pub const CONFIG_KEY_SECURITY_BUSINESS_HOURS: &str = "security.business_hours"; // 工作时间,如 08:00-20:00,为空时不检测非工作时间登录
+pub const CONFIG_KEY_SECURITY_BUSINESS_DAYS: &str = "security.business_days"; // 工作日,1-7表示周一到周日,如 1,2,3,4,5
+
+/// 定时刷新间隔,防止变更通知丢失
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// 订阅断开后的重连间隔
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 参数值类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigValueType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Json,
+}
+
+impl FromStr for ConfigValueType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "string" => Ok(ConfigValueType::String),
+            "integer" => Ok(ConfigValueType::Integer),
+            "float" => Ok(ConfigValueType::Float),
+            "boolean" => Ok(ConfigValueType::Boolean),
+            "json" => Ok(ConfigValueType::Json),
+            _ => Err(anyhow!("参数类型错误: {}", s)),
+        }
+    }
+}
+
+impl ConfigValueType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigValueType::String => "string",
+            ConfigValueType::Integer => "integer",
+            ConfigValueType::Float => "float",
+            ConfigValueType::Boolean => "boolean",
+            ConfigValueType::Json => "json",
+        }
+    }
+
+    /// 校验参数值是否符合类型
+    pub fn validate(&self, value: &str) -> Result<()> {
+        let valid = match self {
+            ConfigValueType::String => true,
+            ConfigValueType::Integer => value.trim().parse::<i64>().is_ok(),
+            ConfigValueType::Float => value.trim().parse::<f64>().is_ok(),
+            ConfigValueType::Boolean => parse_bool(value).is_some(),
+            ConfigValueType::Json => serde_json::from_str::<serde_json::Value>(value).is_ok(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(anyhow!("参数值与类型不匹配: {}", value))
+        }
+    }
+}
+
+/// 参数缓存 (租户id, key) -> value,只包含启用的参数
+static CONFIG_CACHE: Lazy<DashMap<(i64, String), String>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 参数变更版本,每次重新加载后加1
+static CONFIG_VERSION: Lazy<watch::Sender<u64>> = Lazy::new(|| {
+    watch::channel(0).0
+});
+static DATABASE: OnceLock<DatabaseConnection> = OnceLock::new();
+
+/// 加载参数并监听变更,服务启动时调用一次
+pub async fn init(db: &DatabaseConnection) -> Result<()> {
+    if DATABASE.set(db.clone()).is_err() {
+        return Ok(());
+    }
+    reload().await?;
+
+    // 订阅变更通知,断开后重新订阅
+    tokio::spawn(async {
+        loop {
+            let result = AsyncRedisManager::subscribe(REDIS_CHANNEL_SYSTEM_CONFIG, |key| {
+                info!("system config changed: {}", key);
+                tokio::spawn(async {
+                    if let Err(e) = reload().await {
+                        error!("reload system config error: {}", e);
+                    }
+                });
+            }).await;
+            if let Err(e) = result {
+                error!("subscribe system config error: {}", e);
+            }
+            tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+        }
+    });
+
+    // 定时刷新
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = reload().await {
+                error!("refresh system config error: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 从数据库重新加载全部参数
+pub async fn reload() -> Result<()> {
+    let db = DATABASE.get().ok_or_else(|| anyhow!("system config is not initialized"))?;
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "SELECT tenant_id, config_key, config_value FROM system_config WHERE deleted = 0 AND status = ?",
+        [STATUS_ENABLE.into()],
+    );
+    let rows = db.query_all(stmt).await?;
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        let tenant_id: i64 = row.try_get("", "tenant_id")?;
+        let key: String = row.try_get("", "config_key")?;
+        let value: String = row.try_get("", "config_value")?;
+        values.push(((tenant_id, key), value));
+    }
+    replace(values);
+    Ok(())
+}
+
+/// 替换参数缓存并通知变更
+pub(crate) fn replace(values: Vec<((i64, String), String)>) {
+    CONFIG_CACHE.retain(|k, _| values.iter().any(|(key, _)| key == k));
+    for (key, value) in values {
+        CONFIG_CACHE.insert(key, value);
+    }
+    CONFIG_VERSION.send_modify(|version| *version += 1);
+}
+
+/// 通知所有服务参数已变更,修改参数后调用
+pub async fn notify_changed(key: &str) {
+    if let Err(e) = AsyncRedisManager::publish(REDIS_CHANNEL_SYSTEM_CONFIG, key).await {
+        error!("publish system config change error: {}", e);
+    }
+}
+
+/// 监听参数变更
+pub fn subscribe() -> watch::Receiver<u64> {
+    CONFIG_VERSION.subscribe()
+}
+
+/// 获取参数原始值,优先使用租户配置,没有则使用根租户配置
+pub fn get_raw(tenant_id: i64, key: &str) -> Option<String> {
+    CONFIG_CACHE.get(&(tenant_id, key.to_string()))
+        .or_else(|| CONFIG_CACHE.get(&(ROOT_TENANT_ID, key.to_string())))
+        .map(|value| value.value().clone())
+}
+
+pub fn get_string(tenant_id: i64, key: &str) -> Option<String> {
+    get_raw(tenant_id, key)
+}
+
+pub fn get_i64(tenant_id: i64, key: &str) -> Option<i64> {
+    get_parsed(tenant_id, key)
+}
+
+pub fn get_f64(tenant_id: i64, key: &str) -> Option<f64> {
+    get_parsed(tenant_id, key)
+}
+
+pub fn get_bool(tenant_id: i64, key: &str) -> Option<bool> {
+    get_raw(tenant_id, key).and_then(|value| parse_bool(&value))
+}
+
+pub fn get_json<T: DeserializeOwned>(tenant_id: i64, key: &str) -> Option<T> {
+    let value = get_raw(tenant_id, key)?;
+    match serde_json::from_str(&value) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("parse system config {} error: {}", key, e);
+            None
+        }
+    }
+}
+
+/// 获取参数,未配置或类型错误时返回默认值
+pub fn get_or<T: FromStr>(tenant_id: i64, key: &str, default: T) -> T {
+    get_parsed(tenant_id, key).unwrap_or(default)
+}
+
+fn get_parsed<T: FromStr>(tenant_id: i64, key: &str) -> Option<T> {
+    let value = get_raw(tenant_id, key)?;
+    match value.trim().parse::<T>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            error!("parse system config {} error, value: {}", key, value);
+            None
+        }
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
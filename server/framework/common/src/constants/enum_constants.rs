@@ -45,4 +45,53 @@ pub const STORE_STATUS_REVIEW_ACCEPT: i8 = 1; // 店铺状态-审核通过
 pub const STORE_STATUS_OPENING: i8 = 2; // 店铺状态-营业中
 pub const STORE_STATUS_PAUSE: i8 = 3; // 店铺状态-暂停营业
 pub const STORE_STATUS_REVIEW_REJECTED: i8 = 4; // 店铺状态-审核驳回
-pub const STORE_STATUS_CLOSED: i8 = 5; // 店铺状态-永久关闭
\ No newline at end of file
+pub const STORE_STATUS_CLOSED: i8 = 5; // 店铺状态-永久关闭
+
+pub const JOB_MISFIRE_FIRE_ONCE: i8 = 0; // 定时任务错过执行-立即执行一次
+pub const JOB_MISFIRE_IGNORE: i8 = 1; // 定时任务错过执行-忽略
+pub const JOB_MISFIRE_FIRE_ALL: i8 = 2; // 定时任务错过执行-全部补执行
+
+pub const JOB_TRIGGER_SCHEDULE: i8 = 0; // 定时任务触发方式-定时
+pub const JOB_TRIGGER_MANUAL: i8 = 1; // 定时任务触发方式-手动
+pub const JOB_TRIGGER_MISFIRE: i8 = 2; // 定时任务触发方式-补执行
+pub const JOB_TRIGGER_RETRY: i8 = 3; // 定时任务触发方式-重试
+
+pub const JOB_LOG_STATUS_RUNNING: i8 = 0; // 定时任务执行状态-执行中
+pub const JOB_LOG_STATUS_SUCCESS: i8 = 1; // 定时任务执行状态-成功
+pub const JOB_LOG_STATUS_FAILURE: i8 = 2; // 定时任务执行状态-失败
+
+pub const BACKGROUND_JOB_STATUS_PENDING: i8 = 0; // 后台作业状态-等待执行
+pub const BACKGROUND_JOB_STATUS_RUNNING: i8 = 1; // 后台作业状态-执行中
+pub const BACKGROUND_JOB_STATUS_SUCCESS: i8 = 2; // 后台作业状态-成功
+pub const BACKGROUND_JOB_STATUS_FAILURE: i8 = 3; // 后台作业状态-失败
+pub const BACKGROUND_JOB_STATUS_CANCELLED: i8 = 4; // 后台作业状态-已取消
+
+pub const NOTICE_TYPE_NOTIFICATION: i8 = 1; // 公告类型-通知
+pub const NOTICE_TYPE_ANNOUNCEMENT: i8 = 2; // 公告类型-公告
+
+pub const NOTICE_STATUS_NORMAL: i8 = 0; // 公告状态-正常
+pub const NOTICE_STATUS_CLOSED: i8 = 1; // 公告状态-关闭
+
+pub const SECURITY_EVENT_NEW_IP: &str = "new_ip"; // 登录安全事件-新IP登录
+pub const SECURITY_EVENT_NEW_DEVICE: &str = "new_device"; // 登录安全事件-新设备登录
+pub const SECURITY_EVENT_IMPOSSIBLE_TRAVEL: &str = "impossible_travel"; // 登录安全事件-短时间内异地登录
+pub const SECURITY_EVENT_FAILED_BURST: &str = "failed_burst"; // 登录安全事件-连续登录失败
+pub const SECURITY_EVENT_OFF_HOURS: &str = "off_hours"; // 登录安全事件-非工作时间登录
+
+pub const SECURITY_LEVEL_LOW: i8 = 1; // 登录安全事件风险等级-低
+pub const SECURITY_LEVEL_MEDIUM: i8 = 2; // 登录安全事件风险等级-中
+pub const SECURITY_LEVEL_HIGH: i8 = 3; // 登录安全事件风险等级-高
+
+pub const PERMISSION_AUDIT_SECURITY: &str = "audit:security"; // 安全分析权限,安全告警通知只发送给拥有该权限的用户
+
+/// 登录安全事件类型名称
+pub fn security_event_name(event_type: &str) -> &'static str {
+    match event_type {
+        SECURITY_EVENT_NEW_IP => "新IP登录",
+        SECURITY_EVENT_NEW_DEVICE => "新设备登录",
+        SECURITY_EVENT_IMPOSSIBLE_TRAVEL => "短时间内异地登录",
+        SECURITY_EVENT_FAILED_BURST => "连续登录失败",
+        SECURITY_EVENT_OFF_HOURS => "非工作时间登录",
+        _ => "未知",
+    }
+}
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserTenantContext {
@@ -31,13 +34,33 @@ pub struct LoginUserContext {
     pub data_permission: Option<DataPermission>, // 数据权限
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct RequestContext {
     pub request_url: String, // 请求地址
     pub method: String, // 请求方法
     pub path_params: String, // 请求地址参数
-    pub data: String, // 请求数据
+    pub data: String, // 请求数据,已脱敏和截断
+    pub json: Option<Arc<Value>>, // json请求数据,已脱敏未截断,审计提取业务编号和参数时使用
+    pub body_hash: String, // 原始请求数据的md5,用于幂等校验
     pub ip: String, // ip地址
     pub user_agent: String, // 用户代理
     pub device_type: String, // 设备类型
+    pub trace_id: String, // 链路追踪编号
+}
+
+/// 日志中只输出截断后的请求数据
+impl fmt::Debug for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestContext")
+            .field("request_url", &self.request_url)
+            .field("method", &self.method)
+            .field("path_params", &self.path_params)
+            .field("data", &self.data)
+            .field("body_hash", &self.body_hash)
+            .field("ip", &self.ip)
+            .field("user_agent", &self.user_agent)
+            .field("device_type", &self.device_type)
+            .field("trace_id", &self.trace_id)
+            .finish_non_exhaustive()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::fmt;
+use anyhow::Result;
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, StringLen, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, DbErr, QueryResult, TryGetError, TryGetable, Value};
+use crate::utils::field_crypto;
+
+/// 加密保存的字符串字段,读取时解密,实体中直接替换 String 使用
+/// 写入前通过 EncryptedString::encrypt 加密,加密失败时返回错误,不会以明文写入
+/// 密文字段不能按值查询,需要查询时另外保存 field_crypto::blind_index 并按盲索引查询
+#[derive(Clone)]
+pub struct EncryptedString {
+    plaintext: String, // 明文
+    cipher: String, // 写入数据库的值,读取的历史明文原样保留,由密钥轮换任务重新加密
+}
+
+impl EncryptedString {
+    /// 加密明文
+    pub fn encrypt(plaintext: &str) -> Result<Self> {
+        Ok(EncryptedString {
+            plaintext: plaintext.to_string(),
+            cipher: field_crypto::encrypt(plaintext)?,
+        })
+    }
+
+    /// 数据库中读取的值
+    fn from_stored(cipher: String) -> Result<Self> {
+        Ok(EncryptedString {
+            plaintext: field_crypto::decrypt(&cipher)?,
+            cipher,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.plaintext
+    }
+
+    pub fn into_inner(self) -> String {
+        self.plaintext
+    }
+}
+
+impl From<EncryptedString> for String {
+    fn from(value: EncryptedString) -> Self {
+        value.plaintext
+    }
+}
+
+/// 每次加密的密文不同,按明文比较
+impl PartialEq for EncryptedString {
+    fn eq(&self, other: &Self) -> bool {
+        self.plaintext == other.plaintext
+    }
+}
+
+impl Eq for EncryptedString {}
+
+/// 日志中不输出明文
+impl fmt::Debug for EncryptedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptedString(******)")
+    }
+}
+
+impl From<EncryptedString> for Value {
+    fn from(value: EncryptedString) -> Self {
+        Value::String(Some(Box::new(value.cipher)))
+    }
+}
+
+impl Nullable for EncryptedString {
+    fn null() -> Value {
+        Value::String(None)
+    }
+}
+
+impl ValueType for EncryptedString {
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        match v {
+            Value::String(Some(value)) => EncryptedString::from_stored(*value).map_err(|_| ValueTypeErr),
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        "EncryptedString".to_owned()
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::String
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::String(StringLen::N(512))
+    }
+}
+
+impl TryGetable for EncryptedString {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let value = <String as TryGetable>::try_get_by(res, index)?;
+        EncryptedString::from_stored(value)
+            .map_err(|e| TryGetError::DbErr(DbErr::Type(format!("decrypt sensitive field failed: {}", e))))
+    }
+}
@@ -1,4 +1,5 @@
 pub mod mysql;
-pub mod redis;
+pub mod redis_pool;
 pub mod redis_constants;
-pub mod mongo;
\ No newline at end of file
+pub mod mongo;
+pub mod encrypted_string;
\ No newline at end of file
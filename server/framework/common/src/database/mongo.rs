@@ -2,18 +2,53 @@ use mongodb::{
     bson::{doc, Document},
     options::ClientOptions,
     Client, Collection,
-    error::Error as MongoError,
+    error::{Error as MongoError, ErrorKind},
 };
 use once_cell::sync::OnceCell;
 use tracing::info;
 use std::env;
-use mongodb::bson::{from_document, to_document};
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use mongodb::bson::{from_document, to_document, Bson, DateTime};
 use mongodb::bson::oid::ObjectId;
-use mongodb::options::FindOptions;
-use serde::Deserialize;
-use crate::base::logger::{LoginLogger, OperationLogger};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::IndexModel;
+use serde::{Deserialize, Serialize};
+use crate::base::logger::{LoginLogger, LoginProfile, OperationLogger, SecurityEvent};
 use crate::base::page::PaginatedResponse;
 use crate::config::config::Config;
+use crate::config::system_config::{self, CONFIG_KEY_LOGGER_RETENTION_DAYS};
+
+/// 日志过期时间,过期且已归档的日志由TTL索引删除
+pub const FIELD_EXPIRE_AT: &str = "expire_at";
+/// 是否已归档
+pub const FIELD_ARCHIVED: &str = "archived";
+/// IPv4地址对应的整数,用于按网段查询
+pub const FIELD_IP_NUMBER: &str = "ip_number";
+/// 日志在redis stream中的id,重新投递的日志按该字段去重
+pub const FIELD_STREAM_ID: &str = "stream_id";
+/// 重复键错误码
+const DUPLICATE_KEY_CODE: i32 = 11000;
+/// 日志最长保留天数,租户参数超过时按该值处理
+const MAX_RETENTION_DAYS: i64 = 3650;
+
+/// 日志集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogCollection {
+    Login, // 登录日志
+    Operation, // 操作日志
+}
+
+impl LogCollection {
+    pub const ALL: [LogCollection; 2] = [LogCollection::Login, LogCollection::Operation];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogCollection::Login => "login",
+            LogCollection::Operation => "operation",
+        }
+    }
+}
 
 static MONGO_MANAGER: OnceCell<MongoManager> = OnceCell::new();
 
@@ -22,13 +57,15 @@ pub struct MongoManager {
     db_name: String,
     login_collection: Collection<Document>,
     operation_collection: Collection<Document>,
+    security_event_collection: Collection<Document>,
+    login_profile_collection: Collection<Document>,
 }
 
 impl MongoManager {
     /// 初始化 MongoDB 客户端并创建单例实例
     pub async fn init() -> Result<(), MongoError> {
         let config = Config::load();
-        let mongo_uri = config.mongo_url;
+        let mongo_uri = config.mongo.url;
 
         let client_options = ClientOptions::parse(&mongo_uri).await?;
         let client = Client::with_options(client_options)?;
@@ -38,13 +75,18 @@ impl MongoManager {
 
         let login_collection = database.collection::<Document>("login_logger");
         let operation_collection = database.collection::<Document>("operation_logger");
+        let security_event_collection = database.collection::<Document>("security_event");
+        let login_profile_collection = database.collection::<Document>("login_profile");
 
         let manager = Self {
             client,
             db_name,
             login_collection,
             operation_collection,
+            security_event_collection,
+            login_profile_collection,
         };
+        manager.ensure_indexes().await?;
 
         MONGO_MANAGER.set(manager).map_err(|_| {
             MongoError::from(std::io::Error::new(
@@ -56,41 +98,133 @@ impl MongoManager {
         Ok(())
     }
 
+    /// 创建索引,索引已存在时忽略
+    /// 全文索引不区分语言,按空格和标点分词
+    async fn ensure_indexes(&self) -> Result<(), MongoError> {
+        let text_fields = [
+            (&self.login_collection, doc! {"username": "text", "user_ip": "text", "user_agent": "text", "operator_nickname": "text"}),
+            (&self.operation_collection, doc! {"action": "text", "request_url": "text", "operator_nickname": "text", "type": "text", "sub_type": "text"}),
+        ];
+        for (collection, text_keys) in text_fields {
+            let indexes = vec![
+                IndexModel::builder()
+                    .keys(text_keys)
+                    .options(IndexOptions::builder()
+                        .name("text_search".to_string())
+                        .default_language("none".to_string())
+                        .build())
+                    .build(),
+                IndexModel::builder()
+                    .keys(doc! {"tenant_id": 1, "operate_time": -1})
+                    .options(IndexOptions::builder().name("tenant_operate_time".to_string()).build())
+                    .build(),
+                IndexModel::builder()
+                    .keys(doc! {"tenant_id": 1, FIELD_IP_NUMBER: 1})
+                    .options(IndexOptions::builder().name("tenant_ip_number".to_string()).build())
+                    .build(),
+                // 历史日志没有stream id,只对有该字段的日志去重
+                IndexModel::builder()
+                    .keys(doc! {FIELD_STREAM_ID: 1})
+                    .options(IndexOptions::builder()
+                        .name("stream_id".to_string())
+                        .unique(true)
+                        .partial_filter_expression(doc! {FIELD_STREAM_ID: {"$exists": true}})
+                        .build())
+                    .build(),
+                // 只删除已归档的日志,归档失败时日志不会丢失
+                IndexModel::builder()
+                    .keys(doc! {FIELD_EXPIRE_AT: 1})
+                    .options(IndexOptions::builder()
+                        .name("expire_at_ttl".to_string())
+                        .expire_after(Duration::from_secs(0))
+                        .partial_filter_expression(doc! {FIELD_ARCHIVED: true})
+                        .build())
+                    .build(),
+            ];
+            collection.create_indexes(indexes).await?;
+        }
+
+        // 安全事件与日志保留相同的天数,不归档
+        let security_indexes = vec![
+            IndexModel::builder()
+                .keys(doc! {"login_log_id": 1, "event_type": 1})
+                .options(IndexOptions::builder().name("login_log_event_type".to_string()).unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! {"tenant_id": 1, "operate_time": -1})
+                .options(IndexOptions::builder().name("tenant_operate_time".to_string()).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! {FIELD_EXPIRE_AT: 1})
+                .options(IndexOptions::builder()
+                    .name("expire_at_ttl".to_string())
+                    .expire_after(Duration::from_secs(0))
+                    .build())
+                .build(),
+        ];
+        self.security_event_collection.create_indexes(security_indexes).await?;
+        Ok(())
+    }
+
+    fn collection(&self, collection: LogCollection) -> &Collection<Document> {
+        match collection {
+            LogCollection::Login => &self.login_collection,
+            LogCollection::Operation => &self.operation_collection,
+        }
+    }
+
     /// 获取单例实例
     pub fn get() -> &'static Self {
         MONGO_MANAGER.get().expect("MongoManager is not initialized. Call MongoManager::init() first.")
     }
 
+    /// 检查连接是否可用
+    pub async fn ping(&self) -> Result<(), MongoError> {
+        self.client.database(&self.db_name).run_command(doc! { "ping": 1 }).await?;
+        Ok(())
+    }
+
+    /// 是否已初始化
+    pub fn is_initialized() -> bool {
+        MONGO_MANAGER.get().is_some()
+    }
+
+    /// 关闭 MongoDB 客户端,未初始化时忽略
+    pub async fn close() {
+        if let Some(manager) = MONGO_MANAGER.get() {
+            manager.client.clone().shutdown().await;
+            info!("mongo client closed");
+        }
+    }
+
     /// 插入登录日志
     pub async fn insert_login_log(&self, log: LoginLogger) -> Result<String, MongoError> {
-        let doc = to_document(&log)?;
+        let doc = log_document(&log, log.tenant_id, log.operate_time, &log.user_ip)?;
         let result = self.login_collection.insert_one(doc).await?;
         Ok(result.inserted_id.to_string())
     }
 
     /// 插入操作日志
     pub async fn insert_operation_log(&self, log: OperationLogger) -> Result<String, MongoError> {
-        let doc = to_document(&log)?;
+        let doc = log_document(&log, log.tenant_id, log.operate_time, &log.user_ip)?;
         let result = self.operation_collection.insert_one(doc).await?;
         Ok(result.inserted_id.to_string())
     }
 
-    /// 批量插入登录日志
-    pub async fn insert_login_logs(&self, logs: Vec<LoginLogger>) -> Result<Vec<String>, MongoError> {
+    /// 批量插入从stream读取的登录日志(stream中的id, 日志),已写入的日志忽略
+    pub async fn insert_login_logs(&self, logs: Vec<(String, LoginLogger)>) -> Result<(), MongoError> {
         let docs: Vec<Document> = logs.into_iter()
-            .map(|log| to_document(&log))
+            .map(|(stream_id, log)| stream_log_document(stream_id, &log, log.tenant_id, log.operate_time, &log.user_ip))
             .collect::<Result<Vec<_>, _>>()?;
-        let result = self.login_collection.insert_many(docs).await?;
-        Ok(result.inserted_ids.into_values().map(|id| id.to_string()).collect())
+        insert_ignore_duplicates(&self.login_collection, docs).await
     }
 
-    /// 批量插入操作日志
-    pub async fn insert_operation_logs(&self, logs: Vec<OperationLogger>) -> Result<Vec<String>, MongoError> {
+    /// 批量插入从stream读取的操作日志(stream中的id, 日志),已写入的日志忽略
+    pub async fn insert_operation_logs(&self, logs: Vec<(String, OperationLogger)>) -> Result<(), MongoError> {
         let docs: Vec<Document> = logs.into_iter()
-            .map(|log| to_document(&log))
+            .map(|(stream_id, log)| stream_log_document(stream_id, &log, log.tenant_id, log.operate_time, &log.user_ip))
             .collect::<Result<Vec<_>, _>>()?;
-        let result = self.operation_collection.insert_many(docs).await?;
-        Ok(result.inserted_ids.into_values().map(|id| id.to_string()).collect())
+        insert_ignore_duplicates(&self.operation_collection, docs).await
     }
 
     /// 查询登录日志
@@ -168,6 +302,149 @@ impl MongoManager {
         })
     }
 
+    /// 按条件分页查询登录日志
+    pub async fn search_login_logs(&self, filter: Document, page: u64, page_size: u64) -> Result<PaginatedResponse<LoginLogger>, MongoError> {
+        let page_result = self.find_paginated::<Document>(&self.login_collection, Some(filter), page, page_size).await?;
+        Ok(PaginatedResponse {
+            list: page_result.list.into_iter().map(from_document).collect::<Result<Vec<_>, _>>()?,
+            total_pages: page_result.total_pages,
+            page,
+            size: page_size,
+            total: page_result.total,
+        })
+    }
+
+    /// 按条件分页查询操作日志
+    pub async fn search_operation_logs(&self, filter: Document, page: u64, page_size: u64) -> Result<PaginatedResponse<OperationLogger>, MongoError> {
+        let page_result = self.find_paginated::<Document>(&self.operation_collection, Some(filter), page, page_size).await?;
+        Ok(PaginatedResponse {
+            list: page_result.list.into_iter().map(from_document).collect::<Result<Vec<_>, _>>()?,
+            total_pages: page_result.total_pages,
+            page,
+            size: page_size,
+            total: page_result.total,
+        })
+    }
+
+    /// 统计日志数
+    pub async fn count_logs(&self, collection: LogCollection, filter: Document) -> Result<u64, MongoError> {
+        self.collection(collection).count_documents(filter).await
+    }
+
+    /// 按_id顺序分批读取日志原始文档,after为上一批最后一条的_id
+    pub async fn find_logs_after(&self, collection: LogCollection, mut filter: Document, after: Option<ObjectId>, limit: i64) -> Result<Vec<Document>, MongoError> {
+        if let Some(after) = after {
+            filter.insert("_id", doc! {"$gt": after});
+        }
+        let options = FindOptions::builder()
+            .sort(doc! {"_id": 1})
+            .limit(Some(limit))
+            .build();
+        let mut cursor = self.collection(collection).find(filter).with_options(options).await?;
+        let mut results = Vec::new();
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+        Ok(results)
+    }
+
+    /// 查询已过期未归档的日志
+    pub async fn find_expired_logs(&self, collection: LogCollection, limit: i64) -> Result<Vec<Document>, MongoError> {
+        let filter = doc! {FIELD_EXPIRE_AT: {"$lte": DateTime::now()}, FIELD_ARCHIVED: {"$ne": true}};
+        self.find_logs_after(collection, filter, None, limit).await
+    }
+
+    /// 标记为已归档,之后由TTL索引删除
+    pub async fn mark_archived(&self, collection: LogCollection, ids: Vec<ObjectId>) -> Result<u64, MongoError> {
+        let filter = doc! {"_id": {"$in": ids}};
+        let result = self.collection(collection).update_many(filter, doc! {"$set": {FIELD_ARCHIVED: true}}).await?;
+        Ok(result.modified_count)
+    }
+
+    /// 为没有过期时间的历史日志按全局保留天数设置过期时间
+    pub async fn backfill_expire_at(&self, collection: LogCollection, retention_days: i64) -> Result<u64, MongoError> {
+        let retention_ms = retention_days.clamp(1, MAX_RETENTION_DAYS) * 24 * 3600 * 1000;
+        let filter = doc! {FIELD_EXPIRE_AT: {"$exists": false}, "operate_time": {"$exists": true}};
+        let update = vec![doc! {"$set": {
+            FIELD_EXPIRE_AT: {"$toDate": {"$add": [{"$multiply": ["$operate_time", 1000_i64]}, retention_ms]}}
+        }}];
+        let result = self.collection(collection).update_many(filter, update).await?;
+        Ok(result.modified_count)
+    }
+
+    /// 保存安全事件,同一条登录日志的同类事件已存在时忽略,返回是否新增
+    pub async fn save_security_event(&self, event: &SecurityEvent) -> Result<bool, MongoError> {
+        let doc = log_document(event, Some(event.tenant_id), Some(event.operate_time), &event.user_ip)?;
+        let filter = doc! {"login_log_id": &event.login_log_id, "event_type": &event.event_type};
+        let result = self.security_event_collection
+            .update_one(filter, doc! {"$setOnInsert": doc})
+            .upsert(true)
+            .await?;
+        Ok(result.upserted_id.is_some())
+    }
+
+    /// 是否存在符合条件的安全事件
+    pub async fn exists_security_event(&self, filter: Document) -> Result<bool, MongoError> {
+        Ok(self.security_event_collection.find_one(filter).await?.is_some())
+    }
+
+    /// 按条件分页查询安全事件,按登录时间倒序
+    pub async fn search_security_events(&self, filter: Document, page: u64, page_size: u64) -> Result<PaginatedResponse<SecurityEvent>, MongoError> {
+        let page_result = self.find_paginated::<Document>(&self.security_event_collection, Some(filter), page, page_size).await?;
+        Ok(PaginatedResponse {
+            list: page_result.list.into_iter().map(from_document).collect::<Result<Vec<_>, _>>()?,
+            total_pages: page_result.total_pages,
+            page,
+            size: page_size,
+            total: page_result.total,
+        })
+    }
+
+    /// 按分组表达式统计安全事件数,返回 (分组值, 数量),按分组值排序
+    pub async fn count_security_events_by(&self, filter: Document, group: Bson) -> Result<Vec<(String, u64)>, MongoError> {
+        let pipeline = vec![
+            doc! {"$match": filter},
+            doc! {"$group": {"_id": group, "count": {"$sum": 1}}},
+            doc! {"$sort": {"_id": 1}},
+        ];
+        let mut cursor = self.security_event_collection.aggregate(pipeline).await?;
+        let mut results = Vec::new();
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current()?;
+            let key = match doc.get("_id") {
+                Some(Bson::String(key)) => key.clone(),
+                Some(key) => key.to_string(),
+                None => String::new(),
+            };
+            let count = match doc.get("count") {
+                Some(Bson::Int32(count)) => *count as u64,
+                Some(Bson::Int64(count)) => *count as u64,
+                _ => 0,
+            };
+            results.push((key, count));
+        }
+        Ok(results)
+    }
+
+    /// 查询用户的登录画像
+    pub async fn get_login_profile(&self, tenant_id: i64, user_id: i64) -> Result<Option<LoginProfile>, MongoError> {
+        let filter = doc! {"_id": LoginProfile::profile_id(tenant_id, user_id)};
+        match self.login_profile_collection.find_one(filter).await? {
+            Some(doc) => Ok(Some(from_document(doc)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 保存用户的登录画像
+    pub async fn save_login_profile(&self, profile: &LoginProfile) -> Result<(), MongoError> {
+        let doc = to_document(profile)?;
+        self.login_profile_collection
+            .replace_one(doc! {"_id": &profile.id}, doc)
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
     /// 分页查询日志
     async fn find_paginated<T>(
         &self,
@@ -274,6 +551,51 @@ impl MongoManager {
     }
 }
 
+/// 日志文档,附加过期时间和IP整数
+fn log_document<T: Serialize>(log: &T, tenant_id: Option<i64>, operate_time: Option<i64>, user_ip: &str) -> Result<Document, MongoError> {
+    let mut doc = to_document(log)?;
+    let retention_days = Config::load().logger_server.retention_days;
+    let retention_days = match tenant_id {
+        Some(tenant_id) => system_config::get_or(tenant_id, CONFIG_KEY_LOGGER_RETENTION_DAYS, retention_days),
+        None => retention_days,
+    };
+    // 系统参数可能被配置为负数或极大值,限制范围防止溢出
+    let retention_days = retention_days.clamp(1, MAX_RETENTION_DAYS);
+    let operate_time = operate_time.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let expire_at = operate_time.saturating_add(retention_days * 24 * 3600).saturating_mul(1000);
+    doc.insert(FIELD_EXPIRE_AT, DateTime::from_millis(expire_at));
+    if let Some(number) = ipv4_number(user_ip) {
+        doc.insert(FIELD_IP_NUMBER, Bson::Int64(number));
+    }
+    Ok(doc)
+}
+
+fn stream_log_document<T: Serialize>(stream_id: String, log: &T, tenant_id: Option<i64>, operate_time: Option<i64>, user_ip: &str) -> Result<Document, MongoError> {
+    let mut doc = log_document(log, tenant_id, operate_time, user_ip)?;
+    doc.insert(FIELD_STREAM_ID, stream_id);
+    Ok(doc)
+}
+
+/// 无序批量插入,上次部分写入的日志重复时忽略,其他日志继续写入
+async fn insert_ignore_duplicates(collection: &Collection<Document>, docs: Vec<Document>) -> Result<(), MongoError> {
+    if docs.is_empty() {
+        return Ok(());
+    }
+    match collection.insert_many(docs).ordered(false).await {
+        Ok(_) => Ok(()),
+        Err(e) => match e.kind.as_ref() {
+            ErrorKind::InsertMany(failure) if failure.write_concern_error.is_none()
+                && failure.write_errors.as_ref().map_or(false, |errors| errors.iter().all(|error| error.code == DUPLICATE_KEY_CODE)) => Ok(()),
+            _ => Err(e),
+        },
+    }
+}
+
+/// IPv4地址转为整数,其他地址返回None
+pub fn ipv4_number(ip: &str) -> Option<i64> {
+    ip.trim().parse::<Ipv4Addr>().ok().map(|ip| u32::from(ip) as i64)
+}
+
 // #[tokio::main]
 // async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //     MongoManager::init().await?;
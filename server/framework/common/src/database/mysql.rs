@@ -20,4 +20,13 @@ pub async fn get_database_instance(database_url: String) -> &'static DatabaseCon
             conn
         })
         .await
+}
+
+/// 关闭数据库连接池,未初始化时忽略
+pub async fn close_database_instance() {
+    if let Some(conn) = DATABASE_INSTANCE.get() {
+        if let Err(e) = conn.clone().close().await {
+            tracing::error!("close database error: {}", e);
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,22 @@
 pub const REDIS_KEY_TENANTS_LIST: &'static str = "synerunify:system:tenants:list"; // 租户列表
 pub const REDIS_KEY_LOGIN_USER_PREFIX: &'static str = "synerunify:system:user:login:"; // 登录的用户信息
-pub const REDIS_KEY_LOGGER_LOGIN_PREFIX: &'static str = "synerunify:system:logger:login"; // 登录日志
-pub const REDIS_KEY_LOGGER_OPERATION_PREFIX: &'static str = "synerunify:system:logger:operation"; // 操作日志
\ No newline at end of file
+pub const REDIS_KEY_LOGGER_LOGIN_PREFIX: &'static str = "synerunify:system:logger:login"; // 登录日志,旧版本的list队列,启动时迁移到stream
+pub const REDIS_KEY_LOGGER_OPERATION_PREFIX: &'static str = "synerunify:system:logger:operation"; // 操作日志,旧版本的list队列,启动时迁移到stream
+pub const REDIS_KEY_LOGGER_LOGIN_STREAM: &'static str = "synerunify:system:logger:stream:login"; // 登录日志
+pub const REDIS_KEY_LOGGER_OPERATION_STREAM: &'static str = "synerunify:system:logger:stream:operation"; // 操作日志
+pub const REDIS_KEY_LOGGER_DEAD_LETTER_PREFIX: &'static str = "synerunify:system:logger:dead:"; // 无法写入的日志
+pub const REDIS_KEY_LOGGER_SECURITY_CURSOR: &'static str = "synerunify:system:logger:security:cursor"; // 登录日志安全分析的进度,已分析的最后一条日志的_id
+pub const REDIS_KEY_IDEMPOTENCY_PREFIX: &'static str = "synerunify:common:idempotency:"; // 幂等请求
+pub const REDIS_KEY_RATE_LIMIT_PREFIX: &'static str = "synerunify:common:rate_limit:"; // 接口限流
+pub const REDIS_CHANNEL_SYSTEM_CONFIG: &'static str = "synerunify:system:config:changed"; // 系统参数变更通知
+pub const REDIS_KEY_LOCK_PREFIX: &'static str = "synerunify:common:lock:"; // 分布式锁
+pub const REDIS_KEY_CACHE_PREFIX: &'static str = "synerunify:common:cache:"; // 缓存
+pub const REDIS_KEY_CACHE_TAG_PREFIX: &'static str = "synerunify:common:cache_tag:"; // 缓存标签
+pub const REDIS_KEY_DOCUMENT_NUMBER_PREFIX: &'static str = "synerunify:common:document_number:"; // 单据编号计数器
+pub const REDIS_KEY_EVENT_STREAM_PREFIX: &'static str = "synerunify:common:event:stream:"; // 领域事件
+pub const REDIS_KEY_EVENT_DEAD_LETTER_PREFIX: &'static str = "synerunify:common:event:dead:"; // 处理失败的领域事件
+pub const REDIS_KEY_EVENT_HANDLED_PREFIX: &'static str = "synerunify:common:event:handled:"; // 已处理的领域事件,订阅方按事件id去重
+pub const REDIS_KEY_JOB_FIRE_PREFIX: &'static str = "synerunify:system:job:fire:"; // 定时任务每次触发只由一个副本执行
+pub const REDIS_CHANNEL_JOB_TRIGGER: &'static str = "synerunify:system:job:trigger"; // 手动触发定时任务
+pub const REDIS_CHANNEL_USER_CHANGED: &'static str = "synerunify:system:user:changed"; // 用户变更通知
+pub const REDIS_CHANNEL_ROLE_CHANGED: &'static str = "synerunify:system:role:changed"; // 角色变更通知
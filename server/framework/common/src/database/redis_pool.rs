@@ -0,0 +1,652 @@
+use crate::config::config::{Config, RedisConfig};
+use futures_util::StreamExt;
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::{SentinelClient, SentinelNodeConnectionInfo, SentinelServerType};
+use redis::streams::{StreamClaimReply, StreamMaxlen, StreamPendingCountReply, StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, Client, Cmd, ErrorKind, FromRedisValue, IntoConnectionInfo, Pipeline, RedisError, RedisFuture, RedisResult, Script, ToRedisArgs, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::{info, warn};
+
+/// redis部署模式
+pub const REDIS_MODE_STANDALONE: &str = "standalone";
+pub const REDIS_MODE_SENTINEL: &str = "sentinel";
+pub const REDIS_MODE_CLUSTER: &str = "cluster";
+
+/// 释放锁,只删除自己持有的锁
+const UNLOCK_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+// 全局连接池,首次使用时初始化
+static REDIS_POOL: OnceCell<RedisPool> = OnceCell::const_new();
+
+/// 单机使用自动重连的多路复用连接,哨兵模式出错时切换到新的主节点,集群使用集群连接
+#[derive(Clone)]
+pub enum RedisConnection {
+    Single(ConnectionManager),
+    Sentinel(SentinelConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Sentinel(conn) => conn.req_packed_command(cmd),
+            RedisConnection::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(&'a mut self, cmd: &'a Pipeline, offset: usize, count: usize) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Sentinel(conn) => conn.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(conn) => conn.get_db(),
+            RedisConnection::Sentinel(conn) => conn.get_db(),
+            RedisConnection::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// 哨兵模式的主节点查询,主从切换后通过哨兵获取新的主节点
+struct SentinelMaster {
+    sentinel: Mutex<SentinelClient>,
+    timeout: Duration,
+}
+
+impl SentinelMaster {
+    async fn resolve(&self) -> RedisResult<Client> {
+        self.sentinel.lock().await.async_get_client().await
+    }
+}
+
+/// 哨兵模式的连接,连接断开或主节点降为从节点时重新查询主节点并替换连接
+/// ConnectionManager只会重连原来的地址,主从切换后需要重新查询
+#[derive(Clone)]
+pub struct SentinelConnection {
+    conn: Arc<RwLock<ConnectionManager>>,
+    master: Arc<SentinelMaster>,
+}
+
+impl SentinelConnection {
+    fn current(&self) -> ConnectionManager {
+        self.conn.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    // 本次请求仍返回错误,后续请求使用新的主节点
+    async fn handle_error(&self, e: &RedisError) {
+        if !is_failover_error(e) {
+            return;
+        }
+        let conn = match self.master.resolve().await {
+            Ok(client) => connect_single(&client, self.master.timeout).await,
+            Err(e) => Err(e),
+        };
+        match conn {
+            Ok(conn) => {
+                info!("redis sentinel master re-resolved after error: {}", e);
+                *self.conn.write().unwrap_or_else(PoisonError::into_inner) = conn;
+            }
+            Err(resolve_error) => warn!("redis sentinel master resolve failed: {}, cause: {}", resolve_error, e),
+        }
+    }
+}
+
+impl ConnectionLike for SentinelConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let result = self.current().req_packed_command(cmd).await;
+            if let Err(e) = &result {
+                self.handle_error(e).await;
+            }
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(&'a mut self, cmd: &'a Pipeline, offset: usize, count: usize) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let result = self.current().req_packed_commands(cmd, offset, count).await;
+            if let Err(e) = &result {
+                self.handle_error(e).await;
+            }
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.current().get_db()
+    }
+}
+
+/// 连接不可用,或者原主节点已降为从节点拒绝写入
+fn is_failover_error(e: &RedisError) -> bool {
+    e.is_io_error() || e.is_connection_dropped() || e.is_connection_refusal() || e.kind() == ErrorKind::ReadOnly
+}
+
+/// 异步连接池,连接都是多路复用的,轮询分配减少单连接排队
+pub struct RedisPool {
+    connections: Vec<RedisConnection>,
+    next: AtomicUsize,
+    pubsub_client: Client, // 订阅使用独立连接
+    sentinel: Option<Arc<SentinelMaster>>,
+}
+
+impl RedisPool {
+    pub async fn connect(config: &RedisConfig) -> RedisResult<Self> {
+        let timeout = Duration::from_secs(config.connection_timeout);
+        let pool_size = config.pool_size.max(1);
+        let mut connections = Vec::with_capacity(pool_size);
+        let mut sentinel_master = None;
+        let pubsub_client = match config.mode.as_str() {
+            REDIS_MODE_CLUSTER => {
+                let client = ClusterClient::builder(config.nodes.clone())
+                    .connection_timeout(timeout)
+                    .build()?;
+                for _ in 0..pool_size {
+                    connections.push(RedisConnection::Cluster(client.get_async_connection().await?));
+                }
+                // 集群的PUBLISH会广播到所有节点,订阅任意节点即可
+                Client::open(config.nodes[0].as_str())?
+            }
+            REDIS_MODE_SENTINEL => {
+                // 哨兵节点的认证信息和db使用redis.url中的配置
+                let node_info = config.url.as_str().into_connection_info()?;
+                let sentinel = SentinelClient::build(
+                    config.nodes.clone(),
+                    config.master_name.clone(),
+                    Some(SentinelNodeConnectionInfo {
+                        redis_connection_info: Some(node_info.redis.clone()),
+                        ..Default::default()
+                    }),
+                    SentinelServerType::Master,
+                )?;
+                let master = Arc::new(SentinelMaster { sentinel: Mutex::new(sentinel), timeout });
+                let client = master.resolve().await?;
+                for _ in 0..pool_size {
+                    connections.push(RedisConnection::Sentinel(SentinelConnection {
+                        conn: Arc::new(RwLock::new(connect_single(&client, timeout).await?)),
+                        master: master.clone(),
+                    }));
+                }
+                sentinel_master = Some(master);
+                client
+            }
+            _ => {
+                let client = Client::open(config.url.as_str())?;
+                for _ in 0..pool_size {
+                    connections.push(RedisConnection::Single(connect_single(&client, timeout).await?));
+                }
+                client
+            }
+        };
+        info!("redis pool connected, mode: {}, size: {}", config.mode, pool_size);
+        Ok(RedisPool { connections, next: AtomicUsize::new(0), pubsub_client, sentinel: sentinel_master })
+    }
+
+    /// 获取一个连接,连接可以clone后在多个任务中并发使用
+    pub fn get(&self) -> RedisConnection {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        self.connections[index].clone()
+    }
+}
+
+async fn connect_single(client: &Client, timeout: Duration) -> RedisResult<ConnectionManager> {
+    match tokio::time::timeout(timeout, ConnectionManager::new(client.clone())).await {
+        Ok(result) => result,
+        Err(_) => Err(RedisError::from(std::io::Error::new(std::io::ErrorKind::TimedOut, "redis connect timeout"))),
+    }
+}
+
+/// 异步redis操作,在async代码中使用,避免阻塞tokio工作线程
+pub struct AsyncRedisManager;
+
+impl AsyncRedisManager {
+    // 获取单例的连接池
+    pub async fn pool() -> RedisResult<&'static RedisPool> {
+        REDIS_POOL.get_or_try_init(|| async {
+            let config = Config::load();
+            RedisPool::connect(&config.redis).await
+        }).await
+    }
+
+    pub async fn connection() -> RedisResult<RedisConnection> {
+        Ok(Self::pool().await?.get())
+    }
+
+    // 检查连接是否可用
+    pub async fn ping() -> RedisResult<()> {
+        let mut conn = Self::connection().await?;
+        redis::cmd("PING").query_async::<String>(&mut conn).await?;
+        Ok(())
+    }
+
+    // String 操作（使用泛型）
+    pub async fn set<K, V>(key: K, value: V) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.set::<_, _, ()>(key, value).await
+    }
+
+    pub async fn get<K, V>(key: K) -> RedisResult<Option<V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.get(key).await
+    }
+
+    // 设置值并指定过期时间(秒)
+    pub async fn set_ex<K, V>(key: K, value: V, seconds: u64) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.set_ex::<_, _, ()>(key, value, seconds).await
+    }
+
+    // key不存在时设置值并指定过期时间(秒),设置成功返回true
+    pub async fn set_nx_ex<K, V>(key: K, value: V, seconds: u64) -> RedisResult<bool>
+    where
+        K: ToRedisArgs,
+        V: ToRedisArgs,
+    {
+        let mut conn = Self::connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(seconds)
+            .query_async(&mut conn)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    pub async fn delete<K>(key: K) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.del::<_, ()>(key).await
+    }
+
+    pub async fn exists<K>(key: K) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.exists(key).await
+    }
+
+    pub async fn incr<K>(key: K, delta: i64) -> RedisResult<i64>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.incr(key, delta).await
+    }
+
+    // JSON 操作,值序列化为json字符串保存
+    pub async fn set_json<K, T>(key: K, value: &T, seconds: Option<u64>) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        T: Serialize,
+    {
+        let json = serde_json::to_string(value).map_err(json_error)?;
+        match seconds {
+            Some(seconds) => Self::set_ex(key, json, seconds).await,
+            None => Self::set(key, json).await,
+        }
+    }
+
+    pub async fn get_json<K, T>(key: K) -> RedisResult<Option<T>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        T: DeserializeOwned,
+    {
+        match Self::get::<_, String>(key).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json).map_err(json_error)?)),
+            None => Ok(None),
+        }
+    }
+
+    // 执行管道,atomic()后作为事务执行
+    pub async fn query_pipeline<T>(pipeline: &Pipeline) -> RedisResult<T>
+    where
+        T: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        pipeline.query_async(&mut conn).await
+    }
+
+    // 执行lua脚本
+    pub async fn invoke_script<T>(script: &Script, keys: &[String], args: &[String]) -> RedisResult<T>
+    where
+        T: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(key);
+        }
+        for arg in args {
+            invocation.arg(arg);
+        }
+        invocation.invoke_async(&mut conn).await
+    }
+
+    // Hash 操作（使用泛型）
+    pub async fn set_hash_field<K, F, V>(key: K, field: F, value: V) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.hset::<_, _, _, ()>(key, field, value).await
+    }
+
+    pub async fn get_hash_field<K, F, V>(key: K, field: F) -> RedisResult<Option<V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.hget(key, field).await
+    }
+
+    pub async fn get_hash_all<K, V>(key: K) -> RedisResult<HashMap<String, V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.hgetall(key).await
+    }
+
+    // List 操作（使用泛型）
+    pub async fn push_list<K, V>(key: K, value: V) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.rpush::<_, _, ()>(key, value).await
+    }
+
+    pub async fn get_list_range<K, V>(key: K, start: isize, stop: isize) -> RedisResult<Vec<V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.lrange(key, start, stop).await
+    }
+
+    pub async fn list_len<K>(key: K) -> RedisResult<usize>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.llen(key).await
+    }
+
+    pub async fn pop_list<K, V>(key: K) -> RedisResult<Option<V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.lpop(key, None).await
+    }
+
+    // 只保留[start, stop]范围内的元素
+    pub async fn list_trim<K>(key: K, start: isize, stop: isize) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.ltrim::<_, ()>(key, start, stop).await
+    }
+
+    // 取出全部元素,LRANGE和LTRIM在事务中执行,避免并发写入时丢失数据
+    pub async fn lpop_all<K, V>(key: K) -> RedisResult<Vec<V>>
+    where
+        K: ToRedisArgs,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        let (values, ): (Vec<V>, ) = redis::pipe()
+            .atomic()
+            .lrange(&key, 0, -1)
+            .ltrim(&key, 1, 0).ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(values)
+    }
+
+    // Set 操作（使用泛型）
+    pub async fn add_to_set<K, V>(key: K, value: V) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.sadd::<_, _, ()>(key, value).await
+    }
+
+    pub async fn get_set_members<K, V>(key: K) -> RedisResult<Vec<V>>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: FromRedisValue,
+    {
+        let mut conn = Self::connection().await?;
+        conn.smembers(key).await
+    }
+
+    pub async fn remove_from_set<K, V>(key: K, value: V) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.srem::<_, _, ()>(key, value).await
+    }
+
+    pub async fn is_set_member<K, V>(key: K, value: V) -> RedisResult<bool>
+    where
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.sismember(key, value).await
+    }
+
+    // 设置过期时间
+    pub async fn set_expiry<K>(key: K, seconds: usize) -> RedisResult<()>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.expire::<_, ()>(key, seconds as i64).await
+    }
+
+    // 加锁,成功返回锁的token,释放锁时需要传入
+    pub async fn try_lock(key: &str, ttl: Duration) -> RedisResult<Option<String>> {
+        let token = format!("{:032x}", rand::random::<u128>());
+        let mut conn = Self::connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut conn)
+            .await?;
+        Ok(result.map(|_| token))
+    }
+
+    // 释放锁,锁已过期或被其他持有者获取时返回false
+    pub async fn unlock(key: &str, token: &str) -> RedisResult<bool> {
+        let script = Script::new(UNLOCK_SCRIPT);
+        let deleted: i64 = Self::invoke_script(&script, &[key.to_string()], &[token.to_string()]).await?;
+        Ok(deleted == 1)
+    }
+
+    // 发布消息
+    pub async fn publish<C, M>(channel: C, message: M) -> RedisResult<()>
+    where
+        C: ToRedisArgs + Send + Sync,
+        M: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.publish::<_, _, ()>(channel, message).await
+    }
+
+    // 订阅频道,接收消息直到连接断开
+    pub async fn subscribe<F>(channel: &str, mut on_message: F) -> RedisResult<()>
+    where
+        F: FnMut(String),
+    {
+        let pool = Self::pool().await?;
+        // 哨兵模式每次订阅重新查询主节点,断开后重新订阅时可以连到切换后的主节点
+        let client = match &pool.sentinel {
+            Some(master) => master.resolve().await?,
+            None => pool.pubsub_client.clone(),
+        };
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.subscribe(channel).await?;
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            let payload: String = message.get_payload()?;
+            on_message(payload);
+        }
+        Ok(())
+    }
+
+    // 追加消息到stream,超过max_len时近似裁剪,返回消息id
+    pub async fn stream_add<K, F, V>(key: K, max_len: usize, fields: &[(F, V)]) -> RedisResult<String>
+    where
+        K: ToRedisArgs + Send + Sync,
+        F: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.xadd_maxlen(key, StreamMaxlen::Approx(max_len), "*", fields).await
+    }
+
+    // 创建消费组,stream不存在时自动创建,消费组已存在时忽略
+    pub async fn stream_create_group(key: &str, group: &str) -> RedisResult<()> {
+        let mut conn = Self::connection().await?;
+        match conn.xgroup_create_mkstream::<_, _, _, ()>(key, group, "0").await {
+            Err(e) if e.code() == Some("BUSYGROUP") => Ok(()),
+            result => result,
+        }
+    }
+
+    // 以消费组方式读取未投递的消息,不阻塞,连接是多路复用的,阻塞读取会影响其他请求
+    pub async fn stream_read_group(key: &str, group: &str, consumer: &str, count: usize) -> RedisResult<StreamReadReply> {
+        let mut conn = Self::connection().await?;
+        let options = StreamReadOptions::default().group(group, consumer).count(count);
+        let reply: Option<StreamReadReply> = conn.xread_options(&[key], &[">"], &options).await?;
+        Ok(reply.unwrap_or_default())
+    }
+
+    // 确认消息已处理
+    pub async fn stream_ack(key: &str, group: &str, ids: &[String]) -> RedisResult<()> {
+        let mut conn = Self::connection().await?;
+        conn.xack::<_, _, _, ()>(key, group, ids).await
+    }
+
+    // 查询消费组中已投递未确认的消息
+    pub async fn stream_pending(key: &str, group: &str, count: usize) -> RedisResult<StreamPendingCountReply> {
+        let mut conn = Self::connection().await?;
+        conn.xpending_count(key, group, "-", "+", count).await
+    }
+
+    // stream消息数
+    pub async fn stream_len<K>(key: K) -> RedisResult<usize>
+    where
+        K: ToRedisArgs + Send + Sync,
+    {
+        let mut conn = Self::connection().await?;
+        conn.xlen(key).await
+    }
+
+    // 消费组状态,消费组不存在时返回None
+    pub async fn stream_group_info(key: &str, group: &str) -> RedisResult<Option<StreamGroupInfo>> {
+        let mut conn = Self::connection().await?;
+        let groups: Vec<HashMap<String, Value>> = redis::cmd("XINFO").arg("GROUPS").arg(key).query_async(&mut conn).await?;
+        for info in groups {
+            let name: Option<String> = info.get("name").map(String::from_redis_value).transpose()?;
+            if name.as_deref() != Some(group) {
+                continue;
+            }
+            let pending: usize = match info.get("pending") {
+                Some(value) => usize::from_redis_value(value)?,
+                None => 0,
+            };
+            let lag: Option<usize> = match info.get("lag") {
+                Some(value) => Option::<usize>::from_redis_value(value)?,
+                None => None,
+            };
+            let last_delivered_id: String = match info.get("last-delivered-id") {
+                Some(value) => String::from_redis_value(value)?,
+                None => "0-0".to_string(),
+            };
+            return Ok(Some(StreamGroupInfo { pending, lag, last_delivered_id }));
+        }
+        Ok(None)
+    }
+
+    // 被裁剪或删除的最大消息id,需要redis 7以上,否则返回None
+    pub async fn stream_max_deleted_id(key: &str) -> RedisResult<Option<String>> {
+        let mut conn = Self::connection().await?;
+        let info: HashMap<String, Value> = redis::cmd("XINFO").arg("STREAM").arg(key).query_async(&mut conn).await?;
+        match info.get("max-deleted-entry-id") {
+            Some(value) => Option::<String>::from_redis_value(value),
+            None => Ok(None),
+        }
+    }
+
+    // 认领空闲时间超过min_idle毫秒的消息,认领后投递次数加1
+    pub async fn stream_claim(key: &str, group: &str, consumer: &str, min_idle: usize, ids: &[String]) -> RedisResult<StreamClaimReply> {
+        let mut conn = Self::connection().await?;
+        conn.xclaim(key, group, consumer, min_idle, ids).await
+    }
+}
+
+/// 消费组状态
+#[derive(Debug, Clone)]
+pub struct StreamGroupInfo {
+    pub pending: usize, // 已投递未确认数
+    pub lag: Option<usize>, // 未投递数,需要redis 7以上,无法计算时为None
+    pub last_delivered_id: String, // 最后投递的消息id
+}
+
+fn json_error(e: serde_json::Error) -> RedisError {
+    RedisError::from((redis::ErrorKind::TypeError, "json error", e.to_string()))
+}
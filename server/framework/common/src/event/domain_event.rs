@@ -0,0 +1,79 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// 领域事件,通过 outbox::publish 在业务事务中写入,由 OutboxRelayTask 投递到redis stream
+/// 事件类型同时作为stream名称,修改后已有的订阅方收不到消息
+pub trait DomainEvent: Serialize + DeserializeOwned + Send + Sync + 'static {
+    const EVENT_TYPE: &'static str;
+}
+
+/// 投递给订阅方的事件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventEnvelope<E> {
+    pub event_id: i64, // 事件id,至少投递一次,订阅方按事件id去重
+    pub event_type: String, // 事件类型
+    pub tenant_id: i64, // 租户id
+    pub occurred_at: i64, // 发生时间(毫秒时间戳)
+    pub payload: E, // 事件内容
+}
+
+/// 订单完成
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrderCompletedEvent {
+    pub order_type: String, // 单据类型,见 document_number 中的单据类型
+    pub order_id: i64, // 订单id
+    pub operator: i64, // 操作人
+}
+
+impl DomainEvent for OrderCompletedEvent {
+    const EVENT_TYPE: &'static str = "order_completed";
+}
+
+/// 库存变动
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InventoryChangedEvent {
+    pub product_id: i64, // 产品id
+    pub warehouse_id: i64, // 仓库id
+    pub change_quantity: i32, // 变动数量,出库为负数
+    pub stock_quantity: i32, // 变动后的库存数量
+    pub operator: i64, // 操作人
+}
+
+impl DomainEvent for InventoryChangedEvent {
+    const EVENT_TYPE: &'static str = "inventory_changed";
+}
+
+/// 租户过期
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TenantExpiredEvent {
+    pub tenant_id: i64, // 过期的租户id
+}
+
+impl DomainEvent for TenantExpiredEvent {
+    const EVENT_TYPE: &'static str = "tenant_expired";
+}
+
+/// 登录安全告警,logger-server分析登录日志后按租户汇总发送
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecurityAlertEvent {
+    pub total: usize, // 本次发现的事件数
+    pub items: Vec<SecurityAlertItem>, // 事件明细,最多 SECURITY_ALERT_MAX_ITEMS 条
+}
+
+/// 登录安全告警的事件明细
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecurityAlertItem {
+    pub event_type: String, // 事件类型,见 SECURITY_EVENT_*
+    pub level: i8, // 风险等级
+    pub username: String, // 用户账号
+    pub user_ip: String, // 用户 IP
+    pub detail: String, // 说明
+    pub operate_time: i64, // 登录时间(秒)
+}
+
+/// 告警中最多包含的事件明细数
+pub const SECURITY_ALERT_MAX_ITEMS: usize = 20;
+
+impl DomainEvent for SecurityAlertEvent {
+    const EVENT_TYPE: &'static str = "security_alert";
+}
@@ -0,0 +1,300 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use redis::streams::{StreamId, StreamPendingId};
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+use crate::database::redis_constants::REDIS_KEY_EVENT_DEAD_LETTER_PREFIX;
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::event::domain_event::{DomainEvent, EventEnvelope};
+use crate::event::outbox::stream_key;
+use crate::monitor::health::instance_id;
+
+/// 每次读取的消息数
+const READ_COUNT: usize = 32;
+/// 没有新消息时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 检查未确认消息的间隔
+const RECLAIM_INTERVAL: Duration = Duration::from_secs(30);
+/// 消息未确认超过该时间(毫秒)后重新投递,包括其他已退出的消费者未处理完的消息
+const RECLAIM_MIN_IDLE_MS: usize = 60_000;
+/// 最大投递次数,超过后转入死信
+const MAX_DELIVERIES: usize = 5;
+/// 死信stream保留的最大消息数
+const DEAD_LETTER_MAX_LEN: usize = 10_000;
+/// 出错后的重试间隔
+const ERROR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 订阅事件,同一个消费组内每个事件只由一个副本处理,不同消费组各自处理一次
+/// handler返回错误时消息保持未确认,超时后重新投递,多次失败后转入死信stream
+pub fn subscribe<E, F, Fut>(group: &str, handler: F)
+where
+    E: DomainEvent,
+    F: Fn(EventEnvelope<E>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let consumer = Consumer {
+        key: stream_key(E::EVENT_TYPE),
+        group: group.to_string(),
+        name: instance_id().to_string(),
+        handler: Arc::new(handler),
+        _event: PhantomData,
+    };
+    info!("subscribe event {}, group: {}, consumer: {}", E::EVENT_TYPE, consumer.group, consumer.name);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = consumer.run().await {
+                error!("event consumer {} error: {}", consumer.key, e);
+            }
+            tokio::time::sleep(ERROR_INTERVAL).await;
+        }
+    });
+}
+
+/// 单条消息的处理结果
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Ack, // 处理成功,确认消息
+    Retry, // 处理失败,保持未确认,超时后重新投递
+    DeadLetter(String), // 重试也不会成功,转入死信
+}
+
+struct Consumer<E, F> {
+    key: String,
+    group: String,
+    name: String,
+    handler: Arc<F>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E, F, Fut> Consumer<E, F>
+where
+    E: DomainEvent,
+    F: Fn(EventEnvelope<E>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    async fn run(&self) -> Result<()> {
+        AsyncRedisManager::stream_create_group(&self.key, &self.group).await?;
+        let mut last_reclaim = Instant::now();
+        loop {
+            let reply = AsyncRedisManager::stream_read_group(&self.key, &self.group, &self.name, READ_COUNT).await?;
+            let entries: Vec<StreamId> = reply.keys.into_iter().flat_map(|key| key.ids).collect();
+            let idle = entries.is_empty();
+            for entry in entries {
+                self.handle(entry).await?;
+            }
+
+            if last_reclaim.elapsed() >= RECLAIM_INTERVAL {
+                self.reclaim().await?;
+                last_reclaim = Instant::now();
+            }
+            if idle {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+
+    /// 处理一条消息,成功后确认
+    async fn handle(&self, entry: StreamId) -> Result<()> {
+        match self.process(&entry).await {
+            Outcome::Ack => AsyncRedisManager::stream_ack(&self.key, &self.group, &[entry.id]).await?,
+            Outcome::Retry => {}
+            Outcome::DeadLetter(reason) => self.dead_letter(&entry, &reason).await?,
+        }
+        Ok(())
+    }
+
+    /// 解析消息并调用handler
+    async fn process(&self, entry: &StreamId) -> Outcome {
+        let envelope = match decode::<E>(entry) {
+            Ok(envelope) => envelope,
+            // 无法解析的消息重试也不会成功,直接转入死信
+            Err(e) => return Outcome::DeadLetter(e.to_string()),
+        };
+        let event_id = envelope.event_id;
+        match (self.handler)(envelope).await {
+            Ok(()) => Outcome::Ack,
+            Err(e) => {
+                warn!("handle event {} {} error: {}", self.key, event_id, e);
+                Outcome::Retry
+            }
+        }
+    }
+
+    /// 重新投递超时未确认的消息,投递次数过多的转入死信
+    async fn reclaim(&self) -> Result<()> {
+        let pending = AsyncRedisManager::stream_pending(&self.key, &self.group, READ_COUNT).await?;
+        let (exhausted, retry) = partition_pending(pending.ids);
+
+        for (ids, exhausted) in [(exhausted, true), (retry, false)] {
+            if ids.is_empty() {
+                continue;
+            }
+            let claimed = AsyncRedisManager::stream_claim(&self.key, &self.group, &self.name, RECLAIM_MIN_IDLE_MS, &ids).await?;
+            for entry in claimed.ids {
+                if exhausted {
+                    self.dead_letter(&entry, "too many deliveries").await?;
+                } else {
+                    self.handle(entry).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 转入死信stream并确认原消息
+    async fn dead_letter(&self, entry: &StreamId, reason: &str) -> Result<()> {
+        let dead_key = format!("{}{}", REDIS_KEY_EVENT_DEAD_LETTER_PREFIX, E::EVENT_TYPE);
+        let fields = dead_letter_fields(entry, &self.group, reason);
+        AsyncRedisManager::stream_add(dead_key, DEAD_LETTER_MAX_LEN, &fields).await?;
+        AsyncRedisManager::stream_ack(&self.key, &self.group, &[entry.id.clone()]).await?;
+        error!("event {} {} moved to dead letter, group: {}, reason: {}", self.key, fields[0].1, self.group, reason);
+        Ok(())
+    }
+}
+
+/// 超时未确认的消息按投递次数分为 (转入死信, 重新投递) 两组
+fn partition_pending(pending: Vec<StreamPendingId>) -> (Vec<String>, Vec<String>) {
+    let (exhausted, retry): (Vec<_>, Vec<_>) = pending.into_iter()
+        .filter(|pending| pending.last_delivered_ms >= RECLAIM_MIN_IDLE_MS)
+        .partition(|pending| pending.times_delivered >= MAX_DELIVERIES);
+    let ids = |pending: Vec<StreamPendingId>| pending.into_iter().map(|pending| pending.id).collect();
+    (ids(exhausted), ids(retry))
+}
+
+/// 死信保留原消息的事件id和内容,以及消费组和失败原因
+fn dead_letter_fields(entry: &StreamId, group: &str, reason: &str) -> [(&'static str, String); 4] {
+    [
+        ("event_id", entry.get("event_id").unwrap_or_default()),
+        ("group", group.to_string()),
+        ("reason", reason.to_string()),
+        ("payload", entry.get("payload").unwrap_or_default()),
+    ]
+}
+
+fn decode<E: DomainEvent>(entry: &StreamId) -> Result<EventEnvelope<E>> {
+    let field = |name: &str| entry.get::<String>(name).ok_or_else(|| anyhow!("missing field {}", name));
+    Ok(EventEnvelope {
+        event_id: field("event_id")?.parse()?,
+        event_type: field("event_type")?,
+        tenant_id: field("tenant_id")?.parse()?,
+        occurred_at: field("occurred_at")?.parse()?,
+        payload: serde_json::from_str(&field("payload")?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use redis::Value;
+    use crate::event::domain_event::TenantExpiredEvent;
+
+    fn entry(fields: &[(&str, &str)]) -> StreamId {
+        StreamId {
+            id: "1700000000000-0".to_string(),
+            map: fields.iter()
+                .map(|(name, value)| (name.to_string(), Value::BulkString(value.as_bytes().to_vec())))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    fn event_entry(payload: &str) -> StreamId {
+        entry(&[
+            ("event_id", "42"),
+            ("event_type", TenantExpiredEvent::EVENT_TYPE),
+            ("tenant_id", "1"),
+            ("occurred_at", "1700000000000"),
+            ("payload", payload),
+        ])
+    }
+
+    fn consumer<F, Fut>(handler: F) -> Consumer<TenantExpiredEvent, F>
+    where
+        F: Fn(EventEnvelope<TenantExpiredEvent>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        Consumer {
+            key: stream_key(TenantExpiredEvent::EVENT_TYPE),
+            group: "test".to_string(),
+            name: "consumer-1".to_string(),
+            handler: Arc::new(handler),
+            _event: PhantomData,
+        }
+    }
+
+    fn pending(id: &str, last_delivered_ms: usize, times_delivered: usize) -> StreamPendingId {
+        StreamPendingId { id: id.to_string(), consumer: "consumer-1".to_string(), last_delivered_ms, times_delivered }
+    }
+
+    #[tokio::test]
+    async fn test_process_acks_handled_event() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let consumer = consumer(move |envelope: EventEnvelope<TenantExpiredEvent>| {
+            let counter = counter.clone();
+            async move {
+                assert_eq!(envelope.event_id, 42);
+                assert_eq!(envelope.payload.tenant_id, 7);
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        assert_eq!(consumer.process(&event_entry(r#"{"tenant_id":7}"#)).await, Outcome::Ack);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_keeps_failed_event_pending() {
+        let consumer = consumer(|_| async { Err(anyhow!("handler failed")) });
+        assert_eq!(consumer.process(&event_entry(r#"{"tenant_id":7}"#)).await, Outcome::Retry);
+    }
+
+    #[tokio::test]
+    async fn test_process_dead_letters_undecodable_event() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let consumer = consumer(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        });
+        // payload不是合法的事件内容
+        assert!(matches!(consumer.process(&event_entry("not json")).await, Outcome::DeadLetter(_)));
+        // 缺少字段
+        let outcome = consumer.process(&entry(&[("event_id", "42")])).await;
+        assert!(matches!(outcome, Outcome::DeadLetter(reason) if reason.contains("missing field")));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_partition_pending() {
+        let (exhausted, retry) = partition_pending(vec![
+            pending("1-0", RECLAIM_MIN_IDLE_MS, MAX_DELIVERIES),
+            pending("2-0", RECLAIM_MIN_IDLE_MS, 1),
+            // 未超时的消息可能还在其他消费者处理中
+            pending("3-0", RECLAIM_MIN_IDLE_MS - 1, MAX_DELIVERIES),
+            pending("4-0", RECLAIM_MIN_IDLE_MS - 1, 1),
+            pending("5-0", RECLAIM_MIN_IDLE_MS * 2, MAX_DELIVERIES + 1),
+        ]);
+        assert_eq!(exhausted, vec!["1-0", "5-0"]);
+        assert_eq!(retry, vec!["2-0"]);
+    }
+
+    #[test]
+    fn test_dead_letter_fields() {
+        let fields = dead_letter_fields(&event_entry(r#"{"tenant_id":7}"#), "test", "too many deliveries");
+        assert_eq!(fields, [
+            ("event_id", "42".to_string()),
+            ("group", "test".to_string()),
+            ("reason", "too many deliveries".to_string()),
+            ("payload", r#"{"tenant_id":7}"#.to_string()),
+        ]);
+        // 原消息缺少字段时仍然可以转入死信
+        let fields = dead_letter_fields(&entry(&[]), "test", "missing field event_id");
+        assert_eq!(fields[0].1, "");
+        assert_eq!(fields[3].1, "");
+    }
+}
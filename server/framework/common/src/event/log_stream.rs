@@ -0,0 +1,268 @@
+use std::cmp::Ordering;
+use std::future::Future;
+use anyhow::{anyhow, Result};
+use redis::streams::StreamId;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::OnceCell;
+use tracing::{error, info, warn};
+use crate::config::config::{Config, LoggerServerConfig};
+use crate::database::redis_constants::{
+    REDIS_KEY_LOGGER_DEAD_LETTER_PREFIX, REDIS_KEY_LOGGER_LOGIN_PREFIX, REDIS_KEY_LOGGER_LOGIN_STREAM,
+    REDIS_KEY_LOGGER_OPERATION_PREFIX, REDIS_KEY_LOGGER_OPERATION_STREAM,
+};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::monitor::health::instance_id;
+use crate::monitor::metrics;
+
+/// 写入mongo的消费组
+pub const LOG_CONSUMER_GROUP: &str = "logger-server";
+/// 每次执行最多处理的批数,积压较多时分多次执行,避免退出时长时间等待
+const MAX_BATCHES_PER_RUN: usize = 100;
+/// 死信stream保留的最大消息数
+const DEAD_LETTER_MAX_LEN: usize = 100_000;
+
+/// 日志队列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogQueue {
+    Login, // 登录日志
+    Operation, // 操作日志
+}
+
+impl LogQueue {
+    pub const ALL: [LogQueue; 2] = [LogQueue::Login, LogQueue::Operation];
+
+    /// 队列名称,用于指标和日志
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogQueue::Login => "login",
+            LogQueue::Operation => "operation",
+        }
+    }
+
+    pub fn stream_key(&self) -> &'static str {
+        match self {
+            LogQueue::Login => REDIS_KEY_LOGGER_LOGIN_STREAM,
+            LogQueue::Operation => REDIS_KEY_LOGGER_OPERATION_STREAM,
+        }
+    }
+
+    pub fn dead_letter_key(&self) -> String {
+        format!("{}{}", REDIS_KEY_LOGGER_DEAD_LETTER_PREFIX, self.name())
+    }
+
+    /// 旧版本使用的list队列
+    fn legacy_key(&self) -> &'static str {
+        match self {
+            LogQueue::Login => REDIS_KEY_LOGGER_LOGIN_PREFIX,
+            LogQueue::Operation => REDIS_KEY_LOGGER_OPERATION_PREFIX,
+        }
+    }
+}
+
+/// 写入日志stream,由logger-server消费后保存到mongo
+pub async fn publish<T: Serialize>(queue: LogQueue, log: &T) -> Result<()> {
+    let payload = serde_json::to_string(log)?;
+    let max_len = Config::load().logger_server.stream_max_len;
+    AsyncRedisManager::stream_add(queue.stream_key(), max_len, &[("payload", payload)]).await?;
+    Ok(())
+}
+
+/// 日志消费者,以消费组方式读取日志,写入成功后确认
+/// 写入失败的日志保持未确认,超过reclaim_idle_secs后重新投递,包括已退出的副本未处理完的日志
+/// 重新投递的日志可能已部分写入,insert需要按stream中的id去重
+/// 无法解析或投递次数超过max_deliveries的日志转入死信stream,不阻塞后续日志
+pub struct LogStreamConsumer {
+    queue: LogQueue,
+    name: String,
+    config: LoggerServerConfig,
+    prepared: OnceCell<()>,
+}
+
+impl LogStreamConsumer {
+    pub fn new(queue: LogQueue) -> Self {
+        LogStreamConsumer {
+            queue,
+            name: instance_id().to_string(),
+            config: Config::load().logger_server,
+            prepared: OnceCell::new(),
+        }
+    }
+
+    /// 处理积压的日志,insert写入一批(stream中的id, 日志),返回写入的日志数
+    pub async fn consume<T, F, Fut>(&self, insert: F) -> Result<usize>
+    where
+        T: DeserializeOwned,
+        F: Fn(Vec<(String, T)>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        self.prepared.get_or_try_init(|| self.prepare()).await?;
+        if let Err(e) = self.check_trimmed().await {
+            warn!("check {} log stream trimmed error: {}", self.queue.name(), e);
+        }
+        let mut saved = self.reclaim(&insert).await?;
+        for _ in 0..MAX_BATCHES_PER_RUN {
+            let reply = AsyncRedisManager::stream_read_group(self.queue.stream_key(), LOG_CONSUMER_GROUP, &self.name, self.config.batch_size).await?;
+            let entries: Vec<StreamId> = reply.keys.into_iter().flat_map(|key| key.ids).collect();
+            if entries.is_empty() {
+                break;
+            }
+            let mut logs = Vec::with_capacity(entries.len());
+            let mut ids = Vec::with_capacity(entries.len());
+            for entry in entries {
+                match decode::<T>(&entry) {
+                    Ok(log) => {
+                        logs.push((entry.id.clone(), log));
+                        ids.push(entry.id);
+                    }
+                    // 无法解析的日志重试也不会成功,直接转入死信
+                    Err(e) => self.dead_letter(&entry, &e.to_string()).await?,
+                }
+            }
+            if ids.is_empty() {
+                continue;
+            }
+            // 写入失败时本批日志都不确认,等待重新投递
+            if let Err(e) = insert(logs).await {
+                metrics::record_logger_messages(self.queue.name(), "retry", ids.len());
+                return Err(anyhow!("save {} logs error: {}", self.queue.name(), e));
+            }
+            AsyncRedisManager::stream_ack(self.queue.stream_key(), LOG_CONSUMER_GROUP, &ids).await?;
+            metrics::record_logger_messages(self.queue.name(), "saved", ids.len());
+            saved += ids.len();
+        }
+        Ok(saved)
+    }
+
+    /// 创建消费组,并迁移旧版本list队列中的日志
+    async fn prepare(&self) -> Result<()> {
+        AsyncRedisManager::stream_create_group(self.queue.stream_key(), LOG_CONSUMER_GROUP).await?;
+        let legacy_key = self.queue.legacy_key();
+        let logs = AsyncRedisManager::get_list_range::<_, String>(legacy_key, 0, -1).await?;
+        if logs.is_empty() {
+            return Ok(());
+        }
+        for log in &logs {
+            AsyncRedisManager::stream_add(self.queue.stream_key(), self.config.stream_max_len, &[("payload", log)]).await?;
+        }
+        // 只删除已迁移的部分
+        AsyncRedisManager::list_trim(legacy_key, logs.len() as isize, -1).await?;
+        info!("migrate {} {} logs from list to stream", logs.len(), self.queue.name());
+        Ok(())
+    }
+
+    /// 检查是否有未消费的日志被最大长度裁剪,需要redis 7以上
+    /// 被裁剪的最大id不小于最早的未确认日志,或大于最后投递的id时,说明有日志未写入就被裁剪
+    async fn check_trimmed(&self) -> Result<()> {
+        let key = self.queue.stream_key();
+        let Some(max_deleted_id) = AsyncRedisManager::stream_max_deleted_id(key).await? else {
+            return Ok(());
+        };
+        let Some(group) = AsyncRedisManager::stream_group_info(key, LOG_CONSUMER_GROUP).await? else {
+            return Ok(());
+        };
+        let oldest_pending = AsyncRedisManager::stream_pending(key, LOG_CONSUMER_GROUP, 1).await?
+            .ids.into_iter().next().map(|pending| pending.id);
+        let pending_trimmed = oldest_pending.map_or(false, |id| compare_id(&max_deleted_id, &id).is_ge());
+        if pending_trimmed || compare_id(&max_deleted_id, &group.last_delivered_id).is_gt() {
+            metrics::record_logger_trimmed(self.queue.name());
+            error!(
+                "unconsumed {} logs were trimmed by stream max len, max deleted id: {}, last delivered id: {}, increase logger_server.stream_max_len",
+                self.queue.name(), max_deleted_id, group.last_delivered_id,
+            );
+        }
+        Ok(())
+    }
+
+    /// 重新投递超时未确认的日志,逐条写入,找出导致整批失败的日志
+    async fn reclaim<T, F, Fut>(&self, insert: &F) -> Result<usize>
+    where
+        T: DeserializeOwned,
+        F: Fn(Vec<(String, T)>) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let key = self.queue.stream_key();
+        let min_idle = (self.config.reclaim_idle_secs * 1000) as usize;
+        let pending = AsyncRedisManager::stream_pending(key, LOG_CONSUMER_GROUP, self.config.batch_size).await?;
+        let (exhausted, retry): (Vec<_>, Vec<_>) = pending.ids.into_iter()
+            .filter(|pending| pending.last_delivered_ms >= min_idle)
+            .partition(|pending| pending.times_delivered >= self.config.max_deliveries);
+
+        let mut saved = 0;
+        for (ids, exhausted) in [(exhausted, true), (retry, false)] {
+            if ids.is_empty() {
+                continue;
+            }
+            let ids: Vec<String> = ids.into_iter().map(|pending| pending.id).collect();
+            // 已被裁剪的日志不会返回,认领后投递次数加1
+            let claimed = AsyncRedisManager::stream_claim(key, LOG_CONSUMER_GROUP, &self.name, min_idle, &ids).await?;
+            for entry in claimed.ids {
+                if exhausted {
+                    self.dead_letter(&entry, "too many deliveries").await?;
+                    continue;
+                }
+                let log = match decode::<T>(&entry) {
+                    Ok(log) => log,
+                    Err(e) => {
+                        self.dead_letter(&entry, &e.to_string()).await?;
+                        continue;
+                    }
+                };
+                match insert(vec![(entry.id.clone(), log)]).await {
+                    Ok(()) => {
+                        AsyncRedisManager::stream_ack(key, LOG_CONSUMER_GROUP, &[entry.id]).await?;
+                        metrics::record_logger_messages(self.queue.name(), "saved", 1);
+                        saved += 1;
+                    }
+                    Err(e) => {
+                        metrics::record_logger_messages(self.queue.name(), "retry", 1);
+                        warn!("save {} log {} error: {}", self.queue.name(), entry.id, e);
+                    }
+                }
+            }
+        }
+        Ok(saved)
+    }
+
+    /// 转入死信stream并确认原日志
+    async fn dead_letter(&self, entry: &StreamId, reason: &str) -> Result<()> {
+        let payload: String = entry.get("payload").unwrap_or_default();
+        let fields = [
+            ("id", entry.id.clone()),
+            ("reason", reason.to_string()),
+            ("payload", payload),
+        ];
+        AsyncRedisManager::stream_add(self.queue.dead_letter_key(), DEAD_LETTER_MAX_LEN, &fields).await?;
+        AsyncRedisManager::stream_ack(self.queue.stream_key(), LOG_CONSUMER_GROUP, &[entry.id.clone()]).await?;
+        metrics::record_logger_messages(self.queue.name(), "dead_letter", 1);
+        error!("{} log {} moved to dead letter, reason: {}", self.queue.name(), entry.id, reason);
+        Ok(())
+    }
+}
+
+/// 比较stream消息id,格式为 毫秒时间戳-序号
+fn compare_id(a: &str, b: &str) -> Ordering {
+    let parse = |id: &str| {
+        let (ms, seq) = id.split_once('-').unwrap_or((id, "0"));
+        (ms.parse::<u64>().unwrap_or_default(), seq.parse::<u64>().unwrap_or_default())
+    };
+    parse(a).cmp(&parse(b))
+}
+
+fn decode<T: DeserializeOwned>(entry: &StreamId) -> Result<T> {
+    let payload: String = entry.get("payload").ok_or_else(|| anyhow!("missing field payload"))?;
+    Ok(serde_json::from_str(&payload)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_id() {
+        assert!(compare_id("1700000000000-1", "1700000000000-0").is_gt());
+        assert!(compare_id("1700000000001-0", "1700000000000-9").is_gt());
+        assert!(compare_id("999-0", "1000-0").is_lt());
+        assert!(compare_id("0-0", "0-0").is_eq());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod domain_event;
+pub mod outbox;
+pub mod event_bus;
+pub mod log_stream;
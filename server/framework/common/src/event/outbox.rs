@@ -0,0 +1,110 @@
+use std::time::Duration;
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait, Value};
+use tracing::{error, info};
+use crate::cache::distributed_lock::DistributedLock;
+use crate::database::redis_constants::REDIS_KEY_EVENT_STREAM_PREFIX;
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::event::domain_event::DomainEvent;
+
+/// 事件状态
+pub const OUTBOX_STATUS_PENDING: i8 = 0; // 待投递
+pub const OUTBOX_STATUS_PUBLISHED: i8 = 1; // 已投递
+
+/// 每批投递数量
+const RELAY_BATCH_SIZE: u64 = 200;
+/// stream保留的最大消息数,近似裁剪
+const STREAM_MAX_LEN: usize = 100_000;
+/// 已投递事件的保留天数
+const PUBLISHED_RETENTION_DAYS: i64 = 7;
+/// 投递锁的租期,多个副本同时只有一个投递,保证事件按顺序进入stream
+const RELAY_LOCK_LEASE: Duration = Duration::from_secs(30);
+
+/// 事件的stream名称
+pub fn stream_key(event_type: &str) -> String {
+    format!("{}{}", REDIS_KEY_EVENT_STREAM_PREFIX, event_type)
+}
+
+/// 写入事件,传入业务事务使事件与业务数据同时提交或回滚
+pub async fn publish<C, E>(db: &C, tenant_id: i64, event: &E) -> Result<()>
+where
+    C: ConnectionTrait,
+    E: DomainEvent,
+{
+    let payload = serde_json::to_string(event)?;
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "INSERT INTO system_event_outbox (event_type, payload, status, tenant_id) VALUES (?, ?, ?, ?)",
+        [E::EVENT_TYPE.into(), payload.into(), OUTBOX_STATUS_PENDING.into(), tenant_id.into()],
+    );
+    db.execute(stmt).await?;
+    Ok(())
+}
+
+/// 投递待投递的事件到redis stream,返回投递数量
+/// 写入stream后再标记已投递,中途失败时下次重新投递,订阅方需要按事件id去重
+pub async fn relay(db: &DatabaseConnection) -> Result<usize> {
+    let lock = DistributedLock::new("event:outbox_relay").with_lease(RELAY_LOCK_LEASE);
+    let guard = match lock.try_acquire().await? {
+        Some(guard) => guard,
+        None => return Ok(0), // 其他副本正在投递
+    };
+
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "SELECT id, event_type, payload, tenant_id, create_time FROM system_event_outbox WHERE status = ? ORDER BY id LIMIT ?",
+        [OUTBOX_STATUS_PENDING.into(), RELAY_BATCH_SIZE.into()],
+    );
+    let rows = db.query_all(stmt).await?;
+    let mut published: Vec<i64> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: i64 = row.try_get("", "id")?;
+        let event_type: String = row.try_get("", "event_type")?;
+        let payload: String = row.try_get("", "payload")?;
+        let tenant_id: i64 = row.try_get("", "tenant_id")?;
+        let create_time: NaiveDateTime = row.try_get("", "create_time")?;
+        let fields = [
+            ("event_id", id.to_string()),
+            ("event_type", event_type.clone()),
+            ("tenant_id", tenant_id.to_string()),
+            ("occurred_at", create_time.and_utc().timestamp_millis().to_string()),
+            ("payload", payload),
+        ];
+        // 失败时停止本批投递,保证同类事件的顺序
+        if let Err(e) = AsyncRedisManager::stream_add(stream_key(&event_type), STREAM_MAX_LEN, &fields).await {
+            error!("relay event {} error: {}", id, e);
+            break;
+        }
+        published.push(id);
+    }
+
+    if !published.is_empty() {
+        let placeholders = vec!["?"; published.len()].join(",");
+        let mut values: Vec<Value> = vec![OUTBOX_STATUS_PUBLISHED.into()];
+        values.extend(published.iter().map(|id| Value::from(*id)));
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::MySql,
+            format!("UPDATE system_event_outbox SET status = ?, publish_time = NOW() WHERE id IN ({})", placeholders),
+            values,
+        );
+        // 投递锁过期后其他副本已接手时不再标记,由持有锁的副本处理
+        let txn = db.begin().await?;
+        guard.check_db_fence(&txn).await?;
+        txn.execute(stmt).await?;
+        txn.commit().await?;
+        info!("relay {} events", published.len());
+    }
+    guard.release().await?;
+    Ok(published.len())
+}
+
+/// 删除过期的已投递事件
+pub async fn purge_published(db: &DatabaseConnection) -> Result<u64> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "DELETE FROM system_event_outbox WHERE status = ? AND publish_time < DATE_SUB(NOW(), INTERVAL ? DAY) LIMIT 1000",
+        [OUTBOX_STATUS_PUBLISHED.into(), PUBLISHED_RETENTION_DAYS.into()],
+    );
+    Ok(db.execute(stmt).await?.rows_affected())
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use dashmap::DashMap;
+use futures_util::future::{join_all, BoxFuture};
+use once_cell::sync::Lazy;
+use tracing::warn;
+
+/// 共享缓存有效期,过期后重新加载,加载失败时仍使用过期的值
+const SHARED_CACHE_TTL: Duration = Duration::from_secs(300);
+/// 共享缓存最大条目数,超过后清理过期条目
+const SHARED_CACHE_CAPACITY: usize = 100_000;
+/// 单次加载超时
+const LOAD_TIMEOUT: Duration = Duration::from_secs(3);
+/// 加载失败后暂停调用的时间,期间只使用缓存
+const FAILURE_BACKOFF: Duration = Duration::from_secs(10);
+
+/// 批量获取名称的方法,参数为id列表,返回id和名称的映射
+pub type Resolver = fn(Vec<i64>) -> BoxFuture<'static, Result<HashMap<i64, String>>>;
+
+/// 扩展字段的值,key为扩展字段名,由 enrich 填充,序列化时输出
+pub type ExtendValues = HashMap<&'static str, String>;
+
+/// 由 #[derive(ExtendFields)] 实现,见 macros::ExtendFields
+pub trait ExtendFields {
+    /// 收集需要获取名称的id,按填充类型分组
+    fn extend_keys(&self, keys: &mut ExtendKeys);
+    /// 写入获取到的名称
+    fn extend_fill(&mut self, names: &ExtendNames);
+    /// 每个填充类型的获取方法
+    fn extend_resolvers() -> Vec<(&'static str, Resolver)>;
+}
+
+/// 按填充类型分组的id
+#[derive(Debug, Default)]
+pub struct ExtendKeys(HashMap<&'static str, HashSet<i64>>);
+
+impl ExtendKeys {
+    pub fn add(&mut self, fill_type: &'static str, id: i64) {
+        self.0.entry(fill_type).or_default().insert(id);
+    }
+}
+
+/// 按填充类型分组的名称
+#[derive(Debug, Default)]
+pub struct ExtendNames(HashMap<&'static str, HashMap<i64, String>>);
+
+impl ExtendNames {
+    pub fn get(&self, fill_type: &str, id: i64) -> Option<&String> {
+        self.0.get(fill_type).and_then(|names| names.get(&id))
+    }
+}
+
+/// 填充扩展字段,整页数据的所有填充类型合并为每种类型一次批量调用,不同类型并发获取
+/// 获取失败时扩展字段为空,不影响接口返回
+pub async fn enrich<T: ExtendFields>(items: &mut [T]) {
+    if items.is_empty() {
+        return;
+    }
+    let mut keys = ExtendKeys::default();
+    for item in items.iter() {
+        item.extend_keys(&mut keys);
+    }
+    let loads = T::extend_resolvers().into_iter()
+        .filter_map(|(fill_type, resolver)| {
+            keys.0.remove(fill_type).map(|ids| async move { (fill_type, load(fill_type, ids, resolver).await) })
+        });
+    let names = ExtendNames(join_all(loads).await.into_iter().collect());
+    for item in items.iter_mut() {
+        item.extend_fill(&names);
+    }
+}
+
+/// 填充单条数据的扩展字段
+pub async fn enrich_one<T: ExtendFields>(item: &mut T) {
+    enrich(std::slice::from_mut(item)).await
+}
+
+tokio::task_local! {
+    static REQUEST_CACHE: Arc<Mutex<HashMap<(&'static str, i64), String>>>;
+}
+
+/// 在请求级缓存中执行,同一请求内多次 enrich 的相同id只获取一次
+pub async fn scope<F: Future>(future: F) -> F::Output {
+    REQUEST_CACHE.scope(Arc::new(Mutex::new(HashMap::new())), future).await
+}
+
+/// 进程内共享缓存: (填充类型, id) -> (名称, 加载时间)
+static SHARED_CACHE: Lazy<DashMap<(&'static str, i64), (String, Instant)>> = Lazy::new(DashMap::new);
+/// 填充类型最近一次加载失败的时间
+static LAST_FAILURE: Lazy<DashMap<&'static str, Instant>> = Lazy::new(DashMap::new);
+
+/// 依次从请求级缓存、共享缓存获取,未命中的id批量调用获取方法
+async fn load(fill_type: &'static str, ids: HashSet<i64>, resolver: Resolver) -> HashMap<i64, String> {
+    let mut names = HashMap::with_capacity(ids.len());
+    let mut missing = Vec::new();
+    let request_cache = REQUEST_CACHE.try_with(|cache| cache.clone()).ok();
+    for id in ids {
+        let cached = request_cache.as_ref()
+            .and_then(|cache| cache.lock().ok().and_then(|cache| cache.get(&(fill_type, id)).cloned()))
+            .or_else(|| SHARED_CACHE.get(&(fill_type, id))
+                .filter(|entry| entry.1.elapsed() < SHARED_CACHE_TTL)
+                .map(|entry| entry.0.clone()));
+        match cached {
+            Some(name) => { names.insert(id, name); }
+            None => missing.push(id),
+        }
+    }
+    if missing.is_empty() {
+        return names;
+    }
+
+    let in_backoff = LAST_FAILURE.get(fill_type).map_or(false, |time| time.elapsed() < FAILURE_BACKOFF);
+    let loaded = if in_backoff {
+        None
+    } else {
+        match tokio::time::timeout(LOAD_TIMEOUT, resolver(missing.clone())).await {
+            Ok(Ok(loaded)) => Some(loaded),
+            Ok(Err(e)) => {
+                warn!("extend field {} load error: {}", fill_type, e);
+                LAST_FAILURE.insert(fill_type, Instant::now());
+                None
+            }
+            Err(_) => {
+                warn!("extend field {} load timeout", fill_type);
+                LAST_FAILURE.insert(fill_type, Instant::now());
+                None
+            }
+        }
+    };
+
+    match loaded {
+        Some(loaded) => {
+            LAST_FAILURE.remove(fill_type);
+            if SHARED_CACHE.len() >= SHARED_CACHE_CAPACITY {
+                SHARED_CACHE.retain(|_, entry| entry.1.elapsed() < SHARED_CACHE_TTL);
+            }
+            let now = Instant::now();
+            for (id, name) in loaded {
+                SHARED_CACHE.insert((fill_type, id), (name.clone(), now));
+                if let Some(cache) = &request_cache {
+                    if let Ok(mut cache) = cache.lock() {
+                        cache.insert((fill_type, id), name.clone());
+                    }
+                }
+                names.insert(id, name);
+            }
+        }
+        None => {
+            // 获取失败时使用过期的缓存
+            for id in missing {
+                if let Some(entry) = SHARED_CACHE.get(&(fill_type, id)) {
+                    names.insert(id, entry.0.clone());
+                }
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use futures_util::FutureExt;
+
+    /// 测试数据,owner对应owner_type,creator对应creator_type
+    struct Item {
+        owner: i64,
+        creator: Option<i64>,
+        extend: ExtendValues,
+    }
+
+    impl Item {
+        fn new(owner: i64, creator: Option<i64>) -> Self {
+            Item { owner, creator, extend: ExtendValues::new() }
+        }
+    }
+
+    static OWNER_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static OWNER_IDS: Lazy<Mutex<Vec<i64>>> = Lazy::new(|| Mutex::new(Vec::new()));
+    static CREATOR_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn resolve_names(ids: Vec<i64>) -> HashMap<i64, String> {
+        ids.into_iter().map(|id| (id, format!("name{}", id))).collect()
+    }
+
+    impl ExtendFields for Item {
+        fn extend_keys(&self, keys: &mut ExtendKeys) {
+            keys.add("test_owner", self.owner);
+            if let Some(creator) = self.creator {
+                keys.add("test_creator", creator);
+            }
+        }
+
+        fn extend_fill(&mut self, names: &ExtendNames) {
+            if let Some(name) = names.get("test_owner", self.owner) {
+                self.extend.insert("owner_name", name.clone());
+            }
+            if let Some(name) = self.creator.and_then(|creator| names.get("test_creator", creator)) {
+                self.extend.insert("creator_name", name.clone());
+            }
+        }
+
+        fn extend_resolvers() -> Vec<(&'static str, Resolver)> {
+            vec![("test_owner", owner_resolver), ("test_creator", creator_resolver)]
+        }
+    }
+
+    fn owner_resolver(ids: Vec<i64>) -> BoxFuture<'static, Result<HashMap<i64, String>>> {
+        OWNER_CALLS.fetch_add(1, Ordering::SeqCst);
+        let mut sorted = ids.clone();
+        sorted.sort();
+        *OWNER_IDS.lock().unwrap() = sorted;
+        async move { Ok(resolve_names(ids)) }.boxed()
+    }
+
+    fn creator_resolver(ids: Vec<i64>) -> BoxFuture<'static, Result<HashMap<i64, String>>> {
+        CREATOR_CALLS.fetch_add(1, Ordering::SeqCst);
+        async move { Ok(resolve_names(ids)) }.boxed()
+    }
+
+    #[tokio::test]
+    async fn test_enrich_batches_by_fill_type() {
+        let mut items = vec![Item::new(1, Some(10)), Item::new(2, None), Item::new(1, Some(10))];
+        enrich(&mut items).await;
+        // 每种填充类型只调用一次,id去重
+        assert_eq!(OWNER_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(CREATOR_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(*OWNER_IDS.lock().unwrap(), vec![1, 2]);
+        assert_eq!(items[0].extend.get("owner_name").map(String::as_str), Some("name1"));
+        assert_eq!(items[0].extend.get("creator_name").map(String::as_str), Some("name10"));
+        assert_eq!(items[1].extend.get("owner_name").map(String::as_str), Some("name2"));
+        assert!(!items[1].extend.contains_key("creator_name"));
+
+        // 共享缓存命中时不再调用
+        let mut item = Item::new(2, Some(10));
+        enrich_one(&mut item).await;
+        assert_eq!(OWNER_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(CREATOR_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(item.extend.get("owner_name").map(String::as_str), Some("name2"));
+
+        let mut items: Vec<Item> = Vec::new();
+        enrich(&mut items).await;
+        assert_eq!(OWNER_CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    static REQUEST_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn request_resolver(ids: Vec<i64>) -> BoxFuture<'static, Result<HashMap<i64, String>>> {
+        REQUEST_CALLS.fetch_add(1, Ordering::SeqCst);
+        async move { Ok(resolve_names(ids)) }.boxed()
+    }
+
+    #[tokio::test]
+    async fn test_request_cache() {
+        let names = scope(async {
+            load("test_request", HashSet::from([1, 2]), request_resolver).await;
+            // 清空共享缓存后仍从请求级缓存获取
+            SHARED_CACHE.retain(|key, _| key.0 != "test_request");
+            load("test_request", HashSet::from([1]), request_resolver).await
+        }).await;
+        assert_eq!(names.get(&1).map(String::as_str), Some("name1"));
+        assert_eq!(REQUEST_CALLS.load(Ordering::SeqCst), 1);
+
+        // 请求结束后不再使用该请求的缓存
+        load("test_request", HashSet::from([1]), request_resolver).await;
+        assert_eq!(REQUEST_CALLS.load(Ordering::SeqCst), 2);
+    }
+
+    static FAILED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn failed_resolver(_ids: Vec<i64>) -> BoxFuture<'static, Result<HashMap<i64, String>>> {
+        FAILED_CALLS.fetch_add(1, Ordering::SeqCst);
+        async { Err(anyhow::anyhow!("unavailable")) }.boxed()
+    }
+
+    #[tokio::test]
+    async fn test_failure_backoff() {
+        let names = load("test_failure", HashSet::from([1]), failed_resolver).await;
+        assert!(names.is_empty());
+        assert!(LAST_FAILURE.contains_key("test_failure"));
+        // 失败后暂停调用
+        load("test_failure", HashSet::from([2]), failed_resolver).await;
+        assert_eq!(FAILED_CALLS.load(Ordering::SeqCst), 1);
+
+        // 暂停结束后重新调用,成功时清除失败记录
+        let Some(expired) = Instant::now().checked_sub(FAILURE_BACKOFF) else {
+            return; // 系统启动时间不足,无法构造过期时间
+        };
+        LAST_FAILURE.insert("test_failure", expired);
+        let names = load("test_failure", HashSet::from([2]), |ids| async move { Ok(resolve_names(ids)) }.boxed()).await;
+        assert_eq!(names.get(&2).map(String::as_str), Some("name2"));
+        assert!(!LAST_FAILURE.contains_key("test_failure"));
+    }
+
+    #[tokio::test]
+    async fn test_stale_fallback() {
+        let Some(expired) = Instant::now().checked_sub(SHARED_CACHE_TTL + Duration::from_secs(1)) else {
+            return; // 系统启动时间不足,无法构造过期时间
+        };
+        SHARED_CACHE.insert(("test_stale", 1), ("stale".to_string(), expired));
+        // 过期的缓存需要重新加载,加载失败时使用过期的值
+        let names = load("test_stale", HashSet::from([1, 2]), |_| async { Err(anyhow::anyhow!("unavailable")) }.boxed()).await;
+        assert_eq!(names.get(&1).map(String::as_str), Some("stale"));
+        assert!(!names.contains_key(&2));
+
+        // 加载成功时刷新
+        LAST_FAILURE.remove("test_stale");
+        let names = load("test_stale", HashSet::from([1]), |ids| async move { Ok(resolve_names(ids)) }.boxed()).await;
+        assert_eq!(names.get(&1).map(String::as_str), Some("name1"));
+        assert!(SHARED_CACHE.get(&("test_stale", 1)).unwrap().1.elapsed() < SHARED_CACHE_TTL);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod string_date_time;
-pub mod extend_field;
\ No newline at end of file
+pub mod extend_field;
+pub mod sensitive;
\ No newline at end of file
@@ -0,0 +1,150 @@
+use std::future::Future;
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+
+/// 查看敏感字段明文的权限
+pub const PERMISSION_SENSITIVE_VIEW: &str = "system:sensitive:view";
+
+tokio::task_local! {
+    static SENSITIVE_VISIBLE: bool;
+}
+
+/// 在指定的敏感字段可见性下执行,授权中间件按登录用户的权限设置
+pub async fn scope<F: Future>(visible: bool, future: F) -> F::Output {
+    SENSITIVE_VISIBLE.scope(visible, future).await
+}
+
+/// 当前是否输出明文,不在请求中时默认脱敏
+pub fn is_visible() -> bool {
+    SENSITIVE_VISIBLE.try_with(|visible| *visible).unwrap_or(false)
+}
+
+/// 响应脱敏,保留前PREFIX位和后SUFFIX位,其余替换为*
+/// 用法: #[serde_as(as = "Option<common::formatter::sensitive::BankAccount>")]
+pub struct Mask<const PREFIX: usize, const SUFFIX: usize>;
+
+/// 银行账号,保留后4位
+pub type BankAccount = Mask<0, 4>;
+/// 手机号、电话,保留前3位和后4位
+pub type Mobile = Mask<3, 4>;
+/// 纳税人识别号、证件号,保留前4位和后4位
+pub type IdNumber = Mask<4, 4>;
+/// 提现账号等其他账号,保留前2位和后2位
+pub type Account = Mask<2, 2>;
+
+impl<const PREFIX: usize, const SUFFIX: usize> SerializeAs<String> for Mask<PREFIX, SUFFIX> {
+    fn serialize_as<S>(value: &String, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if is_visible() {
+            serializer.serialize_str(value)
+        } else {
+            serializer.serialize_str(&mask(value, PREFIX, SUFFIX))
+        }
+    }
+}
+
+impl<'de, const PREFIX: usize, const SUFFIX: usize> DeserializeAs<'de, String> for Mask<PREFIX, SUFFIX> {
+    fn deserialize_as<D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)
+    }
+}
+
+/// 脱敏字符,敏感字段的明文不会包含
+pub const MASK_CHAR: char = '*';
+
+impl<const PREFIX: usize, const SUFFIX: usize> Mask<PREFIX, SUFFIX> {
+    /// 是否为按该规则脱敏后的值: 长度不超过保留位数时全部为*,否则前后保留位不含*且中间全部为*
+    pub fn is_masked(value: &str) -> bool {
+        let chars: Vec<char> = value.chars().collect();
+        if chars.is_empty() {
+            return false;
+        }
+        if chars.len() <= PREFIX + SUFFIX {
+            return chars.iter().all(|c| *c == MASK_CHAR);
+        }
+        let (prefix, rest) = chars.split_at(PREFIX);
+        let (masked, suffix) = rest.split_at(rest.len() - SUFFIX);
+        !prefix.contains(&MASK_CHAR) && !suffix.contains(&MASK_CHAR) && masked.iter().all(|c| *c == MASK_CHAR)
+    }
+
+    /// 修改请求中的敏感字段,前端把响应中的脱敏值原样提交时返回None,不覆盖原值
+    pub fn unmasked(value: &Option<String>) -> Option<&String> {
+        value.as_ref().filter(|value| !Self::is_masked(value))
+    }
+}
+
+/// 长度不超过保留位数时全部替换
+pub fn mask(value: &str, prefix: usize, suffix: usize) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= prefix + suffix {
+        return MASK_CHAR.to_string().repeat(chars.len());
+    }
+    let mut masked: String = chars[..prefix].iter().collect();
+    masked.push_str(&MASK_CHAR.to_string().repeat(chars.len() - prefix - suffix));
+    masked.extend(&chars[chars.len() - suffix..]);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use serde_with::serde_as;
+
+    #[serde_as]
+    #[derive(Serialize)]
+    struct Response {
+        #[serde_as(as = "Option<Mobile>")]
+        mobile: Option<String>,
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(mask("13812345678", 3, 4), "138****5678");
+        assert_eq!(mask("6222020200001234", 0, 4), "************1234");
+        assert_eq!(mask("张三丰先生", 1, 1), "张***生");
+        // 长度不超过保留位数时全部替换
+        assert_eq!(mask("1234567", 3, 4), "*******");
+        assert_eq!(mask("", 3, 4), "");
+    }
+
+    #[test]
+    fn test_is_masked() {
+        assert!(Mobile::is_masked("138****5678"));
+        assert!(Mobile::is_masked("*******"));
+        assert!(BankAccount::is_masked("************1234"));
+        assert!(Mobile::is_masked(&mask("13812345678", 3, 4)));
+        // 明文或其他格式不是脱敏值
+        assert!(!Mobile::is_masked("13812345678"));
+        assert!(!Mobile::is_masked(""));
+        assert!(!Mobile::is_masked("1*3812345678"));
+        assert!(!Mobile::is_masked("13****5678"));
+        assert!(!Mobile::is_masked("138****567*"));
+        assert!(!Mobile::is_masked("138*1*5678"));
+        assert!(!BankAccount::is_masked("********"));
+        assert!(!Account::is_masked("a*b"));
+    }
+
+    #[test]
+    fn test_unmasked() {
+        assert_eq!(Mobile::unmasked(&Some("13912345678".to_string())).map(String::as_str), Some("13912345678"));
+        assert_eq!(Mobile::unmasked(&Some("139****5678".to_string())), None);
+        assert_eq!(Mobile::unmasked(&None), None);
+    }
+
+    #[tokio::test]
+    async fn test_serialize_visible() {
+        let response = Response { mobile: Some("13812345678".to_string()) };
+        // 不在请求中时默认脱敏
+        assert_eq!(serde_json::to_string(&response).unwrap(), r#"{"mobile":"138****5678"}"#);
+        let json = scope(true, async { serde_json::to_string(&response).unwrap() }).await;
+        assert_eq!(json, r#"{"mobile":"13812345678"}"#);
+        let json = scope(false, async { serde_json::to_string(&response).unwrap() }).await;
+        assert_eq!(json, r#"{"mobile":"138****5678"}"#);
+    }
+}
@@ -3,9 +3,14 @@ pub mod config;
 pub mod interceptor;
 pub mod middleware;
 pub mod database;
+pub mod cache;
+pub mod event;
 pub mod utils;
 pub mod context;
 pub mod constants;
 pub mod task;
+pub mod model;
 pub mod state;
-pub mod formatter;
\ No newline at end of file
+pub mod formatter;
+pub mod shutdown;
+pub mod monitor;
\ No newline at end of file
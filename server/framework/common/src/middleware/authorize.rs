@@ -1,5 +1,9 @@
 use crate::config::config::Config;
 use crate::context::context::{LoginUserContext, RequestContext};
+use crate::formatter::sensitive::{self, PERMISSION_SENSITIVE_VIEW};
+use crate::middleware::idempotency::register_route_idempotency;
+use crate::middleware::rate_limit::register_route_rate_limit;
+use crate::middleware::operation_logger::register_route_audit;
 use anyhow::Result;
 use axum::extract::OriginalUri;
 use axum::http::Method;
@@ -56,6 +60,12 @@ fn process_operation(
             if let Ok(method) = Method::from_str(method_str) {
                 register_route_authorizes(method, path, operation_id);
             }
+            // 同时注册路由幂等配置
+            register_route_idempotency(path, operation_id);
+            // 同时注册路由限流规则
+            register_route_rate_limit(path, operation_id);
+            // 同时注册路由审计规则
+            register_route_audit(path, operation_id);
         }
     }
 }
@@ -80,7 +90,7 @@ fn is_dynamic_route(route: &str) -> bool {
 }
 
 /// 检查路由是否匹配path
-fn matches_route(route: &str, path: &str) -> bool {
+pub(crate) fn matches_route(route: &str, path: &str) -> bool {
     // 移除前导和尾随的斜杠以规范化
     let route = route.trim_matches('/').to_string();
     let path = path.trim_matches('/').to_string();
@@ -160,24 +170,31 @@ fn get_authorizes(path: &str) -> Option<Vec<String>> {
     get_authorizes_dynamic_route(path)
 }
 
-pub async fn authorize_handler(request: Request, next: Next) -> Result<Response, StatusCode> {
-    // 获取path
+/// 获取去掉api前缀后的请求路由地址
+pub(crate) fn get_route_path(request: &Request) -> String {
     let config = Config::load();
-    // 请求信息ctx注入
     let original_uri_path = if let Some(path) = request.extensions().get::<OriginalUri>() {
         path.0.path().to_owned()
     } else {
         request.uri().path().to_owned()
     };
-    let path = format!("/{}", original_uri_path.replacen(&(config.api_prefix.clone() + "/"), "", 1));
+    format!("/{}", original_uri_path.replacen(&(config.api_prefix.clone() + "/"), "", 1))
+}
+
+pub async fn authorize_handler(request: Request, next: Next) -> Result<Response, StatusCode> {
+    // 获取path
+    let path = get_route_path(&request);
     // 获取目标路由的权限要求
     let mut authorizes = get_authorizes(path.as_str());
     info!("path: {:?}, authorizes: {:?}", path, authorizes);
+    // 有查看权限时响应中的敏感字段输出明文
+    let sensitive_visible = request.extensions().get::<LoginUserContext>()
+        .map_or(false, |user| user.permissions.iter().any(|p| p == PERMISSION_SENSITIVE_VIEW));
     
     // 没有授权,则直接请求
     match authorizes {
         None => {
-            Ok(next.run(request).await)
+            Ok(sensitive::scope(sensitive_visible, next.run(request)).await)
         }
         Some(mut auth) => {
             let login_user = request.extensions().get::<LoginUserContext>();
@@ -193,7 +210,7 @@ pub async fn authorize_handler(request: Request, next: Next) -> Result<Response,
                     let has_permission = verify_permission(user_permissions, auth.clone());
                     info!("has permission: {:?}, auth: {:?}", has_permission, auth);
                     if has_permission {
-                        Ok(next.run(request).await)
+                        Ok(sensitive::scope(sensitive_visible, next.run(request)).await)
                     } else {
                         Err(StatusCode::FORBIDDEN)
                     }
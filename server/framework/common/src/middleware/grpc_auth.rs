@@ -1,72 +1,124 @@
 use chrono::Utc;
-use tonic::{Request, Status};
-use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::http::header::ToStrError;
+use axum::http::{Request, Response};
+use futures_util::future::BoxFuture;
+use tonic::body::Body;
+use tonic::Status;
+use tower::{Layer, Service};
 use tracing::{error, info};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use jsonwebtoken::errors::{Error, ErrorKind};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::errors::ErrorKind;
 
 use crate::context::context::LoginUserContext;
-use crate::database::redis::RedisManager;
-use crate::database::redis_constants::REDIS_KEY_LOGIN_USER_PREFIX;
-use crate::utils::jwt_utils::{is_valid_tenant, AccessClaims, SECRET_KEY};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::database::redis_constants::{REDIS_KEY_LOGIN_USER_PREFIX, REDIS_KEY_TENANTS_LIST};
+use crate::utils::jwt_utils::{AccessClaims, SECRET_KEY};
+use crate::utils::trace_utils::{TraceContext, TRACEPARENT_HEADER};
 
-pub fn auth_interceptor() -> impl Fn(Request<()>) -> Result<Request<()>, Status> + Clone + Send + Sync + 'static {
-    move |mut request: Request<()>| {
-        // 获取元数据
-        let metadata = request.metadata();
-        let auth_header = metadata
-            .get("authorization")
-            .ok_or_else(|| Status::unauthenticated("授权失败"))?
-            .to_str()
-            .map_err(|_| Status::unauthenticated("授权失败"))?;
+/// grpc请求认证,校验token、租户和登录信息,通过后将登录用户和链路放入请求扩展
+/// 用法: Server::builder().layer(GrpcAuthLayer)
+#[derive(Debug, Clone, Default)]
+pub struct GrpcAuthLayer;
 
-        // 期望 "Bearer <token>"
-        let prefix = "Bearer ";
-        let token = auth_header
-            .strip_prefix(prefix)
-            .ok_or_else(|| Status::unauthenticated("授权失败"))?;
+impl<S> Layer<S> for GrpcAuthLayer {
+    type Service = GrpcAuthService<S>;
 
-        info!("token: {:?}", token);
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcAuthService { inner }
+    }
+}
 
-        let token_data = decode::<AccessClaims>(
-            token,
-            &DecodingKey::from_secret(&*SECRET_KEY),
-            &Validation::new(Algorithm::HS256),
-        ).map_err(|e| {
-            error!("decode token error, {}", e.to_string());
-            match e.kind() {
-                ErrorKind::ExpiredSignature => Status::unauthenticated("认证已过期"),
-                _ => Status::unauthenticated("授权失败")
-            }
-        })?;
+#[derive(Debug, Clone)]
+pub struct GrpcAuthService<S> {
+    inner: S,
+}
 
-        info!("token data: {:?}", token_data);
-        let claims = token_data.claims;
-        let now = Utc::now().timestamp();
-        if claims.exp < now {
-            return Err(Status::unauthenticated("认证已过期"));
-        }
+impl<S, B> Service<Request<B>> for GrpcAuthService<S>
+where
+    S: Service<Request<B>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        // 使用已就绪的服务处理请求
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        // 先取出请求头,请求体不一定是Sync,不能跨await借用请求
+        let auth_header = request.headers().get("authorization").map(|v| v.to_str().map(str::to_string));
+        // 上游传递的链路
+        let trace = request.headers()
+            .get(TRACEPARENT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(TraceContext::from_traceparent)
+            .unwrap_or_else(TraceContext::new_root);
+        Box::pin(async move {
+            let login_user = match authenticate(auth_header).await {
+                Ok(login_user) => login_user,
+                Err(status) => return Ok(status.into_http()),
+            };
+            request.extensions_mut().insert(login_user);
+            request.extensions_mut().insert(trace);
+            inner.call(request).await
+        })
+    }
+}
 
-        match is_valid_tenant(claims.tenant_id) {
-            Ok(valid) => (
-              if !valid {
-                  return Err(Status::unauthenticated("无效租户"))
-              }
-            ),
-            Err(_) => return Err(Status::unauthenticated("授权失败")),
+async fn authenticate(auth_header: Option<Result<String, ToStrError>>) -> Result<LoginUserContext, Status> {
+    let auth_header = auth_header
+        .ok_or_else(|| Status::unauthenticated("授权失败"))?
+        .map_err(|_| Status::unauthenticated("授权失败"))?;
+
+    // 期望 "Bearer <token>"
+    let prefix = "Bearer ";
+    let token = auth_header
+        .strip_prefix(prefix)
+        .ok_or_else(|| Status::unauthenticated("授权失败"))?;
+
+    info!("token: {:?}", token);
+
+    let token_data = decode::<AccessClaims>(
+        token,
+        &DecodingKey::from_secret(&*SECRET_KEY),
+        &Validation::new(Algorithm::HS256),
+    ).map_err(|e| {
+        error!("decode token error, {}", e.to_string());
+        match e.kind() {
+            ErrorKind::ExpiredSignature => Status::unauthenticated("认证已过期"),
+            _ => Status::unauthenticated("授权失败")
         }
+    })?;
 
-        // 获取用户登录信息
-        let context = RedisManager::get::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, claims.device_type, claims.sub));
-        let login_user = match context {
-            Ok(Some(ctx_str)) => serde_json::from_str::<LoginUserContext>(&ctx_str)
-                .map_err(|_| Status::unauthenticated("授权失效")),
-            Ok(None) => Err(Status::unauthenticated("授权失效")),
-            Err(_) => Err(Status::unauthenticated("授权失效")),
-        }?;
+    info!("token data: {:?}", token_data);
+    let claims = token_data.claims;
+    let now = Utc::now().timestamp();
+    if claims.exp < now {
+        return Err(Status::unauthenticated("认证已过期"));
+    }
 
-        request.extensions_mut().insert(login_user);
+    match AsyncRedisManager::is_set_member(REDIS_KEY_TENANTS_LIST, claims.tenant_id).await {
+        Ok(valid) => {
+            if !valid {
+                return Err(Status::unauthenticated("无效租户"));
+            }
+        }
+        Err(_) => return Err(Status::unauthenticated("授权失败")),
+    }
 
-        Ok(request)
+    // 获取用户登录信息
+    let context = AsyncRedisManager::get::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, claims.device_type, claims.sub)).await;
+    match context {
+        Ok(Some(ctx_str)) => serde_json::from_str::<LoginUserContext>(&ctx_str)
+            .map_err(|_| Status::unauthenticated("授权失效")),
+        Ok(None) => Err(Status::unauthenticated("授权失效")),
+        Err(_) => Err(Status::unauthenticated("授权失效")),
     }
-}
\ No newline at end of file
+}
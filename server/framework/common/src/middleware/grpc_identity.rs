@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use axum::http::{Extensions, Request, Response};
+use futures_util::future::BoxFuture;
+use tonic::body::Body;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tonic::Status;
+use tower::{Layer, Service};
+use tracing::warn;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+use crate::config::config::Config;
+
+/// 客户端证书中的服务身份,校验通过后放入请求扩展,grpc方法中可通过 request.extensions().get::<ServiceIdentity>() 获取
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceIdentity(pub String);
+
+/// 从证书中获取服务身份: 优先使用 spiffe://<信任域>/<服务名> 格式的URI SAN中的服务名,没有时使用CN
+pub fn identity_from_cert(der: &[u8]) -> Option<String> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+    let from_uri = cert.subject_alternative_name().ok().flatten().and_then(|san| {
+        san.value.general_names.iter().find_map(|name| match name {
+            GeneralName::URI(uri) => uri.strip_prefix("spiffe://")
+                .and_then(|path| path.rsplit('/').next())
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            _ => None,
+        })
+    });
+    from_uri.or_else(|| {
+        cert.subject().iter_common_name().next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(str::to_string)
+    })
+}
+
+/// 连接中客户端证书的服务身份,明文连接或未提供证书时返回None
+fn peer_identity(extensions: &Extensions) -> Option<String> {
+    let certs = extensions.get::<TlsConnectInfo<TcpConnectInfo>>()?.peer_certs()?;
+    certs.first().and_then(|cert| identity_from_cert(cert.as_ref()))
+}
+
+/// 服务身份是否允许调用rpc,rpc格式为 /包名.服务名/方法名
+/// 允许列表为空时不限制;规则可以是完整rpc、以 /* 结尾的服务前缀或 *
+pub fn is_allowed(allowed_rpcs: &HashMap<String, Vec<String>>, identity: Option<&str>, rpc: &str) -> bool {
+    if allowed_rpcs.is_empty() {
+        return true;
+    }
+    let Some(identity) = identity else {
+        return false;
+    };
+    // 配置的key不区分大小写
+    allowed_rpcs.iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case(identity))
+        .flat_map(|(_, rpcs)| rpcs)
+        .any(|rule| {
+            rule == "*" || rule == rpc || rule.strip_suffix('*').map_or(false, |prefix| prefix.ends_with('/') && rpc.starts_with(prefix))
+        })
+}
+
+/// grpc服务身份校验,按 grpc.tls.allowed_rpcs 限制每个服务可以调用的rpc
+/// 用法: Server::builder().layer(ServiceIdentityLayer::from_config())
+#[derive(Debug, Clone)]
+pub struct ServiceIdentityLayer {
+    allowed_rpcs: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl ServiceIdentityLayer {
+    pub fn new(allowed_rpcs: HashMap<String, Vec<String>>) -> Self {
+        ServiceIdentityLayer { allowed_rpcs: Arc::new(allowed_rpcs) }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(Config::load().grpc.tls.allowed_rpcs)
+    }
+}
+
+impl<S> Layer<S> for ServiceIdentityLayer {
+    type Service = ServiceIdentityService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ServiceIdentityService { inner, allowed_rpcs: self.allowed_rpcs.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceIdentityService<S> {
+    inner: S,
+    allowed_rpcs: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl<S, B> Service<Request<B>> for ServiceIdentityService<S>
+where
+    S: Service<Request<B>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let identity = peer_identity(request.extensions());
+        let rpc = request.uri().path().to_string();
+        if !is_allowed(&self.allowed_rpcs, identity.as_deref(), &rpc) {
+            warn!("grpc {} denied for service identity {:?}", rpc, identity);
+            let status = match identity {
+                Some(_) => Status::permission_denied("服务无权调用"),
+                None => Status::unauthenticated("缺少服务证书"),
+            };
+            return Box::pin(async move { Ok(status.into_http()) });
+        }
+        if let Some(identity) = identity {
+            request.extensions_mut().insert(ServiceIdentity(identity));
+        }
+        // 使用已就绪的服务处理请求
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(inner.call(request))
+    }
+}
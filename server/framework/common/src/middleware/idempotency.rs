@@ -0,0 +1,260 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use anyhow::Result;
+use crate::base::response::{CommonResult, RESULT_CODE_SUCCESS};
+use crate::context::context::{LoginUserContext, RequestContext};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::database::redis_constants::REDIS_KEY_IDEMPOTENCY_PREFIX;
+use crate::middleware::authorize::{get_route_path, matches_route};
+use crate::utils::crypt_utils::get_md5;
+
+/// 幂等请求头
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// 重放响应标识头
+pub const IDEMPOTENCY_REPLAYED_HEADER: &str = "Idempotency-Replayed";
+/// 默认结果保存时间(秒)
+pub const DEFAULT_IDEMPOTENCY_TTL: u64 = 86400;
+/// 请求处理中的锁定时间(秒),防止请求异常退出后key一直无法使用
+const PROCESSING_TTL: u64 = 60;
+/// 可缓存的最大响应大小
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// 操作id和幂等结果保存时间的映射
+pub static OPERATION_IDEMPOTENCY: Lazy<DashMap<String, u64>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 静态路由幂等配置
+pub static STATIC_ROUTE_IDEMPOTENCY: Lazy<DashMap<String, u64>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 动态路由幂等配置
+pub static DYNAMIC_ROUTE_IDEMPOTENCY: Lazy<DashMap<String, u64>> = Lazy::new(|| {
+    DashMap::new()
+});
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum IdempotencyStatus {
+    Processing, // 处理中
+    Completed, // 已完成
+}
+
+/// 保存在redis中的幂等记录
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct IdempotencyRecord {
+    status: IdempotencyStatus, // 处理状态
+    fingerprint: String, // 请求指纹
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http_status: Option<u16>, // 响应状态码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>, // 响应类型
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>, // 响应内容
+}
+
+/// 注册操作id的幂等配置,ttl为结果保存时间(秒)
+pub fn register_operation_idempotency(operation_id: &str, ttl: u64) {
+    OPERATION_IDEMPOTENCY.insert(operation_id.to_string(), ttl);
+}
+
+/// 根据操作id关联路由的幂等配置
+pub(crate) fn register_route_idempotency(path: &str, operation_id: &str) {
+    if let Some(ttl) = OPERATION_IDEMPOTENCY.get(operation_id) {
+        if path.contains('{') || path.contains('*') {
+            DYNAMIC_ROUTE_IDEMPOTENCY.insert(path.to_string(), *ttl.value());
+        } else {
+            STATIC_ROUTE_IDEMPOTENCY.insert(path.to_string(), *ttl.value());
+        }
+    }
+}
+
+/// 获取路由的幂等配置,先匹配静态路由,再匹配动态路由
+fn get_idempotency_ttl(path: &str) -> Option<u64> {
+    if let Some(ttl) = STATIC_ROUTE_IDEMPOTENCY.get(path) {
+        return Some(*ttl.value());
+    }
+    DYNAMIC_ROUTE_IDEMPOTENCY
+        .iter()
+        .find(|entry| matches_route(entry.key(), path))
+        .map(|entry| *entry.value())
+}
+
+/// 幂等中间件,路由需通过require_authorize宏的idempotent参数开启
+/// 同一用户相同Idempotency-Key的请求,处理中返回409,处理完成后重放第一次的响应
+pub async fn idempotency_handler(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let path = get_route_path(&request);
+    let ttl = match get_idempotency_ttl(&path) {
+        Some(ttl) => ttl,
+        None => return Ok(next.run(request).await),
+    };
+    // 客户端未传幂等key,不做处理
+    let idempotency_key = match request.headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        Some(key) => key,
+        None => return Ok(next.run(request).await),
+    };
+    if idempotency_key.len() > 128 {
+        return Ok(reject(StatusCode::BAD_REQUEST, "幂等key长度不能超过128"));
+    }
+
+    let user_id = request.extensions().get::<LoginUserContext>().map(|u| u.id).unwrap_or_default();
+    let redis_key = format!("{}{}:{}", REDIS_KEY_IDEMPOTENCY_PREFIX, user_id, idempotency_key);
+    // 使用原始请求数据的摘要,脱敏和截断后的内容无法区分不同请求
+    let body_hash = request.extensions().get::<RequestContext>().map(|c| c.body_hash.clone()).unwrap_or_default();
+    let query = request.uri().query().unwrap_or_default();
+    let fingerprint = get_md5(&format!("{} {}?{} {}", request.method(), path, query, body_hash));
+
+    let processing = IdempotencyRecord {
+        status: IdempotencyStatus::Processing,
+        fingerprint: fingerprint.clone(),
+        http_status: None,
+        content_type: None,
+        body: None,
+    };
+    let processing_json = serde_json::to_string(&processing).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let acquired = match AsyncRedisManager::set_nx_ex(&redis_key, processing_json, PROCESSING_TTL).await {
+        Ok(acquired) => acquired,
+        Err(e) => {
+            // redis不可用时不阻断业务
+            error!("idempotency lock error, key: {}, {}", redis_key, e);
+            return Ok(next.run(request).await);
+        }
+    };
+
+    if !acquired {
+        return Ok(replay(&redis_key, &fingerprint).await);
+    }
+
+    let response = next.run(request).await;
+    Ok(store(&redis_key, fingerprint, ttl, response).await)
+}
+
+/// 重放已完成的响应,处理中则返回409
+async fn replay(redis_key: &str, fingerprint: &str) -> Response {
+    let record = match AsyncRedisManager::get::<_, String>(redis_key).await {
+        Ok(Some(json)) => serde_json::from_str::<IdempotencyRecord>(&json).ok(),
+        _ => None,
+    };
+    let record = match record {
+        Some(record) => record,
+        None => return reject(StatusCode::CONFLICT, "请求正在处理中,请勿重复提交"),
+    };
+    if record.fingerprint != fingerprint {
+        return reject(StatusCode::UNPROCESSABLE_ENTITY, "幂等key已被其他请求使用");
+    }
+    match record.status {
+        IdempotencyStatus::Processing => reject(StatusCode::CONFLICT, "请求正在处理中,请勿重复提交"),
+        IdempotencyStatus::Completed => {
+            info!("idempotency replay, key: {}", redis_key);
+            let status = record.http_status
+                .and_then(|s| StatusCode::from_u16(s).ok())
+                .unwrap_or(StatusCode::OK);
+            let mut builder = Response::builder()
+                .status(status)
+                .header(IDEMPOTENCY_REPLAYED_HEADER, HeaderValue::from_static("true"));
+            if let Some(content_type) = record.content_type {
+                builder = builder.header(header::CONTENT_TYPE, content_type);
+            }
+            builder
+                .body(Body::from(record.body.unwrap_or_default()))
+                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        }
+    }
+}
+
+/// 保存处理完成的响应,非成功状态码或业务失败的响应则释放key允许客户端重试
+async fn store(redis_key: &str, fingerprint: String, ttl: u64, response: Response) -> Response {
+    let (parts, body) = response.into_parts();
+    if !parts.status.is_success() {
+        release(redis_key).await;
+        return Response::from_parts(parts, body);
+    }
+    let bytes = match to_bytes(body, MAX_RESPONSE_SIZE).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("idempotency read response error, key: {}, {}", redis_key, e);
+            release(redis_key).await;
+            return reject(StatusCode::INTERNAL_SERVER_ERROR, "读取响应失败");
+        }
+    };
+    if !is_success_result(&bytes) {
+        // 业务失败通常是参数或状态问题,修正后使用同一个key重试
+        release(redis_key).await;
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(body) => {
+            let record = IdempotencyRecord {
+                status: IdempotencyStatus::Completed,
+                fingerprint,
+                http_status: Some(parts.status.as_u16()),
+                content_type: parts.headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string()),
+                body: Some(body.to_string()),
+            };
+            if let Err(e) = AsyncRedisManager::set_json(redis_key, &record, Some(ttl)).await {
+                error!("idempotency save response error, key: {}, {}", redis_key, e);
+                release(redis_key).await;
+            }
+        }
+        Err(_) => release(redis_key).await,
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// 响应是否为成功的 CommonResult,http状态码为200但code不是成功码时为业务失败
+/// 不是 CommonResult 格式的响应按http状态码判断
+fn is_success_result(body: &[u8]) -> bool {
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(value) => match value.get("code").filter(|code| !code.is_null()) {
+            Some(code) => code.as_i64() == Some(RESULT_CODE_SUCCESS as i64),
+            None => true,
+        },
+        Err(_) => true,
+    }
+}
+
+async fn release(redis_key: &str) {
+    if let Err(e) = AsyncRedisManager::delete(redis_key).await {
+        error!("idempotency release error, key: {}, {}", redis_key, e);
+    }
+}
+
+fn reject(status: StatusCode, message: &str) -> Response {
+    let result: CommonResult<()> = CommonResult {
+        code: Some(status.as_u16() as i32),
+        data: None,
+        message: Some(message.to_string()),
+    };
+    (status, result).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_success_result() {
+        assert!(is_success_result(br#"{"code":200,"data":1,"message":"success"}"#));
+        assert!(!is_success_result(br#"{"code":500,"data":null,"message":"库存不足"}"#));
+        assert!(!is_success_result(br#"{"code":"200"}"#));
+        // 没有code或其他格式的响应按http状态码判断
+        assert!(is_success_result(br#"{"code":null,"data":null,"message":null}"#));
+        assert!(is_success_result(br#"{"id":1}"#));
+        assert!(is_success_result(b"ok"));
+        assert!(is_success_result(b""));
+    }
+}
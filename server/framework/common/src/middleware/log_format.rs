@@ -0,0 +1,121 @@
+use std::fmt;
+use chrono::Local;
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// 从当前span中提取到日志顶层的字段,请求span记录链路、租户、用户和路由
+const CONTEXT_FIELDS: [&str; 5] = ["trace_id", "span_id", "tenant_id", "user_id", "route"];
+
+/// json日志格式,每行一条日志:
+/// {"timestamp":"...","level":"INFO","service":"system-server","target":"...","trace_id":"...","tenant_id":1,"user_id":1,"route":"/system/...","message":"...","fields":{...}}
+/// span字段需使用 JsonFields 格式化,即 fmt::layer().fmt_fields(JsonFields::new())
+pub struct JsonLogFormatter {
+    service: String,
+}
+
+impl JsonLogFormatter {
+    pub fn new(service: String) -> Self {
+        JsonLogFormatter { service }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for JsonLogFormatter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        // log crate的日志使用原始的target和位置
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+        let mut log = Map::new();
+        log.insert("timestamp".to_string(), Value::from(Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z").to_string()));
+        log.insert("level".to_string(), Value::from(metadata.level().as_str()));
+        log.insert("service".to_string(), Value::from(self.service.as_str()));
+        log.insert("target".to_string(), Value::from(metadata.target()));
+
+        // 外层到内层,内层span的字段覆盖外层
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                let Some(fields) = extensions.get::<FormattedFields<N>>() else {
+                    continue;
+                };
+                if let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(fields) {
+                    for (key, value) in fields {
+                        if CONTEXT_FIELDS.contains(&key.as_str()) {
+                            log.insert(key, value);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        log.insert("message".to_string(), visitor.message.unwrap_or(Value::from("")));
+        if !visitor.fields.is_empty() {
+            log.insert("fields".to_string(), Value::Object(visitor.fields));
+        }
+        if let (Some(file), Some(line)) = (metadata.file(), metadata.line()) {
+            log.insert("location".to_string(), Value::from(format!("{}:{}", file, line)));
+        }
+
+        let line = serde_json::to_string(&log).map_err(|_| fmt::Error)?;
+        writeln!(writer, "{}", line)
+    }
+}
+
+/// 日志事件字段,message单独保存
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<Value>,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        match field.name() {
+            "message" => self.message = Some(value),
+            // tracing-log转换时附加的字段
+            name if name.starts_with("log.") => {}
+            name => { self.fields.insert(name.to_string(), value); }
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, Value::from(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, Value::from(format!("{:?}", value)));
+    }
+}
@@ -1,22 +1,34 @@
 use chrono::Local;
 use std::{fs, panic};
 use std::io;
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use axum::response::{IntoResponse, Response};
-use tracing::error;
-use tracing_appender::rolling;
-use tracing_subscriber::{fmt};
+use axum::routing::get;
+use axum::{Extension, Json, Router};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, reload};
+use tracing_subscriber::fmt::format::JsonFields;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
+use crate::base::response::CommonResult;
 use crate::config::config::Config;
+use crate::context::context::LoginUserContext;
+use crate::middleware::log_format::JsonLogFormatter;
+use crate::utils::jwt_utils::AccessClaims;
 use once_cell::sync::OnceCell;
 use flexi_logger::{Logger, FileSpec, Criterion, Naming, Age, Cleanup, Duplicate, WriteMode};
 use tracing_subscriber::fmt::SubscriberBuilder;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::Registry;
 use tracing_log::LogTracer;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
 
 // 自定义时间格式器
 struct LocalTimeFormatter;
@@ -28,6 +40,12 @@ impl fmt::time::FormatTime for LocalTimeFormatter {
 }
 
 static FILE_GUARD: OnceCell<tracing_appender::non_blocking::WorkerGuard> = OnceCell::new();
+static TRACER_PROVIDER: OnceCell<SdkTracerProvider> = OnceCell::new();
+/// 日志过滤规则,运行中可替换
+static FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// 修改日志级别需要的权限
+pub const PERMISSION_LOG_LEVEL: &str = "system:log:level";
 
 pub async fn init_tracing() -> io::Result<()> {
     // "error"	只显示 error 日志
@@ -35,11 +53,13 @@ pub async fn init_tracing() -> io::Result<()> {
     // "info"	显示 info、warn 和 error
     // "debug"	包括 debug、info、warn、error
     // "trace"	最详细，所有日志都输出
+    // 可按模块设置,如 info,sea_orm=warn
     let config = Config::load();
-    let env_filter = EnvFilter::new(format!("{}", config.log_level));
+    let (filter_layer, filter_handle) = reload::Layer::new(EnvFilter::new(config.log.filter()));
+    let _ = FILTER_HANDLE.set(filter_handle);
 
     // 配置日志文件路径
-    let log_dir = "logs";
+    let log_dir = config.log.dir.as_str();
     fs::create_dir_all(log_dir).map_err(|e| {
         io::Error::new(
             io::ErrorKind::Other,
@@ -47,31 +67,83 @@ pub async fn init_tracing() -> io::Result<()> {
         )
     })?;
 
-    // 创建每日滚动的文件写入器，每天一个文件
-    let file_appender = rolling::daily(log_dir, "app.log");
+    // 按周期滚动的文件写入器,如 app.2025-01-01.log,超过保留数量时删除最早的文件
+    let rotation = match config.log.rotation.to_lowercase().as_str() {
+        "minutely" => Rotation::MINUTELY,
+        "hourly" => Rotation::HOURLY,
+        "never" => Rotation::NEVER,
+        _ => Rotation::DAILY,
+    };
+    let file_appender = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix("app")
+        .filename_suffix("log")
+        .max_log_files(config.log.max_files)
+        .build(log_dir)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to create log file appender: {}", e)))?;
 
-    // 配置非阻塞写入器，限制最多保存30天
-    // let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+    // 配置非阻塞写入器
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
     FILE_GUARD.set(guard).expect("Failed to set FILE_GUARD");
 
-    let file_layer = fmt::layer()
-        .with_writer(non_blocking)
-        .with_ansi(false) // 文件中不需要颜色
-        .with_timer(LocalTimeFormatter);
+    // 日志格式,json格式包含服务名和当前请求的trace_id、tenant_id、user_id、route
+    let service_name = get_service_name(&config);
+    let json_format = config.log.format.eq_ignore_ascii_case("json");
+    let (file_layer, stdout_layer) = if json_format {
+        (
+            fmt::layer()
+                .event_format(JsonLogFormatter::new(service_name.clone()))
+                .fmt_fields(JsonFields::new())
+                .with_writer(non_blocking)
+                .boxed(),
+            fmt::layer()
+                .event_format(JsonLogFormatter::new(service_name.clone()))
+                .fmt_fields(JsonFields::new())
+                .boxed(),
+        )
+    } else {
+        (
+            fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false) // 文件中不需要颜色
+                .with_timer(LocalTimeFormatter)
+                .boxed(),
+            fmt::layer()
+                .with_ansi(true) // 控制台使用颜色
+                .with_timer(LocalTimeFormatter)
+                .boxed(),
+        )
+    };
 
-    // 配置控制台输出层
-    let stdout_layer = fmt::layer()
-        .with_ansi(true) // 控制台使用颜色
-        .with_timer(LocalTimeFormatter);
+    // 配置了otlp地址时导出链路
+    let otel_layer = if config.log.otlp_endpoint.is_empty() {
+        None
+    } else {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(config.log.otlp_endpoint.clone())
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to build otlp exporter: {}", e)))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service_name.clone()).build())
+            .build();
+        let tracer = provider.tracer(service_name);
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        let _ = TRACER_PROVIDER.set(provider);
+        Some(tracing_opentelemetry::layer().with_tracer(tracer))
+    };
 
     // 设置全局默认的 subscriber
     tracing_subscriber::registry()
+        .with(filter_layer)    // 日志过滤,可运行中修改
         .with(file_layer)      // 输出到文件
         .with(stdout_layer)    // 输出到控制台
-        .with(env_filter)      // 应用环境变量过滤
+        .with(otel_layer)      // 导出链路
         .init();
 
+    info!("effective config, profile: {}\n{}", config.profile, config.redacted());
+
     // 设置全局 panic hook
     panic::set_hook(Box::new(|panic_info| {
         let payload = panic_info.payload();
@@ -90,16 +162,91 @@ pub async fn init_tracing() -> io::Result<()> {
         error!("Panic occurred at {}: {}", location, msg);
     }));
 
-    // 清理超过30天的日志文件
-    cleanup_old_logs(log_dir)?;
+    Ok(())
+}
+
+/// 当前日志过滤规则
+pub fn current_log_filter() -> Option<String> {
+    FILTER_HANDLE.get()?.with_current(|filter| filter.to_string()).ok()
+}
 
+/// 修改日志过滤规则,格式同 RUST_LOG,如 info,sea_orm=debug,只影响当前实例
+pub fn set_log_filter(filter: &str) -> anyhow::Result<()> {
+    let env_filter = EnvFilter::try_new(filter)?;
+    let handle = FILTER_HANDLE.get().ok_or_else(|| anyhow!("tracing is not initialized"))?;
+    handle.reload(env_filter)?;
+    info!("log filter changed to {}", filter);
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LogFilterRequest {
+    pub filter: Option<String>, // 日志过滤规则,为空时恢复配置中的规则
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogFilterResponse {
+    pub filter: String, // 当前日志过滤规则
+}
+
+/// 日志级别管理路由: GET /admin/log_level 查看, POST /admin/log_level 修改
+/// 需要登录并拥有 system:log:level 权限
+pub fn log_level_router() -> Router {
+    Router::new()
+        .route("/admin/log_level", get(get_log_level).post(update_log_level))
+        .layer(axum::middleware::from_extractor::<AccessClaims>())
+}
+
+async fn get_log_level(Extension(login_user): Extension<LoginUserContext>) -> Result<CommonResult<LogFilterResponse>, StatusCode> {
+    check_log_permission(&login_user)?;
+    Ok(CommonResult::with_data(LogFilterResponse { filter: current_log_filter().unwrap_or_default() }))
+}
+
+async fn update_log_level(Extension(login_user): Extension<LoginUserContext>, Json(request): Json<LogFilterRequest>) -> Result<CommonResult<LogFilterResponse>, StatusCode> {
+    check_log_permission(&login_user)?;
+    let filter = match request.filter {
+        Some(filter) if !filter.trim().is_empty() => filter,
+        _ => Config::load().log.filter(),
+    };
+    if let Err(e) = set_log_filter(&filter) {
+        return Ok(CommonResult::with_err(&format!("日志过滤规则无效: {}", e)));
+    }
+    info!("log filter changed by user {}", login_user.id);
+    Ok(CommonResult::with_data(LogFilterResponse { filter }))
+}
+
+fn check_log_permission(login_user: &LoginUserContext) -> Result<(), StatusCode> {
+    if login_user.permissions.iter().any(|permission| permission == PERMISSION_LOG_LEVEL) {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+/// 链路中的服务名,未配置时使用程序名
+fn get_service_name(config: &Config) -> String {
+    if !config.log.otlp_service_name.is_empty() {
+        return config.log.otlp_service_name.clone();
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "synerunify".to_string())
+}
+
+/// 导出剩余的链路数据,退出前调用
+pub fn shutdown_tracing() {
+    if let Some(provider) = TRACER_PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            error!("shutdown tracer provider error: {}", e);
+        }
+    }
+}
+
 pub async fn init_tracing_flexi() -> anyhow::Result<()> {
     let config = Config::load();
 
-    Logger::try_with_str(&config.log_level)?
+    Logger::try_with_str(&config.log.level)?
         .log_to_file(FileSpec::default().directory("logs").basename("app"))
         .write_mode(WriteMode::BufferAndFlush)
         .rotate(
@@ -121,32 +268,6 @@ pub async fn init_tracing_flexi() -> anyhow::Result<()> {
     Ok(())
 }
 
-// 清理超过30天的日志文件
-fn cleanup_old_logs(log_dir: &str) -> io::Result<()> {
-    let now = Local::now();
-    for entry in fs::read_dir(log_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    let age = now.signed_duration_since(chrono::DateTime::<Local>::from(modified));
-                    if age.num_days() > 30 {
-                        fs::remove_file(&path).map_err(|e| {
-                            io::Error::new(
-                                io::ErrorKind::Other,
-                                format!("Failed to remove old log file {}: {}", path.display(), e),
-                            )
-                        })?;
-                    }
-                }
-            }
-        }
-    }
-    Ok(())
-}
-
 // 自定义异常处理中间件
 pub async fn panic_handler(req: Request<Body>, next: axum::middleware::Next) -> impl IntoResponse {
     // 将异步调用包装在 catch_unwind 中
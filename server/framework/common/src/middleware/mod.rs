@@ -2,4 +2,8 @@ pub mod logger;
 pub mod request_context;
 pub mod authorize;
 pub mod operation_logger;
-pub mod grpc_auth;
\ No newline at end of file
+pub mod grpc_auth;
+pub mod idempotency;
+pub mod rate_limit;
+pub mod grpc_identity;
+pub mod log_format;
@@ -1,4 +1,4 @@
-use std::future::Future;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 use axum::extract::Request;
 use axum::http::StatusCode;
@@ -6,13 +6,132 @@ use axum::middleware::Next;
 use axum::response::Response;
 use chrono::Utc;
 use anyhow::Result;
-use tracing::info;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, FromQueryResult, JsonValue, Statement};
+use serde_json::{json, Map, Value};
+use tracing::{error, info, warn};
 use crate::base::logger::OperationLogger;
 use crate::base::response::CommonResultJsonString;
 use crate::context::context::{LoginUserContext, RequestContext};
-use crate::database::redis::RedisManager;
-use crate::database::redis_constants::REDIS_KEY_LOGGER_OPERATION_PREFIX;
+use crate::database::mysql::DATABASE_INSTANCE;
+use crate::event::log_stream::{self, LogQueue};
+use crate::middleware::authorize::{get_route_path, matches_route};
+use crate::utils::redaction::{self, Redactor, REDACTED};
 use crate::utils::snowflake_generator::SnowflakeGenerator;
+use crate::shutdown::shutdown::spawn_tracked;
+
+/// 比较前后数据时忽略的字段
+const DIFF_IGNORE_FIELDS: [&str; 4] = ["update_time", "updater", "create_time", "creator"];
+
+/// 业务编号来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum BizIdSource {
+    Path(String), // 路由参数,如 path.id 对应 /delete/{id}
+    Body(String), // 请求体json字段,支持多级,如 body.id、body.order.id
+    Query(String), // 查询参数,如 query.id
+    Response, // 响应中的data,如新增返回的id
+}
+
+impl BizIdSource {
+    /// 解析 path.id/body.id/query.id/response
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "response" {
+            return Some(BizIdSource::Response);
+        }
+        let (source, name) = value.split_once('.')?;
+        match source {
+            "path" => Some(BizIdSource::Path(name.to_string())),
+            "body" => Some(BizIdSource::Body(name.to_string())),
+            "query" => Some(BizIdSource::Query(name.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// 操作审计规则
+#[derive(Debug, Clone)]
+pub struct AuditRule {
+    pub module: String, // 业务模块,保存到日志的type
+    pub action: String, // 操作,保存到日志的sub_type,如 create/update/delete
+    pub biz_id: Option<BizIdSource>, // 业务编号来源
+    pub table: Option<String>, // 业务数据表,配置后记录修改前后的字段差异
+    pub redact: HashSet<String>, // 请求、响应和差异中需要脱敏的字段
+}
+
+/// 操作id和审计规则的映射
+pub static OPERATION_AUDITS: Lazy<DashMap<String, AuditRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 静态路由审计规则
+pub static STATIC_ROUTE_AUDITS: Lazy<DashMap<String, AuditRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 动态路由审计规则
+pub static DYNAMIC_ROUTE_AUDITS: Lazy<DashMap<String, AuditRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 表名和是否有tenant_id字段的映射
+static TENANT_TABLES: Lazy<DashMap<String, bool>> = Lazy::new(|| {
+    DashMap::new()
+});
+
+/// 注册操作id的审计规则,由audit宏调用
+pub fn register_operation_audit(operation_id: &str, module: &str, action: &str, biz_id: &str, table: &str, redact: &[&str]) {
+    let biz_id_source = if biz_id.is_empty() {
+        None
+    } else {
+        let source = BizIdSource::parse(biz_id);
+        if source.is_none() {
+            error!("register audit {} error: invalid biz_id {}", operation_id, biz_id);
+        }
+        source
+    };
+    // 表名拼接到sql中,只允许字母、数字和下划线
+    let table = Some(table.to_string()).filter(|table| !table.is_empty());
+    if let Some(table) = &table {
+        if !table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            error!("register audit {} error: invalid table {}", operation_id, table);
+            return;
+        }
+    }
+    OPERATION_AUDITS.insert(operation_id.to_string(), AuditRule {
+        module: module.to_string(),
+        action: action.to_string(),
+        biz_id: biz_id_source,
+        table,
+        redact: redact.iter().map(|field| field.to_string()).collect(),
+    });
+}
+
+/// 根据操作id关联路由的审计规则
+pub(crate) fn register_route_audit(path: &str, operation_id: &str) {
+    if let Some(rule) = OPERATION_AUDITS.get(operation_id) {
+        if path.contains('{') || path.contains('*') {
+            DYNAMIC_ROUTE_AUDITS.insert(path.to_string(), rule.value().clone());
+        } else {
+            STATIC_ROUTE_AUDITS.insert(path.to_string(), rule.value().clone());
+        }
+    }
+}
+
+/// 获取路由的审计规则和路由模板,先匹配静态路由,再匹配动态路由
+fn get_route_audit(path: &str) -> Option<(String, AuditRule)> {
+    if let Some(rule) = STATIC_ROUTE_AUDITS.get(path) {
+        return Some((path.to_string(), rule.value().clone()));
+    }
+    DYNAMIC_ROUTE_AUDITS
+        .iter()
+        .find(|entry| matches_route(entry.key(), path))
+        .map(|entry| (entry.key().clone(), entry.value().clone()))
+}
+
+/// 审计信息,请求处理前获取
+struct AuditContext {
+    rule: AuditRule,
+    biz_id: Option<i64>,
+    before: Option<Value>, // 修改前的数据
+}
 
 pub async fn operation_logger_handler(request: Request, next: Next) -> Result<Response, StatusCode> {
     // let start = Instant::now();
@@ -22,6 +141,20 @@ pub async fn operation_logger_handler(request: Request, next: Next) -> Result<Re
     };
     let login_user = request.extensions().get::<LoginUserContext>().cloned();
 
+    // 配置了审计规则时,获取业务编号和修改前的数据
+    let path = get_route_path(&request);
+    let audit = match get_route_audit(&path) {
+        Some((route, rule)) => {
+            let biz_id = rule.biz_id.as_ref().and_then(|source| request_biz_id(source, &route, &path, &request_context));
+            let before = match (&rule.table, biz_id) {
+                (Some(table), Some(id)) => load_snapshot(table, id, login_user.as_ref().map(|u| u.tenant_id)).await,
+                _ => None,
+            };
+            Some(AuditContext { rule, biz_id, before })
+        }
+        None => None,
+    };
+
     // info!("operation logger time1: {:?}ms", Instant::now().duration_since(start).as_secs_f64() * 1000.0);
     let now = Instant::now();
     let request_end = next.run(request).await;
@@ -31,15 +164,16 @@ pub async fn operation_logger_handler(request: Request, next: Next) -> Result<Re
         Some(x) => x.0.clone(),
         None => "".to_string(),
     };
+    let status = request_end.status();
     // 记录操作日志,异步
-    add_logger(request_context, login_user, result_string, duration);
+    add_logger(request_context, login_user, audit, path, result_string, status, duration);
     // info!("operation logger time3: {:?}ms", Instant::now().duration_since(start).as_secs_f64() * 1000.0);
     Ok(request_end)
 }
 
-fn add_logger(request_context: RequestContext, login_user: Option<LoginUserContext>, result: String, duration: Duration) {
-    tokio::spawn(async move {
-        match add_logger_redis(request_context, login_user, result, duration).await {
+fn add_logger(request_context: RequestContext, login_user: Option<LoginUserContext>, audit: Option<AuditContext>, route: String, result: String, status: StatusCode, duration: Duration) {
+    spawn_tracked(async move {
+        match add_logger_redis(request_context, login_user, audit, route, result, status, duration).await {
             Ok(_) => {}
             Err(e) => {
                 tracing::info!("add operation log error: {}", e.to_string());
@@ -48,30 +182,33 @@ fn add_logger(request_context: RequestContext, login_user: Option<LoginUserConte
     });
 }
 
-async fn add_logger_redis(request_context: RequestContext, login_user: Option<LoginUserContext>, result: String, duration: Duration) -> Result<()> {
+async fn add_logger_redis(request_context: RequestContext, login_user: Option<LoginUserContext>, audit: Option<AuditContext>, route: String, result: String, status: StatusCode, duration: Duration) -> Result<()> {
     // info!("login user, {:?}", login_user.clone());
     let user_id = login_user.clone().map(|u| u.id);
     let user_nickname = login_user.clone().map(|u| u.nickname);
     let tenant_id = login_user.clone().map(|u| u.tenant_id);
     let department_code = login_user.clone().map(|u| u.department_code);
     let department_id = login_user.clone().map(|u| u.department_id);
-    // TODO
+    let result_json = serde_json::from_str::<Value>(&result).ok();
+    let success = is_success(result_json.as_ref(), status);
+    // 响应中的令牌、账号等敏感信息脱敏后保存
+    let result = redaction::redactor().redact_body(&route, &result);
     let mut operation_logger = OperationLogger {
         id: None,
-        trace_id: None,
+        trace_id: Some(request_context.trace_id.clone()).filter(|id| !id.is_empty()),
         user_id,
         user_type: None,
         r#type: None,
         sub_type: None,
         biz_id: None,
         action: None,
-        success: Some(true),
+        success: Some(success),
         result,
         extra: None,
-        request_method: request_context.method,
-        request_url: request_context.request_url,
-        user_ip: request_context.ip,
-        user_agent: request_context.user_agent,
+        request_method: request_context.method.clone(),
+        request_url: request_context.request_url.clone(),
+        user_ip: request_context.ip.clone(),
+        user_agent: request_context.user_agent.clone(),
         department_code,
         department_id,
         duration: Some(duration.as_millis() as i64),
@@ -81,13 +218,319 @@ async fn add_logger_redis(request_context: RequestContext, login_user: Option<Lo
         deleted: Some(false),
         tenant_id
     };
+    if let Some(audit) = audit {
+        enrich(&mut operation_logger, audit, &request_context, &route, result_json, success).await;
+    }
     // 生成id
-    let generator = SnowflakeGenerator::new_with_machine_id(1000).unwrap();
+    let generator = SnowflakeGenerator::global();
     match generator.generate() {
         Ok(id) => operation_logger.id = Some(id),
         Err(e) => operation_logger.id = None
     }
     info!("operation logger: {:?}", operation_logger);
-    RedisManager::push_list::<_, String>(REDIS_KEY_LOGGER_OPERATION_PREFIX, serde_json::to_string(&operation_logger)?)?;
+    log_stream::publish(LogQueue::Operation, &operation_logger).await?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 按审计规则补充模块、操作、业务编号、请求参数和修改前后的差异
+async fn enrich(operation_logger: &mut OperationLogger, audit: AuditContext, request_context: &RequestContext, route: &str, result_json: Option<Value>, success: bool) {
+    let AuditContext { rule, biz_id, before } = audit;
+    let biz_id = match rule.biz_id {
+        Some(BizIdSource::Response) => result_json.as_ref().and_then(|result| result.get("data")).and_then(as_i64),
+        _ => biz_id,
+    };
+
+    // 只记录成功的修改,失败时数据未变化
+    let diff = match (&rule.table, biz_id, success) {
+        (Some(table), Some(id), true) => {
+            let after = load_snapshot(table, id, operation_logger.tenant_id).await;
+            diff(before.as_ref(), after.as_ref(), &rule.redact, redaction::redactor())
+        }
+        _ => Map::new(),
+    };
+
+    let mut extra = Map::new();
+    if let Some(params) = request_context.json.as_deref() {
+        let mut params = params.clone();
+        redact(&mut params, &rule.redact);
+        extra.insert("params".to_string(), params);
+    }
+    if !diff.is_empty() {
+        extra.insert("diff".to_string(), Value::Object(diff.clone()));
+    }
+    if let Some(mut result_json) = result_json.filter(|_| !rule.redact.is_empty()) {
+        redact(&mut result_json, &rule.redact);
+        operation_logger.result = redaction::redactor().redact_body(route, &result_json.to_string());
+    }
+
+    // 操作内容,如 update system_user 1: nickname, status
+    let mut action = format!("{} {}", rule.action, rule.module);
+    if let Some(id) = biz_id {
+        action.push_str(&format!(" {}", id));
+    }
+    if !diff.is_empty() {
+        action.push_str(&format!(": {}", diff.keys().cloned().collect::<Vec<_>>().join(", ")));
+    }
+
+    operation_logger.r#type = Some(rule.module);
+    operation_logger.sub_type = Some(rule.action);
+    operation_logger.biz_id = biz_id;
+    operation_logger.action = Some(action);
+    operation_logger.extra = Some(Value::Object(extra).to_string()).filter(|extra| extra != "{}");
+}
+
+/// 响应为CommonResult时按code判断,否则按http状态码判断
+fn is_success(result: Option<&Value>, status: StatusCode) -> bool {
+    match result.and_then(|result| result.get("code")).and_then(Value::as_i64) {
+        Some(code) => code == 200,
+        None => status.is_success(),
+    }
+}
+
+/// 从请求中获取业务编号
+fn request_biz_id(source: &BizIdSource, route: &str, path: &str, request_context: &RequestContext) -> Option<i64> {
+    match source {
+        BizIdSource::Path(name) => path_param(route, path, name).and_then(|value| value.parse().ok()),
+        BizIdSource::Body(name) => {
+            let body = request_context.json.as_deref()?;
+            name.split('.').try_fold(body, |value, key| value.get(key)).and_then(as_i64)
+        }
+        BizIdSource::Query(name) => request_context.path_params.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.parse().ok()),
+        BizIdSource::Response => None,
+    }
+}
+
+/// 获取路由参数,如路由 /delete/{id} 和地址 /delete/1 的id为1
+fn path_param(route: &str, path: &str, name: &str) -> Option<String> {
+    let placeholder = format!("{{{}}}", name);
+    route.trim_matches('/').split('/')
+        .zip(path.trim_matches('/').split('/'))
+        .find(|(segment, _)| *segment == placeholder)
+        .map(|(_, value)| value.to_string())
+}
+
+/// 编号可能是数字或字符串(超过js精度的id按字符串返回)
+fn as_i64(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+}
+
+/// 读取业务数据,失败时不记录差异
+/// 有tenant_id字段的表只读取当前租户的数据,未登录时不读取
+async fn load_snapshot(table: &str, id: i64, tenant_id: Option<i64>) -> Option<Value> {
+    let db = DATABASE_INSTANCE.get()?;
+    let tenant_id = match is_tenant_table(db, table).await {
+        Ok(true) => Some(tenant_id?),
+        Ok(false) => None,
+        Err(e) => {
+            warn!("load audit snapshot {} {} error: {}", table, id, e);
+            return None;
+        }
+    };
+    match JsonValue::find_by_statement(snapshot_statement(table, id, tenant_id)).one(db).await {
+        Ok(row) => row,
+        Err(e) => {
+            warn!("load audit snapshot {} {} error: {}", table, id, e);
+            None
+        }
+    }
+}
+
+fn snapshot_statement(table: &str, id: i64, tenant_id: Option<i64>) -> Statement {
+    match tenant_id {
+        Some(tenant_id) => Statement::from_sql_and_values(
+            DbBackend::MySql,
+            format!("SELECT * FROM `{}` WHERE id = ? AND tenant_id = ?", table),
+            [id.into(), tenant_id.into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DbBackend::MySql,
+            format!("SELECT * FROM `{}` WHERE id = ?", table),
+            [id.into()],
+        ),
+    }
+}
+
+/// 表是否有tenant_id字段,结果按表名缓存
+async fn is_tenant_table(db: &DatabaseConnection, table: &str) -> Result<bool> {
+    if let Some(tenant_table) = TENANT_TABLES.get(table) {
+        return Ok(*tenant_table);
+    }
+    let statement = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "SELECT COUNT(*) AS count FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND COLUMN_NAME = 'tenant_id'",
+        [table.into()],
+    );
+    let count: i64 = match db.query_one(statement).await? {
+        Some(row) => row.try_get("", "count")?,
+        None => 0,
+    };
+    TENANT_TABLES.insert(table.to_string(), count > 0);
+    Ok(count > 0)
+}
+
+/// 修改前后有变化的字段: {"字段": {"before": 修改前, "after": 修改后}},新增时before为null,删除时after为null
+fn diff(before: Option<&Value>, after: Option<&Value>, redact_fields: &HashSet<String>, redactor: &Redactor) -> Map<String, Value> {
+    let empty = Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut diff = Map::new();
+    for field in fields {
+        if DIFF_IGNORE_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        let (old, new) = (before.get(field).unwrap_or(&Value::Null), after.get(field).unwrap_or(&Value::Null));
+        if old == new {
+            continue;
+        }
+        let change = if redact_fields.contains(field) || redactor.is_sensitive_key(field) {
+            json!({ "before": REDACTED, "after": REDACTED })
+        } else {
+            json!({ "before": old, "after": new })
+        };
+        diff.insert(field.clone(), change);
+    }
+    diff
+}
+
+/// 脱敏json中的字段,包括嵌套的对象和数组
+fn redact(value: &mut Value, fields: &HashSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if fields.contains(key) {
+                    *value = Value::from(REDACTED);
+                } else {
+                    redact(value, fields);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact(value, fields)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use sea_orm::sea_query::Values;
+    use crate::config::config::RedactionConfig;
+
+    fn fields(fields: &[&str]) -> HashSet<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    fn redactor() -> Redactor {
+        Redactor::new(&RedactionConfig::default())
+    }
+
+    #[test]
+    fn test_parse_biz_id() {
+        assert_eq!(BizIdSource::parse("path.id"), Some(BizIdSource::Path("id".to_string())));
+        assert_eq!(BizIdSource::parse("body.order.id"), Some(BizIdSource::Body("order.id".to_string())));
+        assert_eq!(BizIdSource::parse("query.id"), Some(BizIdSource::Query("id".to_string())));
+        assert_eq!(BizIdSource::parse("response"), Some(BizIdSource::Response));
+        assert_eq!(BizIdSource::parse("header.id"), None);
+        assert_eq!(BizIdSource::parse("id"), None);
+    }
+
+    #[test]
+    fn test_path_param() {
+        assert_eq!(path_param("/system_user/delete/{id}", "/system_user/delete/1", "id"), Some("1".to_string()));
+        assert_eq!(path_param("/{tenant_id}/user/{id}", "/2/user/3", "tenant_id"), Some("2".to_string()));
+        assert_eq!(path_param("/system_user/delete/{id}", "/system_user/delete/1", "user_id"), None);
+        assert_eq!(path_param("/system_user/update", "/system_user/update", "id"), None);
+    }
+
+    #[test]
+    fn test_request_biz_id() {
+        let body = json!({"id": "1234567890123456789", "order": {"id": 2}, "name": "a"});
+        let request_context = RequestContext {
+            path_params: "page=1&id=3".to_string(),
+            json: Some(Arc::new(body)),
+            ..Default::default()
+        };
+        let route = "/system_user/{id}";
+        let path = "/system_user/4";
+        let biz_id = |source: &str| request_biz_id(&BizIdSource::parse(source).unwrap(), route, path, &request_context);
+        assert_eq!(biz_id("path.id"), Some(4));
+        // 超过js精度的id按字符串提交
+        assert_eq!(biz_id("body.id"), Some(1234567890123456789));
+        assert_eq!(biz_id("body.order.id"), Some(2));
+        assert_eq!(biz_id("body.name"), None);
+        assert_eq!(biz_id("body.missing.id"), None);
+        assert_eq!(biz_id("query.id"), Some(3));
+        assert_eq!(biz_id("query.size"), None);
+        assert_eq!(biz_id("response"), None);
+
+        // 没有json请求体
+        let request_context = RequestContext::default();
+        assert_eq!(request_biz_id(&BizIdSource::Body("id".to_string()), route, path, &request_context), None);
+    }
+
+    #[test]
+    fn test_is_success() {
+        assert!(is_success(Some(&json!({"code": 200})), StatusCode::OK));
+        assert!(!is_success(Some(&json!({"code": 500, "message": "error"})), StatusCode::OK));
+        assert!(is_success(None, StatusCode::NO_CONTENT));
+        assert!(!is_success(None, StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = json!({"id": 1, "nickname": "a", "status": 0, "password": "x", "remark": "r", "update_time": "t1"});
+        let after = json!({"id": 1, "nickname": "b", "status": 0, "password": "y", "remark": "r", "update_time": "t2", "email": "e"});
+        let diff = diff(Some(&before), Some(&after), &fields(&["remark"]), &redactor());
+        assert_eq!(diff.keys().cloned().collect::<Vec<_>>(), vec!["email", "nickname", "password"]);
+        assert_eq!(diff["nickname"], json!({"before": "a", "after": "b"}));
+        assert_eq!(diff["email"], json!({"before": null, "after": "e"}));
+        // 敏感字段只记录变化
+        assert_eq!(diff["password"], json!({"before": REDACTED, "after": REDACTED}));
+    }
+
+    #[test]
+    fn test_diff_create_and_delete() {
+        let value = json!({"id": 1, "nickname": "a", "creator": 1});
+        let created = diff(None, Some(&value), &HashSet::new(), &redactor());
+        assert_eq!(created.keys().cloned().collect::<Vec<_>>(), vec!["id", "nickname"]);
+        assert_eq!(created["id"], json!({"before": null, "after": 1}));
+        let deleted = diff(Some(&value), None, &HashSet::new(), &redactor());
+        assert_eq!(deleted["nickname"], json!({"before": "a", "after": null}));
+        assert!(diff(Some(&value), Some(&value), &HashSet::new(), &redactor()).is_empty());
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut value = json!({
+            "username": "admin",
+            "password": "123456",
+            "items": [{"bank_account": "6222", "amount": 1}],
+            "profile": {"password": "abc"}
+        });
+        redact(&mut value, &fields(&["password", "bank_account"]));
+        assert_eq!(value, json!({
+            "username": "admin",
+            "password": REDACTED,
+            "items": [{"bank_account": REDACTED, "amount": 1}],
+            "profile": {"password": REDACTED}
+        }));
+    }
+
+    #[test]
+    fn test_snapshot_statement() {
+        let statement = snapshot_statement("system_user", 1, Some(2));
+        assert_eq!(statement.sql, "SELECT * FROM `system_user` WHERE id = ? AND tenant_id = ?");
+        assert_eq!(statement.values, Some(Values(vec![1i64.into(), 2i64.into()])));
+        let statement = snapshot_statement("system_tenant", 1, None);
+        assert_eq!(statement.sql, "SELECT * FROM `system_tenant` WHERE id = ?");
+        assert_eq!(statement.values, Some(Values(vec![1i64.into()])));
+    }
+}
@@ -0,0 +1,413 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use axum::extract::Request;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use redis::Script;
+use tracing::{error, info};
+use anyhow::Result;
+use crate::base::response::CommonResult;
+use crate::constants::enums::DeviceType;
+use crate::context::context::{LoginUserContext, RequestContext};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::database::redis_constants::REDIS_KEY_RATE_LIMIT_PREFIX;
+use crate::middleware::authorize::{get_route_path, matches_route};
+
+/// api key请求头
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// 限流维度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitKey {
+    Ip, // 按ip
+    User, // 按用户,未登录时按ip
+    Tenant, // 按租户,未登录时按ip
+    ApiKey, // 按api key,没有时按ip
+}
+
+impl FromStr for RateLimitKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ip" => Ok(RateLimitKey::Ip),
+            "user" => Ok(RateLimitKey::User),
+            "tenant" => Ok(RateLimitKey::Tenant),
+            "api_key" | "apikey" => Ok(RateLimitKey::ApiKey),
+            _ => Err(format!("unknown rate limit key: {}", s)),
+        }
+    }
+}
+
+/// 限流规则,window秒内最多limit次请求(滑动窗口)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitRule {
+    pub limit: u64, // 窗口内最大请求数
+    pub window: u64, // 窗口大小(秒)
+    pub key: RateLimitKey, // 限流维度
+}
+
+impl RateLimitRule {
+    pub fn new(limit: u64, window: u64, key: RateLimitKey) -> Self {
+        RateLimitRule { limit, window, key }
+    }
+}
+
+/// 操作id和限流规则的映射
+pub static OPERATION_RATE_LIMITS: Lazy<DashMap<String, RateLimitRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 静态路由限流规则
+pub static STATIC_ROUTE_RATE_LIMITS: Lazy<DashMap<String, RateLimitRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 动态路由限流规则
+pub static DYNAMIC_ROUTE_RATE_LIMITS: Lazy<DashMap<String, RateLimitRule>> = Lazy::new(|| {
+    DashMap::new()
+});
+/// 各设备类型的默认限流规则,路由未配置限流时使用
+pub static DEVICE_RATE_LIMITS: Lazy<DashMap<String, RateLimitRule>> = Lazy::new(|| {
+    let map = DashMap::new();
+    let pc: &'static str = DeviceType::Pc.into();
+    let web: &'static str = DeviceType::Web.into();
+    let mobile: &'static str = DeviceType::Mobile.into();
+    map.insert(pc.to_string(), RateLimitRule::new(600, 60, RateLimitKey::User));
+    map.insert(web.to_string(), RateLimitRule::new(600, 60, RateLimitKey::User));
+    map.insert(mobile.to_string(), RateLimitRule::new(300, 60, RateLimitKey::User));
+    map
+});
+
+/// 滑动窗口限流脚本,返回 {是否通过, 剩余次数, 重置时间(毫秒)}
+static SLIDING_WINDOW_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(r#"
+        local key = KEYS[1]
+        local now = tonumber(ARGV[1])
+        local window = tonumber(ARGV[2])
+        local limit = tonumber(ARGV[3])
+        local member = ARGV[4]
+        redis.call('ZREMRANGEBYSCORE', key, 0, now - window)
+        local count = redis.call('ZCARD', key)
+        local allowed = 0
+        if count < limit then
+            redis.call('ZADD', key, now, member)
+            count = count + 1
+            allowed = 1
+        end
+        redis.call('PEXPIRE', key, window)
+        local reset = window
+        local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+        if oldest[2] then
+            reset = tonumber(oldest[2]) + window - now
+        end
+        return {allowed, limit - count, reset}
+    "#)
+});
+
+static REQUEST_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// 注册操作id的限流规则
+pub fn register_operation_rate_limit(operation_id: &str, limit: u64, window: u64, key: &str) {
+    let key = RateLimitKey::from_str(key).unwrap_or_else(|e| {
+        error!("register rate limit {} error: {}, use ip", operation_id, e);
+        RateLimitKey::Ip
+    });
+    OPERATION_RATE_LIMITS.insert(operation_id.to_string(), RateLimitRule::new(limit, window, key));
+}
+
+/// 设置设备类型的默认限流规则
+pub fn register_device_rate_limit(device_type: DeviceType, rule: RateLimitRule) {
+    let device: &'static str = device_type.into();
+    DEVICE_RATE_LIMITS.insert(device.to_string(), rule);
+}
+
+/// 根据操作id关联路由的限流规则
+pub(crate) fn register_route_rate_limit(path: &str, operation_id: &str) {
+    if let Some(rule) = OPERATION_RATE_LIMITS.get(operation_id) {
+        if path.contains('{') || path.contains('*') {
+            DYNAMIC_ROUTE_RATE_LIMITS.insert(path.to_string(), *rule.value());
+        } else {
+            STATIC_ROUTE_RATE_LIMITS.insert(path.to_string(), *rule.value());
+        }
+    }
+}
+
+/// 获取路由的限流规则,先匹配静态路由,再匹配动态路由
+fn get_route_rate_limit(path: &str) -> Option<RateLimitRule> {
+    if let Some(rule) = STATIC_ROUTE_RATE_LIMITS.get(path) {
+        return Some(*rule.value());
+    }
+    DYNAMIC_ROUTE_RATE_LIMITS
+        .iter()
+        .find(|entry| matches_route(entry.key(), path))
+        .map(|entry| *entry.value())
+}
+
+/// 限流结果
+struct RateLimitDecision {
+    allowed: bool,
+    limit: u64,
+    remaining: u64,
+    reset: u64, // 秒
+}
+
+/// 限流中间件,需放在request_context_handler之后
+/// 路由通过rate_limit宏配置规则,未配置的路由使用设备类型的默认规则
+pub async fn rate_limit_handler(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let path = get_route_path(&request);
+    let request_context = request.extensions().get::<RequestContext>().cloned().unwrap_or_default();
+    let (scope, rule) = match get_route_rate_limit(&path) {
+        Some(rule) => (path.clone(), rule),
+        None => {
+            let device = if request_context.device_type.is_empty() {
+                let web: &'static str = DeviceType::Web.into();
+                web.to_string()
+            } else {
+                request_context.device_type.clone()
+            };
+            match DEVICE_RATE_LIMITS.get(&device) {
+                Some(rule) => (format!("default:{}", device), *rule.value()),
+                None => return Ok(next.run(request).await),
+            }
+        }
+    };
+
+    let identity = get_identity(&request, &request_context, rule.key);
+    let redis_key = rate_limit_key(&scope, &identity);
+    let decision = match check(&redis_key, &rule).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            // redis不可用时不阻断业务
+            error!("rate limit check error, key: {}, {}", redis_key, e);
+            return Ok(next.run(request).await);
+        }
+    };
+
+    if !decision.allowed {
+        info!("rate limited, key: {}", redis_key);
+        let result: CommonResult<()> = CommonResult {
+            code: Some(StatusCode::TOO_MANY_REQUESTS.as_u16() as i32),
+            data: None,
+            message: Some("请求过于频繁,请稍后再试".to_string()),
+        };
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, result).into_response();
+        set_headers(response.headers_mut(), &decision);
+        if let Ok(value) = HeaderValue::from_str(&decision.reset.to_string()) {
+            response.headers_mut().insert("Retry-After", value);
+        }
+        return Ok(response);
+    }
+
+    let mut response = next.run(request).await;
+    set_headers(response.headers_mut(), &decision);
+    Ok(response)
+}
+
+/// 获取限流对象标识
+fn get_identity(request: &Request, request_context: &RequestContext, key: RateLimitKey) -> String {
+    let login_user = request.extensions().get::<LoginUserContext>();
+    let identity = match key {
+        RateLimitKey::Ip => None,
+        RateLimitKey::User => login_user.map(|u| format!("user:{}", u.id)),
+        RateLimitKey::Tenant => login_user.map(|u| format!("tenant:{}", u.tenant_id)),
+        RateLimitKey::ApiKey => request.headers()
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .map(|v| format!("api_key:{}", v)),
+    };
+    identity.unwrap_or_else(|| format!("ip:{}", request_context.ip))
+}
+
+/// 限流redis key,scope为路由或默认规则的设备类型
+fn rate_limit_key(scope: &str, identity: &str) -> String {
+    format!("{}{}:{}", REDIS_KEY_RATE_LIMIT_PREFIX, scope, identity)
+}
+
+async fn check(redis_key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let now_ms = now.as_millis() as u64;
+    let window_ms = rule.window * 1000;
+    // 同一毫秒内的请求需要不同的member
+    let member = format!("{}-{}-{}", now.as_nanos(), std::process::id(), REQUEST_SEQUENCE.fetch_add(1, Ordering::Relaxed));
+    let (allowed, remaining, reset): (i64, i64, i64) = AsyncRedisManager::invoke_script(
+        &SLIDING_WINDOW_SCRIPT,
+        &[redis_key.to_string()],
+        &[now_ms.to_string(), window_ms.to_string(), rule.limit.to_string(), member],
+    ).await?;
+    Ok(decide(rule, allowed, remaining, reset))
+}
+
+/// 转换脚本返回值,重置时间向上取整到秒
+fn decide(rule: &RateLimitRule, allowed: i64, remaining: i64, reset_ms: i64) -> RateLimitDecision {
+    RateLimitDecision {
+        allowed: allowed == 1,
+        limit: rule.limit,
+        remaining: remaining.max(0) as u64,
+        reset: (reset_ms.max(0) as u64 + 999) / 1000,
+    }
+}
+
+/// 设置标准限流响应头
+fn set_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    let values = [
+        ("RateLimit-Limit", decision.limit),
+        ("RateLimit-Remaining", decision.remaining),
+        ("RateLimit-Reset", decision.reset),
+    ];
+    for (name, value) in values {
+        if let Ok(value) = HeaderValue::from_str(&value.to_string()) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn login_user(id: i64, tenant_id: i64) -> LoginUserContext {
+        LoginUserContext {
+            device_type: "web".to_string(),
+            id,
+            nickname: "test".to_string(),
+            tenant_id,
+            department_id: 0,
+            department_code: String::new(),
+            role_id: 0,
+            permissions: vec![],
+            data_permission: None,
+        }
+    }
+
+    fn request_context(ip: &str) -> RequestContext {
+        RequestContext { ip: ip.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(RateLimitKey::from_str("IP"), Ok(RateLimitKey::Ip));
+        assert_eq!(RateLimitKey::from_str("user"), Ok(RateLimitKey::User));
+        assert_eq!(RateLimitKey::from_str("tenant"), Ok(RateLimitKey::Tenant));
+        assert_eq!(RateLimitKey::from_str("api_key"), Ok(RateLimitKey::ApiKey));
+        assert_eq!(RateLimitKey::from_str("apikey"), Ok(RateLimitKey::ApiKey));
+        assert!(RateLimitKey::from_str("device").is_err());
+    }
+
+    #[test]
+    fn test_register_unknown_key_falls_back_to_ip() {
+        register_operation_rate_limit("test_rate_limit_unknown_key", 5, 10, "device");
+        let rule = *OPERATION_RATE_LIMITS.get("test_rate_limit_unknown_key").unwrap();
+        assert_eq!(rule, RateLimitRule::new(5, 10, RateLimitKey::Ip));
+    }
+
+    #[test]
+    fn test_route_rate_limit() {
+        register_operation_rate_limit("test_rate_limit_static", 10, 60, "ip");
+        register_operation_rate_limit("test_rate_limit_dynamic", 20, 30, "user");
+        register_route_rate_limit("/test_rate_limit/login", "test_rate_limit_static");
+        register_route_rate_limit("/test_rate_limit/file/{id}", "test_rate_limit_dynamic");
+        // 未注册限流的操作不关联路由
+        register_route_rate_limit("/test_rate_limit/none", "test_rate_limit_none");
+
+        assert_eq!(get_route_rate_limit("/test_rate_limit/login"), Some(RateLimitRule::new(10, 60, RateLimitKey::Ip)));
+        assert_eq!(get_route_rate_limit("/test_rate_limit/file/1"), Some(RateLimitRule::new(20, 30, RateLimitKey::User)));
+        assert!(STATIC_ROUTE_RATE_LIMITS.get("/test_rate_limit/file/{id}").is_none());
+        assert_eq!(get_route_rate_limit("/test_rate_limit/none"), None);
+        assert_eq!(get_route_rate_limit("/test_rate_limit/file/1/2"), None);
+    }
+
+    #[test]
+    fn test_identity() {
+        let context = request_context("127.0.0.1");
+        let request = axum::http::Request::builder().uri("/").body(Body::empty()).unwrap();
+        // 未登录和没有api key时按ip
+        assert_eq!(get_identity(&request, &context, RateLimitKey::Ip), "ip:127.0.0.1");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::User), "ip:127.0.0.1");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::Tenant), "ip:127.0.0.1");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::ApiKey), "ip:127.0.0.1");
+
+        let mut request = axum::http::Request::builder().uri("/").header(API_KEY_HEADER, "key1").body(Body::empty()).unwrap();
+        request.extensions_mut().insert(login_user(1, 2));
+        assert_eq!(get_identity(&request, &context, RateLimitKey::Ip), "ip:127.0.0.1");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::User), "user:1");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::Tenant), "tenant:2");
+        assert_eq!(get_identity(&request, &context, RateLimitKey::ApiKey), "api_key:key1");
+
+        let request = axum::http::Request::builder().uri("/").header(API_KEY_HEADER, "").body(Body::empty()).unwrap();
+        assert_eq!(get_identity(&request, &context, RateLimitKey::ApiKey), "ip:127.0.0.1");
+    }
+
+    #[test]
+    fn test_rate_limit_key() {
+        assert_eq!(
+            rate_limit_key("/system_auth/login", "ip:127.0.0.1"),
+            format!("{}/system_auth/login:ip:127.0.0.1", REDIS_KEY_RATE_LIMIT_PREFIX)
+        );
+        assert_eq!(
+            rate_limit_key("default:web", "user:1"),
+            format!("{}default:web:user:1", REDIS_KEY_RATE_LIMIT_PREFIX)
+        );
+    }
+
+    #[test]
+    fn test_decide() {
+        let rule = RateLimitRule::new(10, 60, RateLimitKey::Ip);
+        let decision = decide(&rule, 1, 9, 60000);
+        assert!(decision.allowed);
+        assert_eq!((decision.limit, decision.remaining, decision.reset), (10, 9, 60));
+        // 不足一秒向上取整
+        let decision = decide(&rule, 0, 0, 1);
+        assert!(!decision.allowed);
+        assert_eq!((decision.remaining, decision.reset), (0, 1));
+        let decision = decide(&rule, 0, -1, -5);
+        assert_eq!((decision.remaining, decision.reset), (0, 0));
+    }
+
+    #[test]
+    fn test_set_headers() {
+        let mut headers = HeaderMap::new();
+        set_headers(&mut headers, &RateLimitDecision { allowed: true, limit: 10, remaining: 3, reset: 42 });
+        assert_eq!(headers.get("RateLimit-Limit").unwrap(), "10");
+        assert_eq!(headers.get("RateLimit-Remaining").unwrap(), "3");
+        assert_eq!(headers.get("RateLimit-Reset").unwrap(), "42");
+    }
+
+    async fn invoke(conn: &mut redis::aio::MultiplexedConnection, key: &str, now: u64, member: &str) -> (i64, i64, i64) {
+        SLIDING_WINDOW_SCRIPT
+            .key(key)
+            .arg(now)
+            .arg(1000)
+            .arg(2)
+            .arg(member)
+            .invoke_async(conn)
+            .await
+            .unwrap()
+    }
+
+    /// 需要本地redis
+    #[tokio::test]
+    #[ignore]
+    async fn test_sliding_window_script() {
+        let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        let key = rate_limit_key("test", "sliding_window");
+        let _: () = redis::cmd("DEL").arg(&key).query_async(&mut conn).await.unwrap();
+
+        assert_eq!(invoke(&mut conn, &key, 1000, "a").await, (1, 1, 1000));
+        assert_eq!(invoke(&mut conn, &key, 1100, "b").await, (1, 0, 900));
+        // 超过限制时不记录请求
+        assert_eq!(invoke(&mut conn, &key, 1200, "c").await, (0, 0, 800));
+        assert_eq!(invoke(&mut conn, &key, 1300, "d").await, (0, 0, 700));
+        // 窗口滑过第一个请求后放行
+        assert_eq!(invoke(&mut conn, &key, 2001, "e").await, (1, 0, 99));
+        let ttl: i64 = redis::cmd("PTTL").arg(&key).query_async(&mut conn).await.unwrap();
+        assert!(ttl > 0 && ttl <= 1000);
+
+        let _: () = redis::cmd("DEL").arg(&key).query_async(&mut conn).await.unwrap();
+    }
+}
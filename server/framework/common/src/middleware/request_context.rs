@@ -1,7 +1,8 @@
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use axum::{
     body::{Body, Bytes},
-    extract::{OriginalUri, Request},
+    extract::{MatchedPath, OriginalUri, Request},
     http::StatusCode,
     middleware::Next,
     response::IntoResponse,
@@ -12,11 +13,18 @@ use axum::extract::{ConnectInfo, State};
 use axum::http::request::Parts;
 use axum_extra::TypedHeader;
 use headers::UserAgent;
-use tracing::info;
+use serde_json::Value;
+use axum::http::HeaderValue;
+use axum::http::header::CONTENT_TYPE;
+use tracing::{info, Instrument};
 use uaparser::{Parser, UserAgentParser};
 use crate::{config::config::Config, constants::enums::DeviceType};
-use crate::context::context::RequestContext;
+use crate::context::context::{LoginUserContext, RequestContext};
 use crate::state::app_state::AppState;
+use crate::middleware::authorize::get_route_path;
+use crate::utils::redaction;
+use crate::utils::trace_utils::{self, TraceContext, TRACE_ID_HEADER};
+use crate::formatter::extend_field;
 
 /// req上下文注入中间件 同时进行jwt授权验证
 pub async fn request_context_handler(State(state): State<AppState>, user_agent: Option<TypedHeader<UserAgent>>, request: Request, next: Next) -> Result<impl IntoResponse, (StatusCode, String)> {
@@ -29,11 +37,58 @@ pub async fn request_context_handler(State(state): State<AppState>, user_agent:
     let method = request.method().to_string();
     let path_params = request.uri().query().unwrap_or("").to_string();
 
+    // 链路追踪,使用上游传递的traceparent或生成新的链路
+    let mut trace = TraceContext::from_headers(request.headers());
+    // 路由模板,日志按接口聚合
+    let route = request.extensions().get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| request_url.clone());
+    let span = tracing::info_span!(
+        "request",
+        method = %method,
+        url = %request_url,
+        route = %route,
+        trace_id = tracing::field::Empty,
+        span_id = tracing::field::Empty,
+        tenant_id = tracing::field::Empty,
+        user_id = tracing::field::Empty,
+    );
+    trace.attach(&span);
+    if let Some(login_user) = request.extensions().get::<LoginUserContext>() {
+        span.record("tenant_id", login_user.tenant_id);
+        span.record("user_id", login_user.id);
+    }
+
+    // 文件上传等不记录内容,也不读取到内存
+    let redactor = redaction::redactor();
+    let content_type = request.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let skip_body = redactor.is_skipped(&content_type);
+    let route_path = get_route_path(&request);
+    // 查询参数可能包含令牌,如文件下载的 ?token=
+    let path_params = redactor.redact_body(&route_path, &path_params);
+
     let (parts, req_body) = request.into_parts();
 
-    let (bytes, body_data) = match get_body_data(req_body).await {
-        Err(e) => return Err(e),
-        Ok((x, y)) => (x, y),
+    let (body, body_data, body_json, body_hash) = if skip_body {
+        (req_body, format!("[{} omitted]", content_type), None, String::new())
+    } else {
+        match get_body_data(req_body).await {
+            Err(e) => return Err(e),
+            // 脱敏后保存,日志中不出现密码等敏感信息,摘要使用原始数据
+            Ok((bytes, data)) => {
+                let body_hash = format!("{:x}", md5::compute(&bytes));
+                // 日志内容会截断,审计需要完整的参数,json单独保存
+                let body_json = serde_json::from_str::<Value>(&data).ok().map(|mut value| {
+                    redactor.redact_value(&route_path, &mut value);
+                    Arc::new(value)
+                });
+                (Body::from(bytes), redactor.redact_body(&route_path, &data), body_json, body_hash)
+            }
+        }
     };
 
     // 连接信息
@@ -84,16 +139,26 @@ pub async fn request_context_handler(State(state): State<AppState>, user_agent:
         path_params,
         method: method.to_string(),
         data: body_data.clone(),
+        json: body_json,
+        body_hash,
         ip,
         user_agent: user_agent_header.to_string(),
         device_type: device_str.to_string(),
+        trace_id: trace.trace_id.clone(),
     };
-    info!("request context: {:?}", request_context);
-    let mut request = Request::from_parts(parts, Body::from(bytes));
+    span.in_scope(|| info!("request context: {:?}", request_context));
+    let mut request = Request::from_parts(parts, body);
 
     request.extensions_mut().insert(request_context);
+    request.extensions_mut().insert(trace.clone());
 
-    let response = next.run(request).await;
+    let trace_id = trace.trace_id.clone();
+    let mut response = trace_utils::scope(trace, extend_field::scope(next.run(request)))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&trace_id) {
+        response.headers_mut().insert(TRACE_ID_HEADER, value);
+    }
     Ok(response)
 }
 
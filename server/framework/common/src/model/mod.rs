@@ -0,0 +1 @@
+pub mod system_background_job;
\ No newline at end of file
@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "system_background_job")]
+pub struct Model {
+    
+    #[sea_orm(primary_key)]
+    pub id: i64, // 作业id
+    
+    pub job_type: String, // 作业类型
+    
+    pub payload: String, // 作业参数
+    
+    pub priority: i32, // 优先级,大的先执行
+    
+    pub status: i8, // 状态（0等待执行 1执行中 2成功 3失败 4已取消）
+    
+    pub progress: i32, // 进度（0-100）
+    
+    pub progress_message: Option<String>, // 进度说明
+    
+    pub attempts: i32, // 已执行次数
+    
+    pub cancel_requested: bool, // 是否请求取消
+    
+    pub run_at: NaiveDateTime, // 可执行时间,失败重试时延后
+    
+    pub worker: Option<String>, // 执行实例
+    
+    pub start_time: Option<NaiveDateTime>, // 开始时间
+    
+    pub heartbeat_time: Option<NaiveDateTime>, // 心跳时间
+    
+    pub finish_time: Option<NaiveDateTime>, // 结束时间
+    
+    pub artifact_file_id: Option<i64>, // 结果文件id
+    
+    pub error: Option<String>, // 错误信息
+    
+    pub department_code: String, // 部门编码
+    
+    pub department_id: i64, // 部门ID
+    
+    pub creator: Option<i64>, // 创建者id
+    
+    pub create_time: NaiveDateTime, // 创建时间
+    
+    pub update_time: NaiveDateTime, // 更新时间
+    
+    pub tenant_id: i64, // 租户编号
+    
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<Entity> for Entity {
+    fn to() -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use once_cell::sync::Lazy;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::database::mongo::MongoManager;
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::monitor::metrics;
+use crate::shutdown::shutdown::ShutdownSignal;
+use crate::utils::grpc_tls;
+use crate::utils::minio_utils::MinioClient;
+
+/// 单项检查超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 当前服务实例的标识,主机名加进程id
+static INSTANCE_ID: Lazy<String> = Lazy::new(|| {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+    format!("{}-{}", host, std::process::id())
+});
+
+pub fn instance_id() -> &'static str {
+    &INSTANCE_ID
+}
+
+type CheckFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type CheckFn = Arc<dyn Fn() -> CheckFuture + Send + Sync>;
+
+/// 就绪检查项,按服务实际依赖添加
+#[derive(Clone, Default)]
+pub struct HealthChecker {
+    db: Option<DatabaseConnection>,
+    redis: bool,
+    mongo: bool,
+    minio: Option<MinioClient>,
+    grpc: Vec<(String, String)>, // (名称, 地址)
+    custom: Vec<(String, CheckFn)>, // 自定义检查
+    shutdown: Option<ShutdownSignal>, // 收到退出信号后就绪检查返回DOWN
+}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_database(mut self, db: DatabaseConnection) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    pub fn with_redis(mut self) -> Self {
+        self.redis = true;
+        self
+    }
+
+    pub fn with_mongo(mut self) -> Self {
+        self.mongo = true;
+        self
+    }
+
+    pub fn with_minio(mut self, minio: MinioClient) -> Self {
+        self.minio = Some(minio);
+        self
+    }
+
+    pub fn with_grpc(mut self, name: &str, url: &str) -> Self {
+        self.grpc.push((name.to_string(), url.to_string()));
+        self
+    }
+
+    /// 自定义检查项,返回错误时就绪检查失败
+    pub fn with_check<F, Fut>(mut self, name: &str, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.custom.push((name.to_string(), Arc::new(move || Box::pin(check()))));
+        self
+    }
+
+    /// 退出信号,默认使用全局退出信号
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    fn is_shutdown(&self) -> bool {
+        self.shutdown.as_ref().is_some_and(|shutdown| shutdown.is_shutdown())
+    }
+
+    fn checks(&self) -> Vec<(String, CheckFuture)> {
+        let mut checks: Vec<(String, CheckFuture)> = Vec::new();
+        if let Some(db) = self.db.clone() {
+            checks.push(("mysql".to_string(), Box::pin(async move {
+                db.ping().await?;
+                Ok(())
+            })));
+        }
+        if self.redis {
+            checks.push(("redis".to_string(), Box::pin(async move {
+                AsyncRedisManager::ping().await?;
+                Ok(())
+            })));
+        }
+        if self.mongo {
+            checks.push(("mongo".to_string(), Box::pin(async move {
+                if !MongoManager::is_initialized() {
+                    return Err(anyhow::anyhow!("mongo is not initialized"));
+                }
+                MongoManager::get().ping().await?;
+                Ok(())
+            })));
+        }
+        if let Some(minio) = self.minio.clone() {
+            checks.push(("minio".to_string(), Box::pin(async move {
+                minio.ping().await
+            })));
+        }
+        for (name, url) in self.grpc.clone() {
+            checks.push((format!("grpc_{}", name), Box::pin(async move {
+                grpc_tls::endpoint(&url)?
+                    .connect_timeout(CHECK_TIMEOUT)
+                    .connect()
+                    .await?;
+                Ok(())
+            })));
+        }
+        for (name, check) in &self.custom {
+            checks.push((name.clone(), check()));
+        }
+        checks
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct CheckResult {
+    status: &'static str, // UP/DOWN
+    duration_ms: u128, // 检查耗时
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>, // 错误信息
+}
+
+#[derive(Serialize, Debug)]
+struct HealthResponse {
+    status: &'static str, // UP/DOWN
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    checks: BTreeMap<String, CheckResult>, // 各依赖检查结果
+}
+
+/// 健康检查和指标路由: /health/live, /health/ready, /metrics
+pub fn health_router(checker: HealthChecker) -> Router {
+    let checker = match checker.shutdown {
+        Some(_) => checker,
+        None => checker.with_shutdown(ShutdownSignal::listen()),
+    };
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .route("/metrics", get(metrics_text))
+        .with_state(Arc::new(checker))
+}
+
+/// 存活检查,进程可以响应即返回成功
+async fn live() -> Json<HealthResponse> {
+    Json(HealthResponse { status: "UP", checks: BTreeMap::new() })
+}
+
+/// 就绪检查,所有依赖可用时返回200,否则返回503
+/// 收到退出信号后直接返回503,负载均衡在排空期间不再转发新请求
+async fn ready(State(checker): State<Arc<HealthChecker>>) -> Response {
+    if checker.is_shutdown() {
+        let mut checks = BTreeMap::new();
+        checks.insert("shutdown".to_string(), CheckResult { status: "DOWN", duration_ms: 0, error: Some("shutting down".to_string()) });
+        let body = HealthResponse { status: "DOWN", checks };
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+    }
+
+    let start = Instant::now();
+    let handles: Vec<_> = checker.checks()
+        .into_iter()
+        .map(|(name, check)| {
+            let handle = tokio::spawn(async move {
+                let start = Instant::now();
+                let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+                    Ok(result) => result,
+                    Err(_) => Err(anyhow::anyhow!("timeout after {:?}", CHECK_TIMEOUT)),
+                };
+                let duration_ms = start.elapsed().as_millis();
+                match result {
+                    Ok(_) => CheckResult { status: "UP", duration_ms, error: None },
+                    Err(e) => CheckResult { status: "DOWN", duration_ms, error: Some(e.to_string()) },
+                }
+            });
+            (name, handle)
+        })
+        .collect();
+
+    let mut checks = BTreeMap::new();
+    for (name, handle) in handles {
+        // 检查panic时视为不可用
+        let result = handle.await.unwrap_or_else(|e| CheckResult {
+            status: "DOWN",
+            duration_ms: start.elapsed().as_millis(),
+            error: Some(format!("check panicked: {}", e)),
+        });
+        checks.insert(name, result);
+    }
+    let up = checks.values().all(|c| c.status == "UP");
+    let status_code = if up { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    let body = HealthResponse { status: if up { "UP" } else { "DOWN" }, checks };
+    (status_code, Json(body)).into_response()
+}
+
+/// prometheus指标
+async fn metrics_text(State(checker): State<Arc<HealthChecker>>) -> Response {
+    let body = metrics::gather(checker.db.as_ref(), checker.redis).await;
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        body,
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use axum::body::Body;
+    use axum::http::Request;
+    use serde_json::Value;
+    use tower::Service;
+
+    fn checker() -> HealthChecker {
+        HealthChecker::new().with_shutdown(ShutdownSignal::detached())
+    }
+
+    async fn call_ready(checker: HealthChecker) -> (StatusCode, Value) {
+        let response = ready(State(Arc::new(checker))).await;
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn panicking_check() -> anyhow::Result<()> {
+        panic!("check failed")
+    }
+
+    #[tokio::test]
+    async fn test_live() {
+        let Json(body) = live().await;
+        assert_eq!(body.status, "UP");
+        assert!(body.checks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ready() {
+        let checker = checker().with_check("a", || async { Ok(()) });
+        let (status, body) = call_ready(checker).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "UP");
+        assert_eq!(body["checks"]["a"]["status"], "UP");
+    }
+
+    #[tokio::test]
+    async fn test_ready_check_failed() {
+        let checker = checker()
+            .with_check("a", || async { Ok(()) })
+            .with_check("b", || async { Err(anyhow::anyhow!("connection refused")) });
+        let (status, body) = call_ready(checker).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "DOWN");
+        assert_eq!(body["checks"]["a"]["status"], "UP");
+        assert_eq!(body["checks"]["b"]["status"], "DOWN");
+        assert_eq!(body["checks"]["b"]["error"], "connection refused");
+    }
+
+    #[tokio::test]
+    async fn test_ready_check_panicked() {
+        let checker = checker().with_check("a", panicking_check);
+        let (status, body) = call_ready(checker).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["a"]["status"], "DOWN");
+        assert!(body["checks"]["a"]["error"].as_str().unwrap().starts_with("check panicked"));
+    }
+
+    #[tokio::test]
+    async fn test_ready_after_shutdown() {
+        let shutdown = ShutdownSignal::detached();
+        let called = Arc::new(AtomicBool::new(false));
+        let flag = called.clone();
+        let checker = HealthChecker::new()
+            .with_shutdown(shutdown.clone())
+            .with_check("a", move || {
+                flag.store(true, Ordering::SeqCst);
+                async { Ok(()) }
+            });
+        let checker = Arc::new(checker);
+        let response = ready(State(checker.clone())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        shutdown.trigger();
+        called.store(false, Ordering::SeqCst);
+        let response = ready(State(checker)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["checks"]["shutdown"]["status"], "DOWN");
+        // 退出中不再检查依赖
+        assert!(!called.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_route() {
+        metrics::record_task_execution("test_health_metrics", true, Duration::from_millis(1));
+        let mut router = health_router(checker());
+        let request = Request::builder().uri("/metrics").body(Body::empty()).unwrap();
+        let response = router.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/plain; version=0.0.4"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("task_executions_total"));
+        assert!(body.contains(r#"task="test_health_metrics""#));
+    }
+}
@@ -0,0 +1,280 @@
+use std::time::{Duration, Instant};
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, Encoder, HistogramVec,
+    IntCounterVec, IntGaugeVec, TextEncoder,
+};
+use sea_orm::DatabaseConnection;
+use tracing::{error, warn};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::event::log_stream::{LogQueue, LOG_CONSUMER_GROUP};
+
+/// 采集日志积压的超时时间,redis不可用时不阻塞 /metrics 的其他指标
+const LOGGER_QUEUE_GATHER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// http请求数
+static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "Total number of HTTP requests",
+        &["method", "route", "status"]
+    ).expect("register http_requests_total")
+});
+/// http请求耗时
+static HTTP_REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency in seconds",
+        &["method", "route"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    ).expect("register http_request_duration_seconds")
+});
+/// 数据库连接池
+static DB_POOL_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "db_pool_connections",
+        "Database pool connections by state",
+        &["state"]
+    ).expect("register db_pool_connections")
+});
+/// 定时任务执行次数
+static TASK_EXECUTIONS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "task_executions_total",
+        "Total number of scheduled task executions",
+        &["task", "result"]
+    ).expect("register task_executions_total")
+});
+/// 定时任务执行耗时
+static TASK_EXECUTION_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "task_execution_duration_seconds",
+        "Scheduled task execution time in seconds",
+        &["task"]
+    ).expect("register task_execution_duration_seconds")
+});
+/// 日志队列长度
+static LOGGER_QUEUE_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "logger_queue_depth",
+        "Number of logs waiting in redis",
+        &["queue"]
+    ).expect("register logger_queue_depth")
+});
+/// 已投递未确认的日志数
+static LOGGER_QUEUE_PENDING: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "logger_queue_pending",
+        "Number of logs delivered but not yet saved",
+        &["queue"]
+    ).expect("register logger_queue_pending")
+});
+/// 死信日志数
+static LOGGER_DEAD_LETTER_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "logger_dead_letter_depth",
+        "Number of logs in dead letter stream",
+        &["queue"]
+    ).expect("register logger_dead_letter_depth")
+});
+/// 日志处理数 saved/retry/dead_letter
+static LOGGER_MESSAGES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "logger_messages_total",
+        "Total number of logs consumed by result",
+        &["queue", "result"]
+    ).expect("register logger_messages_total")
+});
+/// 未消费的日志被stream最大长度裁剪的次数,出现时需要调大 logger_server.stream_max_len 或提高消费能力
+static LOGGER_QUEUE_TRIMMED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "logger_queue_trimmed_total",
+        "Number of times unconsumed logs were trimmed by stream max length",
+        &["queue"]
+    ).expect("register logger_queue_trimmed_total")
+});
+/// grpc客户端调用次数
+static GRPC_CLIENT_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "grpc_client_requests_total",
+        "Total number of gRPC client calls",
+        &["service", "rpc", "code"]
+    ).expect("register grpc_client_requests_total")
+});
+/// grpc客户端调用耗时,包括重试
+static GRPC_CLIENT_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "grpc_client_duration_seconds",
+        "gRPC client call latency in seconds",
+        &["service", "rpc"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    ).expect("register grpc_client_duration_seconds")
+});
+/// grpc客户端重试次数
+static GRPC_CLIENT_RETRIES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "grpc_client_retries_total",
+        "Total number of gRPC client retries",
+        &["service", "rpc"]
+    ).expect("register grpc_client_retries_total")
+});
+/// grpc客户端熔断状态 0关闭 1打开 2半开
+static GRPC_CLIENT_CIRCUIT_STATE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "grpc_client_circuit_state",
+        "gRPC client circuit breaker state, 0 closed, 1 open, 2 half open",
+        &["service"]
+    ).expect("register grpc_client_circuit_state")
+});
+
+/// 请求指标中间件,记录各路由的请求数和耗时
+pub async fn metrics_handler(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => normalize_path(request.uri().path()),
+    };
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let status = response.status().as_u16().to_string();
+    HTTP_REQUESTS_TOTAL.with_label_values(&[&method, &route, &status]).inc();
+    HTTP_REQUEST_DURATION_SECONDS.with_label_values(&[&method, &route]).observe(elapsed);
+    response
+}
+
+/// 没有匹配路由时将数字段替换为{id},避免标签数量过多
+fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 记录定时任务执行结果
+pub fn record_task_execution(task: &str, success: bool, duration: Duration) {
+    let result = if success { "success" } else { "error" };
+    TASK_EXECUTIONS_TOTAL.with_label_values(&[task, result]).inc();
+    TASK_EXECUTION_DURATION_SECONDS.with_label_values(&[task]).observe(duration.as_secs_f64());
+}
+
+/// 记录grpc客户端调用结果
+pub fn record_grpc_call(service: &str, rpc: &str, code: &str, duration: Duration) {
+    GRPC_CLIENT_REQUESTS_TOTAL.with_label_values(&[service, rpc, code]).inc();
+    GRPC_CLIENT_DURATION_SECONDS.with_label_values(&[service, rpc]).observe(duration.as_secs_f64());
+}
+
+/// 记录日志处理结果
+pub fn record_logger_messages(queue: &str, result: &str, count: usize) {
+    LOGGER_MESSAGES_TOTAL.with_label_values(&[queue, result]).inc_by(count as u64);
+}
+
+/// 日志积压情况,stream或消费组未创建时跳过
+async fn gather_logger_queue(queue: LogQueue) {
+    let name = queue.name();
+    match AsyncRedisManager::stream_group_info(queue.stream_key(), LOG_CONSUMER_GROUP).await {
+        Ok(Some(group)) => {
+            LOGGER_QUEUE_PENDING.with_label_values(&[name]).set(group.pending as i64);
+            // redis 7以下无法计算未投递数,使用stream长度
+            let depth = match group.lag {
+                Some(lag) => lag,
+                None => AsyncRedisManager::stream_len(queue.stream_key()).await.unwrap_or_default(),
+            };
+            LOGGER_QUEUE_DEPTH.with_label_values(&[name]).set(depth as i64);
+        }
+        Ok(None) => {}
+        Err(e) if e.code() == Some("ERR") => {} // stream不存在
+        Err(e) => error!("get logger queue depth error, queue: {}, {}", name, e),
+    }
+    if let Ok(len) = AsyncRedisManager::stream_len(queue.dead_letter_key()).await {
+        LOGGER_DEAD_LETTER_DEPTH.with_label_values(&[name]).set(len as i64);
+    }
+}
+
+/// 记录未消费的日志被stream最大长度裁剪
+pub fn record_logger_trimmed(queue: &str) {
+    LOGGER_QUEUE_TRIMMED_TOTAL.with_label_values(&[queue]).inc();
+}
+
+/// 记录grpc客户端重试
+pub fn record_grpc_retry(service: &str, rpc: &str) {
+    GRPC_CLIENT_RETRIES_TOTAL.with_label_values(&[service, rpc]).inc();
+}
+
+/// 记录grpc客户端熔断状态
+pub fn set_grpc_circuit_state(service: &str, state: i64) {
+    GRPC_CLIENT_CIRCUIT_STATE.with_label_values(&[service]).set(state);
+}
+
+/// 采集需要实时计算的指标并输出prometheus文本格式,logger_queue为false时不采集redis中的日志积压
+pub async fn gather(db: Option<&DatabaseConnection>, logger_queue: bool) -> String {
+    if let Some(db) = db {
+        let pool = db.get_mysql_connection_pool();
+        let size = pool.size() as i64;
+        let idle = pool.num_idle() as i64;
+        DB_POOL_CONNECTIONS.with_label_values(&["total"]).set(size);
+        DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+        DB_POOL_CONNECTIONS.with_label_values(&["active"]).set(size - idle);
+    }
+    if logger_queue {
+        let queues = futures_util::future::join_all(LogQueue::ALL.map(gather_logger_queue));
+        if tokio::time::timeout(LOGGER_QUEUE_GATHER_TIMEOUT, queues).await.is_err() {
+            warn!("gather logger queue metrics timeout");
+        }
+    }
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        error!("encode metrics error: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::Service;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/system/system_user/123"), "/system/system_user/{id}");
+        assert_eq!(normalize_path("/system/v1/list"), "/system/v1/list");
+        assert_eq!(normalize_path("/"), "/");
+    }
+
+    #[tokio::test]
+    async fn test_metrics_handler_uses_matched_path() {
+        let mut router: Router = Router::new()
+            .route("/test_metrics/{id}", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(metrics_handler));
+        let labels = ["GET", "/test_metrics/{id}", "200"];
+        let before = HTTP_REQUESTS_TOTAL.with_label_values(&labels).get();
+        for id in [1, 2] {
+            let request = axum::http::Request::builder().uri(format!("/test_metrics/{}", id)).body(Body::empty()).unwrap();
+            router.call(request).await.unwrap();
+        }
+        assert_eq!(HTTP_REQUESTS_TOTAL.with_label_values(&labels).get(), before + 2);
+        assert!(HTTP_REQUEST_DURATION_SECONDS.with_label_values(&labels[..2]).get_sample_count() >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_gather() {
+        record_grpc_call("test_gather", "Get", "Ok", Duration::from_millis(1));
+        let text = gather(None, false).await;
+        assert!(text.contains("# TYPE grpc_client_requests_total counter"));
+        assert!(text.contains(r#"service="test_gather""#));
+    }
+}
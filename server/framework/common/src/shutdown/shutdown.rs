@@ -0,0 +1,136 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{info, warn};
+use crate::database::mongo::MongoManager;
+use crate::database::mysql::close_database_instance;
+use crate::middleware::logger::shutdown_tracing;
+use crate::task::task_manager::TaskManager;
+use crate::utils::snowflake_generator::SnowflakeGenerator;
+
+static SHUTDOWN_SIGNAL: OnceLock<ShutdownSignal> = OnceLock::new();
+/// 未完成的后台写入数量(日志写入redis等)
+static PENDING_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// 退出信号,收到SIGINT/SIGTERM或手动触发后通知所有监听者
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    /// 获取全局退出信号,第一次调用时注册系统信号监听
+    pub fn listen() -> ShutdownSignal {
+        SHUTDOWN_SIGNAL.get_or_init(|| {
+            let signal = ShutdownSignal::detached();
+            let trigger = signal.clone();
+            tokio::spawn(async move {
+                wait_os_signal().await;
+                info!("received exit signal, shutting down");
+                trigger.trigger();
+            });
+            signal
+        }).clone()
+    }
+
+    /// 不监听系统信号的退出信号,只能手动触发
+    pub(crate) fn detached() -> ShutdownSignal {
+        let (sender, receiver) = watch::channel(false);
+        ShutdownSignal { sender: Arc::new(sender), receiver }
+    }
+
+    /// 手动触发退出,如某个服务异常退出时通知其他服务
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// 等待退出信号
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        let _ = receiver.wait_for(|v| *v).await;
+    }
+
+    /// 等待退出信号,用于需要'static future的场景,如axum/tonic的graceful shutdown
+    pub async fn wait_owned(self) {
+        self.wait().await
+    }
+
+    /// 收到退出信号后再等待timeout,作为请求排空的截止时间
+    pub async fn drain_deadline(&self, timeout: Duration) {
+        self.wait().await;
+        tokio::time::sleep(timeout).await;
+        warn!("drain timeout after {:?}, force exit", timeout);
+    }
+}
+
+async fn wait_os_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// 启动需要在退出前完成的后台任务,如日志写入redis
+pub fn spawn_tracked<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    PENDING_TASKS.fetch_add(1, Ordering::SeqCst);
+    tokio::spawn(async move {
+        future.await;
+        PENDING_TASKS.fetch_sub(1, Ordering::SeqCst);
+    });
+}
+
+/// 等待后台任务完成,超时返回未完成的数量
+pub async fn flush_pending(timeout: Duration) -> usize {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let pending = PENDING_TASKS.load(Ordering::SeqCst);
+        if pending == 0 || Instant::now() >= deadline {
+            return pending;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// 服务停止后清理资源: 停止定时任务,等待未完成的日志写入,释放雪花算法机器id,关闭数据库和mongo连接,导出剩余链路
+pub async fn cleanup(task_manager: Option<&TaskManager>, timeout: Duration) {
+    if let Some(task_manager) = task_manager {
+        task_manager.shutdown_gracefully(timeout).await;
+    }
+    let pending = flush_pending(timeout).await;
+    if pending > 0 {
+        warn!("{} pending background tasks not finished before exit", pending);
+    }
+    SnowflakeGenerator::release_global().await;
+    close_database_instance().await;
+    MongoManager::close().await;
+    info!("shutdown completed");
+    shutdown_tracing();
+}
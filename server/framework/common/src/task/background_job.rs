@@ -0,0 +1,764 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use futures_util::FutureExt;
+use sea_orm::sea_query::{Alias, Expr, Func, Query, SimpleExpr, SubQueryStatement};
+use sea_orm::{ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Select, Statement, TransactionTrait, UpdateMany};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use crate::cache::distributed_lock::DistributedLock;
+use crate::config::config::JobQueueConfig;
+use crate::constants::enum_constants::{BACKGROUND_JOB_STATUS_CANCELLED, BACKGROUND_JOB_STATUS_FAILURE, BACKGROUND_JOB_STATUS_PENDING, BACKGROUND_JOB_STATUS_RUNNING, BACKGROUND_JOB_STATUS_SUCCESS, STATUS_ENABLE};
+use crate::context::context::LoginUserContext;
+use crate::model::system_background_job::{ActiveModel, Column, Entity, Model};
+use crate::monitor::health::instance_id;
+use crate::shutdown::shutdown::{spawn_tracked, ShutdownSignal};
+use crate::utils::minio_utils::MinioClient;
+
+pub use async_trait::async_trait;
+
+/// 作业类型
+pub const JOB_TYPE_ERP_INVENTORY_EXPORT: &str = "erp.inventory_export"; // 库存导出
+pub const JOB_TYPE_LOGGER_LOGIN_EXPORT: &str = "logger.login_export"; // 登录日志导出
+pub const JOB_TYPE_LOGGER_OPERATION_EXPORT: &str = "logger.operation_export"; // 操作日志导出
+
+/// 可以通过通用接口提交的作业类型
+/// 需要单独权限的作业(如日志导出)不能加入,只能通过各自带权限校验的接口提交
+pub const JOB_TYPES: &[&str] = &[
+    JOB_TYPE_ERP_INVENTORY_EXPORT,
+];
+
+/// 心跳间隔,同时刷新取消标记
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// 心跳超时(秒),执行中的作业超过该时间没有心跳视为执行的副本已退出,重新排队
+const HEARTBEAT_TIMEOUT_SECS: i64 = 120;
+/// 检查心跳超时作业的间隔
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+/// 领取作业的锁租期,领取时检查租户并发数,需要串行
+const CLAIM_LOCK_LEASE: Duration = Duration::from_secs(10);
+/// 重试的初始间隔(秒),每次失败翻倍
+const RETRY_BASE_SECS: i64 = 10;
+/// 重试的最大间隔(秒)
+const RETRY_MAX_SECS: i64 = 3600;
+/// 错误信息最大长度
+const MAX_ERROR_LEN: usize = 2000;
+
+/// 后台作业处理器,执行导出、导入、统计重建等耗时较长的工作
+/// 作业提交后保存在 system_background_job 表,由注册了对应处理器的服务领取执行
+#[async_trait]
+pub trait JobHandler: Send + Sync + 'static {
+    /// 作业类型,提交和领取时使用,修改后未执行的作业无法领取
+    const JOB_TYPE: &'static str;
+    /// 作业参数
+    type Payload: Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    /// 执行作业,返回的文件保存到 system_file 作为作业结果
+    async fn handle(&self, ctx: &JobContext, payload: Self::Payload) -> Result<Option<JobArtifact>>;
+
+    /// 最大执行次数,包括失败后的重试
+    fn max_attempts(&self) -> i32 {
+        3
+    }
+}
+
+/// 作业生成的文件
+pub struct JobArtifact {
+    pub file_name: String, // 文件名
+    pub content_type: String, // 文件类型
+    pub data: Vec<u8>, // 文件内容
+}
+
+/// 作业已取消,处理器检查到取消后返回该错误,作业不会重试
+#[derive(Debug)]
+pub struct JobCancelled;
+
+impl fmt::Display for JobCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "作业已取消")
+    }
+}
+
+impl std::error::Error for JobCancelled {}
+
+/// 作业执行上下文,用于上报进度和检查取消
+pub struct JobContext {
+    pub job_id: i64, // 作业id
+    pub tenant_id: i64, // 租户id
+    pub creator: Option<i64>, // 提交人
+    pub attempt: i32, // 第几次执行
+    db: DatabaseConnection,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    /// 上报进度(0-100),同时检查是否已取消,已取消或作业已被重新排队时返回 JobCancelled
+    pub async fn set_progress(&self, progress: i32, message: &str) -> Result<()> {
+        let result = Entity::update_many()
+            .col_expr(Column::Progress, Expr::value(progress.clamp(0, 100)))
+            .col_expr(Column::ProgressMessage, Expr::value(message))
+            .col_expr(Column::HeartbeatTime, now())
+            .filter(owned_condition(self.job_id, self.attempt))
+            .exec(&self.db).await?;
+        if result.rows_affected == 0 {
+            warn!("background job {} attempt {} is no longer owned by this worker", self.job_id, self.attempt);
+            self.cancelled.store(true, Ordering::SeqCst);
+        } else {
+            refresh_cancelled(&self.db, self.job_id, &self.cancelled).await?;
+        }
+        self.check_cancelled()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 已取消时返回 JobCancelled,处理器在循环中调用以尽快停止
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(JobCancelled.into());
+        }
+        Ok(())
+    }
+}
+
+/// 提交作业,返回作业id,优先级大的先执行
+pub async fn submit<H, C>(db: &C, login_user: &LoginUserContext, payload: &H::Payload, priority: i32) -> Result<i64>
+where
+    H: JobHandler,
+    C: ConnectionTrait,
+{
+    submit_raw(db, login_user, H::JOB_TYPE, serde_json::to_string(payload)?, priority).await
+}
+
+/// 按作业类型提交作业,参数为json
+pub async fn submit_raw<C: ConnectionTrait>(db: &C, login_user: &LoginUserContext, job_type: &str, payload: String, priority: i32) -> Result<i64> {
+    let result = Entity::insert(new_job(login_user, job_type, payload, priority)).exec(db).await?;
+    Ok(result.last_insert_id)
+}
+
+/// 待执行的作业
+fn new_job(login_user: &LoginUserContext, job_type: &str, payload: String, priority: i32) -> ActiveModel {
+    ActiveModel {
+        job_type: Set(job_type.to_string()),
+        payload: Set(payload),
+        priority: Set(priority),
+        status: Set(BACKGROUND_JOB_STATUS_PENDING),
+        run_at: Set(Local::now().naive_local()),
+        department_code: Set(login_user.department_code.clone()),
+        department_id: Set(login_user.department_id),
+        creator: Set(Some(login_user.id)),
+        tenant_id: Set(login_user.tenant_id),
+        ..Default::default()
+    }
+}
+
+/// 取消作业,等待中的直接取消,执行中的标记取消由处理器停止,返回作业是否可以取消
+pub async fn cancel(db: &DatabaseConnection, tenant_id: i64, id: i64) -> Result<bool> {
+    let result = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_CANCELLED))
+        .col_expr(Column::FinishTime, now())
+        .filter(Column::Id.eq(id))
+        .filter(Column::TenantId.eq(tenant_id))
+        .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_PENDING))
+        .exec(db).await?;
+    if result.rows_affected > 0 {
+        return Ok(true);
+    }
+    let result = Entity::update_many()
+        .col_expr(Column::CancelRequested, Expr::value(true))
+        .filter(Column::Id.eq(id))
+        .filter(Column::TenantId.eq(tenant_id))
+        .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_RUNNING))
+        .exec(db).await?;
+    Ok(result.rows_affected > 0)
+}
+
+#[async_trait]
+trait ErasedHandler: Send + Sync {
+    async fn run(&self, ctx: &JobContext, payload: &str) -> Result<Option<JobArtifact>>;
+    fn max_attempts(&self) -> i32;
+}
+
+struct TypedHandler<H>(H);
+
+#[async_trait]
+impl<H: JobHandler> ErasedHandler for TypedHandler<H> {
+    async fn run(&self, ctx: &JobContext, payload: &str) -> Result<Option<JobArtifact>> {
+        let payload: H::Payload = serde_json::from_str(payload).map_err(|e| anyhow!("作业参数错误: {}", e))?;
+        self.0.handle(ctx, payload).await
+    }
+
+    fn max_attempts(&self) -> i32 {
+        self.0.max_attempts()
+    }
+}
+
+/// 领取到的作业
+struct ClaimedJob {
+    id: i64,
+    job_type: String,
+    payload: String,
+    attempts: i32,
+    department_code: String,
+    department_id: i64,
+    creator: Option<i64>,
+    tenant_id: i64,
+}
+
+impl From<Model> for ClaimedJob {
+    fn from(model: Model) -> Self {
+        ClaimedJob {
+            id: model.id,
+            job_type: model.job_type,
+            payload: model.payload,
+            attempts: model.attempts + 1,
+            department_code: model.department_code,
+            department_id: model.department_id,
+            creator: model.creator,
+            tenant_id: model.tenant_id,
+        }
+    }
+}
+
+/// 执行期间的心跳协程,作业结束或执行协程退出时停止
+struct HeartbeatGuard(JoinHandle<()>);
+
+impl Drop for HeartbeatGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// 后台作业队列,注册处理器后启动工作协程
+pub struct JobQueue {
+    db: DatabaseConnection,
+    minio: Option<MinioClient>,
+    handlers: HashMap<&'static str, Arc<dyn ErasedHandler>>,
+}
+
+impl JobQueue {
+    pub fn new(db: DatabaseConnection) -> Self {
+        JobQueue { db, minio: None, handlers: HashMap::new() }
+    }
+
+    /// 作业结果文件的存储,不设置时返回文件的作业会失败
+    pub fn with_minio(mut self, minio: MinioClient) -> Self {
+        self.minio = Some(minio);
+        self
+    }
+
+    pub fn register<H: JobHandler>(mut self, handler: H) -> Self {
+        self.handlers.insert(H::JOB_TYPE, Arc::new(TypedHandler(handler)));
+        self
+    }
+
+    /// 启动工作协程,收到退出信号后不再领取新作业,执行中的作业在退出超时前完成
+    pub fn start(self, config: &JobQueueConfig) {
+        if self.handlers.is_empty() {
+            return;
+        }
+        info!("start job queue, workers: {}, job types: {:?}", config.workers, self.handlers.keys().collect::<Vec<_>>());
+        let queue = Arc::new(self);
+
+        let reaper = queue.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REAP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = reaper.reap().await {
+                    error!("reap background job error: {}", e);
+                }
+            }
+        });
+
+        for _ in 0..config.workers {
+            let worker = queue.clone();
+            let tenant_concurrency = config.tenant_concurrency;
+            let poll_interval = Duration::from_millis(config.poll_interval_ms);
+            spawn_tracked(async move {
+                let shutdown_signal = ShutdownSignal::listen();
+                while !shutdown_signal.is_shutdown() {
+                    let claimed = match worker.claim(tenant_concurrency).await {
+                        Ok(claimed) => claimed,
+                        Err(e) => {
+                            error!("claim background job error: {}", e);
+                            None
+                        }
+                    };
+                    match claimed {
+                        Some(job) => worker.execute(job).await,
+                        None => {
+                            tokio::select! {
+                                _ = tokio::time::sleep(poll_interval) => {}
+                                _ = shutdown_signal.wait() => {}
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    /// 领取一个可执行的作业,按优先级和提交顺序,跳过执行数已达上限的租户
+    async fn claim(&self, tenant_concurrency: i64) -> Result<Option<ClaimedJob>> {
+        let lock = DistributedLock::new("background_job:claim").with_lease(CLAIM_LOCK_LEASE);
+        let guard = match lock.try_acquire().await? {
+            Some(guard) => guard,
+            None => return Ok(None), // 其他副本正在领取
+        };
+
+        let job_types: Vec<&str> = self.handlers.keys().copied().collect();
+        let job: ClaimedJob = match claim_query(&job_types, tenant_concurrency).one(&self.db).await? {
+            Some(model) => model.into(),
+            None => {
+                guard.release().await?;
+                return Ok(None);
+            }
+        };
+        // 领取锁过期后其他副本可能已领取同一个作业,校验fencing token后再写入
+        let txn = self.db.begin().await?;
+        guard.check_db_fence(&txn).await?;
+        let claimed = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_RUNNING))
+            .col_expr(Column::Attempts, Expr::value(job.attempts))
+            .col_expr(Column::Worker, Expr::value(instance_id()))
+            .col_expr(Column::StartTime, now())
+            .col_expr(Column::HeartbeatTime, now())
+            .filter(Column::Id.eq(job.id))
+            .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_PENDING))
+            .exec(&txn).await?.rows_affected > 0;
+        txn.commit().await?;
+        guard.release().await?;
+        Ok(if claimed { Some(job) } else { None })
+    }
+
+    /// 执行作业并保存结果
+    async fn execute(&self, job: ClaimedJob) {
+        let handler = match self.handlers.get(job.job_type.as_str()) {
+            Some(handler) => handler.clone(),
+            None => return,
+        };
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let ctx = JobContext {
+            job_id: job.id,
+            tenant_id: job.tenant_id,
+            creator: job.creator,
+            attempt: job.attempts,
+            db: self.db.clone(),
+            cancelled: cancelled.clone(),
+        };
+
+        // 执行期间定时心跳
+        let (job_id, attempts) = (job.id, job.attempts);
+        let heartbeat_db = self.db.clone();
+        let heartbeat = HeartbeatGuard(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = heartbeat_and_refresh(&heartbeat_db, job_id, attempts, &cancelled).await {
+                    warn!("background job {} heartbeat error: {}", job_id, e);
+                }
+            }
+        }));
+
+        info!("execute background job {} {}, attempt: {}", job.id, job.job_type, job.attempts);
+        let result = if job.attempts > handler.max_attempts() {
+            Err(anyhow!("执行次数超过上限"))
+        } else {
+            match run_handler(handler.as_ref(), &ctx, &job.payload).await {
+                Ok(Some(artifact)) => self.save_artifact(&job, artifact).await.map(Some),
+                Ok(None) => Ok(None),
+                Err(e) => Err(e),
+            }
+        };
+        drop(heartbeat);
+
+        let finished = match result {
+            Ok(artifact_file_id) => self.finish_success(&job, artifact_file_id).await,
+            Err(e) if e.is::<JobCancelled>() || ctx.is_cancelled() => self.finish_status(&job, BACKGROUND_JOB_STATUS_CANCELLED, None).await,
+            Err(e) => {
+                warn!("background job {} {} failed, attempt: {}, error: {}", job.id, job.job_type, job.attempts, e);
+                if job.attempts < handler.max_attempts() {
+                    self.retry_later(&job, &e.to_string()).await
+                } else {
+                    self.finish_status(&job, BACKGROUND_JOB_STATUS_FAILURE, Some(e.to_string())).await
+                }
+            }
+        };
+        match finished {
+            Ok(true) => {}
+            // 心跳超时后已被重新排队,结果以新的执行为准
+            Ok(false) => warn!("background job {} attempt {} is no longer owned by this worker, result discarded", job.id, job.attempts),
+            Err(e) => error!("save background job {} result error: {}", job.id, e),
+        }
+    }
+
+    /// 上传结果文件并写入 system_file,返回文件id
+    async fn save_artifact(&self, job: &ClaimedJob, artifact: JobArtifact) -> Result<i64> {
+        let minio = self.minio.as_ref().ok_or_else(|| anyhow!("文件存储未配置"))?;
+        let path = minio.upload_file(&artifact.file_name, &artifact.data).await
+            .map_err(|e| anyhow!("文件上传失败: {:?}", e))?;
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::MySql,
+            "INSERT INTO system_file (file_name, file_type, file_size, file_path, status, department_code, department_id, creator, updater, tenant_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            [
+                artifact.file_name.into(), artifact.content_type.into(), (artifact.data.len() as i64).into(), path.into(), STATUS_ENABLE.into(),
+                job.department_code.clone().into(), job.department_id.into(), job.creator.into(), job.creator.into(), job.tenant_id.into(),
+            ],
+        );
+        Ok(self.db.execute(stmt).await?.last_insert_id() as i64)
+    }
+
+    /// 以下保存结果的方法返回作业是否仍由当前执行者持有,否则不修改
+    async fn finish_success(&self, job: &ClaimedJob, artifact_file_id: Option<i64>) -> Result<bool> {
+        let result = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_SUCCESS))
+            .col_expr(Column::Progress, Expr::value(100))
+            .col_expr(Column::ArtifactFileId, Expr::value(artifact_file_id))
+            .col_expr(Column::Error, Expr::value(Option::<String>::None))
+            .col_expr(Column::FinishTime, now())
+            .filter(owned_condition(job.id, job.attempts))
+            .exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn finish_status(&self, job: &ClaimedJob, status: i8, error: Option<String>) -> Result<bool> {
+        let result = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status))
+            .col_expr(Column::Error, Expr::value(error.map(truncate_error)))
+            .col_expr(Column::FinishTime, now())
+            .filter(owned_condition(job.id, job.attempts))
+            .exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 失败后按指数退避重新排队
+    async fn retry_later(&self, job: &ClaimedJob, error: &str) -> Result<bool> {
+        let result = retry_update(job, error).exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 心跳超时的作业重新排队,已请求取消的直接取消
+    async fn reap(&self) -> Result<()> {
+        let (cancel, requeue) = reap_updates();
+        cancel.exec(&self.db).await?;
+        let requeued = requeue.exec(&self.db).await?.rows_affected;
+        if requeued > 0 {
+            warn!("requeue {} background jobs after heartbeat timeout", requeued);
+        }
+        Ok(())
+    }
+}
+
+/// 执行处理器,处理器panic时按执行失败处理,不影响工作协程
+async fn run_handler(handler: &dyn ErasedHandler, ctx: &JobContext, payload: &str) -> Result<Option<JobArtifact>> {
+    match AssertUnwindSafe(handler.run(ctx, payload)).catch_unwind().await {
+        Ok(result) => result,
+        Err(panic) => Err(anyhow!("作业执行异常: {}", panic_message(panic.as_ref()))),
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// 可领取的作业: 按优先级和提交顺序,跳过执行数已达上限的租户
+fn claim_query(job_types: &[&str], tenant_concurrency: i64) -> Select<Entity> {
+    let running = Alias::new("r");
+    let running_count = Query::select()
+        .expr(Func::count(Expr::col((running.clone(), Column::Id))))
+        .from_as(Entity, running.clone())
+        .and_where(Expr::col((running.clone(), Column::TenantId)).equals((Entity, Column::TenantId)))
+        .and_where(Expr::col((running, Column::Status)).eq(BACKGROUND_JOB_STATUS_RUNNING))
+        .to_owned();
+    Entity::find()
+        .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_PENDING))
+        .filter(Expr::col((Entity, Column::RunAt)).lte(now()))
+        .filter(Column::JobType.is_in(job_types.iter().copied()))
+        .filter(
+            Expr::expr(SimpleExpr::SubQuery(None, Box::new(SubQueryStatement::SelectStatement(running_count))))
+                .lt(tenant_concurrency),
+        )
+        .order_by_desc(Column::Priority)
+        .order_by_asc(Column::Id)
+        .limit(1)
+}
+
+/// 重新排队,按执行次数指数退避
+fn retry_update(job: &ClaimedJob, error: &str) -> UpdateMany<Entity> {
+    Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_PENDING))
+        .col_expr(Column::Error, Expr::value(truncate_error(error.to_string())))
+        .col_expr(Column::Worker, Expr::value(Option::<String>::None))
+        .col_expr(Column::RunAt, Expr::cust_with_values("DATE_ADD(NOW(3), INTERVAL ? SECOND)", [retry_delay(job.attempts)]))
+        .filter(owned_condition(job.id, job.attempts))
+}
+
+/// 第几次执行失败后的重试间隔(秒)
+fn retry_delay(attempts: i32) -> i64 {
+    (RETRY_BASE_SECS << (attempts - 1).clamp(0, 16)).min(RETRY_MAX_SECS)
+}
+
+/// 心跳超时的作业: 已请求取消的直接取消,其余重新排队
+fn reap_updates() -> (UpdateMany<Entity>, UpdateMany<Entity>) {
+    let timed_out = Expr::col((Entity, Column::HeartbeatTime))
+        .lt(Expr::cust_with_values("DATE_SUB(NOW(3), INTERVAL ? SECOND)", [HEARTBEAT_TIMEOUT_SECS]));
+    let cancel = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_CANCELLED))
+        .col_expr(Column::FinishTime, now())
+        .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_RUNNING))
+        .filter(Column::CancelRequested.eq(true))
+        .filter(timed_out.clone());
+    let requeue = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BACKGROUND_JOB_STATUS_PENDING))
+        .col_expr(Column::Worker, Expr::value(Option::<String>::None))
+        .col_expr(Column::RunAt, now())
+        .filter(Column::Status.eq(BACKGROUND_JOB_STATUS_RUNNING))
+        .filter(timed_out);
+    (cancel, requeue)
+}
+
+/// 心跳,作业已被重新排队时标记取消,让处理器尽快停止
+async fn heartbeat_and_refresh(db: &DatabaseConnection, id: i64, attempts: i32, cancelled: &AtomicBool) -> Result<()> {
+    let result = Entity::update_many()
+        .col_expr(Column::HeartbeatTime, now())
+        .filter(owned_condition(id, attempts))
+        .exec(db).await?;
+    if result.rows_affected == 0 {
+        warn!("background job {} attempt {} is no longer owned by this worker", id, attempts);
+        cancelled.store(true, Ordering::SeqCst);
+        return Ok(());
+    }
+    refresh_cancelled(db, id, cancelled).await
+}
+
+/// 作业仍由当前执行者持有: 心跳超时后作业会被重新排队,由其他执行者领取,旧的执行者不能再修改
+fn owned_condition(id: i64, attempts: i32) -> Condition {
+    Condition::all()
+        .add(Column::Id.eq(id))
+        .add(Column::Worker.eq(instance_id()))
+        .add(Column::Attempts.eq(attempts))
+        .add(Column::Status.eq(BACKGROUND_JOB_STATUS_RUNNING))
+}
+
+/// 数据库时间,多个副本之间以数据库时间为准
+fn now() -> SimpleExpr {
+    Expr::cust("NOW(3)")
+}
+
+async fn refresh_cancelled(db: &DatabaseConnection, id: i64, cancelled: &AtomicBool) -> Result<()> {
+    let cancel_requested: Option<bool> = Entity::find_by_id(id)
+        .select_only()
+        .column(Column::CancelRequested)
+        .into_tuple()
+        .one(db).await?;
+    if cancel_requested == Some(true) {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+fn truncate_error(error: String) -> String {
+    error.chars().take(MAX_ERROR_LEN).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::QueryTrait;
+
+    fn login_user() -> LoginUserContext {
+        LoginUserContext {
+            device_type: "pc".to_string(),
+            id: 7,
+            nickname: "admin".to_string(),
+            tenant_id: 3,
+            department_id: 5,
+            department_code: "0001".to_string(),
+            role_id: 1,
+            permissions: Vec::new(),
+            data_permission: None,
+        }
+    }
+
+    fn claimed_job(attempts: i32) -> ClaimedJob {
+        ClaimedJob {
+            id: 11,
+            job_type: JOB_TYPE_ERP_INVENTORY_EXPORT.to_string(),
+            payload: "{}".to_string(),
+            attempts,
+            department_code: "0001".to_string(),
+            department_id: 5,
+            creator: Some(7),
+            tenant_id: 3,
+        }
+    }
+
+    fn context() -> JobContext {
+        JobContext {
+            job_id: 11,
+            tenant_id: 3,
+            creator: Some(7),
+            attempt: 1,
+            db: DatabaseConnection::Disconnected,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    struct PanicHandler;
+
+    #[async_trait]
+    impl JobHandler for PanicHandler {
+        const JOB_TYPE: &'static str = "test.panic";
+        type Payload = serde_json::Value;
+
+        async fn handle(&self, _ctx: &JobContext, _payload: Self::Payload) -> Result<Option<JobArtifact>> {
+            panic!("handler panicked");
+        }
+    }
+
+    #[test]
+    fn test_new_job() {
+        let job = new_job(&login_user(), JOB_TYPE_ERP_INVENTORY_EXPORT, "{}".to_string(), 5);
+        assert!(job.id.is_not_set());
+        assert_eq!(job.job_type, Set(JOB_TYPE_ERP_INVENTORY_EXPORT.to_string()));
+        assert_eq!(job.priority, Set(5));
+        assert_eq!(job.status, Set(BACKGROUND_JOB_STATUS_PENDING));
+        assert_eq!(job.creator, Set(Some(7)));
+        assert_eq!(job.tenant_id, Set(3));
+        assert_eq!(job.department_code, Set("0001".to_string()));
+        assert!(job.attempts.is_not_set());
+    }
+
+    #[test]
+    fn test_claim_query() {
+        let sql = claim_query(&[JOB_TYPE_ERP_INVENTORY_EXPORT], 2).build(DbBackend::MySql).to_string();
+        assert!(sql.contains("`status` = 0"), "{}", sql);
+        assert!(sql.contains("`run_at` <= NOW(3)"), "{}", sql);
+        assert!(sql.contains("`job_type` IN ('erp.inventory_export')"), "{}", sql);
+        assert!(sql.contains("`priority` DESC"), "{}", sql);
+        assert!(sql.contains("`id` ASC"), "{}", sql);
+        assert!(sql.contains("LIMIT 1"), "{}", sql);
+    }
+
+    #[test]
+    fn test_claim_query_limits_tenant_concurrency() {
+        let sql = claim_query(&[JOB_TYPE_ERP_INVENTORY_EXPORT], 2).build(DbBackend::MySql).to_string();
+        assert!(sql.contains("COUNT(`r`.`id`)"), "{}", sql);
+        assert!(sql.contains("`r`.`tenant_id` = `system_background_job`.`tenant_id`"), "{}", sql);
+        assert!(sql.contains("`r`.`status` = 1"), "{}", sql);
+        assert!(sql.contains(") < 2"), "{}", sql);
+    }
+
+    #[test]
+    fn test_claimed_job_increments_attempts() {
+        let job = claimed_job(0);
+        let model = Model {
+            id: job.id,
+            job_type: job.job_type,
+            payload: job.payload,
+            priority: 0,
+            status: BACKGROUND_JOB_STATUS_PENDING,
+            progress: 0,
+            progress_message: None,
+            attempts: 2,
+            cancel_requested: false,
+            run_at: Local::now().naive_local(),
+            worker: None,
+            start_time: None,
+            heartbeat_time: None,
+            finish_time: None,
+            artifact_file_id: None,
+            error: None,
+            department_code: job.department_code,
+            department_id: job.department_id,
+            creator: job.creator,
+            create_time: Local::now().naive_local(),
+            update_time: Local::now().naive_local(),
+            tenant_id: job.tenant_id,
+        };
+        let claimed: ClaimedJob = model.into();
+        assert_eq!(claimed.attempts, 3);
+        assert_eq!(claimed.tenant_id, 3);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), 10);
+        assert_eq!(retry_delay(2), 20);
+        assert_eq!(retry_delay(3), 40);
+        assert_eq!(retry_delay(20), RETRY_MAX_SECS);
+    }
+
+    #[test]
+    fn test_retry_update() {
+        let sql = retry_update(&claimed_job(2), "boom").build(DbBackend::MySql).to_string();
+        assert!(sql.contains("`status` = 0"), "{}", sql);
+        assert!(sql.contains("`error` = 'boom'"), "{}", sql);
+        assert!(sql.contains("`worker` = NULL"), "{}", sql);
+        assert!(sql.contains("DATE_ADD(NOW(3), INTERVAL 20 SECOND)"), "{}", sql);
+        // 只能修改当前执行者持有的作业
+        assert!(sql.contains("`attempts` = 2"), "{}", sql);
+        assert!(sql.contains("`status` = 1"), "{}", sql);
+        assert!(sql.contains(&format!("`worker` = '{}'", instance_id())), "{}", sql);
+    }
+
+    #[test]
+    fn test_reap_updates() {
+        let (cancel, requeue) = reap_updates();
+        let cancel = cancel.build(DbBackend::MySql).to_string();
+        assert!(cancel.contains("`status` = 4"), "{}", cancel);
+        assert!(cancel.contains("`cancel_requested` = TRUE"), "{}", cancel);
+        assert!(cancel.contains("DATE_SUB(NOW(3), INTERVAL 120 SECOND)"), "{}", cancel);
+        let requeue = requeue.build(DbBackend::MySql).to_string();
+        assert!(requeue.contains("`status` = 0"), "{}", requeue);
+        assert!(requeue.contains("`worker` = NULL"), "{}", requeue);
+        assert!(requeue.contains("`status` = 1"), "{}", requeue);
+        assert!(requeue.contains("DATE_SUB(NOW(3), INTERVAL 120 SECOND)"), "{}", requeue);
+    }
+
+    #[test]
+    fn test_truncate_error() {
+        assert_eq!(truncate_error("错误".repeat(MAX_ERROR_LEN)).chars().count(), MAX_ERROR_LEN);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_fails_job() {
+        let handler = TypedHandler(PanicHandler);
+        let result = run_handler(&handler, &context(), "{}").await;
+        let error = result.err().expect("panic should be reported as failure").to_string();
+        assert!(error.contains("handler panicked"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_guard_aborts_on_drop() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let guard = HeartbeatGuard(tokio::spawn(async move {
+            let _tx = tx;
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        }));
+        drop(guard);
+        // 协程被中止后发送端随之释放
+        let received = tokio::time::timeout(Duration::from_secs(1), rx).await.expect("heartbeat should be aborted");
+        assert!(received.is_err());
+    }
+}
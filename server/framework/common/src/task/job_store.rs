@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait};
+use crate::cache::distributed_lock::LockGuard;
+use crate::constants::enum_constants::{JOB_LOG_STATUS_RUNNING, ROOT_TENANT_ID, STATUS_ENABLE};
+
+/// 执行记录保留天数
+const LOG_RETENTION_DAYS: i64 = 30;
+/// 错误信息最大长度
+const MAX_ERROR_LEN: usize = 2000;
+
+/// 定时任务的持久化状态,表 system_job 和 system_job_log
+#[derive(Debug, Clone)]
+pub struct JobRecord {
+    pub status: i8, // 状态 0正常 1暂停
+    pub misfire_policy: i8, // 错过执行的处理策略
+    pub last_fire_time: Option<NaiveDateTime>, // 上次计划执行时间
+}
+
+/// 注册任务,已存在时只更新cron表达式,暂停状态和错过执行策略以数据库为准
+pub async fn register(db: &DatabaseConnection, name: &str, cron_expr: &str, misfire_policy: i8) -> Result<JobRecord> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "INSERT INTO system_job (name, cron_expression, status, misfire_policy, tenant_id) VALUES (?, ?, ?, ?, ?) \
+         ON DUPLICATE KEY UPDATE cron_expression = VALUES(cron_expression), deleted = 0",
+        [name.into(), cron_expr.into(), STATUS_ENABLE.into(), misfire_policy.into(), ROOT_TENANT_ID.into()],
+    );
+    db.execute(stmt).await?;
+    load(db, name).await?.ok_or_else(|| anyhow!("job {} not found", name))
+}
+
+pub async fn load(db: &DatabaseConnection, name: &str) -> Result<Option<JobRecord>> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "SELECT status, misfire_policy, last_fire_time FROM system_job WHERE name = ? AND deleted = 0",
+        [name.into()],
+    );
+    let row = match db.query_one(stmt).await? {
+        Some(row) => row,
+        None => return Ok(None),
+    };
+    Ok(Some(JobRecord {
+        status: row.try_get("", "status")?,
+        misfire_policy: row.try_get("", "misfire_policy")?,
+        last_fire_time: row.try_get("", "last_fire_time")?,
+    }))
+}
+
+/// 更新下次执行时间
+pub async fn update_next_fire(db: &DatabaseConnection, name: &str, next_fire_time: Option<DateTime<Local>>) -> Result<()> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "UPDATE system_job SET next_fire_time = ? WHERE name = ?",
+        [next_fire_time.map(|time| time.naive_local()).into(), name.into()],
+    );
+    db.execute(stmt).await?;
+    Ok(())
+}
+
+/// 计划执行完成后更新上次执行时间,用于重启后判断错过的执行
+/// 执行锁过期后其他副本已开始执行时不再更新
+pub async fn update_last_fire(db: &DatabaseConnection, name: &str, fire_time: DateTime<Local>, guard: &LockGuard) -> Result<()> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "UPDATE system_job SET last_fire_time = ? WHERE name = ? AND (last_fire_time IS NULL OR last_fire_time < ?)",
+        [fire_time.naive_local().into(), name.into(), fire_time.naive_local().into()],
+    );
+    let txn = db.begin().await?;
+    guard.check_db_fence(&txn).await?;
+    txn.execute(stmt).await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+/// 记录开始执行,返回记录id
+pub async fn log_start(db: &DatabaseConnection, name: &str, trigger_type: i8, attempt: i32, instance: &str) -> Result<i64> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "INSERT INTO system_job_log (job_name, trigger_type, attempt, status, instance, start_time, tenant_id) VALUES (?, ?, ?, ?, ?, NOW(3), ?)",
+        [name.into(), trigger_type.into(), attempt.into(), JOB_LOG_STATUS_RUNNING.into(), instance.into(), ROOT_TENANT_ID.into()],
+    );
+    let result = db.execute(stmt).await?;
+    Ok(result.last_insert_id() as i64)
+}
+
+/// 记录执行结果
+pub async fn log_finish(db: &DatabaseConnection, log_id: i64, status: i8, duration_ms: i64, error: Option<String>) -> Result<()> {
+    let error = error.map(|error| error.chars().take(MAX_ERROR_LEN).collect::<String>());
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "UPDATE system_job_log SET status = ?, end_time = NOW(3), duration = ?, error = ? WHERE id = ?",
+        [status.into(), duration_ms.into(), error.into(), log_id.into()],
+    );
+    db.execute(stmt).await?;
+    Ok(())
+}
+
+/// 删除过期的执行记录
+pub async fn purge_logs(db: &DatabaseConnection) -> Result<u64> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::MySql,
+        "DELETE FROM system_job_log WHERE start_time < DATE_SUB(NOW(), INTERVAL ? DAY) LIMIT 5000",
+        [LOG_RETENTION_DAYS.into()],
+    );
+    Ok(db.execute(stmt).await?.rows_affected())
+}
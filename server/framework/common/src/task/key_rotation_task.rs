@@ -0,0 +1,99 @@
+use std::error::Error;
+use anyhow::Result;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use tracing::{info, warn};
+use crate::task::task_manager::{async_trait, ErrorAction, Task};
+use crate::utils::field_crypto;
+
+/// 每批重新加密的行数
+const BATCH_SIZE: u64 = 500;
+
+/// 加密字段,表需要有自增主键id
+pub struct EncryptedColumn {
+    pub table: &'static str, // 表名
+    pub column: &'static str, // 加密字段
+    pub index_column: Option<&'static str>, // 盲索引字段,重新加密时一起更新
+}
+
+// 密钥轮换任务,把旧密钥加密的数据和历史明文用当前密钥重新加密
+pub struct KeyRotationTask {
+    pub name: String,
+    pub db: DatabaseConnection,
+    columns: Vec<EncryptedColumn>,
+}
+
+impl KeyRotationTask {
+    /// name为任务唯一标识,每个服务只轮换自己的表
+    pub fn new(name: &str, db: DatabaseConnection, columns: Vec<EncryptedColumn>) -> Self {
+        KeyRotationTask {
+            name: name.to_string(),
+            db,
+            columns,
+        }
+    }
+
+    async fn rotate_column(&self, column: &EncryptedColumn) -> Result<u64> {
+        let select_sql = format!(
+            "SELECT id, {column} AS value FROM {table} WHERE id > ? AND {column} IS NOT NULL AND {column} <> '' AND {column} NOT LIKE ? ORDER BY id LIMIT {limit}",
+            column = column.column, table = column.table, limit = BATCH_SIZE,
+        );
+        // 条件中带上原值,执行期间被修改的数据不覆盖
+        let update_sql = match column.index_column {
+            Some(index_column) => format!("UPDATE {} SET {} = ?, {} = ? WHERE id = ? AND {} = ?", column.table, column.column, index_column, column.column),
+            None => format!("UPDATE {} SET {} = ? WHERE id = ? AND {} = ?", column.table, column.column, column.column),
+        };
+        let current_prefix = format!("{}%", field_crypto::current_prefix());
+        let mut last_id: i64 = 0;
+        let mut rotated: u64 = 0;
+        loop {
+            let stmt = Statement::from_sql_and_values(DbBackend::MySql, &select_sql, [last_id.into(), current_prefix.clone().into()]);
+            let rows = self.db.query_all(stmt).await?;
+            if rows.is_empty() {
+                break;
+            }
+            for row in rows {
+                let id: i64 = row.try_get("", "id")?;
+                let value: String = row.try_get("", "value")?;
+                last_id = id;
+                let plaintext = match field_crypto::decrypt(&value) {
+                    Ok(plaintext) => plaintext,
+                    Err(e) => {
+                        // 密钥已删除或数据损坏,跳过并保留原值
+                        warn!("decrypt {}.{} id {} error: {}", column.table, column.column, id, e);
+                        continue;
+                    }
+                };
+                let cipher = field_crypto::encrypt(&plaintext)?;
+                let values = match column.index_column {
+                    Some(_) => vec![cipher.into(), field_crypto::blind_index(&plaintext).into(), id.into(), value.into()],
+                    None => vec![cipher.into(), id.into(), value.into()],
+                };
+                let result = self.db.execute(Statement::from_sql_and_values(DbBackend::MySql, &update_sql, values)).await?;
+                rotated += result.rows_affected();
+            }
+        }
+        Ok(rotated)
+    }
+}
+
+#[async_trait]
+impl Task for KeyRotationTask {
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for column in &self.columns {
+            let rotated = self.rotate_column(column).await?;
+            if rotated > 0 {
+                info!("task {} re-encrypted {} rows of {}.{}", self.name, rotated, column.table, column.column);
+            }
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction {
+        tracing::error!("execute task {} error: {}", self.name, error);
+        ErrorAction::Continue
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
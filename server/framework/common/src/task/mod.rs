@@ -1 +1,5 @@
-pub mod task_manager;
\ No newline at end of file
+pub mod task_manager;
+pub mod outbox_relay_task;
+pub mod job_store;
+pub mod background_job;
+pub mod key_rotation_task;
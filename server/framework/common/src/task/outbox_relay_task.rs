@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicI64, Ordering};
+use sea_orm::DatabaseConnection;
+use crate::constants::enum_constants::JOB_MISFIRE_IGNORE;
+use crate::event::outbox;
+use crate::task::task_manager::{async_trait, ErrorAction, Task};
+
+// 领域事件投递任务,把outbox中的事件投递到redis stream,并清理过期的已投递事件
+pub struct OutboxRelayTask {
+    pub name: String,
+    pub db: DatabaseConnection,
+    last_purge: AtomicI64, // 上次清理时间(秒)
+}
+
+/// 清理已投递事件的间隔(秒)
+const PURGE_INTERVAL_SECS: i64 = 3600;
+
+impl OutboxRelayTask {
+    pub fn new(db: DatabaseConnection) -> Self {
+        OutboxRelayTask {
+            name: "outbox relay".to_string(),
+            db,
+            last_purge: AtomicI64::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl Task for OutboxRelayTask {
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // 一次投递一批,有积压时继续投递
+        while outbox::relay(&self.db).await? > 0 {}
+
+        let now = chrono::Utc::now().timestamp();
+        if now - self.last_purge.load(Ordering::SeqCst) >= PURGE_INTERVAL_SECS {
+            self.last_purge.store(now, Ordering::SeqCst);
+            outbox::purge_published(&self.db).await?;
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction {
+        tracing::error!("execute task {} error: {}", self.name, error);
+        ErrorAction::Continue
+    }
+
+    // 每秒执行,错过的执行由下次投递补上
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_IGNORE
+    }
+
+    fn record_history(&self) -> bool {
+        false
+    }
+}
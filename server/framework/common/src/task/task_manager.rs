@@ -3,15 +3,68 @@ use std::error::Error;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio::sync::watch;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use cron::Schedule;
-use chrono::Local;
+use chrono::{DateTime, Local, TimeZone};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use redis::Script;
+use sea_orm::DatabaseConnection;
+use tracing::{error, info, warn};
+use crate::cache::distributed_lock::DistributedLock;
+use crate::config::system_config;
+use crate::constants::enum_constants::{JOB_LOG_STATUS_FAILURE, JOB_LOG_STATUS_SUCCESS, JOB_MISFIRE_FIRE_ALL, JOB_MISFIRE_FIRE_ONCE, JOB_MISFIRE_IGNORE, JOB_TRIGGER_MANUAL, JOB_TRIGGER_MISFIRE, JOB_TRIGGER_RETRY, JOB_TRIGGER_SCHEDULE, ROOT_TENANT_ID, STATUS_ENABLE};
+use crate::database::redis_constants::{REDIS_CHANNEL_JOB_TRIGGER, REDIS_KEY_JOB_FIRE_PREFIX};
+use crate::database::redis_pool::AsyncRedisManager;
+use crate::monitor::health::instance_id;
+use crate::monitor::metrics::record_task_execution;
+use crate::task::job_store;
 
+pub use async_trait::async_trait;
+
+/// 单次触发的最大执行次数,包括 ErrorAction::Retry 的重试
+const MAX_ATTEMPTS: i32 = 3;
+/// 错过执行时最多补执行的次数
+const MAX_MISFIRE_FIRES: usize = 10;
+/// 执行锁的租期,执行期间自动续期
+const JOB_LOCK_LEASE: Duration = Duration::from_secs(60);
+/// 手动触发的去重时间(秒)
+const MANUAL_CLAIM_TTL: u64 = 600;
+/// 订阅断开后的重连间隔
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(5);
+/// 清理执行记录的间隔
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 认领一次计划执行,计划时间大于已认领的时间才成功,多个副本同一时间只有一个执行
+static CLAIM_SCRIPT: Lazy<Script> = Lazy::new(|| Script::new(r#"
+local current = redis.call('GET', KEYS[1])
+if current and tonumber(current) >= tonumber(ARGV[1]) then
+    return 0
+end
+redis.call('SET', KEYS[1], ARGV[1])
+return 1
+"#));
+
+#[async_trait]
 pub trait Task: Send + Sync + 'static {
-    fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
     fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction;
+    /// 任务名称,用于指标统计,同时是任务在 system_job 中的唯一标识
+    fn name(&self) -> String {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name).to_string()
+    }
+    /// 首次注册时的错过执行策略,之后以 system_job 中的配置为准
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_FIRE_ONCE
+    }
+    /// 是否记录执行历史,执行频繁的任务可以关闭
+    fn record_history(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug)]
@@ -21,162 +74,122 @@ pub enum ErrorAction {
     Stop,
 }
 
-pub struct TaskItem {
-    task: Box<dyn Task>,
-    cron_expr: String,
-}
-
 #[derive(Debug, Clone)]
 pub struct TaskStatus {
     pub id: usize,
+    pub name: String,
     pub last_run: Option<chrono::DateTime<chrono::Local>>,
     pub last_error: Option<String>,
     pub running: bool,
 }
 
+type Statuses = Arc<RwLock<HashMap<usize, TaskStatus>>>;
+
+/// 定时任务管理,任务注册到 system_job 表,每次触发只由一个副本执行,执行记录写入 system_job_log
 pub struct TaskManager {
-    task_sender: mpsc::Sender<(usize, TaskItem)>,
-    remove_sender: mpsc::Sender<usize>,
+    db: DatabaseConnection,
     tasks: Arc<RwLock<HashMap<usize, JoinHandle<()>>>>,
-    statuses: Arc<RwLock<HashMap<usize, TaskStatus>>>,
+    statuses: Statuses,
+    triggers: Arc<DashMap<String, mpsc::Sender<String>>>, // 任务名称 -> 手动触发
+    stop_sender: watch::Sender<bool>,
     next_id: usize,
 }
 
 impl TaskManager {
-    pub fn new() -> Self {
-        let (task_sender, mut task_receiver) = mpsc::channel::<(usize, TaskItem)>(100);
-        let (remove_sender, mut remove_receiver) = mpsc::channel::<usize>(100);
-        let tasks = Arc::new(RwLock::new(HashMap::new()));
-        let statuses = Arc::new(RwLock::new(HashMap::new()));
-
-        let tasks_clone = tasks.clone();
-        let statuses_clone = statuses.clone();
+    pub fn new(db: DatabaseConnection) -> Self {
+        let triggers: Arc<DashMap<String, mpsc::Sender<String>>> = Arc::new(DashMap::new());
+        let (stop_sender, _) = watch::channel(false);
 
+        // 订阅手动触发,消息格式为 任务名称:触发id,只处理本服务注册的任务
+        let trigger_senders = triggers.clone();
         tokio::spawn(async move {
             loop {
-                tokio::select! {
-                    Some((id, task_item)) = task_receiver.recv() => {
-                        let task = task_item.task;
-                        let cron_expr = task_item.cron_expr;
-                        let tasks = tasks_clone.clone();
-                        let statuses = statuses_clone.clone();
-
-                        let handle = tokio::spawn(async move {
-                            let mut last_run = None;
-                            let mut last_error = None;
-                            let mut running = true;
-
-                            match Schedule::from_str(&cron_expr) {
-                                Ok(schedule) => {
-                                    loop {
-                                        let now = Local::now();
-                                        if let Some(next) = schedule.after(&now).next() {
-                                            let duration_until_next = (next - now).to_std().unwrap_or(Duration::from_secs(1));
-                                            tokio::time::sleep(duration_until_next).await;
-
-                                            match task.execute() {
-                                                Ok(()) => {
-                                                    last_run = Some(Local::now());
-                                                    last_error = None;
-                                                }
-                                                Err(e) => {
-                                                    last_error = Some(e.to_string());
-                                                    match task.on_error(e) {
-                                                        ErrorAction::Continue => {}
-                                                        ErrorAction::Retry(delay) => {
-                                                            tokio::time::sleep(Duration::from_secs(delay)).await;
-                                                        }
-                                                        ErrorAction::Stop => {
-                                                            running = false;
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        } else {
-                                            running = false;
-                                            break;
-                                        }
-
-                                        let mut statuses = statuses.write().await;
-                                        statuses.insert(id, TaskStatus {
-                                            id,
-                                            last_run,
-                                            last_error: last_error.clone(),
-                                            running,
-                                        });
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("Invalid cron expression for task {}: {}", id, e);
-                                    running = false;
-                                    let mut statuses = statuses.write().await;
-                                    statuses.insert(id, TaskStatus {
-                                        id,
-                                        last_run: None,
-                                        last_error: Some(format!("Invalid cron expression: {}", e)),
-                                        running,
-                                    });
-                                }
+                let result = AsyncRedisManager::subscribe(REDIS_CHANNEL_JOB_TRIGGER, |message| {
+                    if let Some((name, trigger_id)) = message.rsplit_once(':') {
+                        if let Some(sender) = trigger_senders.get(name) {
+                            if let Err(e) = sender.try_send(trigger_id.to_string()) {
+                                warn!("trigger job {} error: {}", name, e);
                             }
-                        });
-
-                        let mut tasks = tasks.write().await;
-                        tasks.insert(id, handle);
-                        let mut statuses = statuses_clone.write().await;
-                        statuses.insert(id, TaskStatus {
-                            id,
-                            last_run: None,
-                            last_error: None,
-                            running: true,
-                        });
-                    }
-                    Some(id) = remove_receiver.recv() => {
-                        let mut tasks = tasks_clone.write().await;
-                        if let Some(handle) = tasks.remove(&id) {
-                            handle.abort();
-                        }
-                        let mut statuses = statuses_clone.write().await;
-                        if let Some(status) = statuses.get_mut(&id) {
-                            status.running = false;
                         }
                     }
+                }).await;
+                if let Err(e) = result {
+                    error!("subscribe job trigger error: {}", e);
+                }
+                tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+            }
+        });
+
+        // 定时清理过期的执行记录
+        let purge_db = db.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = job_store::purge_logs(&purge_db).await {
+                    error!("purge job log error: {}", e);
                 }
             }
         });
 
         TaskManager {
-            task_sender,
-            remove_sender,
-            tasks,
-            statuses,
+            db,
+            tasks: Arc::new(RwLock::new(HashMap::new())),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
+            triggers,
+            stop_sender,
             next_id: 0,
         }
     }
 
     pub async fn add_task(&mut self, task: impl Task, cron_expr: &str) -> usize {
+        self.add_task_item(task, cron_expr, None).await
+    }
+
+    /// 添加使用系统参数配置执行时间的任务,参数未配置时使用cron_expr,参数变更后自动重新调度
+    pub async fn add_task_with_config_key(&mut self, task: impl Task, config_key: &str, cron_expr: &str) -> usize {
+        self.add_task_item(task, cron_expr, Some(config_key.to_string())).await
+    }
+
+    async fn add_task_item(&mut self, task: impl Task, cron_expr: &str, cron_key: Option<String>) -> usize {
         let task_id = self.next_id;
         self.next_id += 1;
 
-        let task_item = TaskItem {
+        let name = task.name();
+        let (trigger_sender, trigger_receiver) = mpsc::channel(16);
+        self.triggers.insert(name.clone(), trigger_sender);
+        let job = Job {
+            id: task_id,
+            name: name.clone(),
             task: Box::new(task),
             cron_expr: cron_expr.to_string(),
+            cron_key,
+            db: self.db.clone(),
+            statuses: self.statuses.clone(),
         };
 
-        if let Err(e) = self.task_sender.send((task_id, task_item)).await {
-            eprintln!("add task error: {}", e);
-        } else {
-            println!("add cron task {}, expression: {}", task_id, cron_expr);
-        }
+        self.statuses.write().await.insert(task_id, TaskStatus {
+            id: task_id,
+            name: name.clone(),
+            last_run: None,
+            last_error: None,
+            running: true,
+        });
+        let handle = tokio::spawn(job.run(self.stop_sender.subscribe(), trigger_receiver));
+        self.tasks.write().await.insert(task_id, handle);
+        info!("add cron task {} {}, expression: {}", task_id, name, cron_expr);
         task_id
     }
 
     pub async fn remove_task(&self, task_id: usize) {
-        if let Err(e) = self.remove_sender.send(task_id).await {
-            eprintln!("remove task error: {}", e);
-        } else {
-            println!("remove task {}", task_id);
+        if let Some(handle) = self.tasks.write().await.remove(&task_id) {
+            handle.abort();
+        }
+        if let Some(status) = self.statuses.write().await.get_mut(&task_id) {
+            status.running = false;
+            self.triggers.remove(&status.name);
         }
+        info!("remove task {}", task_id);
     }
 
     pub async fn get_status(&self, task_id: usize) -> Option<TaskStatus> {
@@ -184,17 +197,493 @@ impl TaskManager {
         statuses.get(&task_id).cloned()
     }
 
+    pub async fn list_status(&self) -> Vec<TaskStatus> {
+        let statuses = self.statuses.read().await;
+        statuses.values().cloned().collect()
+    }
+
+    /// 立即中止所有任务
     pub fn shutdown(&self) {
-        println!("task manager shutdown: {:?}", chrono::Local::now());
-        let tasks = self.tasks.blocking_read();
-        for (_, handle) in tasks.iter() {
-            handle.abort();
+        info!("task manager shutdown: {:?}", chrono::Local::now());
+        self.stop_sender.send_replace(true);
+        // 不能使用blocking_read,在异步上下文中drop时会panic
+        if let Ok(tasks) = self.tasks.try_read() {
+            for (_, handle) in tasks.iter() {
+                handle.abort();
+            }
+        }
+    }
+
+    /// 停止调度新的执行,等待正在执行的任务结束,超时后中止
+    pub async fn shutdown_gracefully(&self, timeout: Duration) {
+        info!("task manager graceful shutdown: {:?}", chrono::Local::now());
+        self.stop_sender.send_replace(true);
+        let handles: Vec<JoinHandle<()>> = {
+            let mut tasks = self.tasks.write().await;
+            tasks.drain().map(|(_, handle)| handle).collect()
+        };
+        let abort_handles: Vec<_> = handles.iter().map(|handle| handle.abort_handle()).collect();
+        let finished = tokio::time::timeout(timeout, async move {
+            for handle in handles {
+                let _ = handle.await;
+            }
+        }).await;
+        if finished.is_err() {
+            warn!("task manager shutdown timeout after {:?}, abort tasks", timeout);
+            for handle in abort_handles {
+                handle.abort();
+            }
+        }
+    }
+}
+
+/// 手动触发任务,由注册了该任务的任一副本执行一次
+pub async fn trigger(name: &str) -> anyhow::Result<()> {
+    let trigger_id = format!("{:016x}", rand::random::<u64>());
+    AsyncRedisManager::publish(REDIS_CHANNEL_JOB_TRIGGER, format!("{}:{}", name, trigger_id)).await?;
+    Ok(())
+}
+
+struct Job {
+    id: usize,
+    name: String,
+    task: Box<dyn Task>,
+    cron_expr: String,
+    cron_key: Option<String>, // 系统参数key,配置后使用参数中的cron表达式
+    db: DatabaseConnection,
+    statuses: Statuses,
+}
+
+impl Job {
+    async fn run(self, mut stop_receiver: watch::Receiver<bool>, mut trigger_receiver: mpsc::Receiver<String>) {
+        let mut current_cron = self.configured_cron();
+        let mut schedule = match Schedule::from_str(&current_cron) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                error!("Invalid cron expression for task {}: {}", self.name, e);
+                self.set_status(None, Some(format!("Invalid cron expression: {}", e)), false).await;
+                return;
+            }
+        };
+
+        // 注册任务,处理停机期间错过的执行
+        match job_store::register(&self.db, &self.name, &current_cron, self.task.misfire_policy()).await {
+            Ok(record) => {
+                let last_fire_time = record.last_fire_time.and_then(|time| Local.from_local_datetime(&time).single());
+                if let Some(last_fire_time) = last_fire_time {
+                    if !self.fire_missed(&schedule, last_fire_time, record.misfire_policy, &mut stop_receiver).await {
+                        self.set_status(None, None, false).await;
+                        return;
+                    }
+                }
+            }
+            Err(e) => error!("register task {} error: {}", self.name, e),
+        }
+
+        let mut config_receiver = system_config::subscribe();
+        loop {
+            // 系统参数中的cron表达式变更后重新调度
+            if self.reload_cron(&mut current_cron, &mut schedule) {
+                if let Err(e) = job_store::register(&self.db, &self.name, &current_cron, self.task.misfire_policy()).await {
+                    error!("register task {} error: {}", self.name, e);
+                }
+            }
+
+            let now = Local::now();
+            let next = match schedule.after(&now).next() {
+                Some(next) => next,
+                None => break,
+            };
+            if let Err(e) = job_store::update_next_fire(&self.db, &self.name, Some(next)).await {
+                error!("update task {} next fire time error: {}", self.name, e);
+            }
+            let duration_until_next = (next - now).to_std().unwrap_or(Duration::from_secs(1));
+            // 等待下次执行,收到停止信号则退出,不会中断正在执行的任务
+            let keep_running = tokio::select! {
+                _ = tokio::time::sleep(duration_until_next) => {
+                    self.fire(next, JOB_TRIGGER_SCHEDULE, None, &mut stop_receiver).await
+                }
+                Some(trigger_id) = trigger_receiver.recv() => {
+                    self.fire(Local::now(), JOB_TRIGGER_MANUAL, Some(trigger_id), &mut stop_receiver).await
+                }
+                _ = wait_stop(&mut stop_receiver) => false,
+                _ = config_receiver.changed(), if self.cron_key.is_some() => true,
+            };
+            if !keep_running {
+                break;
+            }
+        }
+        self.set_status(None, None, false).await;
+    }
+
+    fn configured_cron(&self) -> String {
+        match &self.cron_key {
+            Some(key) => system_config::get_string(ROOT_TENANT_ID, key).unwrap_or_else(|| self.cron_expr.clone()),
+            None => self.cron_expr.clone(),
+        }
+    }
+
+    /// 参数中的cron表达式变更且有效时替换调度,返回是否变更
+    fn reload_cron(&self, current_cron: &mut String, schedule: &mut Schedule) -> bool {
+        if self.cron_key.is_none() {
+            return false;
+        }
+        let expr = self.configured_cron();
+        if expr == *current_cron {
+            return false;
+        }
+        match Schedule::from_str(&expr) {
+            Ok(new_schedule) => {
+                info!("task {} cron changed: {} -> {}", self.name, current_cron, expr);
+                *schedule = new_schedule;
+                *current_cron = expr;
+                true
+            }
+            Err(e) => {
+                error!("Invalid cron expression {} for task {}: {}", expr, self.name, e);
+                false
+            }
+        }
+    }
+
+    /// 按错过执行策略补执行,返回false时停止任务
+    async fn fire_missed(&self, schedule: &Schedule, last_fire_time: DateTime<Local>, misfire_policy: i8, stop_receiver: &mut watch::Receiver<bool>) -> bool {
+        let fires = misfire_fires(schedule, last_fire_time, Local::now(), misfire_policy);
+        if !fires.is_empty() {
+            info!("task {} fire {} missed since {}, policy: {}", self.name, fires.len(), last_fire_time, misfire_policy);
+        }
+        for fire_time in fires {
+            if !self.fire(fire_time, JOB_TRIGGER_MISFIRE, None, stop_receiver).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 执行一次触发,返回false时停止任务
+    async fn fire(&self, fire_time: DateTime<Local>, trigger_type: i8, trigger_id: Option<String>, stop_receiver: &mut watch::Receiver<bool>) -> bool {
+        // 暂停的任务只能手动执行
+        if trigger_type != JOB_TRIGGER_MANUAL {
+            match job_store::load(&self.db, &self.name).await {
+                Ok(Some(record)) if record.status != STATUS_ENABLE => return true,
+                Ok(_) => {}
+                Err(e) => error!("load task {} error: {}", self.name, e),
+            }
+        }
+        if !self.claim(fire_time, trigger_id.as_deref()).await {
+            return true;
         }
+        // 上次执行未结束时跳过本次
+        let lock = DistributedLock::new(format!("job:{}", self.name)).with_lease(JOB_LOCK_LEASE);
+        let guard = match lock.try_acquire().await {
+            Ok(Some(guard)) => guard,
+            Ok(None) => {
+                warn!("task {} is still running, skip fire at {}", self.name, fire_time);
+                return true;
+            }
+            Err(e) => {
+                error!("lock task {} error: {}", self.name, e);
+                return true;
+            }
+        };
+
+        let keep_running = self.run_attempts(trigger_type, stop_receiver).await;
+
+        if trigger_type != JOB_TRIGGER_MANUAL {
+            if let Err(e) = job_store::update_last_fire(&self.db, &self.name, fire_time, &guard).await {
+                error!("update task {} last fire time error: {}", self.name, e);
+            }
+        }
+        if let Err(e) = guard.release().await {
+            error!("unlock task {} error: {}", self.name, e);
+        }
+        keep_running
+    }
+
+    /// 执行任务,按ErrorAction重试,最多执行MAX_ATTEMPTS次,返回false时停止任务
+    async fn run_attempts(&self, trigger_type: i8, stop_receiver: &mut watch::Receiver<bool>) -> bool {
+        let mut keep_running = true;
+        let mut attempt_trigger = trigger_type;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let error = match self.execute(attempt_trigger, attempt).await {
+                Ok(()) => break,
+                Err(e) => e,
+            };
+            match self.task.on_error(error) {
+                ErrorAction::Continue => break,
+                ErrorAction::Retry(delay) => {
+                    if attempt == MAX_ATTEMPTS {
+                        break;
+                    }
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs(delay)) => {}
+                        _ = wait_stop(stop_receiver) => break,
+                    }
+                    attempt_trigger = JOB_TRIGGER_RETRY;
+                }
+                ErrorAction::Stop => {
+                    keep_running = false;
+                    break;
+                }
+            }
+        }
+        keep_running
+    }
+
+    /// 认领本次触发,计划执行按计划时间认领,手动触发按触发id认领
+    async fn claim(&self, fire_time: DateTime<Local>, trigger_id: Option<&str>) -> bool {
+        let key = claim_key(&self.name, trigger_id);
+        let result = match trigger_id {
+            Some(_) => AsyncRedisManager::set_nx_ex(key, instance_id(), MANUAL_CLAIM_TTL).await,
+            None => {
+                AsyncRedisManager::invoke_script::<i64>(&CLAIM_SCRIPT, &[key], &[fire_time.timestamp_millis().to_string()]).await
+                    .map(|claimed| claimed == 1)
+            }
+        };
+        result.unwrap_or_else(|e| {
+            error!("claim task {} error: {}", self.name, e);
+            false
+        })
+    }
+
+    /// 执行任务并记录执行历史
+    async fn execute(&self, trigger_type: i8, attempt: i32) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let log_id = if self.task.record_history() {
+            job_store::log_start(&self.db, &self.name, trigger_type, attempt, instance_id()).await
+                .map_err(|e| error!("save task {} log error: {}", self.name, e))
+                .ok()
+        } else {
+            None
+        };
+
+        let started = std::time::Instant::now();
+        let result = self.task.execute().await;
+        let elapsed = started.elapsed();
+        record_task_execution(&self.name, result.is_ok(), elapsed);
+
+        let error = result.as_ref().err().map(|e| e.to_string());
+        self.set_status(Some(Local::now()), error.clone(), true).await;
+        if let Some(log_id) = log_id {
+            let status = if error.is_none() { JOB_LOG_STATUS_SUCCESS } else { JOB_LOG_STATUS_FAILURE };
+            if let Err(e) = job_store::log_finish(&self.db, log_id, status, elapsed.as_millis() as i64, error).await {
+                error!("save task {} log error: {}", self.name, e);
+            }
+        }
+        result
+    }
+
+    async fn set_status(&self, last_run: Option<DateTime<Local>>, last_error: Option<String>, running: bool) {
+        let mut statuses = self.statuses.write().await;
+        if let Some(status) = statuses.get_mut(&self.id) {
+            if last_run.is_some() {
+                status.last_run = last_run;
+                status.last_error = last_error;
+            } else if last_error.is_some() {
+                status.last_error = last_error;
+            }
+            status.running = running;
+        }
+    }
+}
+
+/// 计算需要补执行的时间,最多取MAX_MISFIRE_FIRES个错过的时间
+fn misfire_fires(schedule: &Schedule, last_fire_time: DateTime<Local>, now: DateTime<Local>, misfire_policy: i8) -> Vec<DateTime<Local>> {
+    let missed: Vec<DateTime<Local>> = schedule.after(&last_fire_time)
+        .take_while(|time| *time <= now)
+        .take(MAX_MISFIRE_FIRES)
+        .collect();
+    match misfire_policy {
+        JOB_MISFIRE_IGNORE => Vec::new(),
+        JOB_MISFIRE_FIRE_ALL => missed,
+        _ => missed.last().cloned().into_iter().collect(),
+    }
+}
+
+/// 触发的认领key,计划执行每个任务一个key,手动触发每个触发id一个key
+fn claim_key(name: &str, trigger_id: Option<&str>) -> String {
+    match trigger_id {
+        Some(trigger_id) => format!("{}{}:manual:{}", REDIS_KEY_JOB_FIRE_PREFIX, name, trigger_id),
+        None => format!("{}{}", REDIS_KEY_JOB_FIRE_PREFIX, name),
     }
 }
 
+/// 等待停止信号
+async fn wait_stop(receiver: &mut watch::Receiver<bool>) {
+    let _ = receiver.wait_for(|stop| *stop).await;
+}
+
 impl Drop for TaskManager {
     fn drop(&mut self) {
         self.shutdown();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FailingTask {
+        calls: Arc<AtomicUsize>,
+        action: fn() -> ErrorAction,
+    }
+
+    #[async_trait]
+    impl Task for FailingTask {
+        async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err("failed".into())
+        }
+
+        fn on_error(&self, _error: Box<dyn Error + Send + Sync>) -> ErrorAction {
+            (self.action)()
+        }
+
+        fn record_history(&self) -> bool {
+            false
+        }
+    }
+
+    fn job(task: impl Task, cron_key: Option<&str>) -> Job {
+        let statuses: Statuses = Arc::new(RwLock::new(HashMap::new()));
+        statuses.try_write().unwrap().insert(0, TaskStatus {
+            id: 0,
+            name: task.name(),
+            last_run: None,
+            last_error: None,
+            running: true,
+        });
+        Job {
+            id: 0,
+            name: task.name(),
+            task: Box::new(task),
+            cron_expr: "0 0 * * * *".to_string(),
+            cron_key: cron_key.map(|key| key.to_string()),
+            db: DatabaseConnection::Disconnected,
+            statuses,
+        }
+    }
+
+    fn failing_job(action: fn() -> ErrorAction) -> (Job, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        (job(FailingTask { calls: calls.clone(), action }, None), calls)
+    }
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_misfire_fires() {
+        let schedule = Schedule::from_str("0 0 * * * *").unwrap();
+        let (last, now) = (time(1, 0, 0), time(1, 3, 30));
+        assert_eq!(misfire_fires(&schedule, last, now, JOB_MISFIRE_FIRE_ALL), vec![time(1, 1, 0), time(1, 2, 0), time(1, 3, 0)]);
+        // 只补执行最近一次
+        assert_eq!(misfire_fires(&schedule, last, now, JOB_MISFIRE_FIRE_ONCE), vec![time(1, 3, 0)]);
+        assert!(misfire_fires(&schedule, last, now, JOB_MISFIRE_IGNORE).is_empty());
+        // 没有错过的执行
+        assert!(misfire_fires(&schedule, last, time(1, 0, 30), JOB_MISFIRE_FIRE_ALL).is_empty());
+        assert!(misfire_fires(&schedule, last, time(1, 0, 30), JOB_MISFIRE_FIRE_ONCE).is_empty());
+    }
+
+    #[test]
+    fn test_misfire_fires_limit() {
+        let schedule = Schedule::from_str("0 0 * * * *").unwrap();
+        let fires = misfire_fires(&schedule, time(1, 0, 0), time(2, 0, 0), JOB_MISFIRE_FIRE_ALL);
+        assert_eq!(fires.len(), MAX_MISFIRE_FIRES);
+        assert_eq!(fires[0], time(1, 1, 0));
+    }
+
+    #[test]
+    fn test_claim_key() {
+        assert_eq!(claim_key("SyncJob", None), format!("{}SyncJob", REDIS_KEY_JOB_FIRE_PREFIX));
+        assert_eq!(claim_key("SyncJob", Some("00ff")), format!("{}SyncJob:manual:00ff", REDIS_KEY_JOB_FIRE_PREFIX));
+    }
+
+    #[tokio::test]
+    async fn test_retry_max_attempts() {
+        let (job, calls) = failing_job(|| ErrorAction::Retry(0));
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        assert!(job.run_attempts(JOB_TRIGGER_SCHEDULE, &mut stop_receiver).await);
+        assert_eq!(calls.load(Ordering::SeqCst), MAX_ATTEMPTS as usize);
+
+        let status = job.statuses.read().await.get(&0).cloned().unwrap();
+        assert!(status.last_run.is_some());
+        assert_eq!(status.last_error.as_deref(), Some("failed"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_stopped() {
+        let (job, calls) = failing_job(|| ErrorAction::Retry(3600));
+        let (_stop_sender, mut stop_receiver) = watch::channel(true);
+        // 等待重试时收到停止信号,不再重试
+        assert!(job.run_attempts(JOB_TRIGGER_SCHEDULE, &mut stop_receiver).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_error_action() {
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        let (job, calls) = failing_job(|| ErrorAction::Continue);
+        assert!(job.run_attempts(JOB_TRIGGER_SCHEDULE, &mut stop_receiver).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (job, calls) = failing_job(|| ErrorAction::Stop);
+        assert!(!job.run_attempts(JOB_TRIGGER_MANUAL, &mut stop_receiver).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_cron() {
+        let key = "test.task.reload_cron";
+        let job = job(FailingTask { calls: Arc::new(AtomicUsize::new(0)), action: || ErrorAction::Continue }, Some(key));
+        let mut current_cron = job.configured_cron();
+        let mut schedule = Schedule::from_str(&current_cron).unwrap();
+        assert_eq!(current_cron, "0 0 * * * *");
+        assert!(!job.reload_cron(&mut current_cron, &mut schedule));
+
+        // 参数变更后收到通知并重新调度
+        let mut receiver = system_config::subscribe();
+        system_config::replace(vec![((ROOT_TENANT_ID, key.to_string()), "0 30 * * * *".to_string())]);
+        tokio::time::timeout(Duration::from_secs(1), receiver.changed()).await.unwrap().unwrap();
+        assert!(job.reload_cron(&mut current_cron, &mut schedule));
+        assert_eq!(current_cron, "0 30 * * * *");
+        assert_eq!(schedule.after(&time(1, 0, 0)).next(), Some(time(1, 0, 30)));
+        assert!(!job.reload_cron(&mut current_cron, &mut schedule));
+
+        // 无效的表达式保持原调度
+        system_config::replace(vec![((ROOT_TENANT_ID, key.to_string()), "invalid".to_string())]);
+        assert!(!job.reload_cron(&mut current_cron, &mut schedule));
+        assert_eq!(current_cron, "0 30 * * * *");
+
+        // 参数删除后恢复默认表达式
+        system_config::replace(Vec::new());
+        assert!(job.reload_cron(&mut current_cron, &mut schedule));
+        assert_eq!(current_cron, "0 0 * * * *");
+    }
+
+    #[test]
+    fn test_reload_cron_without_key() {
+        let (job, _) = failing_job(|| ErrorAction::Continue);
+        let mut current_cron = "0 30 * * * *".to_string();
+        let mut schedule = Schedule::from_str(&current_cron).unwrap();
+        assert!(!job.reload_cron(&mut current_cron, &mut schedule));
+        assert_eq!(current_cron, "0 30 * * * *");
+    }
+
+    /// 需要本地redis
+    #[tokio::test]
+    #[ignore]
+    async fn test_claim_script() {
+        let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        let key = claim_key("test_claim_script", None);
+        let _: () = redis::cmd("DEL").arg(&key).query_async(&mut conn).await.unwrap();
+
+        // 同一计划时间只有一个副本认领成功,更早的计划时间不能再执行
+        for (fire_time, expected) in [(1000, 1), (1000, 0), (999, 0), (2000, 1)] {
+            let claimed: i64 = CLAIM_SCRIPT.key(&key).arg(fire_time).invoke_async(&mut conn).await.unwrap();
+            assert_eq!(claimed, expected, "fire time {}", fire_time);
+        }
+    }
+}
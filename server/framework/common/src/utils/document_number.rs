@@ -0,0 +1,350 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::{Datelike, Local, NaiveDateTime};
+use sea_orm::{ConnectionTrait, DbBackend, Statement, Value};
+use serde::{Deserialize, Serialize};
+use crate::config::system_config;
+use crate::database::redis_constants::REDIS_KEY_DOCUMENT_NUMBER_PREFIX;
+use crate::database::redis_pool::AsyncRedisManager;
+
+/// 单据类型
+pub const DOCUMENT_TYPE_PURCHASE_ORDER: &str = "purchase_order"; // 采购订单
+pub const DOCUMENT_TYPE_PURCHASE_RETURN: &str = "purchase_return"; // 采购退货
+pub const DOCUMENT_TYPE_SALES_ORDER: &str = "sales_order"; // 销售订单
+pub const DOCUMENT_TYPE_SALES_RETURN: &str = "sales_return"; // 销售退货
+pub const DOCUMENT_TYPE_INBOUND_ORDER: &str = "inbound_order"; // 入库单
+pub const DOCUMENT_TYPE_OUTBOUND_ORDER: &str = "outbound_order"; // 出库单
+pub const DOCUMENT_TYPE_INVENTORY_TRANSFER: &str = "inventory_transfer"; // 调拨单
+pub const DOCUMENT_TYPE_INVENTORY_CHECK: &str = "inventory_check"; // 盘点单
+pub const DOCUMENT_TYPE_RECEIPT: &str = "receipt"; // 收款单
+pub const DOCUMENT_TYPE_PAYMENT: &str = "payment"; // 付款单
+
+/// 编号规则的系统参数key前缀,完整key如 common.document_number.purchase_order,租户可以单独配置覆盖
+const CONFIG_KEY_DOCUMENT_NUMBER_PREFIX: &str = "common.document_number.";
+/// 按日/按月重置的计数器保留时间,过期后自动删除
+const DAILY_COUNTER_TTL: i64 = 2 * 24 * 3600;
+const MONTHLY_COUNTER_TTL: i64 = 32 * 24 * 3600;
+
+/// 序号重置周期
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetPolicy {
+    Daily, // 每天
+    Monthly, // 每月
+    Never, // 不重置
+}
+
+/// 编号规则,格式为 前缀-日期-序号[校验位],如 PO-20261018-0001
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NumberRule {
+    pub prefix: String, // 前缀
+    pub separator: String, // 分隔符
+    pub date_pattern: String, // 日期格式,为空时不包含日期
+    pub reset: ResetPolicy, // 序号重置周期
+    pub sequence_length: usize, // 序号最小位数,不足时补0
+    pub check_digit: bool, // 是否在末尾追加校验位
+}
+
+impl Default for NumberRule {
+    fn default() -> Self {
+        NumberRule {
+            prefix: String::new(),
+            separator: "-".to_string(),
+            date_pattern: "%Y%m%d".to_string(),
+            reset: ResetPolicy::Daily,
+            sequence_length: 4,
+            check_digit: false,
+        }
+    }
+}
+
+impl NumberRule {
+    pub fn with_prefix(prefix: &str) -> Self {
+        NumberRule {
+            prefix: prefix.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 校验日期格式,序号按日/按月重置时日期必须能区分周期,否则不同周期会生成相同的编号
+    pub fn validate(&self) -> Result<()> {
+        let items: Vec<Item> = StrftimeItems::new(&self.date_pattern).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            bail!("编号规则日期格式无效: {}", self.date_pattern);
+        }
+        let has = |check: fn(&Item) -> bool| items.iter().any(check);
+        let timestamp = has(|item| matches!(item, Item::Numeric(Numeric::Timestamp, _)));
+        let year = timestamp || has(|item| matches!(item, Item::Numeric(Numeric::Year | Numeric::YearMod100, _)));
+        let ordinal = has(|item| matches!(item, Item::Numeric(Numeric::Ordinal, _)));
+        let month = timestamp || ordinal || has(|item| matches!(item,
+            Item::Numeric(Numeric::Month, _) | Item::Fixed(Fixed::ShortMonthName | Fixed::LongMonthName)));
+        let day = timestamp || ordinal || has(|item| matches!(item, Item::Numeric(Numeric::Day, _)));
+        let valid = match self.reset {
+            ResetPolicy::Daily => year && month && day,
+            ResetPolicy::Monthly => year && month,
+            ResetPolicy::Never => true,
+        };
+        if !valid {
+            bail!("编号规则日期格式 {:?} 不能区分序号重置周期 {:?}", self.date_pattern, self.reset);
+        }
+        Ok(())
+    }
+}
+
+/// 单据类型的默认规则,系统参数中未配置时使用
+pub fn default_rule(document_type: &str) -> NumberRule {
+    let prefix = match document_type {
+        DOCUMENT_TYPE_PURCHASE_ORDER => "PO",
+        DOCUMENT_TYPE_PURCHASE_RETURN => "PR",
+        DOCUMENT_TYPE_SALES_ORDER => "SO",
+        DOCUMENT_TYPE_SALES_RETURN => "SR",
+        DOCUMENT_TYPE_INBOUND_ORDER => "IN",
+        DOCUMENT_TYPE_OUTBOUND_ORDER => "OUT",
+        DOCUMENT_TYPE_INVENTORY_TRANSFER => "TR",
+        DOCUMENT_TYPE_INVENTORY_CHECK => "IC",
+        DOCUMENT_TYPE_RECEIPT => "RC",
+        DOCUMENT_TYPE_PAYMENT => "PM",
+        _ => "NO",
+    };
+    NumberRule::with_prefix(prefix)
+}
+
+/// 单据类型对应的表,用于redis计数器丢失时从已有编号恢复序号
+pub fn document_table(document_type: &str) -> Option<&'static str> {
+    match document_type {
+        DOCUMENT_TYPE_PURCHASE_ORDER => Some("erp_purchase_order"),
+        DOCUMENT_TYPE_PURCHASE_RETURN => Some("erp_purchase_return"),
+        DOCUMENT_TYPE_SALES_ORDER => Some("erp_sales_order"),
+        DOCUMENT_TYPE_SALES_RETURN => Some("erp_sales_return"),
+        DOCUMENT_TYPE_INBOUND_ORDER => Some("erp_inbound_order"),
+        DOCUMENT_TYPE_OUTBOUND_ORDER => Some("erp_outbound_order"),
+        DOCUMENT_TYPE_INVENTORY_TRANSFER => Some("erp_inventory_transfer"),
+        DOCUMENT_TYPE_INVENTORY_CHECK => Some("erp_inventory_check"),
+        DOCUMENT_TYPE_RECEIPT => Some("erp_receipt"),
+        DOCUMENT_TYPE_PAYMENT => Some("erp_payment"),
+        _ => None,
+    }
+}
+
+/// 获取租户的编号规则,租户配置优先,其次是根租户配置,都没有时使用默认规则
+pub fn get_rule(tenant_id: i64, document_type: &str) -> NumberRule {
+    system_config::get_json(tenant_id, &format!("{}{}", CONFIG_KEY_DOCUMENT_NUMBER_PREFIX, document_type))
+        .unwrap_or_else(|| default_rule(document_type))
+}
+
+/// 生成单据编号
+/// 序号使用redis计数器按租户、单据类型和周期原子递增,多副本下不会重复
+/// 计数器不存在时(新周期、redis数据丢失)先用表中本周期最大的序号初始化,避免和已有编号重复
+pub async fn generate<C: ConnectionTrait>(db: &C, tenant_id: i64, document_type: &str) -> Result<String> {
+    let rule = get_rule(tenant_id, document_type);
+    rule.validate()?;
+    let now = Local::now();
+    let date = if rule.date_pattern.is_empty() { String::new() } else { now.format(&rule.date_pattern).to_string() };
+    let today = now.date_naive();
+    let (period, ttl, period_start) = match rule.reset {
+        ResetPolicy::Daily => (now.format("%Y%m%d").to_string(), Some(DAILY_COUNTER_TTL), today.and_hms_opt(0, 0, 0)),
+        ResetPolicy::Monthly => (now.format("%Y%m").to_string(), Some(MONTHLY_COUNTER_TTL), today.with_day(1).and_then(|day| day.and_hms_opt(0, 0, 0))),
+        ResetPolicy::Never => ("all".to_string(), None, None),
+    };
+
+    let key = format!("{}{}:{}:{}", REDIS_KEY_DOCUMENT_NUMBER_PREFIX, tenant_id, document_type, period);
+    let exists = AsyncRedisManager::exists(&key).await
+        .map_err(|e| anyhow!("单据编号生成失败: {}", e))?;
+    if !exists {
+        if let Some(table) = document_table(document_type) {
+            let seed = max_sequence(db, table, tenant_id, &rule, &date, period_start).await?;
+            if seed > 0 {
+                // 多个副本同时初始化时只有第一个生效,查询到的最大序号相同
+                let mut pipeline = redis::pipe();
+                pipeline.atomic().set_nx(&key, seed).ignore();
+                if let Some(ttl) = ttl {
+                    pipeline.expire(&key, ttl).ignore();
+                }
+                AsyncRedisManager::query_pipeline::<()>(&pipeline).await
+                    .map_err(|e| anyhow!("单据编号生成失败: {}", e))?;
+            }
+        }
+    }
+
+    let mut pipeline = redis::pipe();
+    pipeline.atomic().incr(&key, 1);
+    if let Some(ttl) = ttl {
+        pipeline.expire(&key, ttl).ignore();
+    }
+    let (sequence,): (i64,) = AsyncRedisManager::query_pipeline(&pipeline).await
+        .map_err(|e| anyhow!("单据编号生成失败: {}", e))?;
+
+    Ok(format_number(&rule, &date, sequence))
+}
+
+/// 查询表中本周期内按规则生成的最大序号,没有时返回0
+/// 同一规则下编号长度相同时按字符串比较即可,序号超过最小位数时编号更长
+async fn max_sequence<C: ConnectionTrait>(db: &C, table: &str, tenant_id: i64, rule: &NumberRule, date: &str, period_start: Option<NaiveDateTime>) -> Result<i64> {
+    let pattern = format!("{}%", escape_like(&rule.prefix));
+    let (sql, values): (String, Vec<Value>) = match period_start {
+        Some(start) => (
+            format!("SELECT order_number FROM {} WHERE tenant_id = ? AND order_number LIKE ? AND create_time >= ? \
+                     ORDER BY LENGTH(order_number) DESC, order_number DESC LIMIT 1", table),
+            vec![tenant_id.into(), pattern.into(), start.into()],
+        ),
+        None => (
+            format!("SELECT order_number FROM {} WHERE tenant_id = ? AND order_number LIKE ? \
+                     ORDER BY LENGTH(order_number) DESC, order_number DESC LIMIT 1", table),
+            vec![tenant_id.into(), pattern.into()],
+        ),
+    };
+    let stmt = Statement::from_sql_and_values(DbBackend::MySql, sql, values);
+    let number: Option<String> = match db.query_one(stmt).await? {
+        Some(row) => Some(row.try_get("", "order_number")?),
+        None => None,
+    };
+    Ok(number.and_then(|number| parse_sequence(rule, date, &number)).unwrap_or(0))
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// 从编号中取出序号,date为当前周期格式化后的日期,同一规则下日期长度相同
+pub fn parse_sequence(rule: &NumberRule, date: &str, number: &str) -> Option<i64> {
+    if !number.starts_with(&rule.prefix) {
+        return None;
+    }
+    let head_len = number_head(rule, date).len();
+    if head_len > 0 && !number.get(..head_len)?.ends_with(&rule.separator) {
+        return None;
+    }
+    let sequence = number.get(head_len..)?;
+    let sequence = if rule.check_digit { sequence.get(..sequence.len().checked_sub(1)?)? } else { sequence };
+    if sequence.is_empty() || !sequence.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    sequence.parse().ok()
+}
+
+/// 编号中序号之前的部分,包含前缀、日期和分隔符
+fn number_head(rule: &NumberRule, date: &str) -> String {
+    let head = [rule.prefix.as_str(), date]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(&rule.separator);
+    if head.is_empty() { head } else { head + &rule.separator }
+}
+
+/// 按规则拼接编号
+pub fn format_number(rule: &NumberRule, date: &str, sequence: i64) -> String {
+    let mut sequence = format!("{:0width$}", sequence, width = rule.sequence_length);
+    if rule.check_digit {
+        sequence.push(check_digit(&format!("{}{}", date, sequence)));
+    }
+    number_head(rule, date) + &sequence
+}
+
+/// Luhn校验位,只计算数字部分,用于人工录入时发现输错的编号
+pub fn check_digit(digits: &str) -> char {
+    let sum: u32 = digits.chars()
+        .filter_map(|c| c.to_digit(10))
+        .rev()
+        .enumerate()
+        .map(|(i, d)| {
+            // 从右往左,校验位左边第一位开始隔位乘2
+            if i % 2 == 0 {
+                let d = d * 2;
+                if d > 9 { d - 9 } else { d }
+            } else {
+                d
+            }
+        })
+        .sum();
+    char::from_digit((10 - sum % 10) % 10, 10).unwrap_or('0')
+}
+
+/// 校验带校验位的编号
+pub fn verify(rule: &NumberRule, number: &str) -> bool {
+    if !rule.check_digit {
+        return true;
+    }
+    let digits: String = number.strip_prefix(&rule.prefix).unwrap_or(number)
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    if digits.is_empty() {
+        return false;
+    }
+    let (body, last) = digits.split_at(digits.len() - 1);
+    last.starts_with(check_digit(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(check_digit: bool) -> NumberRule {
+        NumberRule { check_digit, ..NumberRule::with_prefix("PO") }
+    }
+
+    #[test]
+    fn check_digit_matches_luhn() {
+        assert_eq!(check_digit("7992739871"), '3');
+        assert_eq!(check_digit("0"), '0');
+        // 非数字字符不参与计算
+        assert_eq!(check_digit("79927-39871"), '3');
+    }
+
+    #[test]
+    fn format_number_joins_parts() {
+        assert_eq!(format_number(&rule(false), "20261018", 1), "PO-20261018-0001");
+        assert_eq!(format_number(&rule(false), "20261018", 12345), "PO-20261018-12345");
+        let no_date = NumberRule { date_pattern: String::new(), reset: ResetPolicy::Never, ..rule(false) };
+        assert_eq!(format_number(&no_date, "", 7), "PO-0007");
+        let no_prefix = NumberRule { prefix: String::new(), separator: String::new(), ..rule(false) };
+        assert_eq!(format_number(&no_prefix, "20261018", 7), "202610180007");
+        assert_eq!(format_number(&rule(true), "20261018", 1), format!("PO-20261018-0001{}", check_digit("202610180001")));
+    }
+
+    #[test]
+    fn verify_check_digit() {
+        let number = format_number(&rule(true), "20261018", 42);
+        assert!(verify(&rule(true), &number));
+        // 改动任意一位都能发现
+        let typo = number.replacen("0042", "0043", 1);
+        assert!(!verify(&rule(true), &typo));
+        assert!(!verify(&rule(true), "PO--"));
+        // 规则没有校验位时不校验
+        assert!(verify(&rule(false), "PO-20261018-0043"));
+    }
+
+    #[test]
+    fn parse_sequence_reverses_format() {
+        for check in [false, true] {
+            let rule = rule(check);
+            for sequence in [1, 42, 12345] {
+                let number = format_number(&rule, "20261018", sequence);
+                assert_eq!(parse_sequence(&rule, "20261018", &number), Some(sequence));
+            }
+        }
+        // 旧的雪花id编号和其他前缀的编号不参与
+        assert_eq!(parse_sequence(&rule(false), "20261018", "62349514941403136"), None);
+        assert_eq!(parse_sequence(&rule(false), "20261018", "SO-20261018-0001"), None);
+    }
+
+    #[test]
+    fn validate_date_pattern_covers_reset_period() {
+        let with = |date_pattern: &str, reset| NumberRule { date_pattern: date_pattern.to_string(), reset, ..rule(false) };
+        assert!(with("%Y%m%d", ResetPolicy::Daily).validate().is_ok());
+        assert!(with("%F", ResetPolicy::Daily).validate().is_ok());
+        assert!(with("%y%j", ResetPolicy::Daily).validate().is_ok());
+        assert!(with("%Y%m", ResetPolicy::Monthly).validate().is_ok());
+        assert!(with("%Y%m%d", ResetPolicy::Monthly).validate().is_ok());
+        assert!(with("", ResetPolicy::Never).validate().is_ok());
+        // 日期比重置周期粗时,同一日期内序号会重复
+        assert!(with("%Y%m", ResetPolicy::Daily).validate().is_err());
+        assert!(with("%m%d", ResetPolicy::Daily).validate().is_err());
+        assert!(with("", ResetPolicy::Daily).validate().is_err());
+        assert!(with("%Y", ResetPolicy::Monthly).validate().is_err());
+        assert!(with("%Q", ResetPolicy::Never).validate().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use sha2::Sha256;
+use crate::config::config::Config;
+
+/// 密文前缀,格式 enc:{密钥编号}:{加密后的数据密钥}:{加密后的数据},没有前缀的按历史明文处理
+const CIPHER_PREFIX: &str = "enc:";
+/// AES-GCM随机数长度
+const NONCE_LEN: usize = 12;
+/// 盲索引长度(字节),十六进制后为32个字符
+const BLIND_INDEX_LEN: usize = 16;
+
+/// 信封加密密钥环: 每个值使用随机数据密钥加密,数据密钥再由主密钥加密后和密文一起保存
+struct KeyRing {
+    active_key: String, // 当前加密使用的主密钥编号
+    keys: HashMap<String, Aes256Gcm>, // 主密钥,旧密钥只用于解密
+    blind_index_key: Vec<u8>, // 盲索引密钥
+}
+
+/// 密钥在启动时由 Config::validate 校验
+static KEY_RING: Lazy<KeyRing> = Lazy::new(|| {
+    let config = Config::load().encryption;
+    KeyRing::new(&config.active_key, &config.keys, &config.blind_index_key)
+});
+
+impl KeyRing {
+    /// 主密钥和盲索引密钥为base64编码,无效的主密钥忽略
+    fn new(active_key: &str, keys: &HashMap<String, String>, blind_index_key: &str) -> Self {
+        let keys = keys.iter()
+            .filter_map(|(id, key)| {
+                let bytes = STANDARD.decode(key).ok()?;
+                Aes256Gcm::new_from_slice(&bytes).ok().map(|cipher| (id.clone(), cipher))
+            })
+            .collect();
+        KeyRing {
+            active_key: active_key.to_string(),
+            keys,
+            blind_index_key: STANDARD.decode(blind_index_key).unwrap_or_default(),
+        }
+    }
+
+    fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let kek = self.keys.get(&self.active_key)
+            .ok_or_else(|| anyhow!("encryption key {} not configured", self.active_key))?;
+        let dek = Aes256Gcm::generate_key(OsRng);
+        let data = seal(&Aes256Gcm::new(&dek), plaintext.as_bytes(), b"")?;
+        // 数据密钥绑定主密钥编号,防止被替换到其他密钥下
+        let wrapped_dek = seal(kek, dek.as_slice(), self.active_key.as_bytes())?;
+        Ok(format!("{}{}:{}:{}", CIPHER_PREFIX, self.active_key, STANDARD_NO_PAD.encode(wrapped_dek), STANDARD_NO_PAD.encode(data)))
+    }
+
+    fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(rest) = value.strip_prefix(CIPHER_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let mut parts = rest.splitn(3, ':');
+        let (Some(key_id), Some(wrapped_dek), Some(data)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(anyhow!("invalid encrypted value"));
+        };
+        let kek = self.keys.get(key_id)
+            .ok_or_else(|| anyhow!("encryption key {} not configured", key_id))?;
+        let dek = open(kek, &STANDARD_NO_PAD.decode(wrapped_dek)?, key_id.as_bytes())?;
+        let dek = Aes256Gcm::new_from_slice(&dek).map_err(|_| anyhow!("invalid data key"))?;
+        let plaintext = open(&dek, &STANDARD_NO_PAD.decode(data)?, b"")?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    fn is_current(&self, value: &str) -> bool {
+        value.strip_prefix(CIPHER_PREFIX)
+            .and_then(|rest| rest.split(':').next())
+            .map_or(false, |key_id| key_id == self.active_key)
+    }
+
+    fn blind_index(&self, value: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.blind_index_key)
+            .expect("hmac accepts keys of any length");
+        mac.update(value.trim().as_bytes());
+        mac.finalize().into_bytes()[..BLIND_INDEX_LEN].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// 加密字段值
+pub fn encrypt(plaintext: &str) -> Result<String> {
+    KEY_RING.encrypt(plaintext)
+}
+
+/// 解密字段值,没有密文前缀的历史明文原样返回
+pub fn decrypt(value: &str) -> Result<String> {
+    KEY_RING.decrypt(value)
+}
+
+/// 是否已使用当前主密钥加密,否则需要重新加密
+pub fn is_current(value: &str) -> bool {
+    KEY_RING.is_current(value)
+}
+
+/// 当前主密钥编号的密文前缀,用于查询需要重新加密的数据
+pub fn current_prefix() -> String {
+    format!("{}{}:", CIPHER_PREFIX, KEY_RING.active_key)
+}
+
+/// 盲索引,密文字段按值等值查询时使用,去掉首尾空白后计算
+pub fn blind_index(value: &str) -> String {
+    KEY_RING.blind_index(value)
+}
+
+/// 加密,结果为 随机数 + 密文
+fn seal(cipher: &Aes256Gcm, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut sealed = nonce.to_vec();
+    sealed.extend(cipher.encrypt(&nonce, Payload { msg: plaintext, aad }).map_err(|_| anyhow!("encrypt failed"))?);
+    Ok(sealed)
+}
+
+fn open(cipher: &Aes256Gcm, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() <= NONCE_LEN {
+        return Err(anyhow!("invalid encrypted value"));
+    }
+    let (nonce, data) = sealed.split_at(NONCE_LEN);
+    cipher.decrypt(Nonce::from_slice(nonce), Payload { msg: data, aad }).map_err(|_| anyhow!("decrypt failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> String {
+        STANDARD.encode([byte; 32])
+    }
+
+    fn ring(active_key: &str, keys: &[(&str, u8)]) -> KeyRing {
+        let keys = keys.iter().map(|(id, byte)| (id.to_string(), key(*byte))).collect();
+        KeyRing::new(active_key, &keys, &key(9))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key_ring = ring("k1", &[("k1", 1)]);
+        let encrypted = key_ring.encrypt("13812345678").unwrap();
+        assert!(encrypted.starts_with("enc:k1:"));
+        assert!(!encrypted.contains("13812345678"));
+        assert_eq!(key_ring.decrypt(&encrypted).unwrap(), "13812345678");
+        // 每次加密使用不同的数据密钥和随机数
+        assert_ne!(key_ring.encrypt("13812345678").unwrap(), encrypted);
+        assert_eq!(key_ring.decrypt(&key_ring.encrypt("").unwrap()).unwrap(), "");
+    }
+
+    #[test]
+    fn test_plaintext_passthrough() {
+        let key_ring = ring("k1", &[("k1", 1)]);
+        assert_eq!(key_ring.decrypt("13812345678").unwrap(), "13812345678");
+        assert!(!key_ring.is_current("13812345678"));
+    }
+
+    #[test]
+    fn test_wrong_key() {
+        let encrypted = ring("k1", &[("k1", 1)]).encrypt("secret").unwrap();
+        // 相同编号的不同主密钥无法解密
+        assert!(ring("k1", &[("k1", 2)]).decrypt(&encrypted).is_err());
+        // 未配置的主密钥
+        assert!(ring("k2", &[("k2", 1)]).decrypt(&encrypted).is_err());
+        assert!(ring("k1", &[]).encrypt("secret").is_err());
+    }
+
+    #[test]
+    fn test_tampered() {
+        let key_ring = ring("k1", &[("k1", 1), ("k2", 1)]);
+        let encrypted = key_ring.encrypt("secret").unwrap();
+        let parts: Vec<&str> = encrypted.splitn(4, ':').collect();
+        let (wrapped_dek, data) = (parts[2], parts[3]);
+
+        let mut bytes = STANDARD_NO_PAD.decode(data).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let tampered = format!("enc:k1:{}:{}", wrapped_dek, STANDARD_NO_PAD.encode(&bytes));
+        assert!(key_ring.decrypt(&tampered).is_err());
+
+        // 数据密钥绑定主密钥编号,相同密钥的其他编号也无法解密
+        let rebound = format!("enc:k2:{}:{}", wrapped_dek, data);
+        assert!(key_ring.decrypt(&rebound).is_err());
+
+        assert!(key_ring.decrypt("enc:k1:abc").is_err());
+        assert!(key_ring.decrypt("enc:k1:YWJj:YWJj").is_err());
+        assert!(key_ring.decrypt("enc:k1:!!:!!").is_err());
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = ring("k1", &[("k1", 1)]);
+        let encrypted = old.encrypt("secret").unwrap();
+        assert!(old.is_current(&encrypted));
+
+        // 切换主密钥后旧密文仍可解密,但需要重新加密
+        let rotated = ring("k2", &[("k1", 1), ("k2", 2)]);
+        assert!(!rotated.is_current(&encrypted));
+        assert_eq!(rotated.decrypt(&encrypted).unwrap(), "secret");
+        let reencrypted = rotated.encrypt(&rotated.decrypt(&encrypted).unwrap()).unwrap();
+        assert!(reencrypted.starts_with("enc:k2:"));
+        assert!(rotated.is_current(&reencrypted));
+        assert!(old.decrypt(&reencrypted).is_err());
+    }
+
+    #[test]
+    fn test_blind_index() {
+        let key_ring = ring("k1", &[("k1", 1)]);
+        let index = key_ring.blind_index("91310000MA1K000000");
+        assert_eq!(index.len(), BLIND_INDEX_LEN * 2);
+        assert!(index.chars().all(|c| c.is_ascii_hexdigit()));
+        // 相同的值稳定,去掉首尾空白,与主密钥轮换无关
+        assert_eq!(key_ring.blind_index(" 91310000MA1K000000 "), index);
+        assert_eq!(ring("k2", &[("k2", 2)]).blind_index("91310000MA1K000000"), index);
+        assert_ne!(key_ring.blind_index("91310000MA1K000001"), index);
+        let other = KeyRing::new("k1", &HashMap::new(), &key(8));
+        assert_ne!(other.blind_index("91310000MA1K000000"), index);
+    }
+}
@@ -0,0 +1,282 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rand::Rng;
+use tonic::transport::Channel;
+use tonic::{Code, Status};
+use tracing::{info, warn};
+use crate::config::config::{Config, GrpcClientConfig};
+use crate::monitor::metrics;
+use crate::utils::grpc_tls;
+
+/// 熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    Closed, // 正常调用
+    Open(Instant), // 熔断中,到期后进入半开
+    HalfOpen(Instant), // 已放行一个探测请求,等待结果;探测请求被取消时没有结果,到期后重新放行
+}
+
+impl CircuitState {
+    fn metric(&self) -> i64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open(_) => 1,
+            CircuitState::HalfOpen(_) => 2,
+        }
+    }
+}
+
+/// 熔断器,按服务统计连续失败次数
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    failures: u32,
+}
+
+impl CircuitBreaker {
+    /// 是否放行请求,熔断到期后只放行一个探测请求,probe_timeout内没有结果时再放行一个
+    fn try_acquire(&mut self, probe_timeout: Duration) -> bool {
+        let now = Instant::now();
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open(until) | CircuitState::HalfOpen(until) if now >= until => {
+                self.state = CircuitState::HalfOpen(now + probe_timeout);
+                true
+            }
+            CircuitState::Open(_) | CircuitState::HalfOpen(_) => false,
+        }
+    }
+
+    fn on_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.failures = 0;
+    }
+
+    fn on_failure(&mut self, threshold: u32, open: Duration) {
+        self.failures += 1;
+        // 探测失败或连续失败达到阈值时熔断
+        if matches!(self.state, CircuitState::HalfOpen(_)) || self.failures >= threshold {
+            self.state = CircuitState::Open(Instant::now() + open);
+        }
+    }
+}
+
+/// 复用的连接: (地址, 证书版本) -> 连接
+static CHANNELS: Lazy<DashMap<String, (u64, Channel)>> = Lazy::new(DashMap::new);
+/// 服务名 -> 熔断器
+static BREAKERS: Lazy<DashMap<&'static str, Arc<Mutex<CircuitBreaker>>>> = Lazy::new(DashMap::new);
+
+/// grpc客户端,同一地址的客户端共用一个连接,连接在第一次调用时建立,断开后自动重连
+/// 用法:
+/// ```ignore
+/// let client = GrpcClient::new("system", &config.grpc.system_service_url);
+/// let users = client.call("GetUser", true, |channel| async move {
+///     SystemClient::from_channel(channel).get_user(ids.clone(), "").await
+/// }).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    service: &'static str, // 服务名,用于熔断、指标和超时配置
+    url: String,
+    config: GrpcClientConfig,
+    timeout: Duration,
+}
+
+impl GrpcClient {
+    pub fn new(service: &'static str, url: &str) -> Self {
+        let config = Config::load().grpc.client;
+        let timeout_ms = config.service_timeouts_ms.get(service).copied().unwrap_or(config.timeout_ms);
+        GrpcClient {
+            service,
+            url: url.to_string(),
+            config,
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    /// 覆盖调用超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 获取复用的连接,证书重新加载后重建
+    pub fn channel(&self) -> Result<Channel> {
+        let version = grpc_tls::current().map_or(0, |tls| tls.version());
+        if let Some(entry) = CHANNELS.get(&self.url) {
+            if entry.0 == version {
+                return Ok(entry.1.clone());
+            }
+        }
+        let channel = grpc_tls::endpoint(&self.url)?
+            .connect_timeout(Duration::from_millis(self.config.connect_timeout_ms))
+            .tcp_keepalive(Some(Duration::from_secs(60)))
+            .http2_keep_alive_interval(Duration::from_secs(30))
+            .keep_alive_while_idle(true)
+            .connect_lazy();
+        CHANNELS.insert(self.url.clone(), (version, channel.clone()));
+        Ok(channel)
+    }
+
+    /// 调用rpc,超时时间包括所有重试;idempotent为true时对连接失败、超时等错误重试
+    /// 服务连续失败后熔断,熔断期间直接返回错误,到期后放行一个探测请求
+    pub async fn call<T, F, Fut>(&self, rpc: &'static str, idempotent: bool, mut f: F) -> Result<T>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let breaker = BREAKERS.entry(self.service)
+            .or_insert_with(|| Arc::new(Mutex::new(CircuitBreaker { state: CircuitState::Closed, failures: 0 })))
+            .clone();
+        let start = Instant::now();
+        let deadline = start + self.timeout;
+        let mut attempt: u32 = 0;
+        loop {
+            // 探测请求最长执行到调用超时,调用方取消请求时不会回写结果
+            let probe_timeout = deadline.saturating_duration_since(Instant::now());
+            if !self.update_breaker(&breaker, |breaker| breaker.try_acquire(probe_timeout)) {
+                metrics::record_grpc_call(self.service, rpc, "circuit_open", start.elapsed());
+                return Err(anyhow!(Status::unavailable(format!("服务{}熔断中", self.service))));
+            }
+
+            let channel = self.channel()?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let result = match tokio::time::timeout(remaining, f(channel)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!(Status::deadline_exceeded(format!("调用{}.{}超时", self.service, rpc)))),
+            };
+            let code = match &result {
+                Ok(_) => Code::Ok,
+                Err(e) => error_code(e),
+            };
+
+            if is_service_failure(code) {
+                let (threshold, open) = (self.config.breaker_failure_threshold, Duration::from_secs(self.config.breaker_open_secs));
+                self.update_breaker(&breaker, |breaker| breaker.on_failure(threshold, open));
+            } else {
+                // 业务错误说明服务可用
+                self.update_breaker(&breaker, |breaker| breaker.on_success());
+            }
+
+            let backoff = self.backoff(attempt);
+            let retryable = idempotent && attempt < self.config.max_retries && is_retryable(code)
+                && Instant::now() + backoff < deadline;
+            match result {
+                Err(e) if retryable => {
+                    attempt += 1;
+                    metrics::record_grpc_retry(self.service, rpc);
+                    warn!("grpc {}.{} failed, retry {} after {:?}: {}", self.service, rpc, attempt, backoff, e);
+                    tokio::time::sleep(backoff).await;
+                }
+                result => {
+                    metrics::record_grpc_call(self.service, rpc, &format!("{:?}", code), start.elapsed());
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// 指数退避加随机抖动
+    fn backoff(&self, attempt: u32) -> Duration {
+        let max = self.config.retry_backoff_ms
+            .saturating_mul(1u64 << attempt.min(16))
+            .min(self.config.retry_max_backoff_ms);
+        let jittered = rand::rng().random_range(max / 2..=max);
+        Duration::from_millis(jittered)
+    }
+
+    fn update_breaker<R>(&self, breaker: &Mutex<CircuitBreaker>, f: impl FnOnce(&mut CircuitBreaker) -> R) -> R {
+        let mut breaker = match breaker.lock() {
+            Ok(breaker) => breaker,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let before = breaker.state;
+        let result = f(&mut breaker);
+        if std::mem::discriminant(&before) != std::mem::discriminant(&breaker.state) {
+            info!("grpc service {} circuit {:?} -> {:?}", self.service, before, breaker.state);
+            metrics::set_grpc_circuit_state(self.service, breaker.state.metric());
+        }
+        result
+    }
+}
+
+/// 错误对应的grpc状态码,连接错误视为服务不可用
+fn error_code(e: &anyhow::Error) -> Code {
+    if let Some(status) = e.downcast_ref::<Status>() {
+        return status.code();
+    }
+    if e.downcast_ref::<tonic::transport::Error>().is_some() {
+        return Code::Unavailable;
+    }
+    Code::Unknown
+}
+
+/// 服务不可用或过载,计入熔断
+fn is_service_failure(code: Code) -> bool {
+    matches!(code, Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted)
+}
+
+/// 可以重试的错误
+fn is_retryable(code: Code) -> bool {
+    matches!(code, Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted | Code::Aborted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPEN: Duration = Duration::from_millis(20);
+    const PROBE_TIMEOUT: Duration = Duration::from_millis(20);
+
+    fn open_breaker() -> CircuitBreaker {
+        let mut breaker = CircuitBreaker { state: CircuitState::Closed, failures: 0 };
+        breaker.on_failure(1, OPEN);
+        breaker
+    }
+
+    #[test]
+    fn test_open_after_threshold() {
+        let mut breaker = CircuitBreaker { state: CircuitState::Closed, failures: 0 };
+        breaker.on_failure(2, OPEN);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+        breaker.on_failure(2, OPEN);
+        assert!(!breaker.try_acquire(PROBE_TIMEOUT));
+    }
+
+    #[test]
+    fn test_half_open_single_probe() {
+        let mut breaker = open_breaker();
+        std::thread::sleep(OPEN);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+        assert!(!breaker.try_acquire(PROBE_TIMEOUT));
+        breaker.on_success();
+        assert_eq!(breaker.state, CircuitState::Closed);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+    }
+
+    #[test]
+    fn test_probe_failure_reopens() {
+        let mut breaker = open_breaker();
+        std::thread::sleep(OPEN);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+        breaker.on_failure(100, OPEN);
+        assert!(matches!(breaker.state, CircuitState::Open(_)));
+        assert!(!breaker.try_acquire(PROBE_TIMEOUT));
+    }
+
+    // 探测请求被取消没有回写结果,超时后放行新的探测请求
+    #[test]
+    fn test_dropped_probe_expires() {
+        let mut breaker = open_breaker();
+        std::thread::sleep(OPEN);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+        assert!(!breaker.try_acquire(PROBE_TIMEOUT));
+        std::thread::sleep(PROBE_TIMEOUT);
+        assert!(breaker.try_acquire(PROBE_TIMEOUT));
+        assert!(!breaker.try_acquire(PROBE_TIMEOUT));
+    }
+}
@@ -0,0 +1,246 @@
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, SystemTime};
+use anyhow::{anyhow, Context, Result};
+use futures_util::Stream;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tracing::{error, info, warn};
+use crate::config::config::{Config, GrpcTlsConfig};
+
+/// 握手超时,超时的连接直接关闭
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// 等待交给grpc服务的连接数
+const INCOMING_BUFFER: usize = 128;
+
+/// TLS连接流,交给 tonic Router::serve_with_incoming_shutdown
+pub type TlsIncoming = Pin<Box<dyn Stream<Item = io::Result<TlsStream<TcpStream>>> + Send>>;
+
+/// 一次加载的证书,重新加载时整体替换
+struct TlsMaterial {
+    ca_pem: Vec<u8>,
+    cert_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+    acceptor: TlsAcceptor,
+    modified: Vec<Option<SystemTime>>, // 证书文件的修改时间
+}
+
+/// 可热加载的grpc证书,服务端每个新连接和客户端每次建立连接都使用最新的证书
+pub struct ReloadableTls {
+    config: GrpcTlsConfig,
+    material: RwLock<Arc<TlsMaterial>>,
+    version: AtomicU64, // 每次重新加载后加1,客户端据此重建连接
+}
+
+impl ReloadableTls {
+    pub fn load(config: GrpcTlsConfig) -> Result<Self> {
+        let material = load_material(&config)?;
+        Ok(ReloadableTls { config, material: RwLock::new(Arc::new(material)), version: AtomicU64::new(0) })
+    }
+
+    /// 证书文件有变化时重新加载,返回是否已重新加载,新证书无效时返回错误并继续使用原证书
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let modified = modified_times(&self.config);
+        if self.current().modified == modified {
+            return Ok(false);
+        }
+        let material = load_material(&self.config)?;
+        *self.material.write().map_err(|_| anyhow!("grpc tls lock poisoned"))? = Arc::new(material);
+        self.version.fetch_add(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// 证书版本
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    /// 服务端握手使用的证书
+    pub fn acceptor(&self) -> TlsAcceptor {
+        self.current().acceptor.clone()
+    }
+
+    /// 客户端证书和信任的CA
+    pub fn client_tls_config(&self) -> ClientTlsConfig {
+        let material = self.current();
+        let mut tls = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(&material.ca_pem))
+            .identity(Identity::from_pem(&material.cert_pem, &material.key_pem));
+        if !self.config.domain_name.is_empty() {
+            tls = tls.domain_name(self.config.domain_name.clone());
+        }
+        tls
+    }
+
+    fn current(&self) -> Arc<TlsMaterial> {
+        match self.material.read() {
+            Ok(material) => material.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+fn load_material(config: &GrpcTlsConfig) -> Result<TlsMaterial> {
+    // 先取修改时间再读文件,读取期间文件被修改时下次检查会重新加载
+    let modified = modified_times(config);
+    let ca_pem = fs::read(&config.ca_cert).with_context(|| format!("read grpc ca cert {}", config.ca_cert))?;
+    let cert_pem = fs::read(&config.cert).with_context(|| format!("read grpc cert {}", config.cert))?;
+    let key_pem = fs::read(&config.key).with_context(|| format!("read grpc key {}", config.key))?;
+    let server_config = server_config(config, &ca_pem, &cert_pem, &key_pem)?;
+    Ok(TlsMaterial {
+        ca_pem,
+        cert_pem,
+        key_pem,
+        acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        modified,
+    })
+}
+
+fn modified_times(config: &GrpcTlsConfig) -> Vec<Option<SystemTime>> {
+    [&config.ca_cert, &config.cert, &config.key].iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+fn server_config(config: &GrpcTlsConfig, ca_pem: &[u8], cert_pem: &[u8], key_pem: &[u8]) -> Result<ServerConfig> {
+    let provider = Arc::new(default_provider());
+    let mut roots = RootCertStore::empty();
+    for cert in parse_certs(ca_pem)? {
+        roots.add(cert)?;
+    }
+    let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone());
+    let verifier = if config.require_client_cert {
+        verifier.build()?
+    } else {
+        verifier.allow_unauthenticated().build()?
+    };
+    let key = rustls_pemfile::private_key(&mut &key_pem[..])?
+        .ok_or_else(|| anyhow!("no private key in {}", config.key))?;
+    let mut server_config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(parse_certs(cert_pem)?, key)?;
+    // grpc使用http2
+    server_config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(server_config)
+}
+
+fn parse_certs(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut &pem[..]).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificate in pem"));
+    }
+    Ok(certs)
+}
+
+static GLOBAL_TLS: OnceLock<Option<Arc<ReloadableTls>>> = OnceLock::new();
+
+/// 当前服务的grpc证书,未启用TLS时返回None
+/// 第一次调用时加载并定时检查证书变化,证书无效时直接退出
+pub fn current() -> Option<Arc<ReloadableTls>> {
+    GLOBAL_TLS.get_or_init(|| {
+        let config = Config::load().grpc.tls;
+        if !config.enabled {
+            return None;
+        }
+        let interval = Duration::from_secs(config.reload_interval);
+        let tls = match ReloadableTls::load(config) {
+            Ok(tls) => Arc::new(tls),
+            Err(e) => {
+                eprintln!("invalid grpc tls configuration: {:#}", e);
+                std::process::exit(1);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => { handle.spawn(watch(tls.clone(), interval)); }
+            Err(_) => warn!("grpc tls loaded outside tokio runtime, certificates will not be reloaded"),
+        }
+        Some(tls)
+    }).clone()
+}
+
+/// 启动时调用,尽早发现证书错误
+pub fn init() {
+    if current().is_some() {
+        info!("grpc tls enabled");
+    }
+}
+
+async fn watch(tls: Arc<ReloadableTls>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match tls.reload_if_changed() {
+            Ok(true) => info!("grpc tls certificates reloaded"),
+            Ok(false) => {}
+            Err(e) => error!("reload grpc tls certificates error: {:#}", e),
+        }
+    }
+}
+
+/// grpc客户端地址,启用TLS时https地址使用客户端证书连接
+pub fn endpoint(url: &str) -> Result<Endpoint> {
+    let mut endpoint = Endpoint::from_shared(url.to_string())?;
+    if url.starts_with("https://") {
+        if let Some(tls) = current() {
+            endpoint = endpoint.tls_config(tls.client_tls_config())?;
+        }
+    }
+    Ok(endpoint)
+}
+
+/// 连接grpc服务
+pub async fn connect(url: &str) -> Result<Channel> {
+    Ok(endpoint(url)?.connect().await?)
+}
+
+/// 监听地址并接收TLS连接
+pub async fn incoming(addr: SocketAddr, tls: Arc<ReloadableTls>) -> Result<TlsIncoming> {
+    let listener = TcpListener::bind(addr).await?;
+    Ok(incoming_from_listener(listener, tls))
+}
+
+/// 接收TLS连接,握手在单独的任务中进行,慢的客户端不影响其他连接
+pub fn incoming_from_listener(listener: TcpListener, tls: Arc<ReloadableTls>) -> TlsIncoming {
+    let (sender, receiver) = mpsc::channel::<io::Result<TlsStream<TcpStream>>>(INCOMING_BUFFER);
+    tokio::spawn(async move {
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("grpc accept error: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                },
+                // grpc服务已停止
+                _ = sender.closed() => break,
+            };
+            let acceptor = tls.acceptor();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => { let _ = sender.send(Ok(stream)).await; }
+                    Ok(Err(e)) => warn!("grpc tls handshake with {} error: {}", peer, e),
+                    Err(_) => warn!("grpc tls handshake with {} timeout", peer),
+                }
+            });
+        }
+    });
+    Box::pin(futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|stream| (stream, receiver))
+    }))
+}
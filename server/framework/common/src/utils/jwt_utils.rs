@@ -1,5 +1,7 @@
+use crate::config::config::Config;
+use crate::config::system_config::{self, CONFIG_KEY_ACCESS_TOKEN_TTL, CONFIG_KEY_REFRESH_TOKEN_TTL};
 use crate::context::context::LoginUserContext;
-use crate::database::redis::RedisManager;
+use crate::database::redis_pool::AsyncRedisManager;
 use crate::database::redis_constants::{REDIS_KEY_LOGIN_USER_PREFIX, REDIS_KEY_TENANTS_LIST};
 use axum::extract::Query;
 use axum::{extract::FromRequestParts, http::{request::Parts, StatusCode}, response::{IntoResponse, Response}, Json, RequestPartsExt};
@@ -8,13 +10,33 @@ use axum_extra::TypedHeader;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use jsonwebtoken::errors::{Error, ErrorKind};
+use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 use serde_json::json;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use utoipa::ToSchema;
 
-pub static SECRET_KEY: Lazy<Vec<u8>> = Lazy::new(|| b"synerunify:token:secret-key".to_vec());
+pub static SECRET_KEY: Lazy<Vec<u8>> = Lazy::new(|| Config::load().jwt.secret.into_bytes());
+/// 令牌最长有效期(秒),租户参数超过时使用全局配置
+const MAX_TOKEN_TTL: i64 = 365 * 24 * 60 * 60;
+
+/// 登录用户信息的来源,未设置时从redis读取
+static LOGIN_USER_RESOLVER: OnceLock<Box<dyn LoginUserResolver>> = OnceLock::new();
+
+/// 根据访问令牌获取登录用户信息,系统服务以外的服务通过系统服务的grpc接口获取
+#[async_trait]
+pub trait LoginUserResolver: Send + Sync + 'static {
+    async fn resolve(&self, token: &str) -> Result<LoginUserContext, AuthError>;
+}
+
+/// 设置登录用户信息的来源,服务启动时调用一次
+pub fn set_login_user_resolver(resolver: impl LoginUserResolver) {
+    if LOGIN_USER_RESOLVER.set(Box::new(resolver)).is_err() {
+        warn!("login user resolver already set");
+    }
+}
 
 // Access Token Claims
 #[derive(Debug, Serialize, Deserialize)]
@@ -79,6 +101,7 @@ pub enum AuthError {
     InvalidTenant,
     InvalidUser,
     UserExpired, // 用户过期,用户权限变化,需重新登录
+    ServiceUnavailable, // 认证服务不可用
 }
 impl IntoResponse for AuthError {
     fn into_response(self) -> Response {
@@ -92,6 +115,7 @@ impl IntoResponse for AuthError {
             AuthError::TokenExpired => (StatusCode::UNAUTHORIZED, "Expired token"),
             AuthError::UserExpired => (StatusCode::UNAUTHORIZED, "Expired user"),
             AuthError::CheckOutToken => (StatusCode::UNAUTHORIZED, "该账户已经退出"),
+            AuthError::ServiceUnavailable => (StatusCode::SERVICE_UNAVAILABLE, "Authentication service unavailable"),
         };
         let body = Json(json!({
             "error": error_message,
@@ -118,7 +142,7 @@ where
             }
         };
 
-        info!("Extracted token from header: {}, path: {}", token, parts.uri);
+        info!("Extracted token from header: {}, path: {}", !token.is_empty(), parts.uri.path());
 
         // 如果 token 为空，并且 URI 以 /file/ 开头，则尝试从查询参数中提取 token
         if token.is_empty() && parts.uri.path().contains("system_file/download") {
@@ -129,44 +153,14 @@ where
                 })?;
             
             token = query.0.token.unwrap_or_default();
-            info!("Extracted token from query: {}", token);
+            info!("Extracted token from query: {}", !token.is_empty());
         }
 
         if token.is_empty() {
             return Err(AuthError::InvalidToken);
         }
 
-        let token_data = decode::<AccessClaims>(
-            &token,
-            &DecodingKey::from_secret(&*SECRET_KEY),
-            &Validation::new(Algorithm::HS256),
-        ).map_err(|e| {
-            error!("decode token error, {}", e.to_string());
-            match e.kind() {
-                ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                _ => AuthError::InvalidToken
-            }
-        })?;
-
-        info!("token data: {:?}", token_data);
-        let claims = token_data.claims;
-        let now = Utc::now().timestamp();
-        if claims.exp < now {
-            return Err(AuthError::TokenExpired);
-        }
-
-        if !is_valid_tenant(claims.tenant_id)? {
-            return Err(AuthError::InvalidTenant);
-        }
-
-        // 获取用户登录信息
-        let context = RedisManager::get::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, claims.device_type, claims.sub));
-        let login_user = match context {
-            Ok(Some(ctx_str)) => serde_json::from_str::<LoginUserContext>(&ctx_str)
-                .map_err(|_| AuthError::UserExpired),
-            Ok(None) => Err(AuthError::UserExpired),
-            Err(_) => Err(AuthError::UserExpired),
-        }?;
+        let (claims, login_user) = authenticate(&token).await?;
         // info!("login user: {:?}", login_user);
         parts.extensions.insert(login_user);
         // parts.extensions.insert(UserTenantContext {
@@ -178,16 +172,71 @@ where
     }
 }
 
-pub fn is_valid_tenant(tenant_id: i64) -> Result<bool, AuthError> {
-    let exists: bool = RedisManager::is_set_member(REDIS_KEY_TENANTS_LIST, tenant_id)
+/// 校验访问令牌,设置了 LoginUserResolver 时登录用户信息从系统服务获取,否则从redis读取
+pub async fn authenticate(token: &str) -> Result<(AccessClaims, LoginUserContext), AuthError> {
+    match LOGIN_USER_RESOLVER.get() {
+        Some(resolver) => {
+            let claims = decode_access_token(token)?;
+            let login_user = resolver.resolve(token).await?;
+            Ok((claims, login_user))
+        }
+        None => validate_token(token).await,
+    }
+}
+
+/// 校验访问令牌的签名和有效期
+fn decode_access_token(token: &str) -> Result<AccessClaims, AuthError> {
+    let token_data = decode::<AccessClaims>(
+        token,
+        &DecodingKey::from_secret(&*SECRET_KEY),
+        &Validation::new(Algorithm::HS256),
+    ).map_err(|e| {
+        error!("decode token error, {}", e.to_string());
+        match e.kind() {
+            ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+            _ => AuthError::InvalidToken
+        }
+    })?;
+
+    info!("token data: {:?}", token_data);
+    let claims = token_data.claims;
+    let now = Utc::now().timestamp();
+    if claims.exp < now {
+        return Err(AuthError::TokenExpired);
+    }
+    Ok(claims)
+}
+
+/// 校验访问令牌,返回令牌信息和登录用户信息,系统服务的http请求和grpc服务共用
+pub async fn validate_token(token: &str) -> Result<(AccessClaims, LoginUserContext), AuthError> {
+    let claims = decode_access_token(token)?;
+
+    if !is_valid_tenant(claims.tenant_id).await? {
+        return Err(AuthError::InvalidTenant);
+    }
+
+    // 获取用户登录信息
+    let context = AsyncRedisManager::get::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, claims.device_type, claims.sub)).await;
+    let login_user = match context {
+        Ok(Some(ctx_str)) => serde_json::from_str::<LoginUserContext>(&ctx_str)
+            .map_err(|_| AuthError::UserExpired),
+        Ok(None) => Err(AuthError::UserExpired),
+        Err(_) => Err(AuthError::UserExpired),
+    }?;
+    Ok((claims, login_user))
+}
+
+pub async fn is_valid_tenant(tenant_id: i64) -> Result<bool, AuthError> {
+    let exists: bool = AsyncRedisManager::is_set_member(REDIS_KEY_TENANTS_LIST, tenant_id)
+        .await
         .map_err(|e| AuthError::InvalidTenant)?;
 
     Ok(exists)
 }
 
-pub fn add_tenants(tenants: &[i64]) {
+pub async fn add_tenants(tenants: &[i64]) {
     // 添加新的租户ID
-    if let Err(e) = RedisManager::add_to_set(REDIS_KEY_TENANTS_LIST, tenants) {
+    if let Err(e) = AsyncRedisManager::add_to_set(REDIS_KEY_TENANTS_LIST, tenants).await {
         error!("add tenants fail: {}", e.to_string());
     }
 }
@@ -195,8 +244,17 @@ pub fn add_tenants(tenants: &[i64]) {
 // 生成 token 对
 pub fn generate_token_pair(device_type: String, user_id: i64, tenant_id: i64) -> Result<AuthBody, AuthError> {
     let now = Utc::now();
+    let config = Config::load();
+    // 有效期优先使用系统参数
+    let (access_token_ttl, refresh_token_ttl) = token_ttl(
+        system_config::get_or(tenant_id, CONFIG_KEY_ACCESS_TOKEN_TTL, config.jwt.access_token_ttl),
+        system_config::get_or(tenant_id, CONFIG_KEY_REFRESH_TOKEN_TTL, config.jwt.refresh_token_ttl),
+        (config.jwt.access_token_ttl, config.jwt.refresh_token_ttl),
+    );
 
-    let access_exp = now.checked_add_signed(Duration::minutes(15)).unwrap().timestamp();
+    let access_exp = now.checked_add_signed(Duration::seconds(access_token_ttl))
+        .ok_or(AuthError::TokenCreation)?
+        .timestamp();
     let access_claims = AccessClaims {
         device_type: device_type.clone(),
         sub: user_id,
@@ -207,7 +265,9 @@ pub fn generate_token_pair(device_type: String, user_id: i64, tenant_id: i64) ->
     let access_token = encode(&Header::default(), &access_claims, &EncodingKey::from_secret(&*SECRET_KEY))
         .map_err(|e| AuthError::TokenCreation)?;
 
-    let refresh_exp = now.checked_add_signed(Duration::days(7)).unwrap().timestamp();
+    let refresh_exp = now.checked_add_signed(Duration::seconds(refresh_token_ttl))
+        .ok_or(AuthError::TokenCreation)?
+        .timestamp();
     let refresh_claims = RefreshClaims {
         device_type: device_type.clone(),
         sub: user_id,
@@ -221,6 +281,16 @@ pub fn generate_token_pair(device_type: String, user_id: i64, tenant_id: i64) ->
     Ok(AuthBody::new(access_token, refresh_token, refresh_exp, now.timestamp()))
 }
 
+/// 校验租户参数中的有效期,不在 (0, MAX_TOKEN_TTL] 内或 access token 不短于 refresh token 时使用全局配置
+fn token_ttl(access_token_ttl: i64, refresh_token_ttl: i64, default: (i64, i64)) -> (i64, i64) {
+    let valid = |ttl: i64| ttl > 0 && ttl <= MAX_TOKEN_TTL;
+    if valid(access_token_ttl) && valid(refresh_token_ttl) && access_token_ttl < refresh_token_ttl {
+        return (access_token_ttl, refresh_token_ttl);
+    }
+    warn!("invalid token ttl {} / {}, use default {:?}", access_token_ttl, refresh_token_ttl, default);
+    default
+}
+
 // 刷新 token
 pub async fn refresh_token(refresh_token: String) -> Result<AuthBody, AuthError> {
     info!("refresh token: {:?}", refresh_token);
@@ -244,10 +314,26 @@ pub async fn refresh_token(refresh_token: String) -> Result<AuthBody, AuthError>
         return Err(AuthError::TokenExpired);
     }
 
-    if !is_valid_tenant(token_data.claims.tenant_id)? {
+    if !is_valid_tenant(token_data.claims.tenant_id).await? {
         error!("invalid tenant");
         return Err(AuthError::InvalidTenant);
     }
 
     generate_token_pair(token_data.claims.device_type, token_data.claims.sub, token_data.claims.tenant_id)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: (i64, i64) = (900, 604800);
+
+    #[test]
+    fn test_token_ttl() {
+        assert_eq!(token_ttl(600, 3600, DEFAULT), (600, 3600));
+        assert_eq!(token_ttl(0, 3600, DEFAULT), DEFAULT);
+        assert_eq!(token_ttl(-1, 3600, DEFAULT), DEFAULT);
+        assert_eq!(token_ttl(3600, 600, DEFAULT), DEFAULT);
+        assert_eq!(token_ttl(600, MAX_TOKEN_TTL, DEFAULT), (600, MAX_TOKEN_TTL));
+        assert_eq!(token_ttl(600, i64::MAX, DEFAULT), DEFAULT);
+    }
+}
@@ -46,6 +46,12 @@ impl MinioClient {
         Ok(MinioClient { client })
     }
 
+    /// 检查服务是否可用
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        self.client.bucket_exists(BUCKET_NAME).send().await?;
+        Ok(())
+    }
+
     pub async fn upload_file(
         &self,
         file_name: &str,
@@ -55,7 +61,7 @@ impl MinioClient {
         let year = now.year();
         let month = format!("{:02}", now.month());
         let day = format!("{:02}", now.day());
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::global();
         let id = match generator.generate() {
             Ok(id) => id,
             Err(e) => return Err(MinioError::Other(Error::msg(e))),
@@ -76,6 +82,20 @@ impl MinioClient {
         Ok(object_name)
     }
 
+    /// 使用指定的对象名上传,用于归档等需要固定路径的文件
+    pub async fn upload_object(
+        &self,
+        object_name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), MinioError> {
+        self.client
+            .put_object_content(BUCKET_NAME, object_name, data)
+            .send()
+            .await
+            .map_err(|e: minio::s3::error::Error| MinioError::Other(e.into()))?;
+        Ok(())
+    }
+
     pub async fn download_file(
         &self,
         object_name: String,
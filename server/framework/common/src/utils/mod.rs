@@ -2,5 +2,11 @@ pub mod jwt_utils;
 pub mod crypt_utils;
 pub mod string_utils;
 pub mod snowflake_generator;
+pub mod document_number;
 pub mod minio_utils;
-pub mod type_utils;
\ No newline at end of file
+pub mod type_utils;
+pub mod trace_utils;
+pub mod field_crypto;
+pub mod grpc_tls;
+pub mod grpc_client;
+pub mod redaction;
\ No newline at end of file
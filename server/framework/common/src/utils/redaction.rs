@@ -0,0 +1,233 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use crate::config::config::{Config, RedactionConfig};
+use crate::middleware::authorize::matches_route;
+
+/// 脱敏后的值
+pub const REDACTED: &str = "******";
+
+/// 请求、响应内容写入日志前的脱敏处理,规则见 log.redaction 配置
+/// 1. 字段名包含 key_patterns 中任一项时脱敏,如 password、old_password、access_token
+/// 2. 按路由配置的json路径脱敏,* 匹配任意字段或数组元素
+/// 3. 内容超过 max_body_size 时截断
+pub struct Redactor {
+    enabled: bool,
+    key_patterns: Vec<String>, // 小写
+    routes: Vec<(String, Vec<Vec<String>>)>, // (路由, json路径)
+    max_body_size: usize,
+    skip_content_types: Vec<String>, // 小写
+}
+
+static REDACTOR: Lazy<Redactor> = Lazy::new(|| Redactor::new(&Config::load().log.redaction));
+
+/// 全局脱敏规则
+pub fn redactor() -> &'static Redactor {
+    &REDACTOR
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Self {
+        Redactor {
+            enabled: config.enabled,
+            key_patterns: config.key_patterns.iter().map(|pattern| pattern.to_lowercase()).collect(),
+            routes: config.routes.iter()
+                .map(|(route, paths)| (route.clone(), paths.iter().map(|path| parse_path(path)).collect()))
+                .collect(),
+            max_body_size: config.max_body_size,
+            skip_content_types: config.skip_content_types.iter().map(|content_type| content_type.to_lowercase()).collect(),
+        }
+    }
+
+    /// 是否不记录内容,如文件上传
+    pub fn is_skipped(&self, content_type: &str) -> bool {
+        let content_type = content_type.to_lowercase();
+        self.skip_content_types.iter().any(|skip| content_type.starts_with(skip.as_str()))
+    }
+
+    /// 字段名是否敏感
+    pub fn is_sensitive_key(&self, key: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let key = key.to_lowercase();
+        self.key_patterns.iter().any(|pattern| key.contains(pattern.as_str()))
+    }
+
+    /// 脱敏并截断请求或响应内容,route为去掉api前缀的路由地址
+    /// json按规则脱敏,表单按字段名脱敏,其他内容只截断
+    pub fn redact_body(&self, route: &str, body: &str) -> String {
+        if !self.enabled || body.is_empty() {
+            return self.truncate(body);
+        }
+        let redacted = match serde_json::from_str::<Value>(body) {
+            Ok(mut value) => {
+                self.redact_value(route, &mut value);
+                value.to_string()
+            }
+            Err(_) if body.contains('=') && !body.contains(char::is_whitespace) => self.redact_form(body),
+            Err(_) => body.to_string(),
+        };
+        self.truncate(&redacted)
+    }
+
+    /// 脱敏json
+    pub fn redact_value(&self, route: &str, value: &mut Value) {
+        if !self.enabled {
+            return;
+        }
+        self.redact_keys(value);
+        for (pattern, paths) in &self.routes {
+            if pattern == route || matches_route(pattern, route) {
+                for path in paths {
+                    redact_path(value, path);
+                }
+            }
+        }
+    }
+
+    /// 超过最大长度时截断,保留完整的utf8字符
+    pub fn truncate(&self, body: &str) -> String {
+        if body.len() <= self.max_body_size {
+            return body.to_string();
+        }
+        let mut end = self.max_body_size;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...(truncated, {} bytes)", &body[..end], body.len())
+    }
+
+    fn redact_keys(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_sensitive_key(key) {
+                        *value = Value::from(REDACTED);
+                    } else {
+                        self.redact_keys(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_keys(value)),
+            _ => {}
+        }
+    }
+
+    /// 脱敏 a=1&password=2 格式的表单
+    fn redact_form(&self, body: &str) -> String {
+        body.split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if self.is_sensitive_key(key) => format!("{}={}", key, REDACTED),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// 解析json路径,如 data.list.*.bank_account,兼容 $. 前缀
+fn parse_path(path: &str) -> Vec<String> {
+    path.trim_start_matches('$')
+        .trim_start_matches('.')
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn redact_path(value: &mut Value, path: &[String]) {
+    let Some((segment, rest)) = path.split_first() else {
+        return;
+    };
+    let children: Vec<&mut Value> = match value {
+        Value::Object(map) if segment == "*" => map.values_mut().collect(),
+        Value::Object(map) => map.get_mut(segment).into_iter().collect(),
+        Value::Array(values) if segment == "*" => values.iter_mut().collect(),
+        Value::Array(values) => segment.parse::<usize>().ok().and_then(|index| values.get_mut(index)).into_iter().collect(),
+        _ => vec![],
+    };
+    for child in children {
+        if rest.is_empty() {
+            *child = Value::from(REDACTED);
+        } else {
+            redact_path(child, rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn redactor(max_body_size: usize) -> Redactor {
+        Redactor::new(&RedactionConfig {
+            max_body_size,
+            routes: HashMap::from([
+                ("/erp/erp_customer/page".to_string(), vec!["data.list.*.name".to_string()]),
+                ("/system/system_user/{id}".to_string(), vec!["$.data.nickname".to_string()]),
+            ]),
+            ..RedactionConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_key_patterns() {
+        let redactor = redactor(8192);
+        assert!(redactor.is_sensitive_key("password"));
+        assert!(redactor.is_sensitive_key("oldPassword"));
+        assert!(redactor.is_sensitive_key("ACCESS_TOKEN"));
+        assert!(redactor.is_sensitive_key("tax_id"));
+        assert!(redactor.is_sensitive_key("mobile"));
+        assert!(redactor.is_sensitive_key("id_card"));
+        assert!(!redactor.is_sensitive_key("username"));
+
+        let body = r#"{"username":"admin","password":"123456","items":[{"bank_account":"6222","amount":1}]}"#;
+        let value: Value = serde_json::from_str(&redactor.redact_body("/system/system_auth/login", body)).unwrap();
+        assert_eq!(value["username"], "admin");
+        assert_eq!(value["password"], REDACTED);
+        assert_eq!(value["items"][0]["bank_account"], REDACTED);
+        assert_eq!(value["items"][0]["amount"], 1);
+    }
+
+    #[test]
+    fn test_route_paths() {
+        let redactor = redactor(8192);
+        let body = r#"{"data":{"list":[{"name":"a"},{"name":"b"}],"nickname":"n"}}"#;
+        let value: Value = serde_json::from_str(&redactor.redact_body("/erp/erp_customer/page", body)).unwrap();
+        assert_eq!(value["data"]["list"][0]["name"], REDACTED);
+        assert_eq!(value["data"]["list"][1]["name"], REDACTED);
+        assert_eq!(value["data"]["nickname"], "n");
+
+        let value: Value = serde_json::from_str(&redactor.redact_body("/system/system_user/1", body)).unwrap();
+        assert_eq!(value["data"]["nickname"], REDACTED);
+        assert_eq!(value["data"]["list"][0]["name"], "a");
+
+        let value: Value = serde_json::from_str(&redactor.redact_body("/erp/erp_supplier/page", body)).unwrap();
+        assert_eq!(value["data"]["list"][0]["name"], "a");
+    }
+
+    #[test]
+    fn test_form_body() {
+        let redactor = redactor(8192);
+        assert_eq!(redactor.redact_body("/file/download", "id=1&token=abc"), format!("id=1&token={}", REDACTED));
+        assert_eq!(redactor.redact_body("/file/download", "id=1&flag"), "id=1&flag");
+        assert_eq!(redactor.redact_body("/file/download", "plain text password=1"), "plain text password=1");
+    }
+
+    #[test]
+    fn test_truncate_char_boundary() {
+        let redactor = redactor(4);
+        assert_eq!(redactor.truncate("abcd"), "abcd");
+        // "中"占3个字节,第4个字节不在字符边界上,截断到前一个字符
+        assert_eq!(redactor.truncate("a中文"), "a中...(truncated, 7 bytes)");
+        assert_eq!(redactor.truncate("中文"), "中...(truncated, 6 bytes)");
+    }
+
+    #[test]
+    fn test_disabled() {
+        let redactor = Redactor::new(&RedactionConfig { enabled: false, ..RedactionConfig::default() });
+        assert!(!redactor.is_sensitive_key("password"));
+        assert_eq!(redactor.redact_body("/", r#"{"password":"1"}"#), r#"{"password":"1"}"#);
+    }
+}
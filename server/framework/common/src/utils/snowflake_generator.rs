@@ -1,131 +1,230 @@
 use chrono::{DateTime, TimeZone, Utc};
-use std::sync::atomic::{AtomicU16, Ordering};
-use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::thread;
-use std::collections::HashSet;
+use once_cell::sync::Lazy;
+use tracing::{error, info, warn};
+use crate::cache::distributed_lock::{monotonic_millis, DistributedLock, LockGuard};
 
-// 雪花算法ID生成器
+/// 自定义纪元 2025-01-01 00:00:00 UTC
+const EPOCH_MS: i64 = 1_735_689_600_000;
+/// 最大机器ID
+const MAX_MACHINE_ID: u16 = 1024;
+/// 未分配机器ID
+const UNASSIGNED_MACHINE_ID: u16 = u16::MAX;
+/// 时钟回拨在此范围内时沿用上次的时间戳继续分配,超出则返回错误
+const MAX_BACKWARD_MS: i64 = 10;
+/// 序列号位数和掩码
+const SEQUENCE_BITS: u32 = 12;
+const MAX_SEQUENCE: u64 = 0xFFF;
+/// 机器ID租期,续期由分布式锁自动完成
+const MACHINE_ID_LEASE: Duration = Duration::from_secs(30);
+/// 检查租约的间隔
+const LEASE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 进程内唯一的生成器,机器ID从redis租用,保证多个副本不重复
+static GLOBAL: Lazy<SnowflakeGenerator> = Lazy::new(|| SnowflakeGenerator {
+    machine_id: AtomicU16::new(UNASSIGNED_MACHINE_ID),
+    lease_valid_until: AtomicU64::new(0),
+    state: AtomicU64::new(0),
+});
+/// 是否已主动释放,释放后不再重新租用
+static RELEASED: AtomicBool = AtomicBool::new(false);
+/// 当前持有的机器ID租约
+static MACHINE_ID_LEASE_GUARD: Lazy<tokio::sync::Mutex<Option<LockGuard>>> = Lazy::new(|| {
+    tokio::sync::Mutex::new(None)
+});
+
+/// 解析后的ID
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnowflakeId {
+    pub timestamp: DateTime<Utc>, // 生成时间
+    pub machine_id: u16, // 机器ID
+    pub sequence: u16, // 序列号
+}
+
+// 雪花算法ID生成器: 41位时间戳 + 10位机器ID + 12位序列号
 pub struct SnowflakeGenerator {
-    machine_id: u16, // 机器ID (0-1023)
-    sequence: AtomicU16, // 序列号
-    last_timestamp: Mutex<i64>, // 上次时间戳
-    time_offset: Mutex<i64>, // 时间偏移量（毫秒）
-    epoch: DateTime<Utc>, // 自定义纪元
+    machine_id: AtomicU16, // 机器ID (0-1023)
+    lease_valid_until: AtomicU64, // 机器ID租约的有效期(monotonic_millis),超过后立即停止生成
+    state: AtomicU64, // 上次分配的 时间戳(相对纪元) << 12 | 序列号,无锁更新,生成时不会阻塞其他线程
 }
 
 impl SnowflakeGenerator {
-    // 无参构造函数，默认machine_id为1
-    pub fn new() -> Self {
-        Self::new_with_machine_id(1).unwrap()
+    // 进程内共享的生成器,服务启动时需先调用 init_global 租用机器ID
+    pub fn global() -> &'static SnowflakeGenerator {
+        &GLOBAL
     }
 
-    // 带machine_id的构造函数
+    // 从redis租用机器ID并初始化全局生成器,租约丢失后自动重新租用
+    pub async fn init_global() -> anyhow::Result<u16> {
+        let mut lease = MACHINE_ID_LEASE_GUARD.lock().await;
+        RELEASED.store(false, Ordering::SeqCst);
+        if lease.is_some() {
+            return Ok(GLOBAL.machine_id());
+        }
+        let (machine_id, guard) = lease_machine_id().await?;
+        GLOBAL.assign(machine_id, &guard);
+        *lease = Some(guard);
+        info!("snowflake machine id leased: {}", machine_id);
+
+        tokio::spawn(watch_lease());
+        Ok(machine_id)
+    }
+
+    // 释放机器ID租约,停机时调用
+    pub async fn release_global() {
+        RELEASED.store(true, Ordering::SeqCst);
+        GLOBAL.unassign();
+        if let Some(guard) = MACHINE_ID_LEASE_GUARD.lock().await.take() {
+            if let Err(e) = guard.release().await {
+                error!("snowflake machine id release error: {}", e);
+            }
+        }
+    }
+
+    // 带machine_id的构造函数,只用于测试或单机工具,服务中使用 global()
     pub fn new_with_machine_id(machine_id: u16) -> Result<Self, &'static str> {
-        if machine_id >= 1024 {
+        if machine_id >= MAX_MACHINE_ID {
             return Err("Machine ID must be less than 1024");
         }
         Ok(SnowflakeGenerator {
-            machine_id,
-            sequence: AtomicU16::new(0),
-            last_timestamp: Mutex::new(0),
-            time_offset: Mutex::new(0),
-            epoch: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            machine_id: AtomicU16::new(machine_id),
+            lease_valid_until: AtomicU64::new(u64::MAX),
+            state: AtomicU64::new(0),
         })
     }
 
-    // 获取当前时间戳（毫秒）
-    fn timestamp_ms(&self) -> i64 {
+    // 使用租用的机器ID,先写入机器ID再写入有效期
+    fn assign(&self, machine_id: u16, guard: &LockGuard) {
+        self.machine_id.store(machine_id, Ordering::SeqCst);
+        self.lease_valid_until.store(guard.valid_until(), Ordering::SeqCst);
+    }
+
+    // 停止使用机器ID,先清除有效期再清除机器ID
+    fn unassign(&self) {
+        self.lease_valid_until.store(0, Ordering::SeqCst);
+        self.machine_id.store(UNASSIGNED_MACHINE_ID, Ordering::SeqCst);
+    }
+
+    pub fn machine_id(&self) -> u16 {
+        self.machine_id.load(Ordering::SeqCst)
+    }
+
+    // 获取相对于纪元的时间戳（毫秒）
+    fn timestamp_since_epoch() -> i64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis() as i64
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0) - EPOCH_MS
     }
 
-    // 获取相对于纪元的时间戳（毫秒）
-    fn timestamp_since_epoch(&self) -> i64 {
-        let epoch_ms = self.epoch.timestamp_millis();
-        self.timestamp_ms() - epoch_ms
-    }
-
-    // 获取调整后的时间戳
-    fn get_adjusted_timestamp(&self) -> Result<i64, &'static str> {
-        let mut last_ts = self.last_timestamp.lock().unwrap();
-        let mut offset = self.time_offset.lock().unwrap();
-        let current_ts = self.timestamp_since_epoch();
-
-        let adjusted_ts = if current_ts < *last_ts {
-            // 时钟回退，借用未来时间
-            *last_ts + 1
-        } else {
-            // 时钟正常，尝试减少偏移量
-            let new_ts = current_ts + *offset;
-            if new_ts > *last_ts {
-                new_ts
+    // 生成ID
+    pub fn generate(&self) -> Result<i64, &'static str> {
+        // 先读有效期再读机器ID,与 assign/unassign 的写入顺序相反
+        let lease_valid_until = self.lease_valid_until.load(Ordering::SeqCst);
+        let machine_id = self.machine_id();
+        if machine_id >= MAX_MACHINE_ID || monotonic_millis() >= lease_valid_until {
+            return Err("Machine ID is not leased");
+        }
+        loop {
+            let now = Self::timestamp_since_epoch();
+            let current = self.state.load(Ordering::Acquire);
+            let last_timestamp = (current >> SEQUENCE_BITS) as i64;
+            let sequence = current & MAX_SEQUENCE;
+            let (timestamp, sequence) = if now > last_timestamp {
+                // 新毫秒，重置序列号
+                (now, 0)
+            } else if last_timestamp - now > MAX_BACKWARD_MS {
+                return Err("Clock moved backwards");
+            } else if sequence < MAX_SEQUENCE {
+                // 同一毫秒或小范围时钟回拨，沿用上次的时间戳增加序列号
+                (last_timestamp, sequence + 1)
+            } else if last_timestamp - now < MAX_BACKWARD_MS {
+                // 序列号溢出，借用下一毫秒
+                (last_timestamp + 1, 0)
             } else {
-                *last_ts + 1
+                // 借用的时间已超过允许范围，让出线程等待时钟追上,不持有锁
+                thread::yield_now();
+                continue;
+            };
+            let next = ((timestamp as u64) << SEQUENCE_BITS) | sequence;
+            if self.state.compare_exchange_weak(current, next, Ordering::AcqRel, Ordering::Acquire).is_err() {
+                // 其他线程已分配,重新读取
+                continue;
             }
-        };
-
-        // 更新偏移量
-        *offset = adjusted_ts - current_ts;
-        if *offset > 172_800_000 {
-            // 最大偏移量限制为两天（172,800,000毫秒）
-            return Err("Time offset too large (exceeds 2 days)");
+            let id = ((timestamp as u64 & 0x1FFFFFFFFFF) << 22)
+                | ((machine_id as u64 & 0x3FF) << 12)
+                | sequence;
+            return Ok(id as i64);
         }
+    }
 
-        // 更新最后时间戳
-        *last_ts = adjusted_ts;
-        Ok(adjusted_ts)
+    // 解析ID中的时间、机器ID和序列号
+    pub fn decode(id: i64) -> SnowflakeId {
+        let id = id as u64;
+        let timestamp_ms = (id >> 22) as i64 + EPOCH_MS;
+        SnowflakeId {
+            timestamp: Utc.timestamp_millis_opt(timestamp_ms).single().unwrap_or_default(),
+            machine_id: ((id >> 12) & 0x3FF) as u16,
+            sequence: (id & 0xFFF) as u16,
+        }
     }
+}
 
-    // 生成订单编号
-    pub fn generate(&self) -> Result<i64, &'static str> {
-        let timestamp = self.get_adjusted_timestamp()?;
-
-        let seq = if timestamp == *self.last_timestamp.lock().unwrap() {
-            // 同一毫秒，增加序列号
-            self.sequence.fetch_add(1, Ordering::SeqCst) & 0xFFF
-        } else {
-            // 新毫秒，重置序列号
-            self.sequence.store(0, Ordering::SeqCst);
-            0
-        };
-
-        if seq >= 4096 {
-            // 序列号溢出，使用下一时间戳
-            let next_ts = self.get_adjusted_timestamp()?;
-            if next_ts == timestamp {
-                return Err("Sequence overflow and timestamp not advanced");
+// 检查租约并同步有效期,丢失后停止使用原机器ID并重新租用,避免与其他副本重复
+// 同步的有效期不晚于租约实际的有效期,检查停顿时生成器在有效期后也会立即停止
+async fn watch_lease() {
+    loop {
+        tokio::time::sleep(LEASE_CHECK_INTERVAL).await;
+        if RELEASED.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut lease = MACHINE_ID_LEASE_GUARD.lock().await;
+        if let Some(guard) = lease.as_ref().filter(|guard| guard.is_held()) {
+            GLOBAL.lease_valid_until.store(guard.valid_until(), Ordering::SeqCst);
+            continue;
+        }
+        if lease.take().is_some() {
+            warn!("snowflake machine id lease lost: {}", GLOBAL.machine_id());
+            GLOBAL.unassign();
+        }
+        // 重新租用失败时下次继续
+        match lease_machine_id().await {
+            Ok((machine_id, guard)) => {
+                GLOBAL.assign(machine_id, &guard);
+                *lease = Some(guard);
+                info!("snowflake machine id re-leased: {}", machine_id);
             }
-            self.sequence.store(0, Ordering::SeqCst);
-            // 构造ID: 41位时间戳 + 10位机器ID + 12位序列号
-            let id = ((next_ts as u64 & 0x1FFFFFFFFFF) << 22)
-                | ((self.machine_id as u64 & 0x3FF) << 12)
-                | (0 as u64);
-            return Ok(id as i64);
+            Err(e) => error!("snowflake machine id lease error: {}", e),
         }
+    }
+}
 
-        // 构造ID: 41位时间戳 + 10位机器ID + 12位序列号
-        let id = ((timestamp as u64 & 0x1FFFFFFFFFF) << 22)
-            | ((self.machine_id as u64 & 0x3FF) << 12)
-            | (seq as u64);
-
-        Ok(id as i64)
+// 从随机位置开始依次尝试租用机器ID
+async fn lease_machine_id() -> anyhow::Result<(u16, LockGuard)> {
+    let start = rand::random::<u16>() % MAX_MACHINE_ID;
+    for offset in 0..MAX_MACHINE_ID {
+        let machine_id = (start + offset) % MAX_MACHINE_ID;
+        let lock = DistributedLock::new(format!("snowflake:machine:{}", machine_id))
+            .with_lease(MACHINE_ID_LEASE);
+        if let Some(guard) = lock.try_acquire().await? {
+            return Ok((machine_id, guard));
+        }
     }
+    Err(anyhow::anyhow!("no snowflake machine id available"))
 }
 
 // 测试代码
 #[cfg(test)]
-#[ignore]
 mod tests {
     use super::*;
-    use std::sync::Arc;
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
 
     #[test]
-    #[ignore]
     fn test_order_number_format() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
         let order_number = generator.generate().unwrap();
 
         // 检查是否为正数且长度合理 (19位以内)
@@ -134,9 +233,8 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn test_unique_ids() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
         let id1 = generator.generate().unwrap();
         let id2 = generator.generate().unwrap();
 
@@ -145,24 +243,41 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
-    fn test_large_offset() {
-        let generator = SnowflakeGenerator::new();
-        {
-            let mut last_ts = generator.last_timestamp.lock().unwrap();
-            let mut offset = generator.time_offset.lock().unwrap();
-            *last_ts = generator.timestamp_since_epoch() + 172_800_001; // 模拟超过两天偏移
-            *offset = 172_800_001;
-        }
+    fn test_clock_backwards() {
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
+        // 模拟时钟回拨1分钟
+        let last_timestamp = SnowflakeGenerator::timestamp_since_epoch() + 60_000;
+        generator.state.store((last_timestamp as u64) << SEQUENCE_BITS, Ordering::SeqCst);
         let result = generator.generate();
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Time offset too large (exceeds 2 days)");
+        assert_eq!(result.unwrap_err(), "Clock moved backwards");
+    }
+
+    #[test]
+    fn test_small_clock_backwards() {
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
+        // 小范围回拨时不等待,沿用上次的时间戳
+        let last_timestamp = SnowflakeGenerator::timestamp_since_epoch() + 5;
+        generator.state.store((last_timestamp as u64) << SEQUENCE_BITS, Ordering::SeqCst);
+        let id = generator.generate().unwrap();
+        assert!(SnowflakeGenerator::decode(id).timestamp.timestamp_millis() >= last_timestamp + EPOCH_MS);
+    }
+
+    #[test]
+    fn test_sequence_overflow() {
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
+        let first = generator.generate().unwrap();
+        // 当前毫秒的序列号已用完,下一个id不能重复且递增
+        let current = generator.state.load(Ordering::SeqCst);
+        generator.state.store(current | MAX_SEQUENCE, Ordering::SeqCst);
+        let last = ((current >> SEQUENCE_BITS) << 22) | (1 << 12) | MAX_SEQUENCE;
+        let next = generator.generate().unwrap();
+        assert!(next as u64 > last && next > first);
+        assert_eq!(SnowflakeGenerator::decode(next).sequence, 0);
     }
 
     #[test]
-    #[ignore]
     fn test_concurrent_generation() {
-        let generator = Arc::new(SnowflakeGenerator::new());
+        let generator = Arc::new(SnowflakeGenerator::new_with_machine_id(1).unwrap());
         let mut handles = vec![];
         let num_threads = 10;
         let ids_per_thread = 1000;
@@ -195,25 +310,32 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
-    fn test_default_machine_id() {
-        let generator = SnowflakeGenerator::new();
-        let order_number = generator.generate().unwrap();
+    fn test_decode() {
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
+        let before = Utc::now().timestamp_millis();
+        let id = generator.generate().unwrap();
+        let decoded = SnowflakeGenerator::decode(id);
 
-        // 检查machine_id是否为1
-        let machine_id = (order_number as u64 >> 12) & 0x3FF; // 提取10位机器ID
-        assert_eq!(machine_id, 1, "Default machine ID should be 1");
+        assert_eq!(decoded.machine_id, 1, "Machine ID should be 1");
+        assert!(decoded.timestamp.timestamp_millis() >= before);
     }
-}
 
-fn main() {
-    let generator = SnowflakeGenerator::new();
+    #[test]
+    fn test_lease_expired() {
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
+        assert!(generator.generate().is_ok());
+        // 租约超过有效期没有续期,立即停止生成
+        generator.lease_valid_until.store(monotonic_millis(), Ordering::SeqCst);
+        assert_eq!(generator.generate().unwrap_err(), "Machine ID is not leased");
+        generator.lease_valid_until.store(monotonic_millis() + 60_000, Ordering::SeqCst);
+        assert!(generator.generate().is_ok());
+        generator.unassign();
+        assert_eq!(generator.generate().unwrap_err(), "Machine ID is not leased");
+    }
 
-    // 生成并打印5个订单编号示例
-    for _ in 0..5 {
-        match generator.generate() {
-            Ok(id) => println!("Generated order number: {:019}", id),
-            Err(e) => println!("Error: {}", e),
-        }
+    #[test]
+    fn test_global_not_leased() {
+        // 未租用机器ID时不能生成
+        assert_eq!(SnowflakeGenerator::global().generate().unwrap_err(), "Machine ID is not leased");
     }
-}
\ No newline at end of file
+}
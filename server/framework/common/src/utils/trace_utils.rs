@@ -0,0 +1,250 @@
+use std::future::Future;
+use axum::http::HeaderMap;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use rand::Rng;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// W3C链路追踪请求头
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// 响应中返回链路编号,方便前端和日志对照
+pub const TRACE_ID_HEADER: &str = "X-Trace-Id";
+
+tokio::task_local! {
+    static CURRENT_TRACE: TraceContext;
+}
+
+/// 链路上下文,格式参考 https://www.w3.org/TR/trace-context/
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraceContext {
+    pub trace_id: String, // 链路编号,32位十六进制
+    pub span_id: String, // 当前服务的span编号,16位十六进制
+    pub parent_span_id: Option<String>, // 上游span编号
+    pub sampled: bool, // 是否采样
+}
+
+impl TraceContext {
+    /// 生成新的链路
+    pub fn new_root() -> Self {
+        TraceContext {
+            trace_id: random_hex(16),
+            span_id: random_hex(8),
+            parent_span_id: None,
+            sampled: true,
+        }
+    }
+
+    /// 解析上游traceparent,生成当前服务的span,格式错误时返回None
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.trim().split('-').collect();
+        if parts.len() < 4 {
+            return None;
+        }
+        let (version, trace_id, parent_id, flags) = (parts[0], parts[1], parts[2], parts[3]);
+        // 版本ff无效,版本00必须正好4段
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.len() != 4) {
+            return None;
+        }
+        if !is_hex(trace_id, 32) || trace_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !is_hex(parent_id, 16) || parent_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !is_hex(flags, 2) {
+            return None;
+        }
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        Some(TraceContext {
+            trace_id: trace_id.to_string(),
+            span_id: random_hex(8),
+            parent_span_id: Some(parent_id.to_string()),
+            sampled: flags & 0x01 == 0x01,
+        })
+    }
+
+    /// 从请求头获取链路,没有或格式错误时生成新的链路
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(TraceContext::from_traceparent)
+            .unwrap_or_else(TraceContext::new_root)
+    }
+
+    /// 传给下游的traceparent,当前span作为下游的父span
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{}", self.trace_id, self.span_id, if self.sampled { "01" } else { "00" })
+    }
+
+    /// 关联tracing span: 设置上游为父span(开启otlp时生效),并使用otlp生成的span编号保持一致
+    pub fn attach(&mut self, span: &Span) {
+        if let Some(parent_span_id) = &self.parent_span_id {
+            if let (Ok(trace_id), Ok(parent_id)) = (TraceId::from_hex(&self.trace_id), SpanId::from_hex(parent_span_id)) {
+                let flags = if self.sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+                let parent = SpanContext::new(trace_id, parent_id, flags, true, TraceState::default());
+                span.set_parent(opentelemetry::Context::new().with_remote_span_context(parent));
+            }
+        }
+        let otel_context = span.context();
+        let span_context = otel_context.span().span_context().clone();
+        if span_context.is_valid() {
+            self.trace_id = span_context.trace_id().to_string();
+            self.span_id = span_context.span_id().to_string();
+        }
+        span.record("trace_id", self.trace_id.as_str());
+        span.record("span_id", self.span_id.as_str());
+    }
+}
+
+/// 在链路上下文中执行,期间调用下游服务时自动传递traceparent
+pub async fn scope<F: Future>(trace: TraceContext, future: F) -> F::Output {
+    CURRENT_TRACE.scope(trace, future).await
+}
+
+/// 获取当前链路
+pub fn current_trace() -> Option<TraceContext> {
+    CURRENT_TRACE.try_with(|trace| trace.clone()).ok()
+}
+
+/// 获取当前链路编号
+pub fn current_trace_id() -> Option<String> {
+    current_trace().map(|trace| trace.trace_id)
+}
+
+/// 获取调用下游服务时需要传递的traceparent,优先使用otlp当前span
+pub fn current_traceparent() -> Option<String> {
+    let otel_context = Span::current().context();
+    let span_context = otel_context.span().span_context().clone();
+    if span_context.is_valid() {
+        let flags = if span_context.is_sampled() { "01" } else { "00" };
+        return Some(format!("00-{}-{}-{}", span_context.trace_id(), span_context.span_id(), flags));
+    }
+    current_trace().map(|trace| trace.traceparent())
+}
+
+/// grpc服务端请求span,用于tonic Server::trace_fn
+pub fn grpc_server_span(path: &str, headers: &HeaderMap) -> Span {
+    let mut trace = TraceContext::from_headers(headers);
+    let span = tracing::info_span!(
+        "grpc",
+        path = %path,
+        route = %path,
+        trace_id = tracing::field::Empty,
+        span_id = tracing::field::Empty,
+    );
+    trace.attach(&span);
+    span
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut rng = rand::rng();
+    loop {
+        let value: String = (0..bytes).map(|_| format!("{:02x}", rng.random::<u8>())).collect();
+        // 全0为无效编号
+        if value.chars().any(|c| c != '0') {
+            return value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT_ID: &str = "00f067aa0ba902b7";
+
+    fn traceparent(version: &str, trace_id: &str, parent_id: &str, flags: &str) -> String {
+        format!("{}-{}-{}-{}", version, trace_id, parent_id, flags)
+    }
+
+    #[test]
+    fn test_parse_valid_traceparent() {
+        let trace = TraceContext::from_traceparent(&traceparent("00", TRACE_ID, PARENT_ID, "01")).unwrap();
+        assert_eq!(trace.trace_id, TRACE_ID);
+        assert_eq!(trace.parent_span_id.as_deref(), Some(PARENT_ID));
+        assert!(trace.sampled);
+        // 当前服务生成新的span
+        assert!(is_hex(&trace.span_id, 16));
+        assert_ne!(trace.span_id, PARENT_ID);
+
+        let trace = TraceContext::from_traceparent(&format!(" {} ", traceparent("00", TRACE_ID, PARENT_ID, "00"))).unwrap();
+        assert!(!trace.sampled);
+        // 未知的标志位忽略
+        assert!(TraceContext::from_traceparent(&traceparent("00", TRACE_ID, PARENT_ID, "03")).unwrap().sampled);
+    }
+
+    #[test]
+    fn test_parse_future_version() {
+        // 更高的版本可以有更多字段,按00版本解析前4段
+        let value = format!("{}-extra", traceparent("01", TRACE_ID, PARENT_ID, "01"));
+        assert_eq!(TraceContext::from_traceparent(&value).unwrap().trace_id, TRACE_ID);
+        // 版本00只能有4段
+        let value = format!("{}-extra", traceparent("00", TRACE_ID, PARENT_ID, "01"));
+        assert_eq!(TraceContext::from_traceparent(&value), None);
+    }
+
+    #[test]
+    fn test_reject_version_ff() {
+        assert_eq!(TraceContext::from_traceparent(&traceparent("ff", TRACE_ID, PARENT_ID, "01")), None);
+    }
+
+    #[test]
+    fn test_reject_all_zero_ids() {
+        let zero_trace = "0".repeat(32);
+        let zero_parent = "0".repeat(16);
+        assert_eq!(TraceContext::from_traceparent(&traceparent("00", &zero_trace, PARENT_ID, "01")), None);
+        assert_eq!(TraceContext::from_traceparent(&traceparent("00", TRACE_ID, &zero_parent, "01")), None);
+    }
+
+    #[test]
+    fn test_reject_malformed() {
+        let malformed = [
+            String::new(),
+            "garbage".to_string(),
+            format!("00-{}-{}", TRACE_ID, PARENT_ID),
+            // 长度错误
+            traceparent("0", TRACE_ID, PARENT_ID, "01"),
+            traceparent("00", &TRACE_ID[1..], PARENT_ID, "01"),
+            traceparent("00", TRACE_ID, &PARENT_ID[1..], "01"),
+            traceparent("00", TRACE_ID, PARENT_ID, "1"),
+            // 非十六进制或大写
+            traceparent("0g", TRACE_ID, PARENT_ID, "01"),
+            traceparent("00", &TRACE_ID.replace('4', "g"), PARENT_ID, "01"),
+            traceparent("00", &TRACE_ID.to_uppercase(), PARENT_ID, "01"),
+            traceparent("00", TRACE_ID, &PARENT_ID.to_uppercase(), "01"),
+            traceparent("00", TRACE_ID, PARENT_ID, "zz"),
+            // 分隔符错误
+            format!("00_{}_{}_01", TRACE_ID, PARENT_ID),
+        ];
+        for value in malformed {
+            assert_eq!(TraceContext::from_traceparent(&value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let root = TraceContext::new_root();
+        let child = TraceContext::from_traceparent(&root.traceparent()).unwrap();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id, Some(root.span_id));
+        assert!(child.sampled);
+    }
+
+    #[test]
+    fn test_from_headers_falls_back_to_new_root() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TRACEPARENT_HEADER, traceparent("ff", TRACE_ID, PARENT_ID, "01").parse().unwrap());
+        let trace = TraceContext::from_headers(&headers);
+        assert_ne!(trace.trace_id, TRACE_ID);
+        assert_eq!(trace.parent_span_id, None);
+
+        headers.insert(TRACEPARENT_HEADER, traceparent("00", TRACE_ID, PARENT_ID, "01").parse().unwrap());
+        assert_eq!(TraceContext::from_headers(&headers).trace_id, TRACE_ID);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::future::{ready, Ready};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::http::uri::PathAndQuery;
+use axum::http::{Request, Response};
+use common::config::config::GrpcTlsConfig;
+use common::middleware::grpc_identity::{identity_from_cert, is_allowed, ServiceIdentity, ServiceIdentityLayer};
+use common::utils::grpc_tls::{incoming_from_listener, ReloadableTls, TlsIncoming};
+use futures_util::future::BoxFuture;
+use futures_util::StreamExt;
+use rcgen::{BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, Ia5String, IsCa, KeyPair, KeyUsagePurpose, SanType};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use tonic::body::Body;
+use tonic::codec::ProstCodec;
+use tonic::server::{NamedService, UnaryService};
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint, Identity, Server};
+use tonic::{Code, Status};
+use tower::Service;
+
+/// 测试用CA,签发服务证书
+struct TestCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+/// 签发的证书和私钥(PEM)
+struct TestCert {
+    cert_pem: String,
+    key_pem: String,
+}
+
+impl TestCa {
+    fn new(name: &str) -> TestCa {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let key = KeyPair::generate().unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        TestCa { cert, key }
+    }
+
+    fn pem(&self) -> String {
+        self.cert.pem()
+    }
+
+    /// 签发服务证书,服务身份写入CN和spiffe URI
+    fn issue(&self, service: &str) -> TestCert {
+        let mut params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+        params.distinguished_name.push(DnType::CommonName, service);
+        params.subject_alt_names.push(SanType::URI(Ia5String::try_from(format!("spiffe://synerunify/{}", service)).unwrap()));
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth, ExtendedKeyUsagePurpose::ClientAuth];
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, &self.cert, &self.key).unwrap();
+        TestCert { cert_pem: cert.pem(), key_pem: key.serialize_pem() }
+    }
+}
+
+/// 证书写入临时目录,生成服务端配置
+struct TestPki {
+    dir: PathBuf,
+    config: GrpcTlsConfig,
+}
+
+impl TestPki {
+    fn new(ca: &TestCa, server: &TestCert, require_client_cert: bool) -> TestPki {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("synerunify-grpc-tls-{}", nanos));
+        fs::create_dir_all(&dir).unwrap();
+        let config = GrpcTlsConfig {
+            enabled: true,
+            ca_cert: dir.join("ca.pem").to_string_lossy().to_string(),
+            cert: dir.join("server.pem").to_string_lossy().to_string(),
+            key: dir.join("server.key").to_string_lossy().to_string(),
+            require_client_cert,
+            ..Default::default()
+        };
+        let pki = TestPki { dir, config };
+        pki.write(ca, server);
+        pki
+    }
+
+    fn write(&self, ca: &TestCa, server: &TestCert) {
+        fs::write(&self.config.ca_cert, ca.pem()).unwrap();
+        fs::write(&self.config.cert, &server.cert_pem).unwrap();
+        fs::write(&self.config.key, &server.key_pem).unwrap();
+    }
+}
+
+impl Drop for TestPki {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+async fn listen(tls: Arc<ReloadableTls>) -> (TlsIncoming, u16) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    (incoming_from_listener(listener, tls), port)
+}
+
+/// 客户端连接,client为None时不提供客户端证书,返回服务端证书的CN
+async fn connect(port: u16, ca: &TestCa, client: Option<&TestCert>) -> Option<String> {
+    let mut roots = RootCertStore::empty();
+    roots.add(ca.cert.der().clone()).unwrap();
+    let builder = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots);
+    let config = match client {
+        Some(client) => {
+            let certs: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut client.cert_pem.as_bytes()).collect::<Result<_, _>>().unwrap();
+            let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut client.key_pem.as_bytes()).unwrap().unwrap();
+            builder.with_client_auth_cert(certs, key).unwrap()
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await
+        .ok()?;
+    let server_cert = stream.get_ref().1.peer_certificates()?.first()?.clone();
+    identity_from_cert(server_cert.as_ref())
+}
+
+/// 等待服务端完成握手的连接,返回客户端证书中的服务身份
+async fn accepted(incoming: &mut TlsIncoming) -> Option<Option<String>> {
+    let stream = tokio::time::timeout(Duration::from_secs(2), incoming.next()).await.ok()??.ok()?;
+    let identity = stream.get_ref().1.peer_certificates()
+        .and_then(|certs| certs.first().and_then(|cert| identity_from_cert(cert.as_ref())));
+    Some(identity)
+}
+
+/// 测试用grpc服务,在响应元数据中返回请求扩展里的服务身份
+#[derive(Clone)]
+struct WhoamiServer;
+
+struct Whoami;
+
+impl UnaryService<()> for Whoami {
+    type Response = ();
+    type Future = Ready<Result<tonic::Response<()>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<()>) -> Self::Future {
+        let mut response = tonic::Response::new(());
+        if let Some(ServiceIdentity(identity)) = request.extensions().get::<ServiceIdentity>() {
+            response.metadata_mut().insert("x-service-identity", identity.parse().unwrap());
+        }
+        ready(Ok(response))
+    }
+}
+
+impl Service<Request<Body>> for WhoamiServer {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        Box::pin(async move {
+            let mut grpc = tonic::server::Grpc::new(ProstCodec::<(), ()>::default());
+            Ok(grpc.unary(Whoami, request).await)
+        })
+    }
+}
+
+impl NamedService for WhoamiServer {
+    const NAME: &'static str = "test.Whoami";
+}
+
+/// 启动带服务身份校验的grpc服务
+async fn serve(pki: &TestPki, allowed_rpcs: HashMap<String, Vec<String>>) -> u16 {
+    let tls = Arc::new(ReloadableTls::load(pki.config.clone()).unwrap());
+    let (incoming, port) = listen(tls).await;
+    let router = Server::builder()
+        .layer(ServiceIdentityLayer::new(allowed_rpcs))
+        .add_service(WhoamiServer);
+    tokio::spawn(router.serve_with_incoming(incoming));
+    port
+}
+
+/// 通过grpc调用获取服务端识别的服务身份
+async fn whoami(port: u16, ca: &TestCa, client: Option<&TestCert>) -> Result<Option<String>, Status> {
+    let mut tls = ClientTlsConfig::new()
+        .ca_certificate(Certificate::from_pem(ca.pem()))
+        .domain_name("localhost");
+    if let Some(client) = client {
+        tls = tls.identity(Identity::from_pem(&client.cert_pem, &client.key_pem));
+    }
+    let channel = Endpoint::from_shared(format!("https://127.0.0.1:{}", port)).unwrap()
+        .tls_config(tls).unwrap()
+        .connect().await.unwrap();
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready().await.unwrap();
+    let response = grpc.unary(tonic::Request::new(()), PathAndQuery::from_static("/test.Whoami/Get"), ProstCodec::<(), ()>::default()).await?;
+    Ok(response.metadata().get("x-service-identity").and_then(|value| value.to_str().ok()).map(str::to_string))
+}
+
+#[tokio::test]
+async fn test_mtls_accepts_client_signed_by_ca() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), true);
+    let tls = Arc::new(ReloadableTls::load(pki.config.clone()).unwrap());
+    let (mut incoming, port) = listen(tls).await;
+
+    let client = ca.issue("erp_server");
+    let server_identity = connect(port, &ca, Some(&client)).await;
+    assert_eq!(server_identity.as_deref(), Some("system_server"));
+    assert_eq!(accepted(&mut incoming).await, Some(Some("erp_server".to_string())));
+}
+
+#[tokio::test]
+async fn test_mtls_rejects_missing_or_untrusted_client_cert() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), true);
+    let tls = Arc::new(ReloadableTls::load(pki.config.clone()).unwrap());
+    let (mut incoming, port) = listen(tls).await;
+
+    // 没有客户端证书
+    let _ = connect(port, &ca, None).await;
+    assert_eq!(accepted(&mut incoming).await, None);
+
+    // 其他CA签发的客户端证书
+    let other_ca = TestCa::new("other ca");
+    let _ = connect(port, &ca, Some(&other_ca.issue("erp_server"))).await;
+    assert_eq!(accepted(&mut incoming).await, None);
+}
+
+#[tokio::test]
+async fn test_tls_without_client_cert_when_not_required() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), false);
+    let tls = Arc::new(ReloadableTls::load(pki.config.clone()).unwrap());
+    let (mut incoming, port) = listen(tls).await;
+
+    assert_eq!(connect(port, &ca, None).await.as_deref(), Some("system_server"));
+    assert_eq!(accepted(&mut incoming).await, Some(None));
+}
+
+#[tokio::test]
+async fn test_reload_changed_certificates() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), true);
+    let tls = Arc::new(ReloadableTls::load(pki.config.clone()).unwrap());
+    let (mut incoming, port) = listen(tls.clone()).await;
+    let client = ca.issue("erp_server");
+
+    assert!(!tls.reload_if_changed().unwrap());
+
+    // 文件修改时间精度可能为秒
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    pki.write(&ca, &ca.issue("system_server_v2"));
+    assert!(tls.reload_if_changed().unwrap());
+    assert_eq!(connect(port, &ca, Some(&client)).await.as_deref(), Some("system_server_v2"));
+    assert!(accepted(&mut incoming).await.is_some());
+
+    // 新证书无效时继续使用原证书
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    fs::write(&pki.config.cert, "invalid").unwrap();
+    assert!(tls.reload_if_changed().is_err());
+    assert_eq!(connect(port, &ca, Some(&client)).await.as_deref(), Some("system_server_v2"));
+}
+
+#[test]
+fn test_identity_from_cert_prefers_spiffe_uri() {
+    let ca = TestCa::new("synerunify test ca");
+    let cert = ca.issue("mall_server");
+    let der: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut cert.cert_pem.as_bytes()).collect::<Result<_, _>>().unwrap();
+    assert_eq!(identity_from_cert(der[0].as_ref()).as_deref(), Some("mall_server"));
+    assert_eq!(identity_from_cert(b"not a certificate"), None);
+}
+
+#[test]
+fn test_allowed_rpcs() {
+    let empty = HashMap::new();
+    assert!(is_allowed(&empty, None, "/system.SystemService/GetUser"));
+
+    let allowed = HashMap::from([
+        ("erp_server".to_string(), vec!["/system.SystemService/*".to_string()]),
+        ("mall_server".to_string(), vec!["/system.SystemService/GetUser".to_string()]),
+        ("admin".to_string(), vec!["*".to_string()]),
+    ]);
+    assert!(is_allowed(&allowed, Some("erp_server"), "/system.SystemService/ValidateToken"));
+    assert!(!is_allowed(&allowed, Some("erp_server"), "/system.SystemServiceX/GetUser"));
+    assert!(is_allowed(&allowed, Some("mall_server"), "/system.SystemService/GetUser"));
+    assert!(!is_allowed(&allowed, Some("mall_server"), "/system.SystemService/GetTenant"));
+    assert!(is_allowed(&allowed, Some("ADMIN"), "/gocaptcha.GoCaptchaService/CheckStatus"));
+    assert!(!is_allowed(&allowed, Some("unknown"), "/system.SystemService/GetUser"));
+    assert!(!is_allowed(&allowed, None, "/system.SystemService/GetUser"));
+}
+
+#[tokio::test]
+async fn test_service_identity_from_tls_connect_info() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), true);
+    let allowed = HashMap::from([("erp_server".to_string(), vec!["/test.Whoami/*".to_string()])]);
+    let port = serve(&pki, allowed).await;
+
+    // 客户端证书的服务身份经 TlsConnectInfo 传入请求扩展
+    let identity = whoami(port, &ca, Some(&ca.issue("erp_server"))).await.unwrap();
+    assert_eq!(identity.as_deref(), Some("erp_server"));
+
+    // 不在允许列表中的服务
+    let status = whoami(port, &ca, Some(&ca.issue("mall_server"))).await.unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_service_identity_missing_client_cert() {
+    let ca = TestCa::new("synerunify test ca");
+    let pki = TestPki::new(&ca, &ca.issue("system_server"), false);
+
+    // 不限制时没有客户端证书也可以调用,请求中没有服务身份
+    let port = serve(&pki, HashMap::new()).await;
+    assert_eq!(whoami(port, &ca, None).await.unwrap(), None);
+
+    let allowed = HashMap::from([("erp_server".to_string(), vec!["*".to_string()])]);
+    let port = serve(&pki, allowed).await;
+    let status = whoami(port, &ca, None).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
@@ -10,7 +10,7 @@ mod tests {
     #[test]
     #[ignore]
     fn test_generate() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
 
         // 生成并打印5个订单编号示例
         for _ in 0..5 {
@@ -24,7 +24,7 @@ mod tests {
     #[test]
     #[ignore]
     fn test_order_number_format() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
         let order_number = generator.generate().unwrap();
 
         // 检查是否为正数且长度合理 (19位以内)
@@ -35,7 +35,7 @@ mod tests {
     #[test]
     #[ignore]
     fn test_unique_ids() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
         let id1 = generator.generate().unwrap();
         let id2 = generator.generate().unwrap();
 
@@ -46,7 +46,7 @@ mod tests {
     // #[test]
     // #[ignore]
     // fn test_large_offset() {
-    //     let generator = SnowflakeGenerator::new();
+    //     let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
     //     {
     //         let mut last_ts = generator.last_timestamp.lock().unwrap();
     //         let mut offset = generator.time_offset.lock().unwrap();
@@ -96,7 +96,7 @@ mod tests {
     #[test]
     #[ignore]
     fn test_default_machine_id() {
-        let generator = SnowflakeGenerator::new();
+        let generator = SnowflakeGenerator::new_with_machine_id(1).unwrap();
         let order_number = generator.generate().unwrap();
 
         // 检查machine_id是否为1
@@ -4,7 +4,7 @@ use std::collections::BTreeMap;
 
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
-use syn::parse::{Parse, ParseStream};
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::{parse_macro_input, parse_str, Data, DeriveInput, Expr, Fields, Ident, ItemFn, Lit, LitStr, Meta, MetaNameValue, Path, Token, Type};
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
@@ -18,6 +18,8 @@ pub fn require_authorize(args: TokenStream, input: TokenStream) -> TokenStream {
     // 提取 operation_id 和 authorize 参数
     let mut operation_id = String::new();
     let mut authorizes = Vec::new();
+    let mut idempotent = false;
+    let mut idempotent_ttl: Option<u64> = None;
 
     for arg in args {
         if arg.path.is_ident("operation_id") {
@@ -35,6 +37,18 @@ pub fn require_authorize(args: TokenStream, input: TokenStream) -> TokenStream {
                         .collect();
                 }
             }
+        } else if arg.path.is_ident("idempotent") {
+            if let Expr::Lit(expr_lit) = &arg.value {
+                if let Lit::Bool(lit) = &expr_lit.lit {
+                    idempotent = lit.value;
+                }
+            }
+        } else if arg.path.is_ident("idempotent_ttl") {
+            if let Expr::Lit(expr_lit) = &arg.value {
+                if let Lit::Int(lit) = &expr_lit.lit {
+                    idempotent_ttl = lit.base10_parse::<u64>().ok();
+                }
+            }
         }
     }
 
@@ -52,6 +66,19 @@ pub fn require_authorize(args: TokenStream, input: TokenStream) -> TokenStream {
         fn_name.span()
     );
 
+    // 开启幂等时注册幂等配置
+    let register_idempotency = if idempotent {
+        let ttl = match idempotent_ttl {
+            Some(ttl) => quote! { #ttl },
+            None => quote! { common::middleware::idempotency::DEFAULT_IDEMPOTENCY_TTL },
+        };
+        quote! {
+            common::middleware::idempotency::register_operation_idempotency(#operation_id, #ttl);
+        }
+    } else {
+        quote! {}
+    };
+
     // 生成注册代码
     let expanded = quote! {
         #item_fn
@@ -60,6 +87,7 @@ pub fn require_authorize(args: TokenStream, input: TokenStream) -> TokenStream {
         #[ctor::ctor]
         fn #register_fn_name() {
             common::middleware::authorize::register_operation_authorizes(#operation_id, vec![#(#authorizes.to_string()),*]);
+            #register_idempotency
         }
     };
 
@@ -67,6 +95,143 @@ pub fn require_authorize(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 
+/// 接口限流定义宏,window秒内最多limit次请求,key为限流维度: ip/user/tenant/api_key
+#[proc_macro_attribute]
+pub fn rate_limit(args: TokenStream, input: TokenStream) -> TokenStream {
+    rate_limit_impl(args.into(), input.into()).into()
+}
+
+fn rate_limit_impl(args: proc_macro2::TokenStream, input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let args = match Punctuated::<MetaNameValue, Comma>::parse_terminated.parse2(args) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let mut operation_id = String::new();
+    let mut limit: u64 = 0;
+    let mut window: u64 = 60;
+    let mut key = "ip".to_string();
+
+    for arg in args {
+        if let Expr::Lit(expr_lit) = &arg.value {
+            if arg.path.is_ident("operation_id") {
+                if let Lit::Str(lit) = &expr_lit.lit {
+                    operation_id = lit.value();
+                }
+            } else if arg.path.is_ident("limit") {
+                if let Lit::Int(lit) = &expr_lit.lit {
+                    limit = lit.base10_parse::<u64>().unwrap_or(0);
+                }
+            } else if arg.path.is_ident("window") {
+                if let Lit::Int(lit) = &expr_lit.lit {
+                    window = lit.base10_parse::<u64>().unwrap_or(60);
+                }
+            } else if arg.path.is_ident("key") {
+                if let Lit::Str(lit) = &expr_lit.lit {
+                    key = lit.value();
+                }
+            }
+        }
+    }
+
+    let item_fn = match syn::parse2::<ItemFn>(input) {
+        Ok(item_fn) => item_fn,
+        Err(e) => return e.to_compile_error(),
+    };
+    if operation_id.is_empty() || limit == 0 {
+        return syn::Error::new_spanned(&item_fn.sig.ident, "rate_limit requires operation_id and limit")
+            .to_compile_error();
+    }
+    let fn_name = item_fn.sig.ident.clone();
+
+    // 生成唯一的注册函数名
+    let register_fn_name = syn::Ident::new(
+        &format!("{}_register_rate_limit", fn_name),
+        fn_name.span()
+    );
+
+    quote! {
+        #item_fn
+
+        // 在模块初始化时注册限流规则
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            common::middleware::rate_limit::register_operation_rate_limit(#operation_id, #limit, #window, #key);
+        }
+    }
+}
+
+/// 操作审计定义宏,操作日志记录业务模块、操作、业务编号,配置table时记录修改前后的字段差异
+/// biz_id为业务编号来源: path.id/body.id/query.id/response,redact为需要脱敏的字段,逗号分隔
+#[proc_macro_attribute]
+pub fn audit(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args with Punctuated::<MetaNameValue, Comma>::parse_terminated);
+
+    let mut operation_id = String::new();
+    let mut module = String::new();
+    let mut action = String::new();
+    let mut biz_id = String::new();
+    let mut table = String::new();
+    let mut redact: Vec<String> = Vec::new();
+
+    for arg in args {
+        if let Expr::Lit(expr_lit) = &arg.value {
+            if let Lit::Str(lit) = &expr_lit.lit {
+                if arg.path.is_ident("operation_id") {
+                    operation_id = lit.value();
+                } else if arg.path.is_ident("module") {
+                    module = lit.value();
+                } else if arg.path.is_ident("action") {
+                    action = lit.value();
+                } else if arg.path.is_ident("biz_id") {
+                    biz_id = lit.value();
+                } else if arg.path.is_ident("table") {
+                    table = lit.value();
+                } else if arg.path.is_ident("redact") {
+                    redact = lit.value()
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+            }
+        }
+    }
+
+    let item_fn = parse_macro_input!(input as ItemFn);
+    if operation_id.is_empty() || module.is_empty() || action.is_empty() {
+        return syn::Error::new_spanned(&item_fn.sig.ident, "audit requires operation_id, module and action")
+            .to_compile_error()
+            .into();
+    }
+    let valid_biz_id = biz_id.is_empty() || biz_id == "response"
+        || ["path.", "body.", "query."].iter().any(|prefix| biz_id.starts_with(prefix) && biz_id.len() > prefix.len());
+    if !valid_biz_id {
+        return syn::Error::new_spanned(&item_fn.sig.ident, "audit biz_id must be path.<name>, body.<name>, query.<name> or response")
+            .to_compile_error()
+            .into();
+    }
+    let fn_name = item_fn.sig.ident.clone();
+
+    // 生成唯一的注册函数名
+    let register_fn_name = syn::Ident::new(
+        &format!("{}_register_audit", fn_name),
+        fn_name.span()
+    );
+
+    let expanded = quote! {
+        #item_fn
+
+        // 在模块初始化时注册审计规则
+        #[ctor::ctor]
+        fn #register_fn_name() {
+            common::middleware::operation_logger::register_operation_audit(#operation_id, #module, #action, #biz_id, #table, &[#(#redact),*]);
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
 // 示例用法
 /*
 #[cfg(test)]
@@ -79,15 +244,35 @@ mod example {
     async fn test_handler(state: AppState) -> String {
         "Hello".to_string()
     }
+
+    // 每个ip 60秒内最多请求10次
+    #[rate_limit(operation_id = "login", limit = 10, window = 60, key = "ip")]
+    async fn login_handler(state: AppState) -> String {
+        "Hello".to_string()
+    }
+
+    // 开启幂等,相同Idempotency-Key的请求在3600秒内重放第一次的响应
+    #[require_authorize(operation_id = "order_create", authorize = "", idempotent = true, idempotent_ttl = 3600)]
+    async fn create_handler(state: AppState) -> String {
+        "Hello".to_string()
+    }
+
+    // 记录审计日志,业务编号取请求体的id,记录system_user修改前后的差异,password脱敏
+    #[audit(operation_id = "system_user_update", module = "system_user", action = "update", biz_id = "body.id", table = "system_user", redact = "password")]
+    async fn update_handler(state: AppState) -> String {
+        "Hello".to_string()
+    }
 }
 */
 
 /// 字段扩展宏,给字段扩展自定义字段,值为根据方法获取的值
+/// 值在返回前由 common::formatter::extend_field::enrich 异步批量获取,保存在 #[extend_fields(values)] 标记的字段中
 #[derive(Default)]
 struct ExtendFieldsArgs {
     field: Option<String>,
     fill_type: Option<String>,
     invocation: Option<Path>,
+    values: bool, // 保存扩展字段值的字段
 }
 
 #[derive(Debug)]
@@ -163,6 +348,12 @@ impl Parse for ExtendFieldsArgs {
                             }
                             _ => return Err(syn::Error::new_spanned(&nv.path, "Unknown attribute")),
                         }
+                    } else if let syn::Meta::Path(path) = &meta {
+                        if path.is_ident("values") {
+                            args.values = true;
+                        } else {
+                            return Err(syn::Error::new_spanned(path, "Unknown attribute"));
+                        }
                     } else {
                         return Err(syn::Error::new_spanned(&meta, "Expected name-value attribute"));
                     }
@@ -194,6 +385,8 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
 
     // 收集需要添加额外字段的字段及其参数
     let mut extend_field_info = Vec::new();
+    // 保存扩展字段值的字段
+    let mut values_ident: Option<&Ident> = None;
     // 收集serde_as
     let mut serde_as_field_info = Vec::new();
     for field in fields.iter() {
@@ -206,6 +399,10 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
             // eprintln!("field attr path: {:?}", attr.path());
             if attr.path().is_ident("extend_fields") {
                 let args: ExtendFieldsArgs = attr.parse_args().unwrap_or_default();
+                if args.values {
+                    values_ident = Some(field_ident);
+                    continue;
+                }
                 let field_str = field_ident.to_string();
                 let field_name = args.field.unwrap_or_else(|| {
                     if field_str == "id" {
@@ -246,86 +443,69 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
         }
     }
 
-    // 生成序列化逻辑
-    // let serialize_extend_fields = extend_field_info.iter().map(|(ident, field_name, fill_type, invocation, is_option)| {
-    //     if let Some(invocation) = invocation {
-    //         if *is_option {
-    //             quote! {
-    //                 let value = self.#ident.as_ref().map(|v| #invocation(v, #fill_type)).unwrap_or_default();
-    //                 map.serialize_entry(#field_name, &value)?;
-    //             }
-    //         } else {
-    //             quote! {
-    //                 let value = #invocation(&self.#ident, #fill_type);
-    //                 map.serialize_entry(#field_name, &value)?;
-    //             }
-    //         }
-    //     } else {
-    //         quote! {
-    //             map.serialize_entry(#field_name, &self.#ident)?;
-    //         }
-    //     }
-    // });
-
-    // 按 fill_type 分组字段
-    let mut grouped_fields: BTreeMap<String, Vec<(&Ident, &String, &Path, bool)>> = BTreeMap::new();
+    // 按 fill_type 分组字段,未指定 fill_type 时使用 invocation 路径作为缓存分组
+    let mut grouped_fields: BTreeMap<String, (&Path, Vec<(&Ident, &String, bool)>)> = BTreeMap::new();
     let mut normal_fields = Vec::new();
 
     for (ident, field_name, fill_type, invocation, is_option) in &extend_field_info {
         if let Some(invocation) = invocation {
+            let fill_type = if fill_type.is_empty() {
+                invocation.to_token_stream().to_string().replace(' ', "")
+            } else {
+                fill_type.clone()
+            };
             grouped_fields
-                .entry(fill_type.clone())
-                .or_default()
-                .push((ident, field_name, invocation, *is_option));
+                .entry(fill_type)
+                .or_insert_with(|| (invocation, Vec::new()))
+                .1
+                .push((ident, field_name, *is_option));
         } else {
             normal_fields.push((ident, field_name));
         }
     }
 
-    let mut serialize_extend_fields = quote! {};
-    for (fill_type, fields) in grouped_fields {
-        let invocation = fields[0].2; // 同一组使用同一个 invocation
+    if !grouped_fields.is_empty() && values_ident.is_none() {
+        return syn::Error::new_spanned(name, "ExtendFields with invocation requires a field marked #[extend_fields(values)]")
+            .to_compile_error()
+            .into();
+    }
 
-        let collect_ids = fields.iter().map(|(ident, _, _, is_option)| {
-            if *is_option {
-                quote! {
-                    if let Some(v) = &self.#ident {
-                        __ids.push(v.clone());
-                    }
-                }
-            } else {
-                quote! {
-                    __ids.push(self.#ident.clone());
-                }
-            }
+    let mut collect_keys = quote! {};
+    let mut fill_values = quote! {};
+    let mut resolvers = Vec::new();
+    let mut serialize_extend_fields = quote! {};
+    for (fill_type, (invocation, fields)) in &grouped_fields {
+        // 同一组使用同一个 invocation,签名为 async fn(Vec<i64>) -> anyhow::Result<HashMap<i64, String>>
+        resolvers.push(quote! {
+            (#fill_type, |ids| Box::pin(#invocation(ids)))
         });
-
-        let insert_results = fields.iter().map(|(ident, field_name, _, is_option)| {
+        for (ident, field_name, is_option) in fields {
             if *is_option {
-                quote! {
-                    if let Some(v) = &self.#ident {
-                        if let Some(val) = __map.get(v) {
-                            map.serialize_entry(#field_name, val)?;
-                        }
+                collect_keys.extend(quote! {
+                    if let Some(id) = &self.#ident {
+                        keys.add(#fill_type, id.clone());
                     }
-                }
+                });
+                fill_values.extend(quote! {
+                    if let Some(name) = self.#ident.as_ref().and_then(|id| names.get(#fill_type, id.clone())) {
+                        self.#values_ident.insert(#field_name, name.clone());
+                    }
+                });
             } else {
-                quote! {
-                    if let Some(val) = __map.get(&self.#ident) {
-                        map.serialize_entry(#field_name, val)?;
+                collect_keys.extend(quote! {
+                    keys.add(#fill_type, self.#ident.clone());
+                });
+                fill_values.extend(quote! {
+                    if let Some(name) = names.get(#fill_type, self.#ident.clone()) {
+                        self.#values_ident.insert(#field_name, name.clone());
                     }
-                }
-            }
-        });
-
-        serialize_extend_fields.extend(quote! {
-            {
-                let mut __ids = vec![];
-                #(#collect_ids)*
-                let __map = #invocation(__ids);
-                #(#insert_results)*
+                });
             }
-        });
+            // 未填充时输出null
+            serialize_extend_fields.extend(quote! {
+                map.serialize_entry(#field_name, &self.#values_ident.get(#field_name))?;
+            });
+        }
     }
 
     // 处理无 invocation 的字段
@@ -383,6 +563,7 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
         let ident = field.ident.as_ref().unwrap();
         // Check if this field is NOT in serde_as_field_info or extend_field_info
         !serde_as_field_info.iter().any(|(i, _, _)| i == &ident)
+            && values_ident.map_or(true, |values| values != ident)
         //  && !extend_field_info.iter().any(|(i, _, _, _, _)| i == &ident)
     }).map(|field| {
         let ident = field.ident.as_ref().unwrap();
@@ -433,6 +614,21 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
                 map.end()
             }
         }
+
+        #[allow(unused_variables)]
+        impl common::formatter::extend_field::ExtendFields for #name {
+            fn extend_keys(&self, keys: &mut common::formatter::extend_field::ExtendKeys) {
+                #collect_keys
+            }
+
+            fn extend_fill(&mut self, names: &common::formatter::extend_field::ExtendNames) {
+                #fill_values
+            }
+
+            fn extend_resolvers() -> Vec<(&'static str, common::formatter::extend_field::Resolver)> {
+                vec![#(#resolvers),*]
+            }
+        }
     };
 
     TokenStream::from(expanded)
@@ -443,16 +639,21 @@ pub fn derive_extend_fields(input: TokenStream) -> TokenStream {
 #[serde_as]
 #[derive(Deserialize, ExtendFields)]
 struct User {
-    #[extend_fields(invocation = "system_common::service::system::get_user_name")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     id: i64,
-    #[extend_fields(invocation = "system_common::service::system::get_user_name")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     user_id: i64,
-    #[extend_fields(invocation = "system_common::service::system::get_user_name")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     creator: Option<i64>,
     value: String,
     #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
     date: NaiveDateTime,
+    #[serde(skip)]
+    #[extend_fields(values)]
+    extend: common::formatter::extend_field::ExtendValues,
 }
+// 返回前填充,整页数据只调用一次 get_user_names
+common::formatter::extend_field::enrich(&mut users).await;
 序列化结果：
 {
   "id": 1,        
@@ -460,8 +661,47 @@ struct User {
   "creator": null,
   "value": "user",
   "date": "2025-06-20 02:33:05",
-  "id_name": "超级管理员",
+  "name": "超级管理员",
   "user_name": "超级管理员",
-  "creator_name": ""
+  "creator_name": null
+}
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: proc_macro2::TokenStream) -> String {
+        let input = quote! {
+            async fn login_handler() -> String {
+                "Hello".to_string()
+            }
+        };
+        rate_limit_impl(args, input).to_string()
+    }
+
+    #[test]
+    fn test_rate_limit_register() {
+        let expanded = expand(quote! { operation_id = "login", limit = 10, window = 30, key = "user" });
+        assert!(expanded.contains("async fn login_handler"));
+        assert!(expanded.contains("ctor :: ctor"));
+        assert!(expanded.contains("fn login_handler_register_rate_limit"));
+        assert!(expanded.contains(r#"register_operation_rate_limit ("login" , 10u64 , 30u64 , "user")"#));
+    }
+
+    #[test]
+    fn test_rate_limit_defaults() {
+        let expanded = expand(quote! { operation_id = "login", limit = 10 });
+        assert!(expanded.contains(r#"register_operation_rate_limit ("login" , 10u64 , 60u64 , "ip")"#));
+    }
+
+    #[test]
+    fn test_rate_limit_requires_limit() {
+        let expanded = expand(quote! { operation_id = "login" });
+        assert!(expanded.contains("compile_error"));
+        assert!(!expanded.contains("register_operation_rate_limit"));
+
+        let expanded = expand(quote! { limit = 10 });
+        assert!(expanded.contains("compile_error"));
+    }
 }
-*/
\ No newline at end of file
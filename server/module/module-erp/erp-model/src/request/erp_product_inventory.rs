@@ -25,6 +25,14 @@ pub struct ErpProductInventoryInOutRequest {
     
 }
 
+/// 库存导出,同时作为后台作业的参数
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ExportErpProductInventoryRequest {
+    
+    pub warehouse_id: Option<i64>, // 仓库ID,为空时导出全部仓库
+    
+}
+
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UpdateErpProductInventoryRequest {
     
@@ -14,6 +14,7 @@ pub struct ErpCustomerResponse {
     
     pub contact_person: Option<String>, // 联系人
     
+    #[serde_as(as = "Option<common::formatter::sensitive::Mobile>")]
     pub phone: Option<String>, // 电话
     
     pub email: Option<String>, // 邮箱
@@ -24,12 +25,14 @@ pub struct ErpCustomerResponse {
     
     pub sort: i32, // 排序
     
+    #[serde_as(as = "Option<common::formatter::sensitive::IdNumber>")]
     pub tax_id: Option<String>, // 纳税人识别号
     
     pub tax_rate: Option<i32>, // 税率,精确到万分位
     
     pub bank_name: Option<String>, // 开户行
     
+    #[serde_as(as = "Option<common::formatter::sensitive::BankAccount>")]
     pub bank_account: Option<String>, // 银行账号
     
     pub bank_address: Option<String>, // 开户地址
@@ -12,7 +12,7 @@ pub struct ErpInboundOrderResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -62,7 +62,7 @@ pub struct ErpInboundOrderPagePurchaseResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -106,7 +106,7 @@ pub struct ErpInboundOrderPagePurchaseResponse {
 
     /****************** 信息 ******************/
 
-    pub purchase_order_number: Option<i64>, // 采购订单编号
+    pub purchase_order_number: Option<String>, // 采购订单编号
 
     pub supplier_name: Option<String>, // 供应商名
 
@@ -120,7 +120,7 @@ pub struct ErpInboundOrderPageOtherResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -176,7 +176,7 @@ pub struct ErpInboundOrderBasePurchaseResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -210,7 +210,7 @@ pub struct ErpInboundOrderBaseOtherResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -244,7 +244,7 @@ pub struct ErpInboundOrderInfoPurchaseResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -282,7 +282,7 @@ pub struct ErpInboundOrderInfoOtherResponse {
     
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
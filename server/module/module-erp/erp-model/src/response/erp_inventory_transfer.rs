@@ -11,7 +11,7 @@ pub struct ErpInventoryTransferResponse {
     
     pub id: i64, // 调拨记录ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     // #[serde_as(as = "DisplayFromStr")]
     // #[serde(with = "serde_with::chrono::naive_datetime")]
@@ -49,7 +49,7 @@ pub struct ErpInventoryTransferBaseResponse {
     
     pub id: i64, // 调拨记录ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     // #[serde_as(as = "DisplayFromStr")]
     // #[serde(with = "serde_with::chrono::naive_datetime")]
@@ -12,7 +12,7 @@ pub struct ErpOutboundOrderResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -62,7 +62,7 @@ pub struct ErpOutboundOrderPageSalesResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -106,7 +106,7 @@ pub struct ErpOutboundOrderPageSalesResponse {
 
     /****************** 信息 ******************/
 
-    pub sale_order_number: Option<i64>, // 销售订单编号
+    pub sale_order_number: Option<String>, // 销售订单编号
 
     pub customer_name: Option<String>, // 客户名
 
@@ -120,7 +120,7 @@ pub struct ErpOutboundOrderPageOtherResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -176,7 +176,7 @@ pub struct ErpOutboundOrderBaseSalesResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -210,7 +210,7 @@ pub struct ErpOutboundOrderBaseOtherResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -244,7 +244,7 @@ pub struct ErpOutboundOrderInfoSalesResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
@@ -302,7 +302,7 @@ pub struct ErpOutboundOrderInfoOtherResponse {
     
     pub id: i64, // 出库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sale_id: Option<i64>, // 销售订单ID
     
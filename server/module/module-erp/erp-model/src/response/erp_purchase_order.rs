@@ -12,7 +12,7 @@ pub struct ErpPurchaseOrderResponse {
     
     pub id: i64, // 采购订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub supplier_id: i64, // 供应商ID
     
@@ -64,7 +64,7 @@ pub struct ErpPurchaseOrderPageResponse {
     
     pub id: i64, // 采购订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub supplier_id: i64, // 供应商ID
     
@@ -122,7 +122,7 @@ pub struct ErpPurchaseOrderBaseResponse {
     
     pub id: i64, // 采购订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub supplier_id: i64, // 供应商ID
     
@@ -158,7 +158,7 @@ pub struct ErpPurchaseOrderInfoResponse {
     
     pub id: i64, // 采购订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub supplier_id: i64, // 供应商ID
     
@@ -11,7 +11,7 @@ pub struct ErpPurchaseReturnResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_order_id: i64, // 采购订单ID
     
@@ -63,7 +63,7 @@ pub struct ErpPurchaseReturnPageResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_order_id: i64, // 采购订单ID
     
@@ -109,7 +109,7 @@ pub struct ErpPurchaseReturnPageResponse {
 
     /****************** 信息 ******************/
 
-    pub purchase_order_number: Option<i64>, // 采购订单编号
+    pub purchase_order_number: Option<String>, // 采购订单编号
 
     pub supplier_name: Option<String>, // 供应商名
 
@@ -123,7 +123,7 @@ pub struct ErpPurchaseReturnBaseResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_order_id: i64, // 采购订单ID
     
@@ -159,7 +159,7 @@ pub struct ErpPurchaseReturnInfoResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_order_id: i64, // 采购订单ID
     
@@ -12,7 +12,7 @@ pub struct ErpReceiptResponse {
     
     pub id: i64, // 收款ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -64,7 +64,7 @@ pub struct ErpReceiptPageResponse {
     
     pub id: i64, // 收款ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -122,7 +122,7 @@ pub struct ErpReceiptBaseResponse {
     
     pub id: i64, // 收款ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -158,7 +158,7 @@ pub struct ErpReceiptInfoResponse {
     
     pub id: i64, // 收款ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -12,7 +12,7 @@ pub struct ErpSalesOrderResponse {
     
     pub id: i64, // 订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -61,7 +61,7 @@ pub struct ErpSalesOrderPageResponse {
     
     pub id: i64, // 订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -119,7 +119,7 @@ pub struct ErpSalesOrderBaseResponse {
     
     pub id: i64, // 订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -155,7 +155,7 @@ pub struct ErpSalesOrderInfoResponse {
     
     pub id: i64, // 订单ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub customer_id: i64, // 客户ID
     
@@ -11,7 +11,7 @@ pub struct ErpSalesReturnResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sales_order_id: i64, // 销售订单ID
     
@@ -63,7 +63,7 @@ pub struct ErpSalesReturnPageResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sales_order_id: i64, // 销售订单ID
     
@@ -109,7 +109,7 @@ pub struct ErpSalesReturnPageResponse {
 
     /****************** 信息 ******************/
 
-    pub sales_order_number: Option<i64>, // 销售订单编号
+    pub sales_order_number: Option<String>, // 销售订单编号
 
     pub customer_name: Option<String>, // 客户名
 
@@ -123,7 +123,7 @@ pub struct ErpSalesReturnBaseResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sales_order_id: i64, // 销售订单ID
     
@@ -159,7 +159,7 @@ pub struct ErpSalesReturnInfoResponse {
     
     pub id: i64, // 退货ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub sales_order_id: i64, // 销售订单ID
     
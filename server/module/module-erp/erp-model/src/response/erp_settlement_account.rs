@@ -14,6 +14,7 @@ pub struct ErpSettlementAccountResponse {
     
     pub bank_name: Option<String>, // 开户行
     
+    #[serde_as(as = "Option<common::formatter::sensitive::BankAccount>")]
     pub bank_account: Option<String>, // 银行账号
     
     pub status: i8, // 状态
@@ -14,6 +14,7 @@ pub struct ErpSupplierResponse {
     
     pub contact_person: Option<String>, // 联系人
     
+    #[serde_as(as = "Option<common::formatter::sensitive::Mobile>")]
     pub phone: Option<String>, // 电话
     
     pub email: Option<String>, // 邮箱
@@ -22,12 +23,14 @@ pub struct ErpSupplierResponse {
     
     pub status: i8, // 状态
     
+    #[serde_as(as = "Option<common::formatter::sensitive::IdNumber>")]
     pub tax_id: Option<String>, // 纳税人识别号
     
     pub tax_rate: Option<i32>, // 税率,精确到万分位
     
     pub bank_name: Option<String>, // 开户行
     
+    #[serde_as(as = "Option<common::formatter::sensitive::BankAccount>")]
     pub bank_account: Option<String>, // 银行账号
     
     pub bank_address: Option<String>, // 开户地址
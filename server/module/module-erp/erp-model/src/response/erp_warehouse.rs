@@ -4,10 +4,10 @@ use serde::{Serialize, Deserialize};
 use utoipa::ToSchema;
 use serde_with::{serde_as, DisplayFromStr};
 use common::formatter::string_date_time::StringDateTime;
+use common::formatter::extend_field::ExtendValues;
 
 #[serde_as]
-#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
-// #[derive(Deserialize, ExtendFields, Debug, Clone, ToSchema)]
+#[derive(Deserialize, ExtendFields, Debug, Clone, ToSchema)]
 pub struct ErpWarehouseResponse {
     
     pub id: i64, // 仓库ID
@@ -28,7 +28,7 @@ pub struct ErpWarehouseResponse {
     
     pub remarks: Option<String>, // 备注
     
-    // #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names_batch")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     pub creator: Option<i64>, // 创建者ID
     
     // #[serde_as(as = "DisplayFromStr")]
@@ -37,6 +37,7 @@ pub struct ErpWarehouseResponse {
     #[schema(value_type = String, format = Date)]
     pub create_time: NaiveDateTime, // 创建时间
     
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     pub updater: Option<i64>, // 更新者ID
     
     // #[serde_as(as = "DisplayFromStr")]
@@ -45,4 +46,8 @@ pub struct ErpWarehouseResponse {
     #[schema(value_type = String, format = Date)]
     pub update_time: NaiveDateTime, // 更新时间
     
+    #[serde(skip)]
+    #[extend_fields(values)]
+    pub extend: ExtendValues, // 扩展字段,由enrich填充
+    
 }
\ No newline at end of file
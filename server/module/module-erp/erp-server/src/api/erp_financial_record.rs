@@ -48,7 +48,7 @@ pub async fn erp_financial_record_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_financial_record_create", authorize = "")]
+#[require_authorize(operation_id = "erp_financial_record_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
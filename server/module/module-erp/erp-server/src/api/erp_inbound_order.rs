@@ -58,7 +58,7 @@ pub async fn erp_inbound_order_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_inbound_order_create_purchase", authorize = "")]
+#[require_authorize(operation_id = "erp_inbound_order_create_purchase", authorize = "", idempotent = true)]
 async fn create_purchase(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -83,7 +83,7 @@ async fn create_purchase(
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_inbound_order_create_other", authorize = "")]
+#[require_authorize(operation_id = "erp_inbound_order_create_other", authorize = "", idempotent = true)]
 async fn create_other(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -49,7 +49,7 @@ pub async fn erp_inventory_check_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_inventory_check_create", authorize = "")]
+#[require_authorize(operation_id = "erp_inventory_check_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -49,7 +49,7 @@ pub async fn erp_inventory_transfer_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_inventory_transfer_create", authorize = "")]
+#[require_authorize(operation_id = "erp_inventory_transfer_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -58,7 +58,7 @@ async fn create(
     match service::erp_inventory_transfer::create(&state.db, login_user, payload).await {
         Ok(id) => {CommonResult::with_data(id)}
         Err(e) => {
-            println!("create transfer error, {:#?}", e);
+            tracing::error!("create transfer error, {:#?}", e);
             CommonResult::with_err(&e.to_string())
         }
     }
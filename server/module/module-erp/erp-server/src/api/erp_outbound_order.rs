@@ -56,7 +56,7 @@ pub async fn erp_outbound_order_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_outbound_order_create_sale", authorize = "")]
+#[require_authorize(operation_id = "erp_outbound_order_create_sale", authorize = "", idempotent = true)]
 async fn create_sale(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -81,7 +81,7 @@ async fn create_sale(
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_outbound_order_create_other", authorize = "")]
+#[require_authorize(operation_id = "erp_outbound_order_create_other", authorize = "", idempotent = true)]
 async fn create_other(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -50,7 +50,7 @@ pub async fn erp_payment_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_payment_create", authorize = "")]
+#[require_authorize(operation_id = "erp_payment_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -191,7 +191,7 @@ async fn list(
     match service::erp_product::list(&state.db, login_user).await {
         Ok(data) => {CommonResult::with_data(data)}
         Err(e) => {
-            eprintln!("list product error, {:#?}", e);
+            tracing::error!("list product error, {:#?}", e);
             CommonResult::with_err(&e.to_string())
         }
     }
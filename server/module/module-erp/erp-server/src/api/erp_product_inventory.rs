@@ -6,7 +6,7 @@ use ctor;
 use macros::require_authorize;
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
-use erp_model::{request::erp_product_inventory::{CreateErpProductInventoryRequest, PaginatedKeywordRequest, UpdateErpProductInventoryRequest}, response::erp_product_inventory::ErpProductInventoryPageResponse};
+use erp_model::{request::erp_product_inventory::{CreateErpProductInventoryRequest, ExportErpProductInventoryRequest, PaginatedKeywordRequest, UpdateErpProductInventoryRequest}, response::erp_product_inventory::ErpProductInventoryPageResponse};
 use erp_model::response::erp_product_inventory::ErpProductInventoryResponse;
 use common::base::response::CommonResult;
 use common::context::context::LoginUserContext;
@@ -21,6 +21,7 @@ pub async fn erp_product_inventory_router(state: AppState) -> OpenApiRouter {
         .routes(routes!(get_by_id))
         .routes(routes!(list))
         .routes(routes!(page))
+        .routes(routes!(export))
         .with_state(state)
 }
 
@@ -191,3 +192,28 @@ async fn list(
         Err(e) => {CommonResult::with_err(&e.to_string())}
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/export",
+    operation_id = "erp_product_inventory_export",
+    request_body(content = ExportErpProductInventoryRequest, description = "export", content_type = "application/json"),
+    responses(
+        (status = 200, description = "background job id", body = CommonResult<i64>)
+    ),
+    tag = "erp_product_inventory",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "erp_product_inventory_export", authorize = "")]
+async fn export(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<ExportErpProductInventoryRequest>,
+) -> CommonResult<i64> {
+    match service::erp_product_inventory::export(&state.db, login_user, payload).await {
+        Ok(id) => {CommonResult::with_data(id)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
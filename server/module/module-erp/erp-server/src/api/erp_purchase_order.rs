@@ -54,7 +54,7 @@ pub async fn erp_purchase_order_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_purchase_order_create", authorize = "")]
+#[require_authorize(operation_id = "erp_purchase_order_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -50,7 +50,7 @@ pub async fn erp_receipt_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_receipt_create", authorize = "")]
+#[require_authorize(operation_id = "erp_receipt_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
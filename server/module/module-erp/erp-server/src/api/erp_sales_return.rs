@@ -51,7 +51,7 @@ pub async fn erp_sales_return_route(state: AppState) -> Router {
         ("bearerAuth" = [])
     )
 )]
-#[require_authorize(operation_id = "erp_sales_return_create", authorize = "")]
+#[require_authorize(operation_id = "erp_sales_return_create", authorize = "", idempotent = true)]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -191,7 +191,7 @@ async fn list(
     match service::erp_warehouse::list(&state.db, login_user).await {
         Ok(data) => {CommonResult::with_data(data)}
         Err(e) => {
-            eprintln!("Error: {:#?}", e);
+            tracing::error!("list warehouse error, {:#?}", e);
             CommonResult::with_err(&e.to_string())
         }
     }
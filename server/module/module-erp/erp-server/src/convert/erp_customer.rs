@@ -1,28 +1,33 @@
 use sea_orm::{Set, NotSet};
+use anyhow::Result;
+use common::database::encrypted_string::EncryptedString;
+use common::formatter::sensitive;
+use common::utils::field_crypto;
 use crate::model::erp_customer::{self, Model as ErpCustomer, ActiveModel as ErpCustomerActiveModel};
 use erp_model::request::erp_customer::{CreateErpCustomerRequest, UpdateErpCustomerRequest};
 use erp_model::response::erp_customer::ErpCustomerResponse;
 
-pub fn create_request_to_model(request: &CreateErpCustomerRequest) -> ErpCustomerActiveModel {
-    ErpCustomerActiveModel {
+pub fn create_request_to_model(request: &CreateErpCustomerRequest) -> Result<ErpCustomerActiveModel> {
+    Ok(ErpCustomerActiveModel {
         name: Set(request.name.clone()),
         contact_person: request.contact_person.as_ref().map_or(NotSet, |contact_person| Set(Some(contact_person.clone()))),
-        phone: request.phone.as_ref().map_or(NotSet, |phone| Set(Some(phone.clone()))),
+        phone: request.phone.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |phone| Set(Some(phone))),
         email: request.email.as_ref().map_or(NotSet, |email| Set(Some(email.clone()))),
         address: request.address.as_ref().map_or(NotSet, |address| Set(Some(address.clone()))),
         status: Set(request.status.clone()),
         sort: Set(request.sort.clone()),
-        tax_id: request.tax_id.as_ref().map_or(NotSet, |tax_id| Set(Some(tax_id.clone()))),
+        tax_id: request.tax_id.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |tax_id| Set(Some(tax_id))),
+        tax_id_index: request.tax_id.as_ref().map_or(NotSet, |tax_id| Set(Some(field_crypto::blind_index(tax_id)))),
         tax_rate: request.tax_rate.as_ref().map_or(NotSet, |tax_rate| Set(Some(tax_rate.clone()))),
         bank_name: request.bank_name.as_ref().map_or(NotSet, |bank_name| Set(Some(bank_name.clone()))),
-        bank_account: request.bank_account.as_ref().map_or(NotSet, |bank_account| Set(Some(bank_account.clone()))),
+        bank_account: request.bank_account.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |bank_account| Set(Some(bank_account))),
         bank_address: request.bank_address.as_ref().map_or(NotSet, |bank_address| Set(Some(bank_address.clone()))),
         remarks: request.remarks.as_ref().map_or(NotSet, |remarks| Set(Some(remarks.clone()))),
         ..Default::default()
-    }
+    })
 }
 
-pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: ErpCustomer) -> ErpCustomerActiveModel {
+pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: ErpCustomer) -> Result<ErpCustomerActiveModel> {
     let mut active_model: ErpCustomerActiveModel = existing.into();
     if let Some(name) = &request.name {
         active_model.name = Set(name.clone());
@@ -30,8 +35,8 @@ pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: Erp
     if let Some(contact_person) = &request.contact_person { 
         active_model.contact_person = Set(Some(contact_person.clone()));
     }
-    if let Some(phone) = &request.phone { 
-        active_model.phone = Set(Some(phone.clone()));
+    if let Some(phone) = sensitive::Mobile::unmasked(&request.phone) { 
+        active_model.phone = Set(Some(EncryptedString::encrypt(phone)?));
     }
     if let Some(email) = &request.email { 
         active_model.email = Set(Some(email.clone()));
@@ -45,8 +50,9 @@ pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: Erp
     if let Some(sort) = &request.sort {
         active_model.sort = Set(sort.clone());
     }
-    if let Some(tax_id) = &request.tax_id { 
-        active_model.tax_id = Set(Some(tax_id.clone()));
+    if let Some(tax_id) = sensitive::IdNumber::unmasked(&request.tax_id) { 
+        active_model.tax_id = Set(Some(EncryptedString::encrypt(tax_id)?));
+        active_model.tax_id_index = Set(Some(field_crypto::blind_index(tax_id)));
     }
     if let Some(tax_rate) = &request.tax_rate { 
         active_model.tax_rate = Set(Some(tax_rate.clone()));
@@ -54,8 +60,8 @@ pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: Erp
     if let Some(bank_name) = &request.bank_name { 
         active_model.bank_name = Set(Some(bank_name.clone()));
     }
-    if let Some(bank_account) = &request.bank_account { 
-        active_model.bank_account = Set(Some(bank_account.clone()));
+    if let Some(bank_account) = sensitive::BankAccount::unmasked(&request.bank_account) { 
+        active_model.bank_account = Set(Some(EncryptedString::encrypt(bank_account)?));
     }
     if let Some(bank_address) = &request.bank_address { 
         active_model.bank_address = Set(Some(bank_address.clone()));
@@ -63,7 +69,7 @@ pub fn update_request_to_model(request: &UpdateErpCustomerRequest, existing: Erp
     if let Some(remarks) = &request.remarks { 
         active_model.remarks = Set(Some(remarks.clone()));
     }
-    active_model
+    Ok(active_model)
 }
 
 pub fn model_to_response(model: ErpCustomer) -> ErpCustomerResponse {
@@ -71,15 +77,15 @@ pub fn model_to_response(model: ErpCustomer) -> ErpCustomerResponse {
         id: model.id,
         name: model.name,
         contact_person: model.contact_person,
-        phone: model.phone,
+        phone: model.phone.map(String::from),
         email: model.email,
         address: model.address,
         status: model.status,
         sort: model.sort,
-        tax_id: model.tax_id,
+        tax_id: model.tax_id.map(String::from),
         tax_rate: model.tax_rate,
         bank_name: model.bank_name,
-        bank_account: model.bank_account,
+        bank_account: model.bank_account.map(String::from),
         bank_address: model.bank_address,
         remarks: model.remarks,
         creator: model.creator,
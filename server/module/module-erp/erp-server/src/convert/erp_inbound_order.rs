@@ -125,7 +125,7 @@ pub fn update_other_request_to_model(request: &UpdateErpInboundOrderOtherRequest
 pub fn model_to_response(model: ErpInboundOrder) -> ErpInboundOrderResponse {
     ErpInboundOrderResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -150,7 +150,7 @@ pub fn model_to_page_purchase_response(model: ErpInboundOrder, model_purchase: O
 
     ErpInboundOrderPagePurchaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -178,7 +178,7 @@ pub fn model_to_page_other_response(model: ErpInboundOrder, model_supplier: Opti
 
     ErpInboundOrderPageOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -202,7 +202,7 @@ pub fn model_to_page_other_response(model: ErpInboundOrder, model_supplier: Opti
 pub fn model_to_base_purchase_response(model: ErpInboundOrder, details: Vec<ErpInboundOrderDetailBasePurchaseResponse>, attachments: Vec<ErpInboundOrderAttachmentBaseResponse>) -> ErpInboundOrderBasePurchaseResponse {
     ErpInboundOrderBasePurchaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -222,7 +222,7 @@ pub fn model_to_info_purchase_response(model: ErpInboundOrder, model_settlement_
 
     ErpInboundOrderInfoPurchaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -242,7 +242,7 @@ pub fn model_to_info_purchase_response(model: ErpInboundOrder, model_settlement_
 pub fn model_to_base_other_response(model: ErpInboundOrder, details: Vec<ErpInboundOrderDetailBaseOtherResponse>, attachments: Vec<ErpInboundOrderAttachmentBaseResponse>) -> ErpInboundOrderBaseOtherResponse {
     ErpInboundOrderBaseOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
@@ -263,7 +263,7 @@ pub fn model_to_info_other_response(model: ErpInboundOrder, model_supplier: Opti
 
     ErpInboundOrderInfoOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_id: model.purchase_id,
         supplier_id: model.supplier_id,
         user_id: model.user_id,
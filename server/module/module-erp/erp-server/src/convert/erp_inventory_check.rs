@@ -27,7 +27,7 @@ pub fn update_request_to_model(request: &UpdateErpInventoryCheckRequest, existin
 pub fn model_to_response(model: ErpInventoryCheck) -> ErpInventoryCheckResponse {
     ErpInventoryCheckResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         check_date: model.check_date,
         remarks: model.remarks,
         department_code: model.department_code,
@@ -42,7 +42,7 @@ pub fn model_to_response(model: ErpInventoryCheck) -> ErpInventoryCheckResponse
 pub fn model_to_base_response(model: ErpInventoryCheck, details: Vec<ErpInventoryCheckDetailBaseResponse>, attachments: Vec<ErpInventoryCheckAttachmentBaseResponse>) -> ErpInventoryCheckBaseResponse {
     ErpInventoryCheckBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         check_date: model.check_date,
         remarks: model.remarks,
 
@@ -27,7 +27,7 @@ pub fn update_request_to_model(request: &UpdateErpInventoryTransferRequest, exis
 pub fn model_to_response(model: ErpInventoryTransfer) -> ErpInventoryTransferResponse {
     ErpInventoryTransferResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         transfer_date: model.transfer_date,
         remarks: model.remarks,
         department_code: model.department_code,
@@ -42,7 +42,7 @@ pub fn model_to_response(model: ErpInventoryTransfer) -> ErpInventoryTransferRes
 pub fn model_to_base_response(model: ErpInventoryTransfer, details: Vec<ErpInventoryTransferDetailBaseResponse>, attachments: Vec<ErpInventoryTransferAttachmentBaseResponse>) -> ErpInventoryTransferBaseResponse {
     ErpInventoryTransferBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         transfer_date: model.transfer_date,
         remarks: model.remarks,
 
@@ -121,7 +121,7 @@ pub fn update_other_request_to_model(request: &UpdateErpOutboundOrderOtherReques
 pub fn model_to_response(model: ErpOutboundOrder) -> ErpOutboundOrderResponse {
     ErpOutboundOrderResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -146,7 +146,7 @@ pub fn model_to_page_sales_response(model: ErpOutboundOrder, model_sales: Option
 
     ErpOutboundOrderPageSalesResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -174,7 +174,7 @@ pub fn model_to_page_other_response(model: ErpOutboundOrder, model_customer: Opt
 
     ErpOutboundOrderPageOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -198,7 +198,7 @@ pub fn model_to_page_other_response(model: ErpOutboundOrder, model_customer: Opt
 pub fn model_to_base_sales_response(model: ErpOutboundOrder, details: Vec<ErpOutboundOrderDetailBaseSalesResponse>, attachments: Vec<ErpOutboundOrderAttachmentBaseResponse>) -> ErpOutboundOrderBaseSalesResponse {
     ErpOutboundOrderBaseSalesResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -216,7 +216,7 @@ pub fn model_to_base_sales_response(model: ErpOutboundOrder, details: Vec<ErpOut
 pub fn model_to_base_other_response(model: ErpOutboundOrder, details: Vec<ErpOutboundOrderDetailBaseOtherResponse>, attachments: Vec<ErpOutboundOrderAttachmentBaseResponse>) -> ErpOutboundOrderBaseOtherResponse {
     ErpOutboundOrderBaseOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -236,7 +236,7 @@ pub fn model_to_info_sales_response(model: ErpOutboundOrder, model_settlement_ac
 
     ErpOutboundOrderInfoSalesResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -265,7 +265,7 @@ pub fn model_to_info_other_response(model: ErpOutboundOrder, model_customer: Opt
 
     ErpOutboundOrderInfoOtherResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sale_id: model.sale_id,
         customer_id: model.customer_id,
         user_id: model.user_id,
@@ -49,7 +49,7 @@ pub fn update_request_to_model(request: &UpdateErpPurchaseOrderRequest, existing
 pub fn model_to_response(model: ErpPurchaseOrder) -> ErpPurchaseOrderResponse {
     ErpPurchaseOrderResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         supplier_id: model.supplier_id,
         user_id: model.user_id,
         purchase_date: model.purchase_date,
@@ -74,7 +74,7 @@ pub fn model_to_page_response(model: ErpPurchaseOrder, model_supplier: Option<Er
 
     ErpPurchaseOrderPageResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         supplier_id: model.supplier_id,
         user_id: model.user_id,
         purchase_date: model.purchase_date,
@@ -99,7 +99,7 @@ pub fn model_to_page_response(model: ErpPurchaseOrder, model_supplier: Option<Er
 pub fn model_to_base_response(model: ErpPurchaseOrder, details: Vec<ErpPurchaseOrderDetailBaseResponse>, attachments: Vec<ErpPurchaseOrderAttachmentBaseResponse>) -> ErpPurchaseOrderBaseResponse {
     ErpPurchaseOrderBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         supplier_id: model.supplier_id,
         user_id: model.user_id,
         purchase_date: model.purchase_date,
@@ -122,7 +122,7 @@ pub fn model_to_info_response(model: ErpPurchaseOrder, model_supplier: Option<Er
 
     ErpPurchaseOrderInfoResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         supplier_id: model.supplier_id,
         user_id: model.user_id,
         purchase_date: model.purchase_date,
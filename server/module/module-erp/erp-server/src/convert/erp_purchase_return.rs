@@ -50,7 +50,7 @@ pub fn update_request_to_model(request: &UpdateErpPurchaseReturnRequest, existin
 pub fn model_to_response(model: ErpPurchaseReturn) -> ErpPurchaseReturnResponse {
     ErpPurchaseReturnResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_order_id: model.purchase_order_id,
         supplier_id: model.supplier_id,
         return_date: model.return_date,
@@ -76,7 +76,7 @@ pub fn model_to_page_response(model: ErpPurchaseReturn, model_purchase: Option<E
 
     ErpPurchaseReturnPageResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_order_id: model.purchase_order_id,
         supplier_id: model.supplier_id,
         return_date: model.return_date,
@@ -102,7 +102,7 @@ pub fn model_to_page_response(model: ErpPurchaseReturn, model_purchase: Option<E
 pub fn model_to_base_response(model: ErpPurchaseReturn, details: Vec<ErpPurchaseReturnDetailBaseResponse>, attachments: Vec<ErpPurchaseReturnAttachmentBaseResponse>) -> ErpPurchaseReturnBaseResponse {
     ErpPurchaseReturnBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_order_id: model.purchase_order_id,
         supplier_id: model.supplier_id,
         return_date: model.return_date,
@@ -123,7 +123,7 @@ pub fn model_to_info_response(model: ErpPurchaseReturn, model_settlement_account
 
     ErpPurchaseReturnInfoResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         purchase_order_id: model.purchase_order_id,
         supplier_id: model.supplier_id,
         return_date: model.return_date,
@@ -49,7 +49,7 @@ pub fn update_request_to_model(request: &UpdateErpSalesOrderRequest, existing: E
 pub fn model_to_response(model: ErpSalesOrder) -> ErpSalesOrderResponse {
     ErpSalesOrderResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         customer_id: model.customer_id,
         user_id: model.user_id,
         order_date: model.order_date,
@@ -74,7 +74,7 @@ pub fn model_to_page_response(model: ErpSalesOrder, model_customer: Option<ErpCu
 
     ErpSalesOrderPageResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         customer_id: model.customer_id,
         user_id: model.user_id,
         order_date: model.order_date,
@@ -99,7 +99,7 @@ pub fn model_to_page_response(model: ErpSalesOrder, model_customer: Option<ErpCu
 pub fn model_to_base_response(model: ErpSalesOrder, details: Vec<ErpSalesOrderDetailBaseResponse>, attachments: Vec<ErpSalesOrderAttachmentBaseResponse>) -> ErpSalesOrderBaseResponse {
     ErpSalesOrderBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         customer_id: model.customer_id,
         user_id: model.user_id,
         order_date: model.order_date,
@@ -121,7 +121,7 @@ pub fn model_to_info_response(model: ErpSalesOrder, model_customer: Option<ErpCu
 
     ErpSalesOrderInfoResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         customer_id: model.customer_id,
         user_id: model.user_id,
         order_date: model.order_date,
@@ -47,7 +47,7 @@ pub fn update_request_to_model(request: &UpdateErpSalesReturnRequest, existing:
 pub fn model_to_response(model: ErpSalesReturn) -> ErpSalesReturnResponse {
     ErpSalesReturnResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sales_order_id: model.sales_order_id,
         customer_id: model.customer_id,
         return_date: model.return_date,
@@ -73,7 +73,7 @@ pub fn model_to_page_response(model: ErpSalesReturn, model_sales: Option<ErpSale
 
     ErpSalesReturnPageResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sales_order_id: model.sales_order_id,
         customer_id: model.customer_id,
         return_date: model.return_date,
@@ -99,7 +99,7 @@ pub fn model_to_page_response(model: ErpSalesReturn, model_sales: Option<ErpSale
 pub fn model_to_base_response(model: ErpSalesReturn, details: Vec<ErpSalesReturnDetailBaseResponse>, attachments: Vec<ErpSalesReturnAttachmentBaseResponse>) -> ErpSalesReturnBaseResponse {
     ErpSalesReturnBaseResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sales_order_id: model.sales_order_id,
         customer_id: model.customer_id,
         return_date: model.return_date,
@@ -120,7 +120,7 @@ pub fn model_to_info_response(model: ErpSalesReturn, model_settlement_account: O
 
     ErpSalesReturnInfoResponse { 
         id: model.id,
-        order_number: model.order_number,
+        order_number: model.order_number.clone(),
         sales_order_id: model.sales_order_id,
         customer_id: model.customer_id,
         return_date: model.return_date,
@@ -1,28 +1,33 @@
 use sea_orm::{Set, NotSet};
+use anyhow::Result;
+use common::database::encrypted_string::EncryptedString;
+use common::formatter::sensitive;
+use common::utils::field_crypto;
 use crate::model::erp_supplier::{self, Model as ErpSupplier, ActiveModel as ErpSupplierActiveModel};
 use erp_model::request::erp_supplier::{CreateErpSupplierRequest, UpdateErpSupplierRequest};
 use erp_model::response::erp_supplier::ErpSupplierResponse;
 
-pub fn create_request_to_model(request: &CreateErpSupplierRequest) -> ErpSupplierActiveModel {
-    ErpSupplierActiveModel {
+pub fn create_request_to_model(request: &CreateErpSupplierRequest) -> Result<ErpSupplierActiveModel> {
+    Ok(ErpSupplierActiveModel {
         name: Set(request.name.clone()),
         contact_person: request.contact_person.as_ref().map_or(NotSet, |contact_person| Set(Some(contact_person.clone()))),
-        phone: request.phone.as_ref().map_or(NotSet, |phone| Set(Some(phone.clone()))),
+        phone: request.phone.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |phone| Set(Some(phone))),
         email: request.email.as_ref().map_or(NotSet, |email| Set(Some(email.clone()))),
         address: request.address.as_ref().map_or(NotSet, |address| Set(Some(address.clone()))),
         status: Set(request.status.clone()),
-        tax_id: request.tax_id.as_ref().map_or(NotSet, |tax_id| Set(Some(tax_id.clone()))),
+        tax_id: request.tax_id.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |tax_id| Set(Some(tax_id))),
+        tax_id_index: request.tax_id.as_ref().map_or(NotSet, |tax_id| Set(Some(field_crypto::blind_index(tax_id)))),
         tax_rate: request.tax_rate.as_ref().map_or(NotSet, |tax_rate| Set(Some(tax_rate.clone()))),
         bank_name: request.bank_name.as_ref().map_or(NotSet, |bank_name| Set(Some(bank_name.clone()))),
-        bank_account: request.bank_account.as_ref().map_or(NotSet, |bank_account| Set(Some(bank_account.clone()))),
+        bank_account: request.bank_account.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |bank_account| Set(Some(bank_account))),
         bank_address: request.bank_address.as_ref().map_or(NotSet, |bank_address| Set(Some(bank_address.clone()))),
         remarks: request.remarks.as_ref().map_or(NotSet, |remarks| Set(Some(remarks.clone()))),
         sort: Set(request.sort.clone()),
         ..Default::default()
-    }
+    })
 }
 
-pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: ErpSupplier) -> ErpSupplierActiveModel {
+pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: ErpSupplier) -> Result<ErpSupplierActiveModel> {
     let mut active_model: ErpSupplierActiveModel = existing.into();
     if let Some(name) = &request.name {
         active_model.name = Set(name.clone());
@@ -30,8 +35,8 @@ pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: Erp
     if let Some(contact_person) = &request.contact_person { 
         active_model.contact_person = Set(Some(contact_person.clone()));
     }
-    if let Some(phone) = &request.phone { 
-        active_model.phone = Set(Some(phone.clone()));
+    if let Some(phone) = sensitive::Mobile::unmasked(&request.phone) { 
+        active_model.phone = Set(Some(EncryptedString::encrypt(phone)?));
     }
     if let Some(email) = &request.email { 
         active_model.email = Set(Some(email.clone()));
@@ -42,8 +47,9 @@ pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: Erp
     if let Some(status) = &request.status { 
         active_model.status = Set(status.clone());
     }
-    if let Some(tax_id) = &request.tax_id { 
-        active_model.tax_id = Set(Some(tax_id.clone()));
+    if let Some(tax_id) = sensitive::IdNumber::unmasked(&request.tax_id) { 
+        active_model.tax_id = Set(Some(EncryptedString::encrypt(tax_id)?));
+        active_model.tax_id_index = Set(Some(field_crypto::blind_index(tax_id)));
     }
     if let Some(tax_rate) = &request.tax_rate { 
         active_model.tax_rate = Set(Some(tax_rate.clone()));
@@ -51,8 +57,8 @@ pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: Erp
     if let Some(bank_name) = &request.bank_name { 
         active_model.bank_name = Set(Some(bank_name.clone()));
     }
-    if let Some(bank_account) = &request.bank_account { 
-        active_model.bank_account = Set(Some(bank_account.clone()));
+    if let Some(bank_account) = sensitive::BankAccount::unmasked(&request.bank_account) { 
+        active_model.bank_account = Set(Some(EncryptedString::encrypt(bank_account)?));
     }
     if let Some(bank_address) = &request.bank_address { 
         active_model.bank_address = Set(Some(bank_address.clone()));
@@ -63,7 +69,7 @@ pub fn update_request_to_model(request: &UpdateErpSupplierRequest, existing: Erp
     if let Some(sort) = &request.sort {
         active_model.sort = Set(sort.clone());
     }
-    active_model
+    Ok(active_model)
 }
 
 pub fn model_to_response(model: ErpSupplier) -> ErpSupplierResponse {
@@ -71,14 +77,14 @@ pub fn model_to_response(model: ErpSupplier) -> ErpSupplierResponse {
         id: model.id,
         name: model.name,
         contact_person: model.contact_person,
-        phone: model.phone,
+        phone: model.phone.map(String::from),
         email: model.email,
         address: model.address,
         status: model.status,
-        tax_id: model.tax_id,
+        tax_id: model.tax_id.map(String::from),
         tax_rate: model.tax_rate,
         bank_name: model.bank_name,
-        bank_account: model.bank_account,
+        bank_account: model.bank_account.map(String::from),
         bank_address: model.bank_address,
         remarks: model.remarks,
         sort: model.sort,
@@ -87,4 +93,63 @@ pub fn model_to_response(model: ErpSupplier) -> ErpSupplierResponse {
         updater: model.updater,
         update_time: model.update_time,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use sea_orm::ActiveValue;
+
+    fn supplier() -> ErpSupplier {
+        ErpSupplier {
+            id: 1,
+            name: "供应商".to_string(),
+            contact_person: None,
+            phone: Some(EncryptedString::encrypt("13812345678").unwrap()),
+            email: None,
+            address: None,
+            status: 0,
+            tax_id: Some(EncryptedString::encrypt("91310000123456789X").unwrap()),
+            tax_id_index: Some("index".to_string()),
+            tax_rate: None,
+            bank_name: None,
+            bank_account: Some(EncryptedString::encrypt("6222021234567890").unwrap()),
+            bank_address: None,
+            remarks: None,
+            sort: 0,
+            creator: None,
+            create_time: NaiveDateTime::default(),
+            updater: None,
+            update_time: NaiveDateTime::default(),
+            deleted: false,
+            tenant_id: 1,
+        }
+    }
+
+    // 编辑页面把脱敏后的响应原样提交,敏感字段不能被脱敏值覆盖
+    #[test]
+    fn test_masked_response_round_trip() {
+        let existing = supplier();
+        let response = serde_json::to_value(model_to_response(existing.clone())).unwrap();
+        assert_eq!(response["phone"], "138****5678");
+        let request: UpdateErpSupplierRequest = serde_json::from_value(response).unwrap();
+
+        let active_model = update_request_to_model(&request, existing.clone()).unwrap();
+        assert_eq!(active_model.phone, ActiveValue::Unchanged(existing.phone.clone()));
+        assert_eq!(active_model.tax_id, ActiveValue::Unchanged(existing.tax_id.clone()));
+        assert_eq!(active_model.tax_id_index, ActiveValue::Unchanged(existing.tax_id_index.clone()));
+        assert_eq!(active_model.bank_account, ActiveValue::Unchanged(existing.bank_account.clone()));
+    }
+
+    #[test]
+    fn test_changed_sensitive_field() {
+        let existing = supplier();
+        let mut response = serde_json::to_value(model_to_response(existing.clone())).unwrap();
+        response["phone"] = "13900000000".into();
+        let request: UpdateErpSupplierRequest = serde_json::from_value(response).unwrap();
+
+        let active_model = update_request_to_model(&request, existing).unwrap();
+        assert_eq!(active_model.phone, ActiveValue::Set(Some(EncryptedString::encrypt("13900000000").unwrap())));
+    }
+}
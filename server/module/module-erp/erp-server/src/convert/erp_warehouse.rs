@@ -61,5 +61,6 @@ pub fn model_to_response(model: ErpWarehouse) -> ErpWarehouseResponse {
         create_time: model.create_time,
         updater: model.updater,
         update_time: model.update_time,
+        extend: Default::default(),
     }
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use anyhow::Result;
+use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryOrder};
+use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::task::background_job::{async_trait, JobArtifact, JobContext, JobHandler, JOB_TYPE_ERP_INVENTORY_EXPORT};
+use erp_model::request::erp_product_inventory::ExportErpProductInventoryRequest;
+use crate::model::erp_product::{Entity as ErpProductEntity, Column as ErpProductColumn};
+use crate::model::erp_product_inventory::{Entity as ErpProductInventoryEntity, Column};
+use crate::model::erp_warehouse::{Entity as ErpWarehouseEntity, Column as ErpWarehouseColumn};
+
+/// 每批读取的库存数量
+const PAGE_SIZE: u64 = 500;
+
+// 库存导出作业,导出为csv文件
+pub struct ErpInventoryExportJob;
+
+#[async_trait]
+impl JobHandler for ErpInventoryExportJob {
+    const JOB_TYPE: &'static str = JOB_TYPE_ERP_INVENTORY_EXPORT;
+    type Payload = ExportErpProductInventoryRequest;
+
+    async fn handle(&self, ctx: &JobContext, payload: Self::Payload) -> Result<Option<JobArtifact>> {
+        let db = ctx.db();
+        let products: HashMap<i64, String> = ErpProductEntity::find_active_with_condition(Condition::all().add(ErpProductColumn::TenantId.eq(ctx.tenant_id)))
+            .all(db).await?
+            .into_iter()
+            .map(|product| (product.id, product.name))
+            .collect();
+        let warehouses: HashMap<i64, String> = ErpWarehouseEntity::find_active_with_condition(Condition::all().add(ErpWarehouseColumn::TenantId.eq(ctx.tenant_id)))
+            .all(db).await?
+            .into_iter()
+            .map(|warehouse| (warehouse.id, warehouse.name))
+            .collect();
+
+        let mut condition = Condition::all().add(Column::TenantId.eq(ctx.tenant_id));
+        if let Some(warehouse_id) = payload.warehouse_id {
+            condition = condition.add(Column::WarehouseId.eq(warehouse_id));
+        }
+        let paginator = ErpProductInventoryEntity::find_active_with_condition(condition)
+            .order_by_asc(Column::Id)
+            .paginate(db, PAGE_SIZE);
+        let pages = paginator.num_pages().await?;
+
+        // 带BOM,excel打开时不乱码
+        let mut csv = String::from("\u{feff}产品ID,产品名称,仓库ID,仓库名称,库存数量\n");
+        for page in 0..pages {
+            ctx.check_cancelled()?;
+            for inventory in paginator.fetch_page(page).await? {
+                let product_name = products.get(&inventory.product_id).map(String::as_str).unwrap_or_default();
+                let warehouse_name = warehouses.get(&inventory.warehouse_id).map(String::as_str).unwrap_or_default();
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    inventory.product_id, csv_field(product_name), inventory.warehouse_id, csv_field(warehouse_name), inventory.stock_quantity
+                ));
+            }
+            ctx.set_progress(((page + 1) * 100 / pages) as i32, &format!("{}/{}", page + 1, pages)).await?;
+        }
+
+        Ok(Some(JobArtifact {
+            file_name: format!("inventory_{}.csv", chrono::Local::now().format("%Y%m%d%H%M%S")),
+            content_type: "text/csv".to_string(),
+            data: csv.into_bytes(),
+        }))
+    }
+}
+
+/// 包含逗号、引号或换行的字段加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1 @@
+pub mod erp_inventory_export_job;
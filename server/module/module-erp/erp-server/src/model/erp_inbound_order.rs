@@ -11,7 +11,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64, // 入库订单ID
 
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub purchase_id: Option<i64>, // 采购订单ID
     
@@ -10,7 +10,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64, // 盘点记录ID
     
-    pub order_number: i64, // 订单编号
+    pub order_number: String, // 订单编号
     
     pub check_date: NaiveDateTime, // 盘点日期
     
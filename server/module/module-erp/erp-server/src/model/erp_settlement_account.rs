@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use sea_orm::Condition;
 use sea_orm::entity::prelude::*;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::database::encrypted_string::EncryptedString;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "erp_settlement_account")]
@@ -14,7 +15,7 @@ pub struct Model {
     
     pub bank_name: Option<String>, // 开户行
     
-    pub bank_account: Option<String>, // 银行账号
+    pub bank_account: Option<EncryptedString>, // 银行账号
     
     pub status: i8, // 状态
     
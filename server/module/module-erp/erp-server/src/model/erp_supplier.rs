@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use sea_orm::Condition;
 use sea_orm::entity::prelude::*;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::database::encrypted_string::EncryptedString;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "erp_supplier")]
@@ -14,7 +15,7 @@ pub struct Model {
     
     pub contact_person: Option<String>, // 联系人
     
-    pub phone: Option<String>, // 电话
+    pub phone: Option<EncryptedString>, // 电话
     
     pub email: Option<String>, // 邮箱
     
@@ -22,13 +23,15 @@ pub struct Model {
     
     pub status: i8, // 状态
     
-    pub tax_id: Option<String>, // 纳税人识别号
+    pub tax_id: Option<EncryptedString>, // 纳税人识别号
+    
+    pub tax_id_index: Option<String>, // 纳税人识别号盲索引
     
     pub tax_rate: Option<i32>, // 税率,精确到万分位
     
     pub bank_name: Option<String>, // 开户行
     
-    pub bank_account: Option<String>, // 银行账号
+    pub bank_account: Option<EncryptedString>, // 银行账号
     
     pub bank_address: Option<String>, // 开户地址
     
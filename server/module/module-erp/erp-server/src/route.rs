@@ -5,6 +5,8 @@ use utoipa_axum::router::OpenApiRouter;
 use common::middleware::request_context::request_context_handler;
 use common::middleware::authorize::{authorize_handler, init_route_authorizes};
 use common::middleware::operation_logger::operation_logger_handler;
+use common::middleware::idempotency::idempotency_handler;
+use common::middleware::rate_limit::rate_limit_handler;
 use common::utils::jwt_utils::AccessClaims;
 use std::sync::Arc;
 use crate::api::erp_customer::{ erp_customer_route, erp_customer_router };
@@ -157,8 +159,10 @@ pub async fn auth_router(state: AppState) -> OpenApiRouter {
         .nest("/erp_settlement_account", erp_settlement_account_router(state.clone()).await)
         .nest("/erp_supplier", erp_supplier_router(state.clone()).await)
         .nest("/erp_warehouse", erp_warehouse_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
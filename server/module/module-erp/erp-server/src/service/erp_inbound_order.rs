@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_INBOUND_ORDER};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_inbound_order::{Model as ErpInboundOrderModel, ActiveModel as ErpInboundOrderActiveModel, Entity as ErpInboundOrderEntity, Column, Relation};
 use crate::model::erp_purchase_order::{Model as ErpPurchaseOrderModel, ActiveModel as ErpPurchaseOrderActiveModel, Entity as ErpPurchaseOrderEntity};
@@ -24,11 +24,7 @@ pub async fn create_purchase(db: &DatabaseConnection, login_user: LoginUserConte
     // 查询采购订单
     let purchase_order = erp_purchase_order::find_by_id(&db, login_user.clone(), request.purchase_id.clone()).await?;
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_inbound_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_inbound_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_INBOUND_ORDER).await?);
     
     erp_inbound_order.supplier_id = Set(purchase_order.supplier_id);
     erp_inbound_order.user_id = Set(login_user.id.clone());
@@ -57,11 +53,7 @@ pub async fn create_purchase(db: &DatabaseConnection, login_user: LoginUserConte
 pub async fn create_other(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpInboundOrderOtherRequest) -> Result<i64> {
     let mut erp_inbound_order = create_other_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_inbound_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_inbound_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_INBOUND_ORDER).await?);
     
     erp_inbound_order.user_id = Set(login_user.id.clone());
     erp_inbound_order.department_id = Set(login_user.department_id.clone());
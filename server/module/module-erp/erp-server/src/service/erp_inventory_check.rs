@@ -1,5 +1,5 @@
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_INVENTORY_CHECK};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait};
 use crate::model::erp_inventory_check::{Model as ErpInventoryCheckModel, ActiveModel as ErpInventoryCheckActiveModel, Entity as ErpInventoryCheckEntity, Column};
 use crate::service::{erp_inventory_check_attachment, erp_inventory_check_detail};
@@ -16,11 +16,7 @@ use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpInventoryCheckRequest) -> Result<i64> {
     let mut erp_inventory_check = create_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_inventory_check.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_inventory_check.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_INVENTORY_CHECK).await?);
 
     erp_inventory_check.department_id = Set(login_user.department_id.clone());
     erp_inventory_check.department_code = Set(login_user.department_code.clone());
@@ -1,5 +1,5 @@
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_INVENTORY_TRANSFER};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait};
 use crate::model::erp_inventory_transfer::{Model as ErpInventoryTransferModel, ActiveModel as ErpInventoryTransferActiveModel, Entity as ErpInventoryTransferEntity, Column};
 use crate::service::{erp_inventory_transfer_attachment, erp_inventory_transfer_detail};
@@ -16,11 +16,7 @@ use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpInventoryTransferRequest) -> Result<i64> {
     let mut erp_inventory_transfer = create_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_inventory_transfer.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_inventory_transfer.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_INVENTORY_TRANSFER).await?);
     erp_inventory_transfer.department_id = Set(login_user.department_id.clone());
     erp_inventory_transfer.department_code = Set(login_user.department_code.clone());
     erp_inventory_transfer.creator = Set(Some(login_user.id.clone()));
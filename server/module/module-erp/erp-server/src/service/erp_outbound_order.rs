@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_OUTBOUND_ORDER};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_outbound_order::{Model as ErpOutboundOrderModel, ActiveModel as ErpOutboundOrderActiveModel, Entity as ErpOutboundOrderEntity, Column, Relation};
 use crate::model::erp_sales_order::{Model as ErpSalesOrderModel, ActiveModel as ErpSalesOrderActiveModel, Entity as ErpSalesOrderEntity};
@@ -23,11 +23,7 @@ pub async fn create_sale(db: &DatabaseConnection, login_user: LoginUserContext,
     // 查询采购订单
     let sale_order = erp_sales_order::find_by_id(&db, login_user.clone(), request.sale_id.clone()).await?;
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_outbound_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_outbound_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_OUTBOUND_ORDER).await?);
     
     erp_outbound_order.customer_id = Set(sale_order.customer_id);
     erp_outbound_order.user_id = Set(login_user.id.clone());
@@ -55,11 +51,7 @@ pub async fn create_sale(db: &DatabaseConnection, login_user: LoginUserContext,
 pub async fn create_other(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpOutboundOrderOtherRequest) -> Result<i64> {
     let mut erp_outbound_order = create_other_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_outbound_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_outbound_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_OUTBOUND_ORDER).await?);
     
     erp_outbound_order.user_id = Set(login_user.id.clone());
     erp_outbound_order.department_id = Set(login_user.department_id.clone());
@@ -8,7 +8,7 @@ use crate::model::erp_product::{Model as ErpProductModel, ActiveModel as ErpProd
 use crate::model::erp_warehouse::{Model as ErpWarehouseModel, ActiveModel as ErpWarehouseActiveModel, Entity as ErpWarehouseEntity};
 use crate::model::erp_product_unit::{Model as ErpProductUnitModel, ActiveModel as ErpProductUnitActiveModel, Entity as ErpProductUnitEntity};
 use crate::service::erp_product_unit;
-use erp_model::request::erp_product_inventory::{CreateErpProductInventoryRequest, ErpProductInventoryInOutRequest, PaginatedKeywordRequest, UpdateErpProductInventoryRequest};
+use erp_model::request::erp_product_inventory::{CreateErpProductInventoryRequest, ErpProductInventoryInOutRequest, ExportErpProductInventoryRequest, PaginatedKeywordRequest, UpdateErpProductInventoryRequest};
 use erp_model::response::erp_product_inventory::{ErpProductInventoryPageResponse, ErpProductInventoryResponse};
 use crate::convert::erp_product_inventory::{create_request_to_model, model_to_page_response, model_to_response, update_request_to_model};
 use anyhow::{Result, anyhow};
@@ -17,6 +17,10 @@ use common::constants::enum_constants::{STATUS_DISABLE, STATUS_ENABLE};
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::event::domain_event::InventoryChangedEvent;
+use common::event::outbox;
+use common::task::background_job;
+use crate::job::erp_inventory_export_job::ErpInventoryExportJob;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpProductInventoryRequest) -> Result<i64> {
     let mut erp_product_inventory = create_request_to_model(&request);
@@ -105,6 +109,11 @@ pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Resu
     Ok(list.into_iter().map(model_to_response).collect())
 }
 
+/// 提交库存导出作业,返回作业id,完成后通过后台作业接口下载
+pub async fn export(db: &DatabaseConnection, login_user: LoginUserContext, request: ExportErpProductInventoryRequest) -> Result<i64> {
+    background_job::submit::<ErpInventoryExportJob, _>(db, &login_user, &request, 0).await
+}
+
 /// 入库加库存
 pub async fn inbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_user: LoginUserContext, requests: Vec<ErpProductInventoryInOutRequest>) -> Result<()> {
     if requests.is_empty() {
@@ -117,6 +126,7 @@ pub async fn inbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_u
             .filter(Column::ProductId.eq(request.product_id))
             .filter(Column::WarehouseId.eq(request.warehouse_id))
             .filter(Column::TenantId.eq(login_user.tenant_id))
+            .lock_exclusive() // 行锁,事务提交前其他出入库等待
             .one(txn)
             .await?;
 
@@ -127,6 +137,7 @@ pub async fn inbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_u
                 active_model.stock_quantity = Set(inventory.stock_quantity + request.quantity);
                 active_model.updater = Set(Some(login_user.id));
                 active_model.update(txn).await?;
+                publish_changed(txn, &login_user, &request, request.quantity, inventory.stock_quantity + request.quantity).await?;
             }
             None => {
                 // 记录不存在，插入新记录
@@ -140,6 +151,7 @@ pub async fn inbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_u
                     ..Default::default()
                 };
                 new_inventory.insert(txn).await?;
+                publish_changed(txn, &login_user, &request, request.quantity, request.quantity).await?;
             }
         }
     }
@@ -159,6 +171,7 @@ pub async fn outbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_
             .filter(Column::ProductId.eq(request.product_id))
             .filter(Column::WarehouseId.eq(request.warehouse_id))
             .filter(Column::TenantId.eq(login_user.tenant_id))
+            .lock_exclusive() // 行锁,事务提交前其他出入库等待
             .one(txn)
             .await?;
 
@@ -173,6 +186,7 @@ pub async fn outbound(db: &DatabaseConnection, txn: &DatabaseTransaction, login_
                 active_model.stock_quantity = Set(inventory.stock_quantity - request.quantity);
                 active_model.updater = Set(Some(login_user.id));
                 active_model.update(txn).await?;
+                publish_changed(txn, &login_user, &request, -request.quantity, inventory.stock_quantity - request.quantity).await?;
             }
             None => {
                 // 记录不存在,报错
@@ -196,6 +210,7 @@ pub async fn set(db: &DatabaseConnection, txn: &DatabaseTransaction, login_user:
             .filter(Column::ProductId.eq(request.product_id))
             .filter(Column::WarehouseId.eq(request.warehouse_id))
             .filter(Column::TenantId.eq(login_user.tenant_id))
+            .lock_exclusive() // 行锁,事务提交前其他出入库等待
             .one(txn)
             .await?;
 
@@ -206,6 +221,7 @@ pub async fn set(db: &DatabaseConnection, txn: &DatabaseTransaction, login_user:
                 active_model.stock_quantity = Set(request.quantity);
                 active_model.updater = Set(Some(login_user.id));
                 active_model.update(txn).await?;
+                publish_changed(txn, &login_user, &request, request.quantity - inventory.stock_quantity, request.quantity).await?;
             }
             None => {
                 // 记录不存在，插入新记录
@@ -219,9 +235,22 @@ pub async fn set(db: &DatabaseConnection, txn: &DatabaseTransaction, login_user:
                     ..Default::default()
                 };
                 new_inventory.insert(txn).await?;
+                publish_changed(txn, &login_user, &request, request.quantity, request.quantity).await?;
             }
         }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 在出入库事务中写入库存变动事件
+async fn publish_changed(txn: &DatabaseTransaction, login_user: &LoginUserContext, request: &ErpProductInventoryInOutRequest, change_quantity: i32, stock_quantity: i32) -> Result<()> {
+    let event = InventoryChangedEvent {
+        product_id: request.product_id,
+        warehouse_id: request.warehouse_id,
+        change_quantity,
+        stock_quantity,
+        operator: login_user.id,
+    };
+    outbox::publish(txn, login_user.tenant_id, &event).await
+}
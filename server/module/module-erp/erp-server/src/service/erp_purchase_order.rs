@@ -1,5 +1,5 @@
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_PURCHASE_ORDER};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use tracing::info;
 use crate::model::erp_purchase_order::{Model as ErpPurchaseOrderModel, ActiveModel as ErpPurchaseOrderActiveModel, Entity as ErpPurchaseOrderEntity, Column, Relation};
@@ -15,15 +15,13 @@ use common::constants::enum_constants::{PURCHASE_ORDER_STATUS_CANCEL, PURCHASE_O
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::event::domain_event::OrderCompletedEvent;
+use common::event::outbox;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpPurchaseOrderRequest) -> Result<i64> {
     let mut erp_purchase_order = create_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_purchase_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_purchase_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_PURCHASE_ORDER).await?);
     
     erp_purchase_order.user_id = Set(login_user.id.clone());
     erp_purchase_order.order_status = Set(PURCHASE_ORDER_STATUS_PLACED);
@@ -247,6 +245,8 @@ pub async fn completed(db: &DatabaseConnection, txn: &DatabaseTransaction, login
         ..Default::default()
     };
     erp_purchase_order.update(txn).await?;
+    let event = OrderCompletedEvent { order_type: DOCUMENT_TYPE_PURCHASE_ORDER.to_string(), order_id: id, operator: login_user.id };
+    outbox::publish(txn, login_user.tenant_id, &event).await?;
     Ok(())
 }
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_PURCHASE_RETURN};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, LoaderTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_purchase_return::{Model as ErpPurchaseReturnModel, ActiveModel as ErpPurchaseReturnActiveModel, Entity as ErpPurchaseReturnEntity, Column, Relation};
 use crate::model::erp_purchase_order::{Model as ErpPurchaseOrderModel, ActiveModel as ErpPurchaseOrderActiveModel, Entity as ErpPurchaseOrderEntity};
@@ -23,11 +23,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     // 查询采购订单
     let purchase_order = erp_purchase_order::find_by_id(&db, login_user.clone(), request.purchase_order_id.clone()).await?;
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_purchase_return.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_purchase_return.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_PURCHASE_RETURN).await?);
     
     erp_purchase_return.supplier_id = Set(purchase_order.supplier_id);
     erp_purchase_return.order_status = Set(PURCHASE_RETURN_STATUS_PLACED);
@@ -1,5 +1,5 @@
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_RECEIPT};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_receipt::{Model as ErpReceiptModel, ActiveModel as ErpReceiptActiveModel, Entity as ErpReceiptEntity, Column, Relation};
 use crate::model::erp_customer::{Model as ErpCustomerModel, ActiveModel as ErpCustomerActiveModel, Entity as ErpCustomerEntity};
@@ -18,11 +18,7 @@ use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpReceiptRequest) -> Result<i64> {
     let mut erp_receipt = create_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_receipt.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_receipt.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_RECEIPT).await?);
     erp_receipt.user_id = Set(login_user.id.clone());
     erp_receipt.department_id = Set(login_user.department_id.clone());
     erp_receipt.department_code = Set(login_user.department_code.clone());
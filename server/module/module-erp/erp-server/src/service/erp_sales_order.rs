@@ -1,5 +1,5 @@
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_SALES_ORDER};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_sales_order::{Model as ErpSalesOrderModel, ActiveModel as ErpSalesOrderActiveModel, Entity as ErpSalesOrderEntity, Column, Relation};
 use crate::model::erp_customer::{Model as ErpCustomerModel, ActiveModel as ErpCustomerActiveModel, Entity as ErpCustomerEntity};
@@ -16,15 +16,13 @@ use common::constants::enum_constants::{SALE_ORDER_STATUS_AWAITING_SIGNATURE, SA
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::event::domain_event::OrderCompletedEvent;
+use common::event::outbox;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpSalesOrderRequest) -> Result<i64> {
     let mut erp_sales_order = create_request_to_model(&request);
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_sales_order.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_sales_order.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_SALES_ORDER).await?);
     
     erp_sales_order.user_id = Set(login_user.id.clone());
     erp_sales_order.order_status = Set(SALE_ORDER_STATUS_PLACED);
@@ -263,7 +261,13 @@ pub async fn completed(db: &DatabaseConnection, login_user: LoginUserContext, id
         order_status: Set(SALE_ORDER_STATUS_COMPLETE),
         ..Default::default()
     };
-    erp_sales_order.update(db).await?;
+    let event = OrderCompletedEvent { order_type: DOCUMENT_TYPE_SALES_ORDER.to_string(), order_id: id, operator: login_user.id };
+    // 开启事务
+    let txn = db.begin().await?;
+    erp_sales_order.update(&txn).await?;
+    outbox::publish(&txn, login_user.tenant_id, &event).await?;
+    // 提交事务
+    txn.commit().await.with_context(|| "Failed to commit transaction")?;
     Ok(())
 }
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use common::interceptor::orm::simple_support::SimpleSupport;
-use common::utils::snowflake_generator::SnowflakeGenerator;
+use common::utils::document_number::{self, DOCUMENT_TYPE_SALES_RETURN};
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
 use crate::model::erp_sales_return::{Model as ErpSalesReturnModel, ActiveModel as ErpSalesReturnActiveModel, Entity as ErpSalesReturnEntity, Column, Relation};
 use crate::model::erp_sales_order::{Model as ErpSalesOrderModel, ActiveModel as ErpSalesOrderActiveModel, Entity as ErpSalesOrderEntity};
@@ -23,11 +23,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     // 查询销售订单
     let sales_order = erp_sales_order::find_by_id(&db, login_user.clone(), request.sales_order_id.clone()).await?;
     // 生成订单编号
-    let generator = SnowflakeGenerator::new();
-    match generator.generate() {
-        Ok(id) => erp_sales_return.order_number = Set(id),
-        Err(e) => return Err(anyhow!("订单编号生成失败")),
-    }
+    erp_sales_return.order_number = Set(document_number::generate(db, login_user.tenant_id, DOCUMENT_TYPE_SALES_RETURN).await?);
 
     erp_sales_return.customer_id = Set(sales_order.customer_id);
     erp_sales_return.order_status = Set(SALE_RETURN_STATUS_PLACED);
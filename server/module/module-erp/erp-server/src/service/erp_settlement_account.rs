@@ -12,7 +12,7 @@ use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpSettlementAccountRequest) -> Result<i64> {
-    let mut erp_settlement_account = create_request_to_model(&request);
+    let mut erp_settlement_account = create_request_to_model(&request)?;
     erp_settlement_account.creator = Set(Some(login_user.id));
     erp_settlement_account.updater = Set(Some(login_user.id));
     erp_settlement_account.tenant_id = Set(login_user.tenant_id);
@@ -27,7 +27,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
         .await?
         .ok_or_else(|| anyhow!("记录未找到"))?;
 
-    let mut erp_settlement_account = update_request_to_model(&request, erp_settlement_account);
+    let mut erp_settlement_account = update_request_to_model(&request, erp_settlement_account)?;
     erp_settlement_account.updater = Set(Some(login_user.id));
     erp_settlement_account.update(db).await?;
     Ok(())
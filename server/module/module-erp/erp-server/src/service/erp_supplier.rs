@@ -10,9 +10,10 @@ use common::constants::enum_constants::{STATUS_DISABLE, STATUS_ENABLE};
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::utils::field_crypto;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpSupplierRequest) -> Result<i64> {
-    let mut erp_supplier = create_request_to_model(&request);
+    let mut erp_supplier = create_request_to_model(&request)?;
     erp_supplier.creator = Set(Some(login_user.id));
     erp_supplier.updater = Set(Some(login_user.id));
     erp_supplier.tenant_id = Set(login_user.tenant_id);
@@ -27,7 +28,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
         .await?
         .ok_or_else(|| anyhow!("记录未找到"))?;
 
-    let mut erp_supplier = update_request_to_model(&request, erp_supplier);
+    let mut erp_supplier = update_request_to_model(&request, erp_supplier)?;
     erp_supplier.updater = Set(Some(login_user.id));
     erp_supplier.update(db).await?;
     Ok(())
@@ -55,7 +56,14 @@ pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id
 }
 
 pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedKeywordRequest) -> Result<PaginatedResponse<ErpSupplierResponse>> {
-    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));let paginator = ErpSupplierEntity::find_active_with_condition(condition)
+    let mut condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));
+    // 关键字模糊匹配名称,纳税人识别号加密保存,按盲索引精确匹配
+    if let Some(keyword) = params.keyword.filter(|keyword| !keyword.is_empty()) {
+        condition = condition.add(Condition::any()
+            .add(Column::Name.contains(&keyword))
+            .add(Column::TaxIdIndex.eq(field_crypto::blind_index(&keyword))));
+    }
+    let paginator = ErpSupplierEntity::find_active_with_condition(condition)
         .support_filter(params.base.filter_field, params.base.filter_operator, params.base.filter_value)
         .support_order(params.base.sort_field, params.base.sort, Some(vec![(Column::Sort, Order::Asc)]))
         .paginate(db, params.base.size);
@@ -9,6 +9,7 @@ use sea_orm::ActiveValue::Set;
 use common::constants::enum_constants::{STATUS_DISABLE, STATUS_ENABLE};
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
+use common::formatter::extend_field;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateErpWarehouseRequest) -> Result<i64> {
@@ -51,7 +52,11 @@ pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id
             
     let erp_warehouse = ErpWarehouseEntity::find_active_with_condition(condition)
         .one(db).await?;
-    Ok(erp_warehouse.map(model_to_response))
+    let mut erp_warehouse = erp_warehouse.map(model_to_response);
+    if let Some(erp_warehouse) = erp_warehouse.as_mut() {
+        extend_field::enrich_one(erp_warehouse).await;
+    }
+    Ok(erp_warehouse)
 }
 
 pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedKeywordRequest) -> Result<PaginatedResponse<ErpWarehouseResponse>> {
@@ -63,12 +68,14 @@ pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext
 
     let total = paginator.num_items().await?;
     let total_pages = (total + params.base.size - 1) / params.base.size; // 向上取整
-    let list = paginator
+    let mut list: Vec<ErpWarehouseResponse> = paginator
         .fetch_page(params.base.page - 1) // SeaORM 页码从 0 开始，所以减 1
         .await?
         .into_iter()
         .map(model_to_response)
         .collect();
+    // 整页一次获取创建者和更新者名称
+    extend_field::enrich(&mut list).await;
 
     Ok(PaginatedResponse {
         list,
@@ -82,7 +89,9 @@ pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext
 pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<ErpWarehouseResponse>> {
     let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));let list = ErpWarehouseEntity::find_active_with_condition(condition)
         .all(db).await?;
-    Ok(list.into_iter().map(model_to_response).collect())
+    let mut list: Vec<ErpWarehouseResponse> = list.into_iter().map(model_to_response).collect();
+    extend_field::enrich(&mut list).await;
+    Ok(list)
 }
 
 pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
@@ -0,0 +1,21 @@
+use crate::model::system_background_job::Model as SystemBackgroundJob;
+use file_model::response::system_background_job::SystemBackgroundJobResponse;
+
+pub fn model_to_response(model: SystemBackgroundJob) -> SystemBackgroundJobResponse {
+    SystemBackgroundJobResponse { 
+        id: model.id,
+        job_type: model.job_type,
+        priority: model.priority,
+        status: model.status,
+        progress: model.progress,
+        progress_message: model.progress_message,
+        attempts: model.attempts,
+        cancel_requested: model.cancel_requested,
+        start_time: model.start_time,
+        finish_time: model.finish_time,
+        artifact_file_id: model.artifact_file_id,
+        error: model.error,
+        creator: model.creator,
+        create_time: model.create_time,
+    }
+}
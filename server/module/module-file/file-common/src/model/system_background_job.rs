@@ -0,0 +1,2 @@
+/// 后台作业表由 common 的作业队列使用,实体定义在 common 中
+pub use common::model::system_background_job::*;
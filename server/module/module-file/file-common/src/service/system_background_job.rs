@@ -0,0 +1,98 @@
+use common::constants::enum_constants::BACKGROUND_JOB_STATUS_SUCCESS;
+use common::task::background_job::{self, JOB_TYPES};
+use common::utils::minio_utils::MinioClient;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use crate::model::system_background_job::{Model as SystemBackgroundJobModel, Entity as SystemBackgroundJobEntity, Column};
+use crate::service::system_file;
+use file_model::request::system_background_job::{SubmitSystemBackgroundJobRequest, PaginatedBackgroundJobRequest};
+use file_model::response::system_background_job::SystemBackgroundJobResponse;
+use file_model::response::system_file::SystemFileDataResponse;
+use crate::convert::system_background_job::model_to_response;
+use anyhow::{Result, anyhow};
+use common::base::page::PaginatedResponse;
+use common::context::context::LoginUserContext;
+
+/// 提交作业,由注册了该作业类型的服务执行
+pub async fn submit(db: &DatabaseConnection, login_user: LoginUserContext, request: SubmitSystemBackgroundJobRequest) -> Result<i64> {
+    if !JOB_TYPES.contains(&request.job_type.as_str()) {
+        return Err(anyhow!("作业类型不存在"));
+    }
+    let payload = serde_json::to_string(&request.payload)?;
+    background_job::submit_raw(db, &login_user, &request.job_type, payload, request.priority.unwrap_or(0)).await
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<Option<SystemBackgroundJobResponse>> {
+    let system_background_job = SystemBackgroundJobEntity::find()
+        .filter(own_condition(&login_user).add(Column::Id.eq(id)))
+        .one(db).await?;
+    Ok(system_background_job.map(model_to_response))
+}
+
+/// 当前用户提交的作业
+pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedBackgroundJobRequest) -> Result<PaginatedResponse<SystemBackgroundJobResponse>> {
+    let mut condition = own_condition(&login_user);
+    if let Some(job_type) = &params.job_type {
+        if !job_type.is_empty() {
+            condition = condition.add(Column::JobType.eq(job_type.clone()));
+        }
+    }
+    if let Some(status) = params.status {
+        condition = condition.add(Column::Status.eq(status));
+    }
+
+    let paginator = SystemBackgroundJobEntity::find()
+        .filter(condition)
+        .order_by_desc(Column::Id)
+        .paginate(db, params.base.size);
+
+    let total = paginator.num_items().await?;
+    let total_pages = (total + params.base.size - 1) / params.base.size; // 向上取整
+    let list = paginator
+        .fetch_page(params.base.page - 1) // SeaORM 页码从 0 开始，所以减 1
+        .await?
+        .into_iter()
+        .map(model_to_response)
+        .collect();
+
+    Ok(PaginatedResponse {
+        list,
+        total_pages,
+        page: params.base.page,
+        size: params.base.size,
+        total,
+    })
+}
+
+/// 取消作业,执行中的作业在处理器检查到取消后停止
+pub async fn cancel(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    find_by_id(db, &login_user, id).await?;
+    if !background_job::cancel(db, login_user.tenant_id, id).await? {
+        return Err(anyhow!("作业已结束"));
+    }
+    Ok(())
+}
+
+/// 下载作业结果文件
+pub async fn get_artifact_data(db: &DatabaseConnection, login_user: LoginUserContext, minio: Option<MinioClient>, id: i64) -> Result<Option<SystemFileDataResponse>> {
+    let system_background_job = find_by_id(db, &login_user, id).await?;
+    if system_background_job.status != BACKGROUND_JOB_STATUS_SUCCESS {
+        return Err(anyhow!("作业未完成"));
+    }
+    let file_id = system_background_job.artifact_file_id.ok_or_else(|| anyhow!("作业没有结果文件"))?;
+    system_file::get_file_data(db, login_user, minio, file_id).await
+}
+
+/// 只能查看和操作自己提交的作业
+fn own_condition(login_user: &LoginUserContext) -> Condition {
+    Condition::all()
+        .add(Column::TenantId.eq(login_user.tenant_id))
+        .add(Column::Creator.eq(login_user.id))
+}
+
+async fn find_by_id(db: &DatabaseConnection, login_user: &LoginUserContext, id: i64) -> Result<SystemBackgroundJobModel> {
+    SystemBackgroundJobEntity::find()
+        .filter(own_condition(login_user).add(Column::Id.eq(id)))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))
+}
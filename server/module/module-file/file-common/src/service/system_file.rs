@@ -8,6 +8,8 @@ use file_model::response::system_file::{SystemFileDataResponse, SystemFileRespon
 use crate::convert::system_file::{create_request_to_model, model_to_data_response, model_to_response, update_request_to_model};
 use anyhow::{anyhow, Context, Ok, Result};
 use sea_orm::ActiveValue::Set;
+use common::config::config::Config;
+use common::config::system_config::{self, CONFIG_KEY_UPLOAD_MAX_SIZE};
 use common::constants::enum_constants::{ROOT_TENANT_ID, STATUS_DISABLE, STATUS_ENABLE};
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
@@ -131,6 +133,16 @@ pub async fn list_by_ids(db: &DatabaseConnection, login_user: LoginUserContext,
     Ok(list)
 }
 
+/// 校验上传文件大小,优先使用租户的系统参数,不能超过配置文件中的上限
+fn check_file_size(tenant_id: i64, size: usize) -> Result<()> {
+    let limit = Config::load().file_server.upload_max_size;
+    let max_size = system_config::get_or(tenant_id, CONFIG_KEY_UPLOAD_MAX_SIZE, limit).min(limit);
+    if size > max_size {
+        return Err(anyhow!("文件大小不能超过{}MB", max_size / 1024 / 1024));
+    }
+    Ok(())
+}
+
 pub async fn upload(db: &DatabaseConnection, login_user: LoginUserContext, minio: Option<MinioClient>, mut multipart: Multipart) -> Result<Option<i64>> {
     if minio.is_none() {
         return Err(anyhow!("客户端初始化失败"));
@@ -142,6 +154,7 @@ pub async fn upload(db: &DatabaseConnection, login_user: LoginUserContext, minio
             let file_name = field.file_name().unwrap_or("unnamed").to_string();
             let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
             let data = field.bytes().await.context("文件读取失败")?;
+            check_file_size(login_user.tenant_id, data.len())?;
             
             // Upload to MinIO
             let path = match minio.upload_file(&file_name, &data).await {
@@ -184,6 +197,7 @@ pub async fn upload_for_path(db: &DatabaseConnection, login_user: LoginUserConte
             let file_name = field.file_name().unwrap_or("unnamed").to_string();
             let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
             let data = field.bytes().await.context("文件读取失败")?;
+            check_file_size(login_user.tenant_id, data.len())?;
 
             // Upload to MinIO
             let path = match minio.upload_file(&file_name, &data).await {
@@ -226,6 +240,7 @@ pub async fn upload_oss(minio: Option<MinioClient>, mut multipart: Multipart) ->
         if field.name() == Some("file") {
             let file_name = field.file_name().unwrap_or("unnamed").to_string();
             let data = field.bytes().await.context("文件读取失败")?;
+            check_file_size(ROOT_TENANT_ID, data.len())?;
 
             // Upload to MinIO
             let path = match minio.upload_file(&file_name, &data).await {
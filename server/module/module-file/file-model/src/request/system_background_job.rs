@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use common::base::page::PaginatedRequest;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SubmitSystemBackgroundJobRequest {
+    
+    pub job_type: String, // 作业类型
+    
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value, // 作业参数
+    
+    pub priority: Option<i32>, // 优先级,大的先执行
+    
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PaginatedBackgroundJobRequest {
+    #[serde(flatten)]
+    pub base: PaginatedRequest,
+    pub job_type: Option<String>, // 作业类型
+    pub status: Option<i8>, // 状态（0等待执行 1执行中 2成功 3失败 4已取消）
+}
@@ -0,0 +1,44 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use serde_with::serde_as;
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SystemBackgroundJobResponse {
+    
+    pub id: i64, // 作业id
+    
+    pub job_type: String, // 作业类型
+    
+    pub priority: i32, // 优先级
+    
+    pub status: i8, // 状态（0等待执行 1执行中 2成功 3失败 4已取消）
+    
+    pub progress: i32, // 进度（0-100）
+    
+    pub progress_message: Option<String>, // 进度说明
+    
+    pub attempts: i32, // 已执行次数
+    
+    pub cancel_requested: bool, // 是否请求取消
+    
+    #[serde_as(as = "Option<common::formatter::string_date_time::StringDateTime>")]
+    #[schema(value_type = String, format = Date)]
+    pub start_time: Option<NaiveDateTime>, // 开始时间
+    
+    #[serde_as(as = "Option<common::formatter::string_date_time::StringDateTime>")]
+    #[schema(value_type = String, format = Date)]
+    pub finish_time: Option<NaiveDateTime>, // 结束时间
+    
+    pub artifact_file_id: Option<i64>, // 结果文件id
+    
+    pub error: Option<String>, // 错误信息
+    
+    pub creator: Option<i64>, // 创建者id
+    
+    #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
+    #[schema(value_type = String, format = Date)]
+    pub create_time: NaiveDateTime, // 创建时间
+    
+}
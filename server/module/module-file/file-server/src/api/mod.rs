@@ -1 +1,2 @@
+pub mod system_background_job;
 pub mod system_file;
\ No newline at end of file
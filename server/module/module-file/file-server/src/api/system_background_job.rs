@@ -0,0 +1,196 @@
+use std::sync::Arc;
+use file_common::service;
+use sea_orm::DatabaseConnection;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use ctor;
+use macros::require_authorize;
+use axum::{extract::{Json, Path, Query, State}, http::StatusCode, response::IntoResponse, routing::{get, post}, Extension, Router};
+use common::base::page::PaginatedResponse;
+use file_model::request::system_background_job::{SubmitSystemBackgroundJobRequest, PaginatedBackgroundJobRequest};
+use file_model::response::system_background_job::SystemBackgroundJobResponse;
+use common::base::response::CommonResult;
+use common::context::context::LoginUserContext;
+use common::state::app_state::AppState;
+
+pub async fn system_background_job_router(state: AppState) -> OpenApiRouter {
+    OpenApiRouter::new()
+        .routes(routes!(submit))
+        .routes(routes!(get_by_id))
+        .routes(routes!(page))
+        .routes(routes!(cancel))
+        .routes(routes!(download))
+        .with_state(state)
+}
+
+pub async fn system_background_job_route(state: AppState) -> Router {
+    Router::new()
+        .route("/submit", post(submit))
+        .route("/get/{id}", get(get_by_id))
+        .route("/page", get(page))
+        .route("/cancel/{id}", post(cancel))
+        .route("/download/{id}", get(download))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    post,
+    path = "/submit",
+    operation_id = "system_background_job_submit",
+    request_body(content = SubmitSystemBackgroundJobRequest, description = "submit", content_type = "application/json"),
+    responses(
+        (status = 200, description = "id", body = CommonResult<i64>)
+    ),
+    tag = "system_background_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_background_job_submit", authorize = "")]
+async fn submit(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<SubmitSystemBackgroundJobRequest>,
+) -> CommonResult<i64> {
+    match service::system_background_job::submit(&state.db, login_user, payload).await {
+        Ok(id) => {CommonResult::with_data(id)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/get/{id}",
+    operation_id = "system_background_job_get_by_id",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 200, description = "get by id", body = CommonResult<SystemBackgroundJobResponse>)
+    ),
+    tag = "system_background_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_background_job_get_by_id", authorize = "")]
+async fn get_by_id(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<SystemBackgroundJobResponse> {
+    match service::system_background_job::get_by_id(&state.db, login_user, id).await {
+        Ok(Some(data)) => {CommonResult::with_data(data)}
+        Ok(None) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/page",
+    operation_id = "system_background_job_page",
+    params(
+        ("page" = u64, Query, description = "page number"),
+        ("size" = u64, Query, description = "page size"),
+        ("job_type" = Option<String>, Query, description = "job type"),
+        ("status" = Option<i8>, Query, description = "status")
+    ),
+    responses(
+        (status = 200, description = "get page", body = CommonResult<PaginatedResponse<SystemBackgroundJobResponse>>)
+    ),
+    tag = "system_background_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_background_job_page", authorize = "")]
+async fn page(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Query(params): Query<PaginatedBackgroundJobRequest>,
+) -> CommonResult<PaginatedResponse<SystemBackgroundJobResponse>> {
+    match service::system_background_job::get_paginated(&state.db, login_user, params).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/cancel/{id}",
+    operation_id = "system_background_job_cancel",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "cancel")
+    ),
+    tag = "system_background_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_background_job_cancel", authorize = "")]
+async fn cancel(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_background_job::cancel(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/download/{id}",
+    operation_id = "system_background_job_download",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 200, description = "download", content_type = "application/octet-stream"),
+        (status = 404, description = "File not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "system_background_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_background_job_download", authorize = "")]
+async fn download(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let file = match service::system_background_job::get_artifact_data(&state.db, login_user, state.minio, id).await {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            return Err(StatusCode::NOT_FOUND)
+        },
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    let mut content_type = mime::APPLICATION_OCTET_STREAM;
+    if file.file_type.is_some() {
+        content_type = file.file_type
+        .unwrap()
+        .parse::<mime::Mime>()
+        .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name)
+            ),
+        ],
+        file.data,
+    ))
+}
@@ -4,7 +4,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{rate_limit, require_authorize};
 use axum::{extract::{Json, Multipart, Path, Query, State}, http::StatusCode, response::IntoResponse, routing::{get, post}, Extension, Router};
 use common::base::page::PaginatedResponse;
 use file_model::request::system_file::{CreateSystemFileRequest, PaginatedKeywordRequest, UpdateSystemFileRequest, UploadSystemFileRequest};
@@ -218,6 +218,7 @@ async fn list(
     )
 )]
 #[require_authorize(operation_id = "system_file_upload", authorize = "")]
+#[rate_limit(operation_id = "system_file_upload", limit = 30, window = 60, key = "user")]
 async fn upload(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -244,6 +245,7 @@ async fn upload(
     )
 )]
 #[require_authorize(operation_id = "system_file_upload_for_path", authorize = "")]
+#[rate_limit(operation_id = "system_file_upload_for_path", limit = 30, window = 60, key = "user")]
 async fn upload_for_path(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -1,15 +1,21 @@
 use axum::handler::HandlerWithoutStateExt;
 use axum::http::Method;
 use common::config::config::Config;
+use common::config::system_config;
+use common::utils::snowflake_generator::SnowflakeGenerator;
 use common::database::mysql::{get_database_instance, DATABASE_INSTANCE};
 use common::utils::minio_utils::MinioClient;
 use once_cell::sync::Lazy;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use uaparser::UserAgentParser;
 use common::middleware::logger;
+use common::monitor::health::{health_router, HealthChecker};
+use common::monitor::metrics::metrics_handler;
+use common::shutdown::shutdown::{self, ShutdownSignal};
 use common::state::app_state::AppState;
 
 mod api;
@@ -20,9 +26,13 @@ async fn main() -> Result<(), anyhow::Error> {
     // 初始化日志
     logger::init_tracing().await?;
     let config = Config::load();
-    let database = get_database_instance(config.database_url).await;
+    let database = get_database_instance(config.database.url).await;
+    // 加载系统参数
+    system_config::init(database).await?;
+    // 申请雪花算法机器id
+    SnowflakeGenerator::init_global().await?;
 
-    let minio = MinioClient::new(&config.minio_url, &config.minio_access_key, &config.minio_secret_key).await?;
+    let minio = MinioClient::new(&config.minio.url, &config.minio.access_key, &config.minio.secret_key).await?;
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -31,32 +41,44 @@ async fn main() -> Result<(), anyhow::Error> {
 
     // let ua_parser = UserAgentParser::from_yaml("regexes.yaml").expect("Failed to load regexes.yaml");
 
-    let state = AppState { db: database.clone(), ua_parser: None, minio: Some(minio) };
+    let state = AppState { db: database.clone(), ua_parser: None, minio: Some(minio.clone()) };
+
+    // 健康检查和指标,就绪检查项为服务依赖
+    let health_checker = HealthChecker::new()
+        .with_database(database.clone())
+        .with_redis()
+        .with_minio(minio.clone());
 
     let app = route::api(state).await
+        .layer(axum::middleware::from_fn(metrics_handler)) // 请求指标,需在合并健康检查路由前添加
+        .merge(health_router(health_checker))
+        .merge(logger::log_level_router()) // 运行中修改日志级别
         .layer(cors)
         .layer(axum::middleware::from_fn(logger::panic_handler)); // 添加异常处理中间件
 
-    let addr = format!("0.0.0.0:{}", config.file_server_port);
+    // 退出信号
+    let shutdown_signal = ShutdownSignal::listen();
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout);
+
+    let addr = format!("0.0.0.0:{}", config.file_server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     info!("Server running on {}", addr);
-    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>());
+    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal.clone().wait_owned());
 
+    // 收到退出信号后停止接收新连接,在超时时间内等待处理中的请求完成
     tokio::select! {
         result = server_future => {
             if let Err(e) = result {
-                eprintln!("Web server unexpected exit: {}", e);
+                tracing::error!("Web server unexpected exit: {}", e);
             }
-            println!("Web server has stopped");
+            info!("Web server has stopped");
         }
-        // _ = tokio::signal::ctrl_c() => {
-        //     println!("The program is closed when the exit signal is received");
-        //     task_manager.shutdown();
-        //     return Ok(());
-        // }
+        _ = shutdown_signal.drain_deadline(shutdown_timeout) => {}
     }
 
-    // task_manager.shutdown();
+    // 写入未完成的日志,关闭连接
+    shutdown::cleanup(None, shutdown_timeout).await;
 
     Ok(())
 }
\ No newline at end of file
@@ -1,10 +1,13 @@
 
 use crate::{api::system_file::system_file_router, AppState};
+use axum::extract::DefaultBodyLimit;
 use axum::Router;
-use common::{middleware::{authorize::{authorize_handler, init_route_authorizes}, operation_logger::operation_logger_handler, request_context::request_context_handler}, utils::jwt_utils::AccessClaims};
+use common::config::config::Config;
+use common::{middleware::{authorize::{authorize_handler, init_route_authorizes}, idempotency::idempotency_handler, rate_limit::rate_limit_handler, operation_logger::operation_logger_handler, request_context::request_context_handler}, utils::jwt_utils::AccessClaims};
 use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use crate::api::system_file::system_file_no_auth_router;
+use crate::api::system_background_job::system_background_job_router;
 
 // openapi document
 #[derive(OpenApi)]
@@ -15,6 +18,7 @@ use crate::api::system_file::system_file_no_auth_router;
         version = "1.0.0"
     ),
     tags(
+        (name = "system_background_job", description = "后台作业"),
         (name = "system_file", description = "文件信息"),
     ),
     modifiers(&SecurityAddon)
@@ -37,16 +41,23 @@ pub async fn api(state: AppState) -> Router {
 pub async fn no_auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .nest("/system_file", system_file_no_auth_router(state.clone()).await)
+        .layer(DefaultBodyLimit::max(Config::load().file_server.upload_max_size)) // 上传文件大小限制
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
 }
 
 pub async fn auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
+        .nest("/system_background_job", system_background_job_router(state.clone()).await)
         .nest("/system_file", system_file_router(state.clone()).await)
+        .layer(DefaultBodyLimit::max(Config::load().file_server.upload_max_size)) // 上传文件大小限制
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
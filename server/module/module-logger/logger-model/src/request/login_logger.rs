@@ -7,4 +7,37 @@ pub struct PaginatedKeywordRequest {
     #[serde(flatten)]
     pub base: PaginatedRequest,
     pub keyword: Option<String>,
-}
\ No newline at end of file
+}
+
+/// 登录日志查询条件,同时作为导出作业的参数
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct LoginLoggerFilter {
+
+    pub keyword: Option<String>, // 全文检索,匹配账号、IP、UA、昵称中的完整单词
+
+    pub user_id: Option<i64>, // 用户编号
+
+    pub username: Option<String>, // 用户账号
+
+    pub user_ip: Option<String>, // IP,支持 192.168.1.10、192.168.1.0/24、192.168.1.1-192.168.1.100
+
+    pub success: Option<bool>, // 是否登录成功
+
+    pub start_time: Option<i64>, // 开始时间(秒)
+
+    pub end_time: Option<i64>, // 结束时间(秒)
+
+}
+
+/// 登录日志分页检索
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SearchLoginLoggerRequest {
+
+    pub page: u64, // 页码
+
+    pub size: u64, // 每页数量
+
+    #[serde(flatten)]
+    pub filter: LoginLoggerFilter,
+
+}
@@ -1,2 +1,3 @@
 pub mod login_logger;
-pub mod operation_logger;
\ No newline at end of file
+pub mod operation_logger;
+pub mod security_event;
\ No newline at end of file
@@ -7,4 +7,41 @@ pub struct PaginatedKeywordRequest {
     #[serde(flatten)]
     pub base: PaginatedRequest,
     pub keyword: Option<String>,
-}
\ No newline at end of file
+}
+
+/// 操作日志查询条件,同时作为导出作业的参数
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct OperationLoggerFilter {
+
+    pub keyword: Option<String>, // 全文检索,匹配操作内容、请求地址、操作人、模块中的完整单词
+
+    pub operator: Option<i64>, // 操作人id
+
+    pub user_ip: Option<String>, // IP,支持 192.168.1.10、192.168.1.0/24、192.168.1.1-192.168.1.100
+
+    pub start_time: Option<i64>, // 开始时间(秒)
+
+    pub end_time: Option<i64>, // 结束时间(秒)
+
+    pub request_url: Option<String>, // 请求地址,* 匹配任意字符,如 /system/system_user/*
+
+    pub min_duration: Option<i64>, // 最小耗时(毫秒)
+
+    pub success: Option<bool>, // 操作结果
+
+    pub r#type: Option<String>, // 操作模块
+
+}
+
+/// 操作日志分页检索
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SearchOperationLoggerRequest {
+
+    pub page: u64, // 页码
+
+    pub size: u64, // 每页数量
+
+    #[serde(flatten)]
+    pub filter: OperationLoggerFilter,
+
+}
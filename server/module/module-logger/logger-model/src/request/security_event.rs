@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 安全事件查询条件
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct SecurityEventFilter {
+
+    pub event_type: Option<String>, // 事件类型 new_ip/new_device/impossible_travel/failed_burst/off_hours
+
+    pub min_level: Option<i8>, // 最低风险等级 1低 2中 3高
+
+    pub username: Option<String>, // 用户账号
+
+    pub start_time: Option<i64>, // 开始时间(秒)
+
+    pub end_time: Option<i64>, // 结束时间(秒)
+
+}
+
+/// 安全事件分页检索
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SearchSecurityEventRequest {
+
+    pub page: u64, // 页码
+
+    pub size: u64, // 每页数量
+
+    #[serde(flatten)]
+    pub filter: SecurityEventFilter,
+
+}
+
+/// 安全概览
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SecurityDashboardRequest {
+
+    pub days: Option<i64>, // 统计最近的天数,默认7天
+
+}
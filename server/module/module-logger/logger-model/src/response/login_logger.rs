@@ -16,6 +16,8 @@ pub struct LoginLoggerResponse {
 
     pub result: String, // 登陆结果
 
+    pub success: bool, // 是否登录成功
+
     pub user_ip: String, // 用户 IP
 
     pub user_agent: String, // 浏览器 UA
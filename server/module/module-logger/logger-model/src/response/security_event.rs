@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SecurityEventResponse {
+
+    pub login_log_id: String, // 登录日志的_id
+
+    pub event_type: String, // 事件类型
+
+    pub event_name: String, // 事件类型名称
+
+    pub level: i8, // 风险等级 1低 2中 3高
+
+    pub user_id: Option<i64>, // 用户编号
+
+    pub username: String, // 用户账号
+
+    pub user_ip: String, // 用户 IP
+
+    pub user_agent: String, // 浏览器 UA
+
+    pub location: String, // IP所在地
+
+    pub detail: String, // 说明
+
+    pub operate_time: i64, // 登录时间
+
+}
+
+/// 分组统计
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SecurityEventCount {
+
+    pub key: String, // 分组值,事件类型、风险等级或日期
+
+    pub name: String, // 分组名称
+
+    pub count: u64, // 事件数
+
+}
+
+/// 安全概览
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SecurityDashboardResponse {
+
+    pub days: i64, // 统计的天数
+
+    pub total: u64, // 事件总数
+
+    pub high_risk: u64, // 高风险事件数
+
+    pub by_type: Vec<SecurityEventCount>, // 按事件类型统计
+
+    pub by_level: Vec<SecurityEventCount>, // 按风险等级统计
+
+    pub by_day: Vec<SecurityEventCount>, // 按天统计
+
+    pub recent: Vec<SecurityEventResponse>, // 最近的事件
+
+}
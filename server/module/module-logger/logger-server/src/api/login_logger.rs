@@ -1,16 +1,19 @@
-use axum::{extract::{Query, State}, Extension};
+use axum::{extract::{Query, State}, Extension, Json};
 use macros::require_authorize;
+use tracing::error;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use common::{base::response::CommonResult, context::context::LoginUserContext};
 use common::base::page::PaginatedResponse;
-use logger_model::request::login_logger::PaginatedKeywordRequest;
+use logger_model::request::login_logger::{LoginLoggerFilter, PaginatedKeywordRequest, SearchLoginLoggerRequest};
 use logger_model::response::login_logger::LoginLoggerResponse;
 use crate::{service, AppState};
 
 pub async fn login_logger_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .routes(routes!(page))
+        .routes(routes!(search))
+        .routes(routes!(export))
         .with_state(state)
 }
 
@@ -41,4 +44,57 @@ async fn page(
         Ok(data) => {CommonResult::with_data(data)}
         Err(e) => {CommonResult::with_err(&e.to_string())}
     }
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    post,
+    path = "/search",
+    operation_id = "login_logger_search",
+    request_body(content = SearchLoginLoggerRequest, description = "search", content_type = "application/json"),
+    responses(
+        (status = 200, description = "search", body = CommonResult<PaginatedResponse<LoginLoggerResponse>>)
+    ),
+    tag = "login_logger",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "login_logger_search", authorize = "")]
+async fn search(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<SearchLoginLoggerRequest>,
+) -> CommonResult<PaginatedResponse<LoginLoggerResponse>> {
+    match service::login_logger::search(payload, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {
+            error!("search login log error, {:#?}", e);
+            CommonResult::with_err(&e.to_string())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/export",
+    operation_id = "login_logger_export",
+    request_body(content = LoginLoggerFilter, description = "export", content_type = "application/json"),
+    responses(
+        (status = 200, description = "background job id", body = CommonResult<i64>)
+    ),
+    tag = "login_logger",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "login_logger_export", authorize = "audit:login:export")]
+async fn export(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<LoginLoggerFilter>,
+) -> CommonResult<i64> {
+    match service::login_logger::export(&state.db, login_user, payload).await {
+        Ok(id) => {CommonResult::with_data(id)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
@@ -1,17 +1,19 @@
-use axum::{extract::{Query, State}, Extension};
+use axum::{extract::{Query, State}, Extension, Json};
 use macros::require_authorize;
 use tracing::error;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use common::{base::response::CommonResult, context::context::LoginUserContext};
 use common::base::page::PaginatedResponse;
-use logger_model::request::operation_logger::PaginatedKeywordRequest;
+use logger_model::request::operation_logger::{OperationLoggerFilter, PaginatedKeywordRequest, SearchOperationLoggerRequest};
 use logger_model::response::operation_logger::OperationLoggerResponse;
 use crate::{service, AppState};
 
 pub async fn operation_logger_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .routes(routes!(page))
+        .routes(routes!(search))
+        .routes(routes!(export))
         .with_state(state)
 }
 
@@ -45,4 +47,57 @@ async fn page(
             CommonResult::with_err(&e.to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    post,
+    path = "/search",
+    operation_id = "operation_logger_search",
+    request_body(content = SearchOperationLoggerRequest, description = "search", content_type = "application/json"),
+    responses(
+        (status = 200, description = "search", body = CommonResult<PaginatedResponse<OperationLoggerResponse>>)
+    ),
+    tag = "operation_logger",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "operation_logger_search", authorize = "")]
+async fn search(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<SearchOperationLoggerRequest>,
+) -> CommonResult<PaginatedResponse<OperationLoggerResponse>> {
+    match service::operation_logger::search(payload, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {
+            error!("search operation log error, {:#?}", e);
+            CommonResult::with_err(&e.to_string())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/export",
+    operation_id = "operation_logger_export",
+    request_body(content = OperationLoggerFilter, description = "export", content_type = "application/json"),
+    responses(
+        (status = 200, description = "background job id", body = CommonResult<i64>)
+    ),
+    tag = "operation_logger",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "operation_logger_export", authorize = "audit:operation:export")]
+async fn export(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<OperationLoggerFilter>,
+) -> CommonResult<i64> {
+    match service::operation_logger::export(&state.db, login_user, payload).await {
+        Ok(id) => {CommonResult::with_data(id)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
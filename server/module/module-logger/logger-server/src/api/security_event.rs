@@ -0,0 +1,75 @@
+use axum::{extract::{Query, State}, Extension, Json};
+use macros::require_authorize;
+use tracing::error;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use common::{base::response::CommonResult, context::context::LoginUserContext};
+use common::base::page::PaginatedResponse;
+use logger_model::request::security_event::{SearchSecurityEventRequest, SecurityDashboardRequest};
+use logger_model::response::security_event::{SecurityDashboardResponse, SecurityEventResponse};
+use crate::{service, AppState};
+
+pub async fn security_event_router(state: AppState) -> OpenApiRouter {
+    OpenApiRouter::new()
+        .routes(routes!(dashboard))
+        .routes(routes!(search))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/dashboard",
+    operation_id = "security_event_dashboard",
+    params(
+        ("days" = Option<i64>, Query, description = "recent days, default 7")
+    ),
+    responses(
+        (status = 200, description = "dashboard", body = CommonResult<SecurityDashboardResponse>)
+    ),
+    tag = "security_event",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "security_event_dashboard", authorize = "audit:security")]
+async fn dashboard(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Query(params): Query<SecurityDashboardRequest>,
+) -> CommonResult<SecurityDashboardResponse> {
+    match service::security_event::dashboard(params, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {
+            error!("security dashboard error, {:#?}", e);
+            CommonResult::with_err(&e.to_string())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/search",
+    operation_id = "security_event_search",
+    request_body(content = SearchSecurityEventRequest, description = "search", content_type = "application/json"),
+    responses(
+        (status = 200, description = "search", body = CommonResult<PaginatedResponse<SecurityEventResponse>>)
+    ),
+    tag = "security_event",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "security_event_search", authorize = "audit:security")]
+async fn search(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<SearchSecurityEventRequest>,
+) -> CommonResult<PaginatedResponse<SecurityEventResponse>> {
+    match service::security_event::search(payload, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {
+            error!("search security event error, {:#?}", e);
+            CommonResult::with_err(&e.to_string())
+        }
+    }
+}
@@ -9,6 +9,7 @@ pub fn model_to_response(model: LoginLogger) -> LoginLoggerResponse {
         user_type: model.user_type,
         username: model.username,
         result: model.result,
+        success: model.success.unwrap_or(true),
         user_ip: model.user_ip,
         user_agent: model.user_agent,
         department_code: model.department_code,
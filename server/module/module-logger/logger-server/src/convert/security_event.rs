@@ -0,0 +1,19 @@
+use common::base::logger::SecurityEvent;
+use common::constants::enum_constants::security_event_name;
+use logger_model::response::security_event::SecurityEventResponse;
+
+pub fn model_to_response(model: SecurityEvent) -> SecurityEventResponse {
+    SecurityEventResponse {
+        event_name: security_event_name(&model.event_type).to_string(),
+        login_log_id: model.login_log_id,
+        event_type: model.event_type,
+        level: model.level,
+        user_id: model.user_id,
+        username: model.username,
+        user_ip: model.user_ip,
+        user_agent: model.user_agent,
+        location: model.location,
+        detail: model.detail,
+        operate_time: model.operate_time,
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use mongodb::bson::{from_document, Document};
+use mongodb::bson::oid::ObjectId;
+use common::base::logger::{LoginLogger, OperationLogger};
+use common::config::config::Config;
+use common::database::mongo::{LogCollection, MongoManager};
+use common::task::background_job::{async_trait, JobArtifact, JobContext, JobHandler, JOB_TYPE_LOGGER_LOGIN_EXPORT, JOB_TYPE_LOGGER_OPERATION_EXPORT};
+use logger_model::request::login_logger::LoginLoggerFilter;
+use logger_model::request::operation_logger::OperationLoggerFilter;
+use crate::service::log_filter;
+
+/// 每批读取的日志数量
+const PAGE_SIZE: i64 = 1000;
+
+// 登录日志导出作业,导出为csv文件,供审计使用
+pub struct LoginLoggerExportJob;
+
+#[async_trait]
+impl JobHandler for LoginLoggerExportJob {
+    const JOB_TYPE: &'static str = JOB_TYPE_LOGGER_LOGIN_EXPORT;
+    type Payload = LoginLoggerFilter;
+
+    async fn handle(&self, ctx: &JobContext, payload: Self::Payload) -> Result<Option<JobArtifact>> {
+        let filter = log_filter::login_filter(ctx.tenant_id, &payload)?;
+        let header = "时间,用户编号,账号,结果,IP,UA,链路编号";
+        let csv = export_csv(ctx, LogCollection::Login, filter, header, |doc| {
+            let log: LoginLogger = from_document(doc)?;
+            Ok(vec![
+                format_time(log.operate_time),
+                log.user_id.map(|id| id.to_string()).unwrap_or_default(),
+                log.username,
+                match log.success {
+                    Some(false) => format!("失败: {}", log.result),
+                    _ => "成功".to_string(),
+                },
+                log.user_ip,
+                log.user_agent,
+                log.trace_id.unwrap_or_default(),
+            ])
+        }).await?;
+        Ok(Some(JobArtifact {
+            file_name: format!("login_logger_{}.csv", Local::now().format("%Y%m%d%H%M%S")),
+            content_type: "text/csv".to_string(),
+            data: csv.into_bytes(),
+        }))
+    }
+}
+
+// 操作日志导出作业,导出为csv文件,供审计使用
+pub struct OperationLoggerExportJob;
+
+#[async_trait]
+impl JobHandler for OperationLoggerExportJob {
+    const JOB_TYPE: &'static str = JOB_TYPE_LOGGER_OPERATION_EXPORT;
+    type Payload = OperationLoggerFilter;
+
+    async fn handle(&self, ctx: &JobContext, payload: Self::Payload) -> Result<Option<JobArtifact>> {
+        let filter = log_filter::operation_filter(ctx.tenant_id, &payload)?;
+        let header = "时间,操作人编号,操作人,模块,操作,业务编号,操作内容,结果,请求方法,请求地址,IP,耗时(毫秒),链路编号";
+        let csv = export_csv(ctx, LogCollection::Operation, filter, header, |doc| {
+            let log: OperationLogger = from_document(doc)?;
+            Ok(vec![
+                format_time(log.operate_time),
+                log.operator.map(|id| id.to_string()).unwrap_or_default(),
+                log.operator_nickname.unwrap_or_default(),
+                log.r#type.unwrap_or_default(),
+                log.sub_type.unwrap_or_default(),
+                log.biz_id.map(|id| id.to_string()).unwrap_or_default(),
+                log.action.unwrap_or_default(),
+                match log.success {
+                    Some(true) => "成功".to_string(),
+                    Some(false) => "失败".to_string(),
+                    None => String::new(),
+                },
+                log.request_method,
+                log.request_url,
+                log.user_ip,
+                log.duration.map(|duration| duration.to_string()).unwrap_or_default(),
+                log.trace_id.unwrap_or_default(),
+            ])
+        }).await?;
+        Ok(Some(JobArtifact {
+            file_name: format!("operation_logger_{}.csv", Local::now().format("%Y%m%d%H%M%S")),
+            content_type: "text/csv".to_string(),
+            data: csv.into_bytes(),
+        }))
+    }
+}
+
+/// 分批读取日志生成csv,超过最大导出数量时失败,需要缩小查询范围
+async fn export_csv<F>(ctx: &JobContext, collection: LogCollection, filter: Document, header: &str, row: F) -> Result<String>
+where
+    F: Fn(Document) -> Result<Vec<String>>,
+{
+    let mongo = MongoManager::get();
+    let max_rows = Config::load().logger_server.export_max_rows;
+    let total = mongo.count_logs(collection, filter.clone()).await?;
+    if total > max_rows {
+        return Err(anyhow!("导出的日志数{}超过上限{},请缩小查询范围", total, max_rows));
+    }
+
+    // 带BOM,excel打开时不乱码
+    let mut csv = format!("\u{feff}{}\n", header);
+    let mut after: Option<ObjectId> = None;
+    let mut exported: u64 = 0;
+    loop {
+        ctx.check_cancelled()?;
+        let docs = mongo.find_logs_after(collection, filter.clone(), after, PAGE_SIZE).await?;
+        let Some(last) = docs.last() else {
+            break;
+        };
+        after = Some(last.get_object_id("_id")?);
+        for doc in docs {
+            let fields = row(doc)?;
+            csv.push_str(&fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+            csv.push('\n');
+            exported += 1;
+        }
+        let progress = if total == 0 { 100 } else { (exported * 100 / total) as i32 };
+        ctx.set_progress(progress, &format!("{}/{}", exported, total)).await?;
+    }
+    Ok(csv)
+}
+
+fn format_time(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// 包含逗号、引号或换行的字段加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("admin"), "admin");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("line\rbreak"), "\"line\rbreak\"");
+    }
+}
@@ -0,0 +1 @@
+pub mod logger_export_job;
\ No newline at end of file
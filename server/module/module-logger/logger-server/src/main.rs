@@ -4,36 +4,71 @@ use common::config::config::Config;
 use common::database::mysql::{get_database_instance, DATABASE_INSTANCE};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use uaparser::UserAgentParser;
 use common::database::mongo::MongoManager;
 use common::middleware::logger;
+use common::monitor::health::{health_router, HealthChecker};
+use common::monitor::metrics::metrics_handler;
+use common::shutdown::shutdown::{self, ShutdownSignal};
 use common::state::app_state::AppState;
 use common::task::task_manager::TaskManager;
 use crate::task::logger_task::LoginLoggerTask;
 use crate::task::operation_logger::OperationLoggerTask;
+use crate::task::log_archive_task::LogArchiveTask;
+use crate::task::security_analysis_task::SecurityAnalysisTask;
+use common::task::outbox_relay_task::OutboxRelayTask;
+use crate::job::logger_export_job::{LoginLoggerExportJob, OperationLoggerExportJob};
+use common::config::system_config;
+use common::task::background_job::JobQueue;
+use common::utils::minio_utils::MinioClient;
+use common::utils::snowflake_generator::SnowflakeGenerator;
 
 mod api;
 mod service;
 mod convert;
 mod route;
 mod task;
+mod job;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     // 初始化日志
     logger::init_tracing().await?;
     let config = Config::load();
+    // 定时任务的调度和执行记录保存在数据库
+    let database = get_database_instance(config.database.url).await;
+    // 加载系统参数,租户的日志保留天数
+    system_config::init(database).await?;
+    // 申请雪花算法机器id
+    SnowflakeGenerator::init_global().await?;
 
     // 初始化mongo
     MongoManager::init().await?;
+    // 归档文件和导出文件保存到minio
+    let minio = MinioClient::new(&config.minio.url, &config.minio.access_key, &config.minio.secret_key).await?;
 
     // 初始化任务管理器
-    let mut task_manager = TaskManager::new();
-    task_manager.add_task(LoginLoggerTask::new(), "*/10 * * * * *").await;
-    task_manager.add_task(OperationLoggerTask::new(), "*/10 * * * * *").await;
+    let mut task_manager = TaskManager::new(database.clone());
+    task_manager.add_task(LoginLoggerTask::new(), &config.logger_server.login_log_flush_cron).await;
+    task_manager.add_task(OperationLoggerTask::new(), &config.logger_server.operation_log_flush_cron).await;
+    task_manager.add_task(LogArchiveTask::new(minio.clone()), &config.logger_server.archive_cron).await;
+    // 分析登录日志,发现的安全事件通过领域事件通知租户
+    if config.logger_server.security.enabled {
+        task_manager.add_task(SecurityAnalysisTask::new(database.clone()), &config.logger_server.security.cron).await;
+    }
+    // 投递领域事件
+    task_manager.add_task(OutboxRelayTask::new(database.clone()), &config.event.relay_cron).await;
+
+    // 启动后台作业,导出审计日志
+    JobQueue::new(database.clone())
+        .with_minio(minio.clone())
+        .register(LoginLoggerExportJob)
+        .register(OperationLoggerExportJob)
+        .start(&config.job_queue);
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -42,35 +77,48 @@ async fn main() -> Result<(), anyhow::Error> {
 
     // let ua_parser = UserAgentParser::from_yaml("regexes.yaml").expect("Failed to load regexes.yaml");
 
-    let state = AppState { db: Default::default(), ua_parser: None, minio: None };
+    let state = AppState { db: database.clone(), ua_parser: None, minio: None };
 
     // let app = Router::new()
     //     .fallback_service(config.api_prefix.as_ref(), route::api(database).await)
     //     .layer(cors);
+    // 健康检查和指标,就绪检查项为服务依赖
+    let health_checker = HealthChecker::new()
+        .with_database(database.clone())
+        .with_redis()
+        .with_mongo()
+        .with_minio(minio.clone());
+
     let app = route::api(state).await
+        .layer(axum::middleware::from_fn(metrics_handler)) // 请求指标,需在合并健康检查路由前添加
+        .merge(health_router(health_checker))
+        .merge(logger::log_level_router()) // 运行中修改日志级别
         .layer(cors)
         .layer(axum::middleware::from_fn(logger::panic_handler)); // 添加异常处理中间件
 
-    let addr = format!("0.0.0.0:{}", config.logger_server_port);
+    // 退出信号
+    let shutdown_signal = ShutdownSignal::listen();
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout);
+
+    let addr = format!("0.0.0.0:{}", config.logger_server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     info!("Server running on {}", addr);
-    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>());
+    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal.clone().wait_owned());
 
+    // 收到退出信号后停止接收新连接,在超时时间内等待处理中的请求完成
     tokio::select! {
         result = server_future => {
             if let Err(e) = result {
-                eprintln!("Web server unexpected exit: {}", e);
+                tracing::error!("Web server unexpected exit: {}", e);
             }
-            println!("Web server has stopped");
-        }
-        _ = tokio::signal::ctrl_c() => {
-            println!("The program is closed when the exit signal is received");
-            task_manager.shutdown();
-            return Ok(());
+            info!("Web server has stopped");
         }
+        _ = shutdown_signal.drain_deadline(shutdown_timeout) => {}
     }
 
-    task_manager.shutdown();
+    // 停止定时任务,写入未完成的日志,关闭连接
+    shutdown::cleanup(Some(&task_manager), shutdown_timeout).await;
 
     Ok(())
 }
\ No newline at end of file
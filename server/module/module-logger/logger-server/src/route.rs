@@ -7,8 +7,11 @@ use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use common::middleware::authorize::{authorize_handler, init_route_authorizes};
 use common::middleware::operation_logger::operation_logger_handler;
+use common::middleware::idempotency::idempotency_handler;
+use common::middleware::rate_limit::rate_limit_handler;
 use crate::api::login_logger::login_logger_router;
 use crate::api::operation_logger::operation_logger_router;
+use crate::api::security_event::security_event_router;
 
 // openapi document
 #[derive(OpenApi)]
@@ -21,6 +24,7 @@ use crate::api::operation_logger::operation_logger_router;
     tags(
         (name = "login_logger", description = "登录日志"),
         (name = "operation_logger", description = "操作日志"),
+        (name = "security_event", description = "登录安全事件"),
     ),
     modifiers(&SecurityAddon)
 )]
@@ -42,8 +46,11 @@ pub async fn auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .nest("/login_logger", login_logger_router(state.clone()).await)
         .nest("/operation_logger", operation_logger_router(state.clone()).await)
+        .nest("/security_event", security_event_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use maxminddb::{geoip2, Reader};
+use once_cell::sync::Lazy;
+use tracing::{info, warn};
+use common::config::config::Config;
+
+/// IP所在地
+#[derive(Debug, Clone)]
+pub struct GeoLocation {
+    pub name: String, // 国家 省 城市
+    pub latitude: f64, // 纬度
+    pub longitude: f64, // 经度
+}
+
+/// 离线IP地址库,第一次查询时加载,文件不存在时不检测异地登录
+static READER: Lazy<Option<Reader<Vec<u8>>>> = Lazy::new(|| {
+    let path = Config::load().logger_server.security.geoip_db_path;
+    match Reader::open_readfile(&path) {
+        Ok(reader) => {
+            info!("load geoip database {}, build epoch: {}", path, reader.metadata.build_epoch);
+            Some(reader)
+        }
+        Err(e) => {
+            warn!("load geoip database {} error, impossible travel detection is disabled: {}", path, e);
+            None
+        }
+    }
+});
+
+/// 查询IP所在地,内网地址或地址库中没有坐标时返回None
+pub fn lookup(ip: &str) -> Option<GeoLocation> {
+    let reader = READER.as_ref()?;
+    let ip: IpAddr = ip.trim().parse().ok()?;
+    let city: geoip2::City = reader.lookup(ip).ok()?;
+    let location = city.location?;
+    let names = [
+        city.country.and_then(|country| local_name(country.names)),
+        city.subdivisions.and_then(|subdivisions| subdivisions.into_iter().next()).and_then(|subdivision| local_name(subdivision.names)),
+        city.city.and_then(|city| local_name(city.names)),
+    ];
+    let mut name: Vec<&str> = Vec::new();
+    for part in names.into_iter().flatten() {
+        // 直辖市的省和城市同名
+        if !name.contains(&part) {
+            name.push(part);
+        }
+    }
+    Some(GeoLocation {
+        name: name.join(" "),
+        latitude: location.latitude?,
+        longitude: location.longitude?,
+    })
+}
+
+/// 优先使用中文名称
+fn local_name<'a>(names: Option<BTreeMap<&'a str, &'a str>>) -> Option<&'a str> {
+    let names = names?;
+    names.get("zh-CN").or_else(|| names.get("en")).copied()
+}
+
+/// 两个坐标之间的球面距离(公里)
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEIJING: (f64, f64) = (39.9042, 116.4074);
+    const SHANGHAI: (f64, f64) = (31.2304, 121.4737);
+
+    #[test]
+    fn test_distance_km() {
+        assert_eq!(distance_km(BEIJING, BEIJING), 0.0);
+        let distance = distance_km(BEIJING, SHANGHAI);
+        assert!((1050.0..1090.0).contains(&distance), "{}", distance);
+        assert!((distance - distance_km(SHANGHAI, BEIJING)).abs() < 1e-9);
+        // 赤道上经度相差90度为四分之一周长
+        assert!((distance_km((0.0, 0.0), (0.0, 90.0)) - 10007.5).abs() < 1.0);
+        // 跨过180度经线取较短的一侧
+        assert!((distance_km((0.0, 179.0), (0.0, -179.0)) - 222.4).abs() < 1.0);
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, Document, Regex};
+use common::database::mongo::{ipv4_number, FIELD_IP_NUMBER};
+use logger_model::request::login_logger::LoginLoggerFilter;
+use logger_model::request::operation_logger::OperationLoggerFilter;
+use logger_model::request::security_event::SecurityEventFilter;
+
+/// 登录日志查询条件,只查询当前租户的日志
+pub fn login_filter(tenant_id: i64, filter: &LoginLoggerFilter) -> Result<Document> {
+    let mut doc = doc! {"tenant_id": tenant_id};
+    add_keyword(&mut doc, filter.keyword.as_deref());
+    if let Some(user_id) = filter.user_id {
+        doc.insert("user_id", user_id);
+    }
+    if let Some(username) = non_empty(filter.username.as_deref()) {
+        doc.insert("username", username);
+    }
+    add_ip(&mut doc, filter.user_ip.as_deref())?;
+    // 旧版本的日志没有是否成功,只记录了成功的登录
+    match filter.success {
+        Some(true) => { doc.insert("success", doc! {"$ne": false}); }
+        Some(false) => { doc.insert("success", false); }
+        None => {}
+    }
+    add_time_range(&mut doc, filter.start_time, filter.end_time);
+    Ok(doc)
+}
+
+/// 操作日志查询条件,只查询当前租户的日志
+pub fn operation_filter(tenant_id: i64, filter: &OperationLoggerFilter) -> Result<Document> {
+    let mut doc = doc! {"tenant_id": tenant_id};
+    add_keyword(&mut doc, filter.keyword.as_deref());
+    if let Some(operator) = filter.operator {
+        doc.insert("operator", operator);
+    }
+    add_ip(&mut doc, filter.user_ip.as_deref())?;
+    add_time_range(&mut doc, filter.start_time, filter.end_time);
+    if let Some(url) = non_empty(filter.request_url.as_deref()) {
+        doc.insert("request_url", Regex { pattern: url_pattern(url), options: String::new() });
+    }
+    if let Some(min_duration) = filter.min_duration {
+        doc.insert("duration", doc! {"$gte": min_duration});
+    }
+    if let Some(success) = filter.success {
+        doc.insert("success", success);
+    }
+    if let Some(r#type) = non_empty(filter.r#type.as_deref()) {
+        doc.insert("type", r#type);
+    }
+    Ok(doc)
+}
+
+/// 安全事件查询条件,只查询当前租户的事件
+pub fn security_event_filter(tenant_id: i64, filter: &SecurityEventFilter) -> Result<Document> {
+    let mut doc = doc! {"tenant_id": tenant_id};
+    if let Some(event_type) = non_empty(filter.event_type.as_deref()) {
+        doc.insert("event_type", event_type);
+    }
+    if let Some(min_level) = filter.min_level {
+        if !(1..=3).contains(&min_level) {
+            return Err(anyhow!("风险等级只能是1-3"));
+        }
+        doc.insert("level", doc! {"$gte": min_level as i32});
+    }
+    if let Some(username) = non_empty(filter.username.as_deref()) {
+        doc.insert("username", username);
+    }
+    add_time_range(&mut doc, filter.start_time, filter.end_time);
+    Ok(doc)
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn add_keyword(doc: &mut Document, keyword: Option<&str>) {
+    if let Some(keyword) = non_empty(keyword) {
+        doc.insert("$text", doc! {"$search": keyword});
+    }
+}
+
+fn add_time_range(doc: &mut Document, start_time: Option<i64>, end_time: Option<i64>) {
+    let mut range = Document::new();
+    if let Some(start_time) = start_time {
+        range.insert("$gte", start_time);
+    }
+    if let Some(end_time) = end_time {
+        range.insert("$lte", end_time);
+    }
+    if !range.is_empty() {
+        doc.insert("operate_time", range);
+    }
+}
+
+/// IPv4按网段或范围查询,其他地址完整匹配
+fn add_ip(doc: &mut Document, ip: Option<&str>) -> Result<()> {
+    let Some(ip) = non_empty(ip) else {
+        return Ok(());
+    };
+    match ip_range(ip)? {
+        Some((start, end)) => doc.insert(FIELD_IP_NUMBER, doc! {"$gte": start, "$lte": end}),
+        None => doc.insert("user_ip", ip),
+    };
+    Ok(())
+}
+
+/// 解析 192.168.1.0/24、192.168.1.1-192.168.1.100 和单个IPv4地址,返回整数范围
+fn ip_range(ip: &str) -> Result<Option<(i64, i64)>> {
+    if let Some((network, prefix)) = ip.split_once('/') {
+        let network = ipv4_number(network).ok_or_else(|| anyhow!("IP网段格式错误: {}", ip))?;
+        let prefix = prefix.trim().parse::<u32>().ok()
+            .filter(|prefix| *prefix <= 32)
+            .ok_or_else(|| anyhow!("IP网段格式错误: {}", ip))?;
+        let size = 1_i64 << (32 - prefix);
+        let start = network & !(size - 1);
+        return Ok(Some((start, start + size - 1)));
+    }
+    if let Some((from, to)) = ip.split_once('-') {
+        return match (ipv4_number(from), ipv4_number(to)) {
+            (Some(from), Some(to)) if from <= to => Ok(Some((from, to))),
+            _ => Err(anyhow!("IP范围格式错误: {}", ip)),
+        };
+    }
+    Ok(ipv4_number(ip).map(|number| (number, number)))
+}
+
+/// 请求地址匹配规则,* 匹配任意字符,没有 * 时按前缀匹配
+fn url_pattern(url: &str) -> String {
+    let pattern = regex::escape(url).replace(r"\*", ".*");
+    if url.contains('*') {
+        format!("^{}$", pattern)
+    } else {
+        format!("^{}", pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_ip_range_cidr() {
+        assert_eq!(ip_range("192.168.1.0/24").unwrap(), Some((3232235776, 3232236031)));
+        // 地址不在网段起始位置时按网段对齐
+        assert_eq!(ip_range("192.168.1.77/24").unwrap(), Some((3232235776, 3232236031)));
+        assert_eq!(ip_range("0.0.0.0/0").unwrap(), Some((0, 4294967295)));
+        assert_eq!(ip_range("10.1.2.3/0").unwrap(), Some((0, 4294967295)));
+        assert_eq!(ip_range("10.1.2.3/32").unwrap(), Some((167838211, 167838211)));
+        assert!(ip_range("10.1.2.3/33").is_err());
+        assert!(ip_range("10.1.2.3/x").is_err());
+        assert!(ip_range("10.1.2/8").is_err());
+    }
+
+    #[test]
+    fn test_ip_range_span_and_single() {
+        assert_eq!(ip_range("192.168.1.1-192.168.1.100").unwrap(), Some((3232235777, 3232235876)));
+        assert_eq!(ip_range("192.168.1.1-192.168.1.1").unwrap(), Some((3232235777, 3232235777)));
+        assert!(ip_range("192.168.1.100-192.168.1.1").is_err());
+        assert!(ip_range("192.168.1.1-").is_err());
+        assert_eq!(ip_range("192.168.1.1").unwrap(), Some((3232235777, 3232235777)));
+        // 非IPv4地址完整匹配
+        assert_eq!(ip_range("::1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_url_pattern() {
+        let matches = |pattern: &str, url: &str| Regex::new(&url_pattern(pattern)).unwrap().is_match(url);
+        // 没有 * 时按前缀匹配
+        assert!(matches("/system/user", "/system/user/page"));
+        assert!(!matches("/system/user", "/api/system/user"));
+        // * 匹配任意字符,需要完整匹配
+        assert!(matches("/system/*/page", "/system/user/page"));
+        assert!(!matches("/system/*/page", "/system/user/page/1"));
+        assert!(matches("*/delete", "/erp/erp_product/delete"));
+        // 其他正则字符按原样匹配
+        assert!(matches("/a.b", "/a.b"));
+        assert!(!matches("/a.b", "/axb"));
+        assert!(matches("/query?(id)", "/query?(id)=1"));
+    }
+}
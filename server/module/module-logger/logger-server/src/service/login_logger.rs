@@ -3,9 +3,16 @@ use common::base::logger::LoginLogger;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::database::mongo::MongoManager;
-use logger_model::request::login_logger::PaginatedKeywordRequest;
+use common::task::background_job;
+use sea_orm::DatabaseConnection;
+use logger_model::request::login_logger::{LoginLoggerFilter, PaginatedKeywordRequest, SearchLoginLoggerRequest};
 use logger_model::response::login_logger::LoginLoggerResponse;
 use crate::convert::login_logger::model_to_response;
+use crate::job::logger_export_job::LoginLoggerExportJob;
+use crate::service::log_filter;
+
+/// 每页最大数量
+const MAX_PAGE_SIZE: u64 = 500;
 
 pub async fn add(login_logger: LoginLogger) -> Result<()> {
     let mongo = MongoManager::get();
@@ -13,7 +20,8 @@ pub async fn add(login_logger: LoginLogger) -> Result<()> {
     Ok(())
 }
 
-pub async fn add_batch(login_loggers: Vec<LoginLogger>) -> Result<()> {
+/// 批量保存从stream读取的日志(stream中的id, 日志)
+pub async fn add_batch(login_loggers: Vec<(String, LoginLogger)>) -> Result<()> {
     let mongo = MongoManager::get();
     mongo.insert_login_logs(login_loggers).await?;
     Ok(())
@@ -43,4 +51,25 @@ pub async fn get_paginated(params: PaginatedKeywordRequest, login_user: LoginUse
         size: page_result.size,
         total: page_result.total,
     })
-}
\ No newline at end of file
+}
+
+/// 按条件分页检索
+pub async fn search(params: SearchLoginLoggerRequest, login_user: LoginUserContext) -> Result<PaginatedResponse<LoginLoggerResponse>> {
+    let filter = log_filter::login_filter(login_user.tenant_id, &params.filter)?;
+    let mongo = MongoManager::get();
+    let page_result = mongo.search_login_logs(filter, params.page.max(1), params.size.clamp(1, MAX_PAGE_SIZE)).await?;
+    Ok(PaginatedResponse {
+        list: page_result.list.into_iter().map(model_to_response).collect(),
+        total_pages: page_result.total_pages,
+        page: page_result.page,
+        size: page_result.size,
+        total: page_result.total,
+    })
+}
+
+/// 提交导出作业,返回作业id
+pub async fn export(db: &DatabaseConnection, login_user: LoginUserContext, filter: LoginLoggerFilter) -> Result<i64> {
+    // 提前校验条件,避免提交无法执行的作业
+    log_filter::login_filter(login_user.tenant_id, &filter)?;
+    background_job::submit::<LoginLoggerExportJob, _>(db, &login_user, &filter, 0).await
+}
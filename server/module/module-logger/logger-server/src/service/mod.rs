@@ -1,2 +1,6 @@
 pub mod login_logger;
-pub mod operation_logger;
\ No newline at end of file
+pub mod operation_logger;
+pub mod log_filter;
+pub mod geo_ip;
+pub mod security_analysis;
+pub mod security_event;
\ No newline at end of file
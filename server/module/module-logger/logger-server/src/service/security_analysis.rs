@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use anyhow::Result;
+use chrono::{Datelike, Local, TimeZone, Timelike, Utc};
+use mongodb::bson::{doc, from_document};
+use mongodb::bson::oid::ObjectId;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use tracing::warn;
+use common::base::logger::{LoginLogger, LoginProfile, SecurityEvent};
+use common::cache::distributed_lock::{DistributedLock, LockGuard};
+use common::config::config::{Config, SecurityAnalysisConfig};
+use common::config::system_config::{self, CONFIG_KEY_SECURITY_BUSINESS_DAYS, CONFIG_KEY_SECURITY_BUSINESS_HOURS};
+use common::constants::enum_constants::{
+    SECURITY_EVENT_FAILED_BURST, SECURITY_EVENT_IMPOSSIBLE_TRAVEL, SECURITY_EVENT_NEW_DEVICE, SECURITY_EVENT_NEW_IP,
+    SECURITY_EVENT_OFF_HOURS, SECURITY_LEVEL_HIGH, SECURITY_LEVEL_LOW, SECURITY_LEVEL_MEDIUM,
+};
+use common::database::mongo::{LogCollection, MongoManager};
+use common::database::redis_constants::REDIS_KEY_LOGGER_SECURITY_CURSOR;
+use common::database::redis_pool::AsyncRedisManager;
+use common::event::domain_event::{SecurityAlertEvent, SecurityAlertItem, SECURITY_ALERT_MAX_ITEMS};
+use common::event::outbox;
+use crate::service::geo_ip::{self, GeoLocation};
+
+/// 每次执行最多分析的批数,积压较多时分多次执行
+const MAX_BATCHES_PER_RUN: usize = 20;
+/// 只分析写入超过该时间(秒)的日志,多个副本同时写入时_id不完全按写入顺序
+const ANALYSIS_DELAY_SECS: i64 = 10;
+/// 分析锁的租期,同时只有一个副本分析,保证用户画像按登录顺序更新
+const ANALYSIS_LOCK_LEASE: Duration = Duration::from_secs(60);
+/// 默认工作日,周一到周五
+const DEFAULT_BUSINESS_DAYS: &str = "1,2,3,4,5";
+/// 设备UA的最大长度
+const MAX_DEVICE_LEN: usize = 256;
+
+/// 分析新写入的登录日志,返回分析的日志数
+/// 按_id顺序分析,进度保存在redis,第一次执行时从当前时间开始,不分析历史日志,避免大量告警
+pub async fn analyse(db: &DatabaseConnection) -> Result<usize> {
+    let lock = DistributedLock::new("logger:security_analysis").with_lease(ANALYSIS_LOCK_LEASE);
+    let guard = match lock.try_acquire().await? {
+        Some(guard) => guard,
+        None => return Ok(0), // 其他副本正在分析
+    };
+
+    let upper = object_id_at(Utc::now().timestamp() - ANALYSIS_DELAY_SECS);
+    let mut cursor = match load_cursor().await? {
+        Some(cursor) => cursor,
+        None => {
+            save_cursor(&guard, upper).await?;
+            guard.release().await?;
+            return Ok(0);
+        }
+    };
+
+    let mongo = MongoManager::get();
+    let mut analyser = Analyser::new(Config::load().logger_server.security);
+    let mut analysed = 0;
+    for _ in 0..MAX_BATCHES_PER_RUN {
+        let filter = doc! {"_id": {"$gt": cursor, "$lt": upper}};
+        let docs = mongo.find_logs_after(LogCollection::Login, filter, None, analyser.config.batch_size as i64).await?;
+        if docs.is_empty() {
+            break;
+        }
+        analysed += docs.len();
+        for doc in docs {
+            let id = doc.get_object_id("_id")?;
+            match from_document::<LoginLogger>(doc) {
+                Ok(log) => analyser.analyse(&id.to_hex(), log).await?,
+                Err(e) => warn!("parse login log {} error: {}", id, e),
+            }
+            cursor = id;
+        }
+        // 先保存画像和通知,再保存进度,中途失败时重新分析本批日志,已记录的事件不会重复通知
+        analyser.flush(db, &guard).await?;
+        save_cursor(&guard, cursor).await?;
+    }
+    guard.release().await?;
+    Ok(analysed)
+}
+
+/// 指定时间(秒)对应的最小ObjectId
+fn object_id_at(timestamp: i64) -> ObjectId {
+    let mut bytes = [0u8; 12];
+    bytes[..4].copy_from_slice(&(timestamp as u32).to_be_bytes());
+    ObjectId::from_bytes(bytes)
+}
+
+async fn load_cursor() -> Result<Option<ObjectId>> {
+    let cursor = AsyncRedisManager::get::<_, String>(REDIS_KEY_LOGGER_SECURITY_CURSOR).await?;
+    Ok(cursor.and_then(|cursor| ObjectId::parse_str(&cursor).ok()))
+}
+
+// 分析锁过期后其他副本已接手时不再保存进度
+async fn save_cursor(guard: &LockGuard, cursor: ObjectId) -> Result<()> {
+    guard.check_fence().await?;
+    AsyncRedisManager::set(REDIS_KEY_LOGGER_SECURITY_CURSOR, cursor.to_hex()).await?;
+    Ok(())
+}
+
+struct Analyser {
+    config: SecurityAnalysisConfig,
+    profiles: HashMap<String, LoginProfile>, // 本批更新的用户画像
+    alerts: HashMap<i64, Vec<SecurityEvent>>, // 需要通知的事件,按租户分组
+}
+
+impl Analyser {
+    fn new(config: SecurityAnalysisConfig) -> Self {
+        Analyser { config, profiles: HashMap::new(), alerts: HashMap::new() }
+    }
+
+    /// 分析一条登录日志
+    async fn analyse(&mut self, log_id: &str, log: LoginLogger) -> Result<()> {
+        let (Some(tenant_id), Some(operate_time)) = (log.tenant_id, log.operate_time) else {
+            return Ok(());
+        };
+        if log.success == Some(false) {
+            if let Some(event) = self.check_failed_burst(log_id, &log, tenant_id, operate_time).await? {
+                self.save(event).await?;
+            }
+            return Ok(());
+        }
+
+        let geo = geo_ip::lookup(&log.user_ip);
+        let mut events = Vec::new();
+        if let Some(detail) = check_off_hours(tenant_id, operate_time) {
+            events.push(new_event(log_id, &log, SECURITY_EVENT_OFF_HOURS, SECURITY_LEVEL_LOW, detail));
+        }
+        if let Some(user_id) = log.user_id {
+            events.extend(self.check_profile(log_id, &log, tenant_id, user_id, operate_time, geo.as_ref()).await?);
+        }
+        let location = geo.map(|geo| geo.name).unwrap_or_default();
+        for mut event in events {
+            event.location = location.clone();
+            self.save(event).await?;
+        }
+        Ok(())
+    }
+
+    /// 时间窗口内登录失败的次数达到阈值时告警,同一个窗口内只告警一次
+    async fn check_failed_burst(&self, log_id: &str, log: &LoginLogger, tenant_id: i64, operate_time: i64) -> Result<Option<SecurityEvent>> {
+        if log.username.is_empty() {
+            return Ok(None);
+        }
+        let mongo = MongoManager::get();
+        let window_start = operate_time - self.config.failed_login_window_secs;
+        let filter = doc! {
+            "tenant_id": tenant_id,
+            "username": &log.username,
+            "success": false,
+            "operate_time": {"$gte": window_start, "$lte": operate_time},
+        };
+        let failures = mongo.count_logs(LogCollection::Login, filter).await?;
+        if failures < self.config.failed_login_threshold {
+            return Ok(None);
+        }
+        let filter = doc! {
+            "tenant_id": tenant_id,
+            "username": &log.username,
+            "event_type": SECURITY_EVENT_FAILED_BURST,
+            "operate_time": {"$gte": window_start},
+        };
+        if mongo.exists_security_event(filter).await? {
+            return Ok(None);
+        }
+        let detail = format!("{}分钟内登录失败{}次,最近一次来自 {}", self.config.failed_login_window_secs / 60, failures, log.user_ip);
+        Ok(Some(new_event(log_id, log, SECURITY_EVENT_FAILED_BURST, SECURITY_LEVEL_HIGH, detail)))
+    }
+
+    /// 与用户画像比较,检测新IP、新设备和短时间内异地登录,并更新画像
+    /// 用户第一次登录时只建立画像,不告警
+    async fn check_profile(
+        &mut self,
+        log_id: &str,
+        log: &LoginLogger,
+        tenant_id: i64,
+        user_id: i64,
+        operate_time: i64,
+        geo: Option<&GeoLocation>,
+    ) -> Result<Vec<SecurityEvent>> {
+        let key = LoginProfile::profile_id(tenant_id, user_id);
+        let profile = match self.profiles.remove(&key) {
+            Some(profile) => Some(profile),
+            None => MongoManager::get().get_login_profile(tenant_id, user_id).await?,
+        };
+        let device = device_key(&log.user_agent);
+        let mut events = Vec::new();
+        let mut profile = match profile {
+            Some(profile) => {
+                if !log.user_ip.is_empty() && !profile.ips.contains(&log.user_ip) {
+                    let detail = format!("首次从 {} 登录", log.user_ip);
+                    events.push(new_event(log_id, log, SECURITY_EVENT_NEW_IP, SECURITY_LEVEL_LOW, detail));
+                }
+                if !device.is_empty() && !profile.devices.contains(&device) {
+                    let detail = format!("首次使用该设备登录: {}", device);
+                    events.push(new_event(log_id, log, SECURITY_EVENT_NEW_DEVICE, SECURITY_LEVEL_MEDIUM, detail));
+                }
+                if let Some(detail) = self.check_travel(&profile, log, operate_time, geo) {
+                    events.push(new_event(log_id, log, SECURITY_EVENT_IMPOSSIBLE_TRAVEL, SECURITY_LEVEL_HIGH, detail));
+                }
+                profile
+            }
+            None => LoginProfile { id: key.clone(), tenant_id, user_id, ..Default::default() },
+        };
+
+        remember(&mut profile.ips, &log.user_ip, self.config.profile_max_entries);
+        remember(&mut profile.devices, &device, self.config.profile_max_entries);
+        // 日志可能晚于之后的登录写入,只用最近的登录更新位置
+        if operate_time >= profile.last_login_time {
+            profile.last_login_time = operate_time;
+            profile.last_ip = log.user_ip.clone();
+            profile.last_location = geo.map(|geo| geo.name.clone()).unwrap_or_default();
+            profile.last_latitude = geo.map(|geo| geo.latitude);
+            profile.last_longitude = geo.map(|geo| geo.longitude);
+        }
+        self.profiles.insert(key, profile);
+        Ok(events)
+    }
+
+    /// 两次登录所在地的距离超过最小距离,且按间隔时间计算的速度超过上限时视为异地登录
+    fn check_travel(&self, profile: &LoginProfile, log: &LoginLogger, operate_time: i64, geo: Option<&GeoLocation>) -> Option<String> {
+        let geo = geo?;
+        let last = (profile.last_latitude?, profile.last_longitude?);
+        if operate_time < profile.last_login_time {
+            return None;
+        }
+        let distance = geo_ip::distance_km(last, (geo.latitude, geo.longitude));
+        if distance < self.config.min_travel_distance_km {
+            return None;
+        }
+        // 间隔按至少1分钟计算
+        let interval = (operate_time - profile.last_login_time).max(60);
+        let speed = distance / (interval as f64 / 3600.0);
+        if speed <= self.config.max_travel_speed_kmh {
+            return None;
+        }
+        Some(format!(
+            "{}分钟内从 {}({}) 到 {}({}),距离约{:.0}公里",
+            interval / 60, profile.last_location, profile.last_ip, geo.name, log.user_ip, distance
+        ))
+    }
+
+    /// 保存事件,新增的事件达到通知等级时加入通知
+    async fn save(&mut self, event: SecurityEvent) -> Result<()> {
+        let created = MongoManager::get().save_security_event(&event).await?;
+        if created && self.config.notify_min_level > 0 && event.level >= self.config.notify_min_level {
+            self.alerts.entry(event.tenant_id).or_default().push(event);
+        }
+        Ok(())
+    }
+
+    /// 保存用户画像,按租户发送通知
+    async fn flush(&mut self, db: &DatabaseConnection, guard: &LockGuard) -> Result<()> {
+        guard.check_fence().await?;
+        let mongo = MongoManager::get();
+        for (_, profile) in self.profiles.drain() {
+            mongo.save_login_profile(&profile).await?;
+        }
+        // 告警和fencing token校验在同一个事务中,锁被接手后不会重复发送
+        let txn = db.begin().await?;
+        guard.check_db_fence(&txn).await?;
+        for (tenant_id, events) in self.alerts.drain() {
+            let alert = SecurityAlertEvent {
+                total: events.len(),
+                items: events.into_iter()
+                    .take(SECURITY_ALERT_MAX_ITEMS)
+                    .map(|event| SecurityAlertItem {
+                        event_type: event.event_type,
+                        level: event.level,
+                        username: event.username,
+                        user_ip: event.user_ip,
+                        detail: event.detail,
+                        operate_time: event.operate_time,
+                    })
+                    .collect(),
+            };
+            outbox::publish(&txn, tenant_id, &alert).await?;
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+}
+
+fn new_event(log_id: &str, log: &LoginLogger, event_type: &str, level: i8, detail: String) -> SecurityEvent {
+    SecurityEvent {
+        login_log_id: log_id.to_string(),
+        event_type: event_type.to_string(),
+        level,
+        user_id: log.user_id,
+        username: log.username.clone(),
+        user_ip: log.user_ip.clone(),
+        user_agent: log.user_agent.clone(),
+        location: String::new(),
+        detail,
+        operate_time: log.operate_time.unwrap_or_default(),
+        create_time: Utc::now().timestamp(),
+        tenant_id: log.tenant_id.unwrap_or_default(),
+    }
+}
+
+/// 按租户配置的工作时间检测,使用服务器时区,未配置或格式错误时不检测
+fn check_off_hours(tenant_id: i64, operate_time: i64) -> Option<String> {
+    let hours = system_config::get_or(tenant_id, CONFIG_KEY_SECURITY_BUSINESS_HOURS, String::new());
+    let (start, end) = parse_business_hours(&hours)?;
+    let days = system_config::get_or(tenant_id, CONFIG_KEY_SECURITY_BUSINESS_DAYS, DEFAULT_BUSINESS_DAYS.to_string());
+    let days: Vec<u32> = days.split(',').filter_map(|day| day.trim().parse().ok()).collect();
+
+    let time = Local.timestamp_opt(operate_time, 0).single()?;
+    let minute = time.hour() * 60 + time.minute();
+    if in_business_hours(start, end, minute) && days.contains(&time.weekday().number_from_monday()) {
+        return None;
+    }
+    Some(format!("{} 登录,工作时间为 {}", time.format("%Y-%m-%d %H:%M"), hours.trim()))
+}
+
+/// 分钟数是否在工作时间内,结束时间小于开始时间时表示跨过零点
+fn in_business_hours(start: u32, end: u32, minute: u32) -> bool {
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// 解析 08:00-20:00,返回开始和结束的分钟数
+fn parse_business_hours(hours: &str) -> Option<(u32, u32)> {
+    let (start, end) = hours.trim().split_once('-')?;
+    let minute = |time: &str| -> Option<u32> {
+        let (hour, minute) = time.trim().split_once(':')?;
+        let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+        (hour <= 24 && minute < 60 && hour * 60 + minute <= 24 * 60).then_some(hour * 60 + minute)
+    };
+    Some((minute(start)?, minute(end)?))
+}
+
+/// 设备标识,使用浏览器UA
+fn device_key(user_agent: &str) -> String {
+    user_agent.trim().chars().take(MAX_DEVICE_LEN).collect()
+}
+
+/// 记录最近使用的值,最近的在前,超过数量时删除最早的
+fn remember(values: &mut Vec<String>, value: &str, max_entries: usize) {
+    if value.is_empty() {
+        return;
+    }
+    values.retain(|existing| existing != value);
+    values.insert(0, value.to_string());
+    values.truncate(max_entries);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEIJING: (f64, f64) = (39.9042, 116.4074);
+    const TIANJIN: (f64, f64) = (39.3434, 117.3616);
+    const SHANGHAI: (f64, f64) = (31.2304, 121.4737);
+    const LAST_LOGIN_TIME: i64 = 1_760_000_000;
+
+    fn profile(last: Option<(f64, f64)>) -> LoginProfile {
+        LoginProfile {
+            last_login_time: LAST_LOGIN_TIME,
+            last_ip: "1.1.1.1".to_string(),
+            last_location: "北京".to_string(),
+            last_latitude: last.map(|last| last.0),
+            last_longitude: last.map(|last| last.1),
+            ..Default::default()
+        }
+    }
+
+    fn geo(location: (f64, f64)) -> GeoLocation {
+        GeoLocation { name: "上海".to_string(), latitude: location.0, longitude: location.1 }
+    }
+
+    fn check_travel(last: Option<(f64, f64)>, to: (f64, f64), elapsed_secs: i64) -> Option<String> {
+        let analyser = Analyser::new(SecurityAnalysisConfig::default());
+        let log = LoginLogger { user_ip: "2.2.2.2".to_string(), ..Default::default() };
+        analyser.check_travel(&profile(last), &log, LAST_LOGIN_TIME + elapsed_secs, Some(&geo(to)))
+    }
+
+    #[test]
+    fn test_parse_business_hours() {
+        assert_eq!(parse_business_hours("08:00-20:00"), Some((480, 1200)));
+        assert_eq!(parse_business_hours(" 22:00 - 06:30 "), Some((1320, 390)));
+        assert_eq!(parse_business_hours("00:00-24:00"), Some((0, 1440)));
+        assert_eq!(parse_business_hours(""), None);
+        assert_eq!(parse_business_hours("08:00"), None);
+        assert_eq!(parse_business_hours("0800-2000"), None);
+        assert_eq!(parse_business_hours("08:60-20:00"), None);
+        assert_eq!(parse_business_hours("08:00-24:01"), None);
+        assert_eq!(parse_business_hours("25:00-26:00"), None);
+    }
+
+    #[test]
+    fn test_in_business_hours() {
+        let (start, end) = parse_business_hours("08:00-20:00").unwrap();
+        assert!(in_business_hours(start, end, 480));
+        assert!(in_business_hours(start, end, 1199));
+        assert!(!in_business_hours(start, end, 1200));
+        assert!(!in_business_hours(start, end, 479));
+        assert!(!in_business_hours(start, end, 0));
+    }
+
+    #[test]
+    fn test_in_business_hours_across_midnight() {
+        let (start, end) = parse_business_hours("22:00-06:00").unwrap();
+        assert!(in_business_hours(start, end, 1320));
+        assert!(in_business_hours(start, end, 1439));
+        assert!(in_business_hours(start, end, 0));
+        assert!(in_business_hours(start, end, 359));
+        assert!(!in_business_hours(start, end, 360));
+        assert!(!in_business_hours(start, end, 720));
+        assert!(!in_business_hours(start, end, 1319));
+    }
+
+    #[test]
+    fn test_check_travel_speed() {
+        // 北京到上海约1070公里,1小时内超过900公里/小时
+        let detail = check_travel(Some(BEIJING), SHANGHAI, 3600).unwrap();
+        assert!(detail.contains("60分钟内从 北京(1.1.1.1) 到 上海(2.2.2.2)"), "{}", detail);
+        assert_eq!(check_travel(Some(BEIJING), SHANGHAI, 2 * 3600), None);
+    }
+
+    #[test]
+    fn test_check_travel_minimum_interval() {
+        // 间隔不足1分钟时按1分钟计算,同一秒登录不会除以0
+        let detail = check_travel(Some(BEIJING), SHANGHAI, 0).unwrap();
+        assert!(detail.starts_with("1分钟内"), "{}", detail);
+        assert!(check_travel(Some(BEIJING), SHANGHAI, 59).unwrap().starts_with("1分钟内"));
+    }
+
+    #[test]
+    fn test_check_travel_skipped() {
+        // 距离小于最小距离时不检测,避免IP定位误差
+        assert_eq!(check_travel(Some(BEIJING), TIANJIN, 60), None);
+        // 没有上次位置
+        assert_eq!(check_travel(None, SHANGHAI, 60), None);
+        // 晚于之后的登录写入的日志
+        assert_eq!(check_travel(Some(BEIJING), SHANGHAI, -60), None);
+    }
+}
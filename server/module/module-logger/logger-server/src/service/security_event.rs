@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::{Days, Local, TimeZone};
+use mongodb::bson::{doc, Bson};
+use common::base::page::PaginatedResponse;
+use common::constants::enum_constants::{security_event_name, SECURITY_LEVEL_HIGH};
+use common::context::context::LoginUserContext;
+use common::database::mongo::MongoManager;
+use logger_model::request::security_event::{SearchSecurityEventRequest, SecurityDashboardRequest};
+use logger_model::response::security_event::{SecurityDashboardResponse, SecurityEventCount, SecurityEventResponse};
+use crate::convert::security_event::model_to_response;
+use crate::service::log_filter;
+
+/// 每页最大数量
+const MAX_PAGE_SIZE: u64 = 500;
+/// 概览默认统计的天数
+const DEFAULT_DASHBOARD_DAYS: i64 = 7;
+/// 概览最多统计的天数
+const MAX_DASHBOARD_DAYS: i64 = 90;
+/// 概览中最近事件的数量
+const RECENT_SIZE: u64 = 10;
+
+/// 按条件分页检索
+pub async fn search(params: SearchSecurityEventRequest, login_user: LoginUserContext) -> Result<PaginatedResponse<SecurityEventResponse>> {
+    let filter = log_filter::security_event_filter(login_user.tenant_id, &params.filter)?;
+    let mongo = MongoManager::get();
+    let page_result = mongo.search_security_events(filter, params.page.max(1), params.size.clamp(1, MAX_PAGE_SIZE)).await?;
+    Ok(PaginatedResponse {
+        list: page_result.list.into_iter().map(model_to_response).collect(),
+        total_pages: page_result.total_pages,
+        page: page_result.page,
+        size: page_result.size,
+        total: page_result.total,
+    })
+}
+
+/// 最近几天的安全事件统计,按服务器时区分天
+pub async fn dashboard(params: SecurityDashboardRequest, login_user: LoginUserContext) -> Result<SecurityDashboardResponse> {
+    let days = params.days.unwrap_or(DEFAULT_DASHBOARD_DAYS).clamp(1, MAX_DASHBOARD_DAYS);
+    let now = Local::now();
+    let first_day = now.date_naive() - Days::new(days as u64 - 1);
+    let start_time = Local.from_local_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .map(|time| time.timestamp())
+        .unwrap_or_default();
+    let filter = doc! {"tenant_id": login_user.tenant_id, "operate_time": {"$gte": start_time}};
+
+    let mongo = MongoManager::get();
+    let by_type = mongo.count_security_events_by(filter.clone(), Bson::String("$event_type".to_string())).await?;
+    let by_level = mongo.count_security_events_by(filter.clone(), Bson::String("$level".to_string())).await?;
+    let day = doc! {"$dateToString": {
+        "format": "%Y-%m-%d",
+        "date": {"$toDate": {"$multiply": ["$operate_time", 1000_i64]}},
+        "timezone": now.format("%:z").to_string(),
+    }};
+    let by_day = mongo.count_security_events_by(filter.clone(), Bson::Document(day)).await?;
+    let recent = mongo.search_security_events(filter, 1, RECENT_SIZE).await?;
+
+    let total: u64 = by_type.iter().map(|(_, count)| count).sum();
+    let high_risk: u64 = by_level.iter()
+        .filter(|(level, _)| *level == SECURITY_LEVEL_HIGH.to_string())
+        .map(|(_, count)| count)
+        .sum();
+    // 没有事件的日期补0
+    let by_day = (0..days as u64)
+        .map(|offset| {
+            let date = (first_day + Days::new(offset)).format("%Y-%m-%d").to_string();
+            let count = by_day.iter().find(|(key, _)| *key == date).map(|(_, count)| *count).unwrap_or_default();
+            SecurityEventCount { name: date.clone(), key: date, count }
+        })
+        .collect();
+
+    Ok(SecurityDashboardResponse {
+        days,
+        total,
+        high_risk,
+        by_type: by_type.into_iter()
+            .map(|(key, count)| SecurityEventCount { name: security_event_name(&key).to_string(), key, count })
+            .collect(),
+        by_level: by_level.into_iter()
+            .map(|(key, count)| SecurityEventCount { name: level_name(&key).to_string(), key, count })
+            .collect(),
+        by_day,
+        recent: recent.list.into_iter().map(model_to_response).collect(),
+    })
+}
+
+fn level_name(level: &str) -> &'static str {
+    match level {
+        "1" => "低",
+        "2" => "中",
+        "3" => "高",
+        _ => "未知",
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Write;
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mongodb::bson::{Bson, Document};
+use mongodb::bson::oid::ObjectId;
+use tracing::info;
+use common::config::config::{Config, LoggerServerConfig};
+use common::constants::enum_constants::JOB_MISFIRE_IGNORE;
+use common::database::mongo::{LogCollection, MongoManager};
+use common::task::task_manager::{async_trait, ErrorAction, Task};
+use common::utils::minio_utils::MinioClient;
+
+/// 每次执行最多归档的批数,积压较多时分多次执行
+const MAX_BATCHES_PER_RUN: usize = 100;
+
+// 日志归档任务,过期的日志按租户压缩为ndjson文件保存到minio,标记已归档后由mongo的TTL索引删除
+// 归档路径: logger/archive/{login|operation}/{租户id}/{年}/{月}/{日}/{第一条日志的_id}.ndjson.gz
+pub struct LogArchiveTask {
+    pub name: String,
+    minio: MinioClient,
+    config: LoggerServerConfig,
+}
+
+impl LogArchiveTask {
+    pub fn new(minio: MinioClient) -> Self {
+        LogArchiveTask { name: "log archive".to_string(), minio, config: Config::load().logger_server }
+    }
+
+    /// 归档一个集合中过期的日志,返回归档的日志数
+    async fn archive(&self, collection: LogCollection) -> Result<usize> {
+        let mongo = MongoManager::get();
+        // 升级前写入的日志没有过期时间,按全局保留天数补充
+        let backfilled = mongo.backfill_expire_at(collection, self.config.retention_days).await?;
+        if backfilled > 0 {
+            info!("set expire time for {} {} logs", backfilled, collection.name());
+        }
+
+        let mut archived = 0;
+        for _ in 0..MAX_BATCHES_PER_RUN {
+            let docs = mongo.find_expired_logs(collection, self.config.archive_batch_size as i64).await?;
+            if docs.is_empty() {
+                break;
+            }
+            let ids = docs.iter()
+                .map(|doc| doc.get_object_id("_id"))
+                .collect::<Result<Vec<ObjectId>, _>>()?;
+            // 关闭归档时直接删除
+            if self.config.archive_enabled {
+                self.upload(collection, docs).await?;
+            }
+            archived += ids.len();
+            mongo.mark_archived(collection, ids).await?;
+        }
+        Ok(archived)
+    }
+
+    /// 按租户上传,上传失败时日志保持未归档,下次重新上传
+    async fn upload(&self, collection: LogCollection, docs: Vec<Document>) -> Result<()> {
+        let mut tenants: BTreeMap<i64, Vec<Document>> = BTreeMap::new();
+        for doc in docs {
+            let tenant_id = doc.get_i64("tenant_id").unwrap_or_default();
+            tenants.entry(tenant_id).or_default().push(doc);
+        }
+        let date = Local::now().format("%Y/%m/%d");
+        for (tenant_id, docs) in tenants {
+            let first_id = docs[0].get_object_id("_id")?;
+            let object_name = format!("logger/archive/{}/{}/{}/{}.ndjson.gz", collection.name(), tenant_id, date, first_id.to_hex());
+            let data = compress(docs)?;
+            self.minio.upload_object(&object_name, data).await
+                .map_err(|e| anyhow!("upload archive {} error: {:?}", object_name, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// 每行一条日志的json,gzip压缩
+fn compress(docs: Vec<Document>) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for doc in docs {
+        let line = serde_json::to_string(&Bson::Document(doc).into_relaxed_extjson())?;
+        encoder.write_all(line.as_bytes())?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+#[async_trait]
+impl Task for LogArchiveTask {
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for collection in LogCollection::ALL {
+            let archived = self.archive(collection).await?;
+            if archived > 0 {
+                info!("archive {} {} logs", archived, collection.name());
+            }
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction {
+        tracing::error!("execute task {} error: {}", self.name, error);
+        ErrorAction::Continue
+    }
+
+    // 每次执行都会处理全部过期的日志,错过的执行不需要补
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_IGNORE
+    }
+}
@@ -1,44 +1,26 @@
 use std::error::Error;
-use utoipa::gen::serde_json;
-use common::base::logger::LoginLogger;
-use common::database::redis::RedisManager;
-use common::database::redis_constants::REDIS_KEY_LOGGER_LOGIN_PREFIX;
-use common::task::task_manager::{ErrorAction, Task};
+use common::constants::enum_constants::JOB_MISFIRE_IGNORE;
+use common::event::log_stream::{LogQueue, LogStreamConsumer};
+use common::task::task_manager::{async_trait, ErrorAction, Task};
 use crate::service;
 
 // 登录日志任务
 pub struct LoginLoggerTask {
     pub name: String,
+    consumer: LogStreamConsumer,
 }
 
 impl LoginLoggerTask {
     pub fn new() -> Self {
-        LoginLoggerTask { name: "login logger".to_string() }
+        LoginLoggerTask { name: "login logger".to_string(), consumer: LogStreamConsumer::new(LogQueue::Login) }
     }
 }
 
+#[async_trait]
 impl Task for LoginLoggerTask {
-    fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        tracing::info!("execute task {}: {:?}", self.name, chrono::Local::now());
-        // 保存登录日志
-        let logs = RedisManager::lpop_all::<_, String>(REDIS_KEY_LOGGER_LOGIN_PREFIX)?;
-        if logs.is_empty() {
-            tracing::info!("login logger is empty");
-            return Ok(())
-        }
-        let mut login_loggers = Vec::new();
-        for log in logs {
-            let login_logger = serde_json::from_str::<LoginLogger>(&log)?;
-            login_loggers.push(login_logger);
-        }
-        tokio::spawn(async move {
-            match service::login_logger::add_batch(login_loggers).await {
-                Ok(_) => {}
-                Err(e) => {
-                    tracing::info!("save login logger error: {:#?}", e);
-                }
-            }
-        });
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // 保存登录日志,写入成功后确认,失败的日志重新投递
+        self.consumer.consume(service::login_logger::add_batch).await?;
         Ok(())
     }
 
@@ -46,4 +28,13 @@ impl Task for LoginLoggerTask {
         tracing::error!("execute task {} error: {}", self.name, error);
         ErrorAction::Continue
     }
-}
\ No newline at end of file
+
+    // 每次执行都会处理积压的日志,错过的执行不需要补
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_IGNORE
+    }
+
+    fn record_history(&self) -> bool {
+        false
+    }
+}
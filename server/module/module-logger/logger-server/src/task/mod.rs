@@ -1,2 +1,4 @@
 pub mod logger_task;
-pub mod operation_logger;
\ No newline at end of file
+pub mod operation_logger;
+pub mod log_archive_task;
+pub mod security_analysis_task;
\ No newline at end of file
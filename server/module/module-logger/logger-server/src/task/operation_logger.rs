@@ -1,50 +1,40 @@
 use std::error::Error;
-use utoipa::gen::serde_json;
-use common::base::logger::OperationLogger;
-use common::database::redis::RedisManager;
-use common::database::redis_constants::REDIS_KEY_LOGGER_OPERATION_PREFIX;
-use common::task::task_manager::{ErrorAction, Task};
+use common::constants::enum_constants::JOB_MISFIRE_IGNORE;
+use common::event::log_stream::{LogQueue, LogStreamConsumer};
+use common::task::task_manager::{async_trait, ErrorAction, Task};
 use crate::service;
 
 // 操作日志任务
 pub struct OperationLoggerTask {
     pub name: String,
+    consumer: LogStreamConsumer,
 }
 
 impl OperationLoggerTask {
     pub fn new() -> Self {
-        OperationLoggerTask { name: "operation logger".to_string() }
+        OperationLoggerTask { name: "operation logger".to_string(), consumer: LogStreamConsumer::new(LogQueue::Operation) }
     }
 }
 
+#[async_trait]
 impl Task for OperationLoggerTask {
-    fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        tracing::info!("execute operation logger task {}: {:?}", self.name, chrono::Local::now());
-        // 保存登录日志
-        let logs = RedisManager::lpop_all::<_, String>(REDIS_KEY_LOGGER_OPERATION_PREFIX)?;
-        if logs.is_empty() {
-            tracing::info!("operation logger is empty");
-            return Ok(())
-        }
-        // tracing::info!("operation loggers, {:?}", logs);
-        let mut operation_loggers = Vec::new();
-        for log in logs {
-            let operation_logger = serde_json::from_str::<OperationLogger>(&log)?;
-            operation_loggers.push(operation_logger);
-        }
-        tokio::spawn(async move {
-            match service::operation_logger::add_batch(operation_loggers).await {
-                Ok(_) => {}
-                Err(e) => {
-                    tracing::info!("save operation logger error: {:#?}", e);
-                }
-            }
-        });
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // 保存操作日志,写入成功后确认,失败的日志重新投递
+        self.consumer.consume(service::operation_logger::add_batch).await?;
         Ok(())
     }
 
     fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction {
-        tracing::error!("execute task {} error: {:#?}", self.name, error);
+        tracing::error!("execute task {} error: {}", self.name, error);
         ErrorAction::Continue
     }
-}
\ No newline at end of file
+
+    // 每次执行都会处理积压的日志,错过的执行不需要补
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_IGNORE
+    }
+
+    fn record_history(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use sea_orm::DatabaseConnection;
+use tracing::info;
+use common::constants::enum_constants::JOB_MISFIRE_IGNORE;
+use common::task::task_manager::{async_trait, ErrorAction, Task};
+use crate::service;
+
+// 登录安全分析任务,检测新IP、新设备、短时间内异地登录、连续登录失败和非工作时间登录,通知租户
+pub struct SecurityAnalysisTask {
+    pub name: String,
+    db: DatabaseConnection,
+}
+
+impl SecurityAnalysisTask {
+    pub fn new(db: DatabaseConnection) -> Self {
+        SecurityAnalysisTask { name: "security analysis".to_string(), db }
+    }
+}
+
+#[async_trait]
+impl Task for SecurityAnalysisTask {
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let analysed = service::security_analysis::analyse(&self.db).await?;
+        if analysed > 0 {
+            info!("analyse {} login logs", analysed);
+        }
+        Ok(())
+    }
+
+    fn on_error(&self, error: Box<dyn Error + Send + Sync>) -> ErrorAction {
+        tracing::error!("execute task {} error: {}", self.name, error);
+        ErrorAction::Continue
+    }
+
+    // 每次执行都会分析积压的日志,错过的执行不需要补
+    fn misfire_policy(&self) -> i8 {
+        JOB_MISFIRE_IGNORE
+    }
+
+    fn record_history(&self) -> bool {
+        false
+    }
+}
@@ -21,6 +21,7 @@ pub struct MallTradeBrokerageWithdrawResponse {
     
     pub user_name: Option<String>, // 真实姓名
     
+    #[serde_as(as = "Option<common::formatter::sensitive::Account>")]
     pub user_account: Option<String>, // 账号
     
     pub bank_name: Option<String>, // 银行名称
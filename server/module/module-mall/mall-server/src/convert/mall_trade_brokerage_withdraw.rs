@@ -1,17 +1,20 @@
 use sea_orm::{Set, NotSet};
+use anyhow::Result;
+use common::database::encrypted_string::EncryptedString;
+use common::formatter::sensitive;
 use crate::model::mall_trade_brokerage_withdraw::{self, Model as MallTradeBrokerageWithdraw, ActiveModel as MallTradeBrokerageWithdrawActiveModel};
 use mall_model::request::mall_trade_brokerage_withdraw::{CreateMallTradeBrokerageWithdrawRequest, UpdateMallTradeBrokerageWithdrawRequest};
 use mall_model::response::mall_trade_brokerage_withdraw::MallTradeBrokerageWithdrawResponse;
 
-pub fn create_request_to_model(request: &CreateMallTradeBrokerageWithdrawRequest) -> MallTradeBrokerageWithdrawActiveModel {
-    MallTradeBrokerageWithdrawActiveModel {
+pub fn create_request_to_model(request: &CreateMallTradeBrokerageWithdrawRequest) -> Result<MallTradeBrokerageWithdrawActiveModel> {
+    Ok(MallTradeBrokerageWithdrawActiveModel {
         user_id: Set(request.user_id.clone()),
         price: Set(request.price.clone()),
         fee_price: Set(request.fee_price.clone()),
         total_price: Set(request.total_price.clone()),
         r#type: Set(request.r#type.clone()),
         user_name: request.user_name.as_ref().map_or(NotSet, |user_name| Set(Some(user_name.clone()))),
-        user_account: request.user_account.as_ref().map_or(NotSet, |user_account| Set(Some(user_account.clone()))),
+        user_account: request.user_account.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |user_account| Set(Some(user_account))),
         bank_name: request.bank_name.as_ref().map_or(NotSet, |bank_name| Set(Some(bank_name.clone()))),
         bank_address: request.bank_address.as_ref().map_or(NotSet, |bank_address| Set(Some(bank_address.clone()))),
         qr_code_url: request.qr_code_url.as_ref().map_or(NotSet, |qr_code_url| Set(Some(qr_code_url.clone()))),
@@ -24,10 +27,10 @@ pub fn create_request_to_model(request: &CreateMallTradeBrokerageWithdrawRequest
         transfer_time: request.transfer_time.as_ref().map_or(NotSet, |transfer_time| Set(Some(transfer_time.clone()))),
         transfer_error_msg: request.transfer_error_msg.as_ref().map_or(NotSet, |transfer_error_msg| Set(Some(transfer_error_msg.clone()))),
         ..Default::default()
-    }
+    })
 }
 
-pub fn update_request_to_model(request: &UpdateMallTradeBrokerageWithdrawRequest, existing: MallTradeBrokerageWithdraw) -> MallTradeBrokerageWithdrawActiveModel {
+pub fn update_request_to_model(request: &UpdateMallTradeBrokerageWithdrawRequest, existing: MallTradeBrokerageWithdraw) -> Result<MallTradeBrokerageWithdrawActiveModel> {
     let mut active_model: MallTradeBrokerageWithdrawActiveModel = existing.into();
     if let Some(user_id) = &request.user_id { 
         active_model.user_id = Set(user_id.clone());
@@ -47,8 +50,8 @@ pub fn update_request_to_model(request: &UpdateMallTradeBrokerageWithdrawRequest
     if let Some(user_name) = &request.user_name { 
         active_model.user_name = Set(Some(user_name.clone()));
     }
-    if let Some(user_account) = &request.user_account { 
-        active_model.user_account = Set(Some(user_account.clone()));
+    if let Some(user_account) = sensitive::unmasked(&request.user_account) { 
+        active_model.user_account = Set(Some(EncryptedString::encrypt(user_account)?));
     }
     if let Some(bank_name) = &request.bank_name { 
         active_model.bank_name = Set(Some(bank_name.clone()));
@@ -83,7 +86,7 @@ pub fn update_request_to_model(request: &UpdateMallTradeBrokerageWithdrawRequest
     if let Some(transfer_error_msg) = &request.transfer_error_msg { 
         active_model.transfer_error_msg = Set(Some(transfer_error_msg.clone()));
     }
-    active_model
+    Ok(active_model)
 }
 
 pub fn model_to_response(model: MallTradeBrokerageWithdraw) -> MallTradeBrokerageWithdrawResponse {
@@ -95,7 +98,7 @@ pub fn model_to_response(model: MallTradeBrokerageWithdraw) -> MallTradeBrokerag
         total_price: model.total_price,
         r#type: model.r#type,
         user_name: model.user_name,
-        user_account: model.user_account,
+        user_account: model.user_account.map(String::from),
         bank_name: model.bank_name,
         bank_address: model.bank_address,
         qr_code_url: model.qr_code_url,
@@ -1,15 +1,25 @@
 use axum::handler::HandlerWithoutStateExt;
 use axum::http::Method;
 use common::config::config::Config;
+use common::utils::grpc_tls;
+use common::utils::jwt_utils;
+use system_common::grpc::system::GrpcLoginUserResolver;
+use common::config::system_config;
+use common::utils::snowflake_generator::SnowflakeGenerator;
 use common::database::mysql::{get_database_instance, DATABASE_INSTANCE};
 use common::task::task_manager::TaskManager;
+use common::task::key_rotation_task::{EncryptedColumn, KeyRotationTask};
 use once_cell::sync::Lazy;
 use std::sync::Arc;
+use std::time::Duration;
 use sea_orm::DatabaseConnection;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use uaparser::UserAgentParser;
 use common::middleware::logger;
+use common::monitor::health::{health_router, HealthChecker};
+use common::monitor::metrics::metrics_handler;
+use common::shutdown::shutdown::{self, ShutdownSignal};
 use common::state::app_state::AppState;
 
 mod api;
@@ -23,7 +33,15 @@ async fn main() -> Result<(), anyhow::Error> {
     // 初始化日志
     logger::init_tracing().await?;
     let config = Config::load();
-    let database = get_database_instance(config.database_url).await;
+    let database = get_database_instance(config.database.url).await;
+    // 加载系统参数
+    system_config::init(database).await?;
+    // 加载grpc证书
+    grpc_tls::init();
+    // 登录用户信息由系统服务校验令牌后返回
+    jwt_utils::set_login_user_resolver(GrpcLoginUserResolver);
+    // 申请雪花算法机器id
+    SnowflakeGenerator::init_global().await?;
 
     // let ua_parser = UserAgentParser::from_yaml("regexes.yaml").expect("Failed to load regexes.yaml");
 
@@ -34,31 +52,50 @@ async fn main() -> Result<(), anyhow::Error> {
 
     let state = AppState { db: database.clone(), ua_parser: None, minio: None };
 
+    // 初始化任务管理器
+    let mut task_manager = TaskManager::new(database.clone());
+    // 敏感字段使用当前密钥重新加密
+    task_manager.add_task(KeyRotationTask::new("MallKeyRotationTask", database.clone(), vec![
+        EncryptedColumn { table: "mall_trade_brokerage_withdraw", column: "user_account", index_column: None },
+    ]), &config.encryption.rotation_cron).await;
+
+    // 健康检查和指标,就绪检查项为服务依赖
+    let health_checker = HealthChecker::new()
+        .with_database(database.clone())
+        .with_redis()
+        .with_grpc("system", &config.grpc.system_service_url);
+
     let app = route::api(state).await
+        .layer(axum::middleware::from_fn(metrics_handler)) // 请求指标,需在合并健康检查路由前添加
+        .merge(health_router(health_checker))
+        .merge(logger::log_level_router()) // 运行中修改日志级别
         .layer(cors)
         .layer(axum::middleware::from_fn(logger::panic_handler)) // 添加异常处理中间件
         ;
 
-    let addr = format!("0.0.0.0:{}", config.mall_server_port);
+    // 退出信号
+    let shutdown_signal = ShutdownSignal::listen();
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout);
+
+    let addr = format!("0.0.0.0:{}", config.mall_server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     info!("Server running on {}", addr);
-    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>());
+    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal.clone().wait_owned());
 
+    // 收到退出信号后停止接收新连接,在超时时间内等待处理中的请求完成
     tokio::select! {
         result = server_future => {
             if let Err(e) = result {
-                eprintln!("Web server unexpected exit: {}", e);
+                tracing::error!("Web server unexpected exit: {}", e);
             }
-            println!("Web server has stopped");
+            info!("Web server has stopped");
         }
-        // _ = tokio::signal::ctrl_c() => {
-        //     println!("The program is closed when the exit signal is received");
-        //     task_manager.shutdown();
-        //     return Ok(());
-        // }
+        _ = shutdown_signal.drain_deadline(shutdown_timeout) => {}
     }
 
-    // task_manager.shutdown();
+    // 写入未完成的日志,关闭连接
+    shutdown::cleanup(Some(&task_manager), shutdown_timeout).await;
 
     Ok(())
 }
\ No newline at end of file
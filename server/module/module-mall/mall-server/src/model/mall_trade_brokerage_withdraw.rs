@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use sea_orm::Condition;
 use sea_orm::entity::prelude::*;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::database::encrypted_string::EncryptedString;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "mall_trade_brokerage_withdraw")]
@@ -22,7 +23,7 @@ pub struct Model {
     
     pub user_name: Option<String>, // 真实姓名
     
-    pub user_account: Option<String>, // 账号
+    pub user_account: Option<EncryptedString>, // 账号
     
     pub bank_name: Option<String>, // 银行名称
     
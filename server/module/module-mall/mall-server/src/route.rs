@@ -5,6 +5,8 @@ use utoipa_axum::router::OpenApiRouter;
 use common::middleware::request_context::request_context_handler;
 use common::middleware::authorize::{authorize_handler, init_route_authorizes};
 use common::middleware::operation_logger::operation_logger_handler;
+use common::middleware::idempotency::idempotency_handler;
+use common::middleware::rate_limit::rate_limit_handler;
 use common::utils::jwt_utils::AccessClaims;
 use std::sync::Arc;
 use crate::api::mall_product_brand::{ mall_product_brand_route, mall_product_brand_router };
@@ -147,8 +149,10 @@ pub async fn no_auth_router(state: AppState) -> OpenApiRouter {
         .nest("/mall_product_category", mall_product_category_no_auth_router(state.clone()).await)
         .nest("/mall_product_spu", mall_product_spu_no_auth_router(state.clone()).await)
         .nest("/mall_store", mall_store_no_auth_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
 }
 
@@ -207,8 +211,10 @@ pub async fn auth_router(state: AppState) -> OpenApiRouter {
         .nest("/mall_trade_order_item", mall_trade_order_item_router(state.clone()).await)
         .nest("/mall_trade_order_log", mall_trade_order_log_router(state.clone()).await)
         .nest("/mall_trade_statistics", mall_trade_statistics_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
@@ -19,7 +19,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     let mut mall_store = create_request_to_model(&request);
 
     // 生成店铺编号
-    let generator = SnowflakeGenerator::new();
+    let generator = SnowflakeGenerator::global();
     match generator.generate() {
         Ok(id) => mall_store.number = Set(format!("S{}", id)),
         Err(e) => return Err(anyhow!("店铺编号生成失败")),
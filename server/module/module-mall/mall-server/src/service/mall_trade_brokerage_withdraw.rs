@@ -12,7 +12,7 @@ use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateMallTradeBrokerageWithdrawRequest) -> Result<i64> {
-    let mut mall_trade_brokerage_withdraw = create_request_to_model(&request);
+    let mut mall_trade_brokerage_withdraw = create_request_to_model(&request)?;
     mall_trade_brokerage_withdraw.creator = Set(Some(login_user.id));
     mall_trade_brokerage_withdraw.updater = Set(Some(login_user.id));
     mall_trade_brokerage_withdraw.tenant_id = Set(login_user.tenant_id);
@@ -27,7 +27,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
         .await?
         .ok_or_else(|| anyhow!("记录未找到"))?;
 
-    let mut mall_trade_brokerage_withdraw = update_request_to_model(&request, mall_trade_brokerage_withdraw);
+    let mut mall_trade_brokerage_withdraw = update_request_to_model(&request, mall_trade_brokerage_withdraw)?;
     mall_trade_brokerage_withdraw.updater = Set(Some(login_user.id));
     mall_trade_brokerage_withdraw.update(db).await?;
     Ok(())
@@ -1,26 +1,39 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use async_trait::async_trait;
 use common::config::config::Config;
+use common::utils::grpc_client::GrpcClient;
+use common::utils::trace_utils::current_traceparent;
+use common::context::context::{DataPermission, LoginUserContext};
+use common::utils::jwt_utils::{AuthError, LoginUserResolver};
 use system_grpc::system_client::SystemClient;
-use system_model::response::{system_department::SystemDepartmentBaseResponse, system_user::SystemUserBaseResponse};
+use system_grpc::system_client::system::{DictDataResponse, RoleChangeEvent, TenantResponse, UserChangeEvent};
+use system_model::response::{system_area::AreaPathResponse, system_department::SystemDepartmentBaseResponse, system_user::SystemUserBaseResponse};
+use tonic::{Code, Status, Streaming};
 
+/// 系统服务客户端,连接复用,查询类调用失败时自动重试
 pub struct GrpcSystemService {
-    client: SystemClient,
+    client: GrpcClient,
+    traceparent: Option<String>,
 }
 
 impl GrpcSystemService {
-    pub async fn new() -> Result<Self> {
+    pub fn new() -> Self {
         let config = Config::load();
-        println!("grpc url, {:?}", config.grpc_system_service_url);
-        let client = SystemClient::new(&config.grpc_system_service_url)
-            .await
-            .map_err(|e| anyhow!("Failed to connect to gRPC service: {}", e))?;
-        Ok(Self { client })
+        Self {
+            client: GrpcClient::new("system", &config.grpc.system_service_url),
+            traceparent: current_traceparent(),
+        }
     }
 
-    pub async fn get_user(&mut self, ids: Vec<i64>) -> Result<Vec<SystemUserBaseResponse>> {
-        let result = self.client
-            .get_user(ids, "")
-            .await?;
+    fn system_client(&self, channel: tonic::transport::Channel) -> SystemClient {
+        SystemClient::from_channel(channel).with_traceparent(self.traceparent.clone())
+    }
+
+    pub async fn get_user(&self, ids: Vec<i64>) -> Result<Vec<SystemUserBaseResponse>> {
+        let result = self.client.call("GetUser", true, |channel| {
+            let (mut client, ids) = (self.system_client(channel), ids.clone());
+            async move { client.get_user(ids, "").await }
+        }).await?;
         let users = result.list.into_iter().map(|user| SystemUserBaseResponse {
           id: user.id,
           nickname: user.nickname,
@@ -28,10 +41,11 @@ impl GrpcSystemService {
         Ok(users)
     }
 
-    pub async fn get_department(&mut self, ids: Vec<i64>) -> Result<Vec<SystemDepartmentBaseResponse>> {
-        let result = self.client
-            .get_department(ids, "")
-            .await?;
+    pub async fn get_department(&self, ids: Vec<i64>) -> Result<Vec<SystemDepartmentBaseResponse>> {
+        let result = self.client.call("GetDepartment", true, |channel| {
+            let (mut client, ids) = (self.system_client(channel), ids.clone());
+            async move { client.get_department(ids, "").await }
+        }).await?;
         let departments = result.list.into_iter().map(|department| SystemDepartmentBaseResponse {
           id: department.id,
           code: "".to_string(),
@@ -39,14 +53,149 @@ impl GrpcSystemService {
         }).collect();
         Ok(departments)
     }
+
+    /// 校验访问令牌,返回登录用户信息
+    pub async fn validate_token(&self, token: &str) -> Result<LoginUserContext> {
+        let user = self.client.call("ValidateToken", true, |channel| {
+            let mut client = self.system_client(channel);
+            async move { client.validate_token(token, "").await }
+        }).await?;
+        Ok(LoginUserContext {
+            device_type: user.device_type,
+            id: user.id,
+            nickname: user.nickname,
+            tenant_id: user.tenant_id,
+            department_id: user.department_id,
+            department_code: user.department_code,
+            role_id: user.role_id,
+            permissions: user.permissions,
+            data_permission: user.data_permission.map(|data_permission| DataPermission {
+                id: data_permission.id,
+                name: data_permission.name,
+                field: data_permission.field,
+                condition: data_permission.condition,
+                value: data_permission.value,
+                data_scope_department_ids: data_permission.data_scope_department_ids,
+            }),
+        })
+    }
+
+    pub async fn check_permission(&self, token: &str, permissions: Vec<String>) -> Result<bool> {
+        self.client.call("CheckPermission", true, |channel| {
+            let (mut client, permissions) = (self.system_client(channel), permissions.clone());
+            async move { client.check_permission(token, permissions, "").await }
+        }).await
+    }
+
+    pub async fn list_subordinate_department_ids(&self, department_id: i64, include_self: bool) -> Result<Vec<i64>> {
+        self.client.call("ListSubordinateDepartmentIds", true, |channel| {
+            let mut client = self.system_client(channel);
+            async move { client.list_subordinate_department_ids(department_id, include_self, "").await }
+        }).await
+    }
+
+    pub async fn get_dict_data(&self, dict_types: Vec<String>) -> Result<Vec<DictDataResponse>> {
+        let result = self.client.call("GetDictData", true, |channel| {
+            let (mut client, dict_types) = (self.system_client(channel), dict_types.clone());
+            async move { client.get_dict_data(dict_types, "").await }
+        }).await?;
+        Ok(result.list)
+    }
+
+    pub async fn resolve_area_path(&self, ids: Vec<i32>) -> Result<Vec<AreaPathResponse>> {
+        let result = self.client.call("ResolveAreaPath", true, |channel| {
+            let (mut client, ids) = (self.system_client(channel), ids.clone());
+            async move { client.resolve_area_path(ids, "").await }
+        }).await?;
+        let areas = result.list.into_iter().map(|area| AreaPathResponse {
+          id: area.id,
+          name: area.name,
+          path: area.path,
+        }).collect();
+        Ok(areas)
+    }
+
+    pub async fn get_tenant(&self, tenant_id: i64) -> Result<TenantResponse> {
+        self.client.call("GetTenant", true, |channel| {
+            let mut client = self.system_client(channel);
+            async move { client.get_tenant(tenant_id, "").await }
+        }).await
+    }
+
+    /// 变更流只对建立订阅计时,不重试,断开后由订阅方重新订阅
+    pub async fn watch_user_changes(&self, tenant_id: Option<i64>) -> Result<Streaming<UserChangeEvent>> {
+        self.client.call("WatchUserChanges", false, |channel| {
+            let mut client = self.system_client(channel);
+            async move { client.watch_user_changes(tenant_id, "").await }
+        }).await
+    }
+
+    pub async fn watch_role_changes(&self, tenant_id: Option<i64>) -> Result<Streaming<RoleChangeEvent>> {
+        self.client.call("WatchRoleChanges", false, |channel| {
+            let mut client = self.system_client(channel);
+            async move { client.watch_role_changes(tenant_id, "").await }
+        }).await
+    }
 }
 
 pub async fn get_user(ids: Vec<i64>) -> Result<Vec<SystemUserBaseResponse>> {
-  let mut service = GrpcSystemService::new().await?;
-  service.get_user(ids).await
+  GrpcSystemService::new().get_user(ids).await
 }
 
 pub async fn get_department(ids: Vec<i64>) -> Result<Vec<SystemDepartmentBaseResponse>> {
-  let mut service = GrpcSystemService::new().await?;
-  service.get_department(ids).await
-}
\ No newline at end of file
+  GrpcSystemService::new().get_department(ids).await
+}
+
+pub async fn validate_token(token: &str) -> Result<LoginUserContext> {
+  GrpcSystemService::new().validate_token(token).await
+}
+
+pub async fn check_permission(token: &str, permissions: Vec<String>) -> Result<bool> {
+  GrpcSystemService::new().check_permission(token, permissions).await
+}
+
+pub async fn list_subordinate_department_ids(department_id: i64, include_self: bool) -> Result<Vec<i64>> {
+  GrpcSystemService::new().list_subordinate_department_ids(department_id, include_self).await
+}
+
+pub async fn get_dict_data(dict_types: Vec<String>) -> Result<Vec<DictDataResponse>> {
+  GrpcSystemService::new().get_dict_data(dict_types).await
+}
+
+pub async fn resolve_area_path(ids: Vec<i32>) -> Result<Vec<AreaPathResponse>> {
+  GrpcSystemService::new().resolve_area_path(ids).await
+}
+
+pub async fn get_tenant(tenant_id: i64) -> Result<TenantResponse> {
+  GrpcSystemService::new().get_tenant(tenant_id).await
+}
+
+/// 通过系统服务校验令牌,其他服务启动时设置: jwt_utils::set_login_user_resolver(GrpcLoginUserResolver)
+pub struct GrpcLoginUserResolver;
+
+#[async_trait]
+impl LoginUserResolver for GrpcLoginUserResolver {
+    async fn resolve(&self, token: &str) -> Result<LoginUserContext, AuthError> {
+        validate_token(token).await.map_err(|e| match e.downcast_ref::<Status>() {
+            Some(status) => auth_error(status),
+            None => {
+                tracing::error!("validate token error: {}", e);
+                AuthError::ServiceUnavailable
+            }
+        })
+    }
+}
+
+/// 系统服务的错误信息与 AuthError 对应
+fn auth_error(status: &Status) -> AuthError {
+    match (status.code(), status.message()) {
+        (Code::Unauthenticated, "认证已过期") => AuthError::TokenExpired,
+        (Code::Unauthenticated, "无效租户") => AuthError::InvalidTenant,
+        (Code::Unauthenticated, "授权失效") => AuthError::UserExpired,
+        (Code::Unauthenticated, _) => AuthError::InvalidToken,
+        _ => {
+            tracing::error!("validate token error: {}", status);
+            AuthError::ServiceUnavailable
+        }
+    }
+}
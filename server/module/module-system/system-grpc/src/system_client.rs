@@ -2,7 +2,10 @@ use anyhow::Result;
 use std::str::FromStr;
 
 use tonic::{metadata::MetadataValue, transport::Channel};
-use crate::{system_client::system::{system_service_client::SystemServiceClient, GetUserRequest, GetUserResponse, GetDepartmentRequest, GetDepartmentResponse}};
+use tonic::Streaming;
+use crate::{system_client::system::{system_service_client::SystemServiceClient, GetUserRequest, GetUserResponse, GetDepartmentRequest, GetDepartmentResponse,
+    ValidateTokenRequest, LoginUserResponse, CheckPermissionRequest, ListSubordinateDepartmentIdsRequest, GetDictDataRequest, GetDictDataResponse,
+    ResolveAreaPathRequest, ResolveAreaPathResponse, GetTenantRequest, TenantResponse, WatchChangesRequest, UserChangeEvent, RoleChangeEvent}};
 
 pub mod system {
     tonic::include_proto!("system");
@@ -10,14 +13,25 @@ pub mod system {
 
 pub struct SystemClient {
     client: SystemServiceClient<Channel>,
+    traceparent: Option<String>, // 链路追踪,随请求传给服务端
 }
 
 impl SystemClient {
     /// 创建一个新的 SystemClient 实例
     pub async fn new(server_addr: &str) -> Result<Self> {
         let channel = Channel::from_shared(server_addr.to_string())?.connect().await?;
-        let client = SystemServiceClient::new(channel);
-        Ok(SystemClient { client })
+        Ok(Self::from_channel(channel))
+    }
+
+    /// 使用已建立的连接创建,用于TLS等需要自定义连接的场景
+    pub fn from_channel(channel: Channel) -> Self {
+        SystemClient { client: SystemServiceClient::new(channel), traceparent: None }
+    }
+
+    /// 设置链路追踪traceparent
+    pub fn with_traceparent(mut self, traceparent: Option<String>) -> Self {
+        self.traceparent = traceparent;
+        self
     }
 
     /// 调用 GetUser 方法
@@ -28,8 +42,11 @@ impl SystemClient {
         // Add authorization to metadata
         let metadata = request.metadata_mut();
         metadata.insert("authorization", MetadataValue::from_str(authorization)?);
+        if let Some(traceparent) = &self.traceparent {
+            metadata.insert("traceparent", MetadataValue::from_str(traceparent)?);
+        }
         let response = self.client.get_user(request).await?;
-        println!("response: {:?}", response);
+        tracing::debug!("response: {:?}", response);
         Ok(response.into_inner())
     }
 
@@ -41,8 +58,92 @@ impl SystemClient {
         // Add authorization to metadata
         let metadata = request.metadata_mut();
         metadata.insert("authorization", MetadataValue::from_str(authorization)?);
+        if let Some(traceparent) = &self.traceparent {
+            metadata.insert("traceparent", MetadataValue::from_str(traceparent)?);
+        }
         let response = self.client.get_department(request).await?;
-        println!("response: {:?}", response);
+        tracing::debug!("response: {:?}", response);
+        Ok(response.into_inner())
+    }
+
+    /// 调用 ValidateToken 方法,token不带 Bearer 前缀
+    pub async fn validate_token(&mut self, token: &str, authorization: &str) -> Result<LoginUserResponse> {
+        let request = self.request(ValidateTokenRequest {
+            token: token.to_string(),
+        }, authorization)?;
+        let response = self.client.validate_token(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// 调用 CheckPermission 方法,拥有任意一个权限即返回true
+    pub async fn check_permission(&mut self, token: &str, permissions: Vec<String>, authorization: &str) -> Result<bool> {
+        let request = self.request(CheckPermissionRequest {
+            token: token.to_string(),
+            permissions,
+        }, authorization)?;
+        let response = self.client.check_permission(request).await?;
+        Ok(response.into_inner().allowed)
+    }
+
+    /// 调用 ListSubordinateDepartmentIds 方法
+    pub async fn list_subordinate_department_ids(&mut self, department_id: i64, include_self: bool, authorization: &str) -> Result<Vec<i64>> {
+        let request = self.request(ListSubordinateDepartmentIdsRequest {
+            department_id,
+            include_self,
+        }, authorization)?;
+        let response = self.client.list_subordinate_department_ids(request).await?;
+        Ok(response.into_inner().id)
+    }
+
+    /// 调用 GetDictData 方法
+    pub async fn get_dict_data(&mut self, dict_types: Vec<String>, authorization: &str) -> Result<GetDictDataResponse> {
+        let request = self.request(GetDictDataRequest {
+            dict_type: dict_types,
+        }, authorization)?;
+        let response = self.client.get_dict_data(request).await?;
         Ok(response.into_inner())
     }
-}
\ No newline at end of file
+
+    /// 调用 ResolveAreaPath 方法
+    pub async fn resolve_area_path(&mut self, area_id_list: Vec<i32>, authorization: &str) -> Result<ResolveAreaPathResponse> {
+        let request = self.request(ResolveAreaPathRequest {
+            id: area_id_list,
+        }, authorization)?;
+        let response = self.client.resolve_area_path(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// 调用 GetTenant 方法
+    pub async fn get_tenant(&mut self, tenant_id: i64, authorization: &str) -> Result<TenantResponse> {
+        let request = self.request(GetTenantRequest {
+            id: tenant_id,
+        }, authorization)?;
+        let response = self.client.get_tenant(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// 调用 WatchUserChanges 方法,tenant_id为空时接收全部租户的变更
+    pub async fn watch_user_changes(&mut self, tenant_id: Option<i64>, authorization: &str) -> Result<Streaming<UserChangeEvent>> {
+        let request = self.request(WatchChangesRequest { tenant_id }, authorization)?;
+        let response = self.client.watch_user_changes(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// 调用 WatchRoleChanges 方法,tenant_id为空时接收全部租户的变更
+    pub async fn watch_role_changes(&mut self, tenant_id: Option<i64>, authorization: &str) -> Result<Streaming<RoleChangeEvent>> {
+        let request = self.request(WatchChangesRequest { tenant_id }, authorization)?;
+        let response = self.client.watch_role_changes(request).await?;
+        Ok(response.into_inner())
+    }
+
+    /// 创建请求,在metadata中添加授权和链路追踪
+    fn request<T>(&self, message: T, authorization: &str) -> Result<tonic::Request<T>> {
+        let mut request = tonic::Request::new(message);
+        let metadata = request.metadata_mut();
+        metadata.insert("authorization", MetadataValue::from_str(authorization)?);
+        if let Some(traceparent) = &self.traceparent {
+            metadata.insert("traceparent", MetadataValue::from_str(traceparent)?);
+        }
+        Ok(request)
+    }
+}
@@ -28,4 +28,10 @@ pub struct RefreshTokenRequest {
 
     pub refresh_token: String, // 刷新token
 
-}
\ No newline at end of file
+}
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CaptchaDataRequest {
+
+    pub id: String, // 验证码类型id
+
+}
@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use common::base::page::PaginatedRequest;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CreateSystemConfigRequest {
+    
+    pub config_key: String, // 参数键
+    
+    pub config_value: String, // 参数值
+    
+    pub value_type: String, // 参数类型 string/integer/float/boolean/json
+    
+    pub name: String, // 参数名称
+    
+    pub remark: Option<String>, // 备注
+    
+    pub status: i8, // 状态（0正常 1停用）
+    
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UpdateSystemConfigRequest {
+    
+    pub id: i64, // id
+    
+    pub config_value: Option<String>, // 参数值
+    
+    pub name: Option<String>, // 参数名称
+    
+    pub remark: Option<String>, // 备注
+    
+    pub status: Option<i8>, // 状态（0正常 1停用）
+    
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PaginatedKeywordRequest {
+    #[serde(flatten)]
+    pub base: PaginatedRequest,
+    pub keyword: Option<String>,
+}
@@ -0,0 +1,29 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use common::base::page::PaginatedRequest;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UpdateSystemJobRequest {
+    
+    pub id: i64, // id
+    
+    pub misfire_policy: Option<i8>, // 错过执行策略（0补执行一次 1忽略 2全部补执行）
+    
+    pub remark: Option<String>, // 备注
+    
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PaginatedKeywordRequest {
+    #[serde(flatten)]
+    pub base: PaginatedRequest,
+    pub keyword: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PaginatedJobLogRequest {
+    #[serde(flatten)]
+    pub base: PaginatedRequest,
+    pub job_name: Option<String>, // 任务名称
+    pub status: Option<i8>, // 状态（0执行中 1成功 2失败）
+}
@@ -23,4 +23,28 @@ pub struct UserResponse {
     pub avatar: Option<String>, // 头像地址
 
     pub status: i8, // 帐号状态（0正常 1停用）
-}
\ No newline at end of file
+}
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct CaptchaDataResponse {
+    pub id: String, // 验证码类型id
+
+    pub captcha_key: String, // 验证码key
+
+    pub master_image_base64: String, // 主图
+
+    pub thumb_image_base64: String, // 缩略图
+
+    pub master_width: i32, // 主图宽度
+
+    pub master_height: i32, // 主图高度
+
+    pub thumb_width: i32, // 缩略图宽度
+
+    pub thumb_height: i32, // 缩略图高度
+
+    pub thumb_size: i32, // 缩略图尺寸,旋转验证码使用
+
+    pub display_x: i32, // 缩略图x坐标,滑动/拖拽验证码使用
+
+    pub display_y: i32, // 缩略图y坐标,滑动/拖拽验证码使用
+}
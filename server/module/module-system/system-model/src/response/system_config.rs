@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use serde_with::{serde_as, DisplayFromStr};
+use common::formatter::string_date_time::StringDateTime;
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SystemConfigResponse {
+    
+    pub id: i64, // id
+    
+    pub config_key: String, // 参数键
+    
+    pub config_value: String, // 参数值
+    
+    pub value_type: String, // 参数类型 string/integer/float/boolean/json
+    
+    pub name: String, // 参数名称
+    
+    pub remark: Option<String>, // 备注
+    
+    pub status: i8, // 状态（0正常 1停用）
+    
+    pub tenant_id: i64, // 租户编号,根租户为全局默认值
+    
+    pub creator: Option<i64>, // 创建者id
+    
+    #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
+    #[schema(value_type = String, format = Date)]
+    pub create_time: NaiveDateTime, // 创建时间
+    
+    pub updater: Option<i64>, // 更新者id
+    
+    #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
+    #[schema(value_type = String, format = Date)]
+    pub update_time: NaiveDateTime, // 更新时间
+    
+}
+
+/// 租户生效的参数值
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SystemConfigValueResponse {
+    
+    pub config_key: String, // 参数键
+    
+    pub config_value: Option<String>, // 生效的参数值
+    
+}
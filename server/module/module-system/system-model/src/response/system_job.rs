@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use serde_with::serde_as;
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SystemJobResponse {
+    
+    pub id: i64, // id
+    
+    pub name: String, // 任务名称
+    
+    pub cron_expression: String, // cron表达式
+    
+    pub status: i8, // 状态（0正常 1暂停）
+    
+    pub misfire_policy: i8, // 错过执行策略（0补执行一次 1忽略 2全部补执行）
+    
+    #[serde_as(as = "Option<common::formatter::string_date_time::StringDateTime>")]
+    #[schema(value_type = String, format = Date)]
+    pub last_fire_time: Option<NaiveDateTime>, // 上次计划执行时间
+    
+    #[serde_as(as = "Option<common::formatter::string_date_time::StringDateTime>")]
+    #[schema(value_type = String, format = Date)]
+    pub next_fire_time: Option<NaiveDateTime>, // 下次计划执行时间
+    
+    pub remark: Option<String>, // 备注
+    
+    pub updater: Option<i64>, // 更新者id
+    
+    #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
+    #[schema(value_type = String, format = Date)]
+    pub update_time: NaiveDateTime, // 更新时间
+    
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SystemJobLogResponse {
+    
+    pub id: i64, // id
+    
+    pub job_name: String, // 任务名称
+    
+    pub trigger_type: i8, // 触发方式（0计划 1手动 2错过补执行 3重试）
+    
+    pub attempt: i32, // 第几次执行
+    
+    pub status: i8, // 状态（0执行中 1成功 2失败）
+    
+    pub instance: String, // 执行实例
+    
+    #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
+    #[schema(value_type = String, format = Date)]
+    pub start_time: NaiveDateTime, // 开始时间
+    
+    #[serde_as(as = "Option<common::formatter::string_date_time::StringDateTime>")]
+    #[schema(value_type = String, format = Date)]
+    pub end_time: Option<NaiveDateTime>, // 结束时间
+    
+    pub duration: Option<i64>, // 耗时（毫秒）
+    
+    pub error: Option<String>, // 错误信息
+    
+}
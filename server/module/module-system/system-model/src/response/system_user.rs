@@ -4,7 +4,7 @@ use utoipa::ToSchema;
 use serde_with::{serde_as, DisplayFromStr};
 use common::formatter::string_date_time::StringDateTime;
 
-// #[serde_as]
+#[serde_as]
 #[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 pub struct SystemUserResponse {
     
@@ -20,6 +20,7 @@ pub struct SystemUserResponse {
     
     pub email: Option<String>, // 用户邮箱
     
+    #[serde_as(as = "Option<common::formatter::sensitive::Mobile>")]
     pub mobile: Option<String>, // 手机号码
     
     pub sex: Option<i8>, // 用户性别
@@ -71,6 +72,7 @@ pub struct SystemUserPageResponse {
     
     pub email: Option<String>, // 用户邮箱
     
+    #[serde_as(as = "Option<common::formatter::sensitive::Mobile>")]
     pub mobile: Option<String>, // 手机号码
     
     pub sex: Option<i8>, // 用户性别
@@ -1,20 +1,22 @@
 use crate::service;
-use axum::{extract::{Json, State}, routing::post, Extension, Router};
+use axum::{extract::{Json, Query, State}, routing::post, Extension, Router};
 use common::base::response::CommonResult;
 use common::context::context::{LoginUserContext, RequestContext};
 use common::utils::jwt_utils::AuthBody;
 use ctor;
-use system_model::request::system_auth::{LoginAccountRequest, LoginRequest, RefreshTokenRequest};
+use macros::rate_limit;
+use system_model::request::system_auth::{CaptchaDataRequest, LoginAccountRequest, LoginRequest, RefreshTokenRequest};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use common::state::app_state::AppState;
-use system_model::response::system_auth::{HomeResponse, UserResponse};
+use system_model::response::system_auth::{CaptchaDataResponse, HomeResponse, UserResponse};
 
 pub async fn system_auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .routes(routes!(login))
         .routes(routes!(login_account))
         .routes(routes!(refresh_token))
+        .routes(routes!(captcha))
         .with_state(state)
 }
 
@@ -42,6 +44,7 @@ pub async fn system_auth_route(state: AppState) -> Router {
     ),
     tag = "system_auth"
 )]
+#[rate_limit(operation_id = "system_auth_login", limit = 10, window = 60, key = "ip")]
 async fn login(
     State(state): State<AppState>,
     Extension(request_context): Extension<RequestContext>,
@@ -63,6 +66,7 @@ async fn login(
     ),
     tag = "system_auth"
 )]
+#[rate_limit(operation_id = "system_auth_login_account", limit = 10, window = 60, key = "ip")]
 async fn login_account(
     State(state): State<AppState>,
     Extension(request_context): Extension<RequestContext>,
@@ -84,6 +88,7 @@ async fn login_account(
     ),
     tag = "system_auth",
 )]
+#[rate_limit(operation_id = "system_auth_refresh_token", limit = 30, window = 60, key = "ip")]
 async fn refresh_token(
     State(state): State<AppState>,
     Json(payload): Json<RefreshTokenRequest>,
@@ -94,6 +99,28 @@ async fn refresh_token(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/captcha",
+    operation_id = "system_auth_captcha",
+    params(
+        ("id" = String, Query, description = "captcha type id")
+    ),
+    responses(
+        (status = 200, description = "captcha data", body = CommonResult<CaptchaDataResponse>)
+    ),
+    tag = "system_auth",
+)]
+#[rate_limit(operation_id = "system_auth_captcha", limit = 20, window = 60, key = "ip")]
+async fn captcha(
+    Query(params): Query<CaptchaDataRequest>,
+) -> CommonResult<CaptchaDataResponse> {
+    match service::system_auth::captcha_data(params.id).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/logout",
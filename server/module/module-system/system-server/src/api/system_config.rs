@@ -0,0 +1,277 @@
+use std::sync::Arc;
+use sea_orm::DatabaseConnection;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use ctor;
+use macros::require_authorize;
+use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
+use common::base::page::PaginatedResponse;
+use system_model::request::system_config::{CreateSystemConfigRequest, UpdateSystemConfigRequest, PaginatedKeywordRequest};
+use system_model::response::system_config::{SystemConfigResponse, SystemConfigValueResponse};
+use common::base::response::CommonResult;
+use common::context::context::LoginUserContext;
+use crate::service;
+use common::state::app_state::AppState;
+
+pub async fn system_config_router(state: AppState) -> OpenApiRouter {
+    OpenApiRouter::new()
+        .routes(routes!(create))
+        .routes(routes!(update))
+        .routes(routes!(delete))
+        .routes(routes!(get_by_id))
+        .routes(routes!(get_value))
+        .routes(routes!(list))
+        .routes(routes!(page))
+        .routes(routes!(enable))
+        .routes(routes!(disable))
+        .with_state(state)
+}
+
+pub async fn system_config_route(state: AppState) -> Router {
+    Router::new()
+        .route("/create", post(create))
+        .route("/update", post(update))
+        .route("/delete/{id}", post(delete))
+        .route("/get/{id}", get(get_by_id))
+        .route("/value/{key}", get(get_value))
+        .route("/list", get(list))
+        .route("/page", get(page))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    post,
+    path = "/create",
+    operation_id = "system_config_create",
+    request_body(content = CreateSystemConfigRequest, description = "create", content_type = "application/json"),
+    responses(
+        (status = 200, description = "id", body = CommonResult<i64>)
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_create", authorize = "system:config:add")]
+async fn create(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<CreateSystemConfigRequest>,
+) -> CommonResult<i64> {
+    match service::system_config::create(&state.db, login_user, payload).await {
+        Ok(id) => {CommonResult::with_data(id)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/update",
+    operation_id = "system_config_update",
+    request_body(content = UpdateSystemConfigRequest, description = "update", content_type = "application/json"),
+    responses(
+        (status = 204, description = "update")
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_update", authorize = "system:config:edit")]
+async fn update(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<UpdateSystemConfigRequest>,
+) -> CommonResult<()> {
+    match service::system_config::update(&state.db, login_user, payload).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/delete/{id}",
+    operation_id = "system_config_delete",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "delete")
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_delete", authorize = "system:config:delete")]
+async fn delete(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_config::delete(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/get/{id}",
+    operation_id = "system_config_get_by_id",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 200, description = "get by id", body = CommonResult<SystemConfigResponse>)
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_get_by_id", authorize = "system:config:get")]
+async fn get_by_id(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<SystemConfigResponse> {
+    match service::system_config::get_by_id(&state.db, login_user, id).await {
+        Ok(Some(data)) => {CommonResult::with_data(data)}
+        Ok(None) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/value/{key}",
+    operation_id = "system_config_get_value",
+    params(
+        ("key" = String, Path, description = "config key")
+    ),
+    responses(
+        (status = 200, description = "get effective value", body = CommonResult<SystemConfigValueResponse>)
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_get_value", authorize = "system:config:get")]
+async fn get_value(
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(key): Path<String>,
+) -> CommonResult<SystemConfigValueResponse> {
+    match service::system_config::get_value(login_user, key).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/page",
+    operation_id = "system_config_page",
+    params(
+        ("page" = u64, Query, description = "page number"),
+        ("size" = u64, Query, description = "page size"),
+        ("keyword" = Option<String>, Query, description = "keyword")
+    ),
+    responses(
+        (status = 200, description = "get page", body = CommonResult<PaginatedResponse<SystemConfigResponse>>)
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_page", authorize = "system:config:get")]
+async fn page(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Query(params): Query<PaginatedKeywordRequest>,
+) -> CommonResult<PaginatedResponse<SystemConfigResponse>> {
+    match service::system_config::get_paginated(&state.db, login_user, params).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/list",
+    operation_id = "system_config_list",
+    responses(
+        (status = 200, description = "list all", body = CommonResult<Vec<SystemConfigResponse>>)
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_list", authorize = "system:config:get")]
+async fn list(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+) -> CommonResult<Vec<SystemConfigResponse>> {
+    match service::system_config::list(&state.db, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/enable/{id}",
+    operation_id = "system_config_enable",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "enable")
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_enable", authorize = "system:config:enable")]
+async fn enable(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_config::enable(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/disable/{id}",
+    operation_id = "system_config_disable",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "disable")
+    ),
+    tag = "system_config",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_config_disable", authorize = "system:config:disable")]
+async fn disable(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_config::disable(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
\ No newline at end of file
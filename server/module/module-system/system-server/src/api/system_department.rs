@@ -3,7 +3,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{audit, require_authorize};
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
 use system_model::{request::system_department::{CreateSystemDepartmentRequest, PaginatedKeywordRequest, UpdateSystemDepartmentRequest}, response::system_department::SystemDepartmentPageResponse};
@@ -51,6 +51,7 @@ pub async fn system_department_route(state: AppState) -> Router {
     )
 )]
 #[require_authorize(operation_id = "system_department_create", authorize = "system:department:add")]
+#[audit(operation_id = "system_department_create", module = "system_department", action = "create", biz_id = "response", table = "system_department")]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -76,6 +77,7 @@ async fn create(
     )
 )]
 #[require_authorize(operation_id = "system_department_update", authorize = "system:department:edit")]
+#[audit(operation_id = "system_department_update", module = "system_department", action = "update", biz_id = "body.id", table = "system_department")]
 async fn update(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -103,6 +105,7 @@ async fn update(
     )
 )]
 #[require_authorize(operation_id = "system_department_delete", authorize = "system:department:delete")]
+#[audit(operation_id = "system_department_delete", module = "system_department", action = "delete", biz_id = "path.id", table = "system_department")]
 async fn delete(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -210,6 +213,7 @@ async fn list(
     )
 )]
 #[require_authorize(operation_id = "system_department_enable", authorize = "system:department:enable")]
+#[audit(operation_id = "system_department_enable", module = "system_department", action = "enable", biz_id = "path.id", table = "system_department")]
 async fn enable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -237,6 +241,7 @@ async fn enable(
     )
 )]
 #[require_authorize(operation_id = "system_department_disable", authorize = "system:department:disable")]
+#[audit(operation_id = "system_department_disable", module = "system_department", action = "disable", biz_id = "path.id", table = "system_department")]
 async fn disable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
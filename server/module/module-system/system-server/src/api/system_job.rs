@@ -0,0 +1,256 @@
+use std::sync::Arc;
+use sea_orm::DatabaseConnection;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use ctor;
+use macros::require_authorize;
+use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
+use common::base::page::PaginatedResponse;
+use system_model::request::system_job::{UpdateSystemJobRequest, PaginatedKeywordRequest, PaginatedJobLogRequest};
+use system_model::response::system_job::{SystemJobResponse, SystemJobLogResponse};
+use common::base::response::CommonResult;
+use common::context::context::LoginUserContext;
+use crate::service;
+use common::state::app_state::AppState;
+
+pub async fn system_job_router(state: AppState) -> OpenApiRouter {
+    OpenApiRouter::new()
+        .routes(routes!(update))
+        .routes(routes!(get_by_id))
+        .routes(routes!(list))
+        .routes(routes!(page))
+        .routes(routes!(pause))
+        .routes(routes!(resume))
+        .routes(routes!(trigger))
+        .routes(routes!(log_page))
+        .with_state(state)
+}
+
+pub async fn system_job_route(state: AppState) -> Router {
+    Router::new()
+        .route("/update", post(update))
+        .route("/get/{id}", get(get_by_id))
+        .route("/list", get(list))
+        .route("/page", get(page))
+        .route("/pause/{id}", post(pause))
+        .route("/resume/{id}", post(resume))
+        .route("/trigger/{id}", post(trigger))
+        .route("/log/page", get(log_page))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    post,
+    path = "/update",
+    operation_id = "system_job_update",
+    request_body(content = UpdateSystemJobRequest, description = "update", content_type = "application/json"),
+    responses(
+        (status = 204, description = "update")
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_update", authorize = "system:job:edit")]
+async fn update(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Json(payload): Json<UpdateSystemJobRequest>,
+) -> CommonResult<()> {
+    match service::system_job::update(&state.db, login_user, payload).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/get/{id}",
+    operation_id = "system_job_get_by_id",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 200, description = "get by id", body = CommonResult<SystemJobResponse>)
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_get_by_id", authorize = "system:job:get")]
+async fn get_by_id(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<SystemJobResponse> {
+    match service::system_job::get_by_id(&state.db, login_user, id).await {
+        Ok(Some(data)) => {CommonResult::with_data(data)}
+        Ok(None) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/page",
+    operation_id = "system_job_page",
+    params(
+        ("page" = u64, Query, description = "page number"),
+        ("size" = u64, Query, description = "page size"),
+        ("keyword" = Option<String>, Query, description = "keyword")
+    ),
+    responses(
+        (status = 200, description = "get page", body = CommonResult<PaginatedResponse<SystemJobResponse>>)
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_page", authorize = "system:job:get")]
+async fn page(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Query(params): Query<PaginatedKeywordRequest>,
+) -> CommonResult<PaginatedResponse<SystemJobResponse>> {
+    match service::system_job::get_paginated(&state.db, login_user, params).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/list",
+    operation_id = "system_job_list",
+    responses(
+        (status = 200, description = "list all", body = CommonResult<Vec<SystemJobResponse>>)
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_list", authorize = "system:job:get")]
+async fn list(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+) -> CommonResult<Vec<SystemJobResponse>> {
+    match service::system_job::list(&state.db, login_user).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/pause/{id}",
+    operation_id = "system_job_pause",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "pause")
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_pause", authorize = "system:job:pause")]
+async fn pause(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_job::pause(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/resume/{id}",
+    operation_id = "system_job_resume",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "resume")
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_resume", authorize = "system:job:resume")]
+async fn resume(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_job::resume(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/trigger/{id}",
+    operation_id = "system_job_trigger",
+    params(
+        ("id" = i64, Path, description = "id")
+    ),
+    responses(
+        (status = 204, description = "trigger")
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_trigger", authorize = "system:job:trigger")]
+async fn trigger(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Path(id): Path<i64>,
+) -> CommonResult<()> {
+    match service::system_job::trigger(&state.db, login_user, id).await {
+        Ok(_) => {CommonResult::with_none()}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/log/page",
+    operation_id = "system_job_log_page",
+    params(
+        ("page" = u64, Query, description = "page number"),
+        ("size" = u64, Query, description = "page size"),
+        ("job_name" = Option<String>, Query, description = "job name"),
+        ("status" = Option<i8>, Query, description = "status")
+    ),
+    responses(
+        (status = 200, description = "get log page", body = CommonResult<PaginatedResponse<SystemJobLogResponse>>)
+    ),
+    tag = "system_job",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[require_authorize(operation_id = "system_job_log_page", authorize = "system:job:log")]
+async fn log_page(
+    State(state): State<AppState>,
+    Extension(login_user): Extension<LoginUserContext>,
+    Query(params): Query<PaginatedJobLogRequest>,
+) -> CommonResult<PaginatedResponse<SystemJobLogResponse>> {
+    match service::system_job::get_log_paginated(&state.db, login_user, params).await {
+        Ok(data) => {CommonResult::with_data(data)}
+        Err(e) => {CommonResult::with_err(&e.to_string())}
+    }
+}
\ No newline at end of file
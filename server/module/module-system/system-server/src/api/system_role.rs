@@ -3,7 +3,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{audit, require_authorize};
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
 use system_model::{request::system_role::{CreateSystemRoleRequest, PaginatedKeywordRequest, UpdateSystemRoleRequest, UpdateSystemRoleRuleRequest}, response::system_role::SystemRoleRuleResponse};
@@ -52,6 +52,7 @@ pub async fn system_role_route(state: AppState) -> Router {
     )
 )]
 #[require_authorize(operation_id = "system_role_create", authorize = "system:role:add")]
+#[audit(operation_id = "system_role_create", module = "system_role", action = "create", biz_id = "response", table = "system_role")]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -77,6 +78,7 @@ async fn create(
     )
 )]
 #[require_authorize(operation_id = "system_role_update", authorize = "system:role:edit")]
+#[audit(operation_id = "system_role_update", module = "system_role", action = "update", biz_id = "body.id", table = "system_role")]
 async fn update(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -102,6 +104,7 @@ async fn update(
     )
 )]
 #[require_authorize(operation_id = "system_role_update_rule", authorize = "system:role:data")]
+#[audit(operation_id = "system_role_update_rule", module = "system_role", action = "update_rule", biz_id = "body.id", table = "system_role")]
 async fn update_rule(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -129,6 +132,7 @@ async fn update_rule(
     )
 )]
 #[require_authorize(operation_id = "system_role_delete", authorize = "system:role:delete")]
+#[audit(operation_id = "system_role_delete", module = "system_role", action = "delete", biz_id = "path.id", table = "system_role")]
 async fn delete(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -236,6 +240,7 @@ async fn list(
     )
 )]
 #[require_authorize(operation_id = "system_role_enable", authorize = "system:role:enable")]
+#[audit(operation_id = "system_role_enable", module = "system_role", action = "enable", biz_id = "path.id", table = "system_role")]
 async fn enable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -263,6 +268,7 @@ async fn enable(
     )
 )]
 #[require_authorize(operation_id = "system_role_disable", authorize = "system:role:disable")]
+#[audit(operation_id = "system_role_disable", module = "system_role", action = "disable", biz_id = "path.id", table = "system_role")]
 async fn disable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -3,7 +3,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{audit, require_authorize};
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
 use system_model::request::system_role_menu::{UpdateSystemRoleMenuRequest};
@@ -42,6 +42,7 @@ pub async fn system_role_menu_route(state: AppState) -> Router {
     )
 )]
 #[require_authorize(operation_id = "system_role_menu_update", authorize = "system:role:menu")]
+#[audit(operation_id = "system_role_menu_update", module = "system_role_menu", action = "update", biz_id = "body.role_id")]
 async fn update(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
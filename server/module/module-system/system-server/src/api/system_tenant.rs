@@ -3,7 +3,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{audit, require_authorize};
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
 use system_model::{request::system_tenant::{CreateSystemTenantRequest, PaginatedKeywordRequest, UpdateSystemTenantRequest}, response::system_tenant::SystemTenantPageResponse};
@@ -57,6 +57,7 @@ pub async fn system_tenant_route(state: AppState) -> Router {
     )
 )]
 #[require_authorize(operation_id = "system_tenant_create", authorize = "system:tenant:list:add")]
+#[audit(operation_id = "system_tenant_create", module = "system_tenant", action = "create", biz_id = "response", table = "system_tenant")]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -65,7 +66,7 @@ async fn create(
     match service::system_tenant::create(&state.db, login_user, payload).await {
         Ok(id) => {CommonResult::with_data(id)}
         Err(e) => {
-            eprintln!("Error: {:#?}", e);
+            tracing::error!("create tenant error, {:#?}", e);
             CommonResult::with_err(&e.to_string())
         }
     }
@@ -85,6 +86,7 @@ async fn create(
     )
 )]
 #[require_authorize(operation_id = "system_tenant_update", authorize = "system:tenant:list:edit")]
+#[audit(operation_id = "system_tenant_update", module = "system_tenant", action = "update", biz_id = "body.id", table = "system_tenant")]
 async fn update(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -112,6 +114,7 @@ async fn update(
     )
 )]
 #[require_authorize(operation_id = "system_tenant_delete", authorize = "system:tenant:list:delete")]
+#[audit(operation_id = "system_tenant_delete", module = "system_tenant", action = "delete", biz_id = "path.id", table = "system_tenant")]
 async fn delete(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -242,6 +245,7 @@ async fn list(
     )
 )]
 #[require_authorize(operation_id = "system_tenant_enable", authorize = "system:tenant:list:enable")]
+#[audit(operation_id = "system_tenant_enable", module = "system_tenant", action = "enable", biz_id = "path.id", table = "system_tenant")]
 async fn enable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -269,6 +273,7 @@ async fn enable(
     )
 )]
 #[require_authorize(operation_id = "system_tenant_disable", authorize = "system:tenant:list:disable")]
+#[audit(operation_id = "system_tenant_disable", module = "system_tenant", action = "disable", biz_id = "path.id", table = "system_tenant")]
 async fn disable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
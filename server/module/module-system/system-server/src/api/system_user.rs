@@ -3,7 +3,7 @@ use sea_orm::DatabaseConnection;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use ctor;
-use macros::require_authorize;
+use macros::{audit, require_authorize};
 use axum::{routing::{get, post}, Router, extract::{State, Path, Json, Query}, response::IntoResponse, Extension};
 use common::base::page::PaginatedResponse;
 use system_model::{request::system_user::{CreateSystemUserRequest, EditPasswordSystemUserRequest, PaginatedKeywordRequest, ResetPasswordSystemUserRequest, UpdateSystemUserRequest}, response::system_user::{SystemUserBaseResponse, SystemUserPageResponse}};
@@ -54,6 +54,7 @@ pub async fn system_user_route(state: AppState) -> Router {
     )
 )]
 #[require_authorize(operation_id = "system_user_create", authorize = "system:user:add")]
+#[audit(operation_id = "system_user_create", module = "system_user", action = "create", biz_id = "response", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn create(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -62,7 +63,7 @@ async fn create(
     match service::system_user::create(&state.db, login_user, payload).await {
         Ok(id) => {CommonResult::with_data(id)}
         Err(e) => {
-            eprintln!("Error: {:#?}", e);
+            tracing::error!("create user error, {:#?}", e);
             CommonResult::with_err(&e.to_string())
         }
     }
@@ -82,6 +83,7 @@ async fn create(
     )
 )]
 #[require_authorize(operation_id = "system_user_update", authorize = "system:user:edit")]
+#[audit(operation_id = "system_user_update", module = "system_user", action = "update", biz_id = "body.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn update(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -109,6 +111,7 @@ async fn update(
     )
 )]
 #[require_authorize(operation_id = "system_user_delete", authorize = "system:user:delete")]
+#[audit(operation_id = "system_user_delete", module = "system_user", action = "delete", biz_id = "path.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn delete(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -216,6 +219,7 @@ async fn list(
     )
 )]
 #[require_authorize(operation_id = "system_user_enable", authorize = "system:user:enable")]
+#[audit(operation_id = "system_user_enable", module = "system_user", action = "enable", biz_id = "path.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn enable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -243,6 +247,7 @@ async fn enable(
     )
 )]
 #[require_authorize(operation_id = "system_user_disable", authorize = "system:user:disable")]
+#[audit(operation_id = "system_user_disable", module = "system_user", action = "disable", biz_id = "path.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn disable(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -294,6 +299,7 @@ async fn list_department_user(
     )
 )]
 #[require_authorize(operation_id = "system_user_reset_password", authorize = "")]
+#[audit(operation_id = "system_user_reset_password", module = "system_user", action = "reset_password", biz_id = "body.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn reset_password(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
@@ -319,6 +325,7 @@ async fn reset_password(
     )
 )]
 #[require_authorize(operation_id = "system_user_edit_password", authorize = "")]
+#[audit(operation_id = "system_user_edit_password", module = "system_user", action = "edit_password", biz_id = "body.id", table = "system_user", redact = "password,old_password,new_password,mobile")]
 async fn edit_password(
     State(state): State<AppState>,
     Extension(login_user): Extension<LoginUserContext>,
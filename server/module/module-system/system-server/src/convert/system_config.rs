@@ -0,0 +1,50 @@
+use sea_orm::{Set, NotSet};
+use crate::model::system_config::{self, Model as SystemConfig, ActiveModel as SystemConfigActiveModel};
+use system_model::request::system_config::{CreateSystemConfigRequest, UpdateSystemConfigRequest};
+use system_model::response::system_config::SystemConfigResponse;
+
+pub fn create_request_to_model(request: &CreateSystemConfigRequest) -> SystemConfigActiveModel {
+    SystemConfigActiveModel {
+        config_key: Set(request.config_key.clone()),
+        config_value: Set(request.config_value.clone()),
+        value_type: Set(request.value_type.to_lowercase()),
+        name: Set(request.name.clone()),
+        remark: request.remark.as_ref().map_or(NotSet, |remark| Set(Some(remark.clone()))),
+        status: Set(request.status.clone()),
+        ..Default::default()
+    }
+}
+
+pub fn update_request_to_model(request: &UpdateSystemConfigRequest, existing: SystemConfig) -> SystemConfigActiveModel {
+    let mut active_model: SystemConfigActiveModel = existing.into();
+    if let Some(config_value) = &request.config_value { 
+        active_model.config_value = Set(config_value.clone());
+    }
+    if let Some(name) = &request.name { 
+        active_model.name = Set(name.clone());
+    }
+    if let Some(remark) = &request.remark { 
+        active_model.remark = Set(Some(remark.clone()));
+    }
+    if let Some(status) = &request.status { 
+        active_model.status = Set(status.clone());
+    }
+    active_model
+}
+
+pub fn model_to_response(model: SystemConfig) -> SystemConfigResponse {
+    SystemConfigResponse { 
+        id: model.id,
+        config_key: model.config_key,
+        config_value: model.config_value,
+        value_type: model.value_type,
+        name: model.name,
+        remark: model.remark,
+        status: model.status,
+        tenant_id: model.tenant_id,
+        creator: model.creator,
+        create_time: model.create_time,
+        updater: model.updater,
+        update_time: model.update_time,
+    }
+}
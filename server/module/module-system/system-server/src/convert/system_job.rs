@@ -0,0 +1,46 @@
+use sea_orm::Set;
+use crate::model::system_job::{Model as SystemJob, ActiveModel as SystemJobActiveModel};
+use crate::model::system_job_log::Model as SystemJobLog;
+use system_model::request::system_job::UpdateSystemJobRequest;
+use system_model::response::system_job::{SystemJobResponse, SystemJobLogResponse};
+
+pub fn update_request_to_model(request: &UpdateSystemJobRequest, existing: SystemJob) -> SystemJobActiveModel {
+    let mut active_model: SystemJobActiveModel = existing.into();
+    if let Some(misfire_policy) = &request.misfire_policy { 
+        active_model.misfire_policy = Set(misfire_policy.clone());
+    }
+    if let Some(remark) = &request.remark { 
+        active_model.remark = Set(Some(remark.clone()));
+    }
+    active_model
+}
+
+pub fn model_to_response(model: SystemJob) -> SystemJobResponse {
+    SystemJobResponse { 
+        id: model.id,
+        name: model.name,
+        cron_expression: model.cron_expression,
+        status: model.status,
+        misfire_policy: model.misfire_policy,
+        last_fire_time: model.last_fire_time,
+        next_fire_time: model.next_fire_time,
+        remark: model.remark,
+        updater: model.updater,
+        update_time: model.update_time,
+    }
+}
+
+pub fn log_model_to_response(model: SystemJobLog) -> SystemJobLogResponse {
+    SystemJobLogResponse { 
+        id: model.id,
+        job_name: model.job_name,
+        trigger_type: model.trigger_type,
+        attempt: model.attempt,
+        status: model.status,
+        instance: model.instance,
+        start_time: model.start_time,
+        end_time: model.end_time,
+        duration: model.duration,
+        error: model.error,
+    }
+}
@@ -1,25 +1,28 @@
 use sea_orm::{Set, NotSet};
+use anyhow::Result;
+use common::database::encrypted_string::EncryptedString;
+use common::formatter::sensitive;
 use crate::model::system_user::{self, Model as SystemUser, ActiveModel as SystemUserActiveModel};
 use crate::model::system_role::{self, Model as SystemRole, ActiveModel as SystemRoleActiveModel};
 use crate::model::system_department::{self, Model as SystemDepartment, ActiveModel as SystemDepartmentActiveModel};
 use system_model::request::system_user::{CreateSystemUserRequest, UpdateSystemUserRequest};
 use system_model::response::system_user::{SystemUserBaseResponse, SystemUserPageResponse, SystemUserResponse};
 
-pub fn create_request_to_model(request: &CreateSystemUserRequest) -> SystemUserActiveModel {
-    SystemUserActiveModel {
+pub fn create_request_to_model(request: &CreateSystemUserRequest) -> Result<SystemUserActiveModel> {
+    Ok(SystemUserActiveModel {
         username: Set(request.username.clone()),
         nickname: Set(request.nickname.clone()),
         remark: request.remark.as_ref().map_or(NotSet, |remark| Set(Some(remark.clone()))),
         email: request.email.as_ref().map_or(NotSet, |email| Set(Some(email.clone()))),
-        mobile: request.mobile.as_ref().map_or(NotSet, |mobile| Set(Some(mobile.clone()))),
+        mobile: request.mobile.as_deref().map(EncryptedString::encrypt).transpose()?.map_or(NotSet, |mobile| Set(Some(mobile))),
         sex: request.sex.as_ref().map_or(NotSet, |sex| Set(Some(sex.clone()))),
         status: Set(request.status.clone()),
         department_id: Set(request.department_id.clone()),
         ..Default::default()
-    }
+    })
 }
 
-pub fn update_request_to_model(request: &UpdateSystemUserRequest, existing: SystemUser) -> SystemUserActiveModel {
+pub fn update_request_to_model(request: &UpdateSystemUserRequest, existing: SystemUser) -> Result<SystemUserActiveModel> {
     let mut active_model: SystemUserActiveModel = existing.into();
     if let Some(nickname) = &request.nickname { 
         active_model.nickname = Set(nickname.clone());
@@ -30,8 +33,8 @@ pub fn update_request_to_model(request: &UpdateSystemUserRequest, existing: Syst
     if let Some(email) = &request.email { 
         active_model.email = Set(Some(email.clone()));
     }
-    if let Some(mobile) = &request.mobile { 
-        active_model.mobile = Set(Some(mobile.clone()));
+    if let Some(mobile) = sensitive::unmasked(&request.mobile) { 
+        active_model.mobile = Set(Some(EncryptedString::encrypt(mobile)?));
     }
     if let Some(sex) = &request.sex { 
         active_model.sex = Set(Some(sex.clone()));
@@ -42,7 +45,7 @@ pub fn update_request_to_model(request: &UpdateSystemUserRequest, existing: Syst
     if let Some(department_id) = &request.department_id { 
         active_model.department_id = Set(department_id.clone());
     }
-    active_model
+    Ok(active_model)
 }
 
 pub fn model_to_response(model: SystemUser) -> SystemUserResponse {
@@ -53,7 +56,7 @@ pub fn model_to_response(model: SystemUser) -> SystemUserResponse {
         nickname: model.nickname,
         remark: model.remark,
         email: model.email,
-        mobile: model.mobile,
+        mobile: model.mobile.map(String::from),
         sex: model.sex,
         avatar: model.avatar,
         status: model.status,
@@ -94,7 +97,7 @@ pub fn model_to_page_response(model: SystemUser, model_role: Option<SystemRole>,
         nickname: model.nickname,
         remark: model.remark,
         email: model.email,
-        mobile: model.mobile,
+        mobile: model.mobile.map(String::from),
         sex: model.sex,
         avatar: model.avatar,
         status: model.status,
@@ -1,18 +1,33 @@
-use anyhow::{anyhow, Result};
-use captcha_grpc_rust::CaptchaClient;
+use anyhow::Result;
+use captcha_grpc_rust::{CaptchaClient, GetDataResponse};
 use common::config::config::Config;
+use common::utils::grpc_client::GrpcClient;
+use common::utils::trace_utils::current_traceparent;
 
 const API_KEY: &str = "synerunify-captcha-secret-key-12345678";
 
+/// 校验验证码,校验后验证码失效,不重试
 pub async fn check_status(captcha_key: String) -> Result<bool> {
-    let mut client = get_client().await?;
-    let result = client
-        .check_status(captcha_key.to_string(), API_KEY)
-        .await?;
-    Ok(result)
+    let config = Config::load();
+    let traceparent = current_traceparent();
+    GrpcClient::new("captcha", &config.grpc.captcha_service_url)
+        .call("CheckStatus", false, |channel| {
+            let mut client = CaptchaClient::from_channel(channel).with_traceparent(traceparent.clone());
+            let captcha_key = captcha_key.clone();
+            async move { client.check_status(captcha_key, API_KEY).await }
+        })
+        .await
 }
 
-async fn get_client() -> Result<CaptchaClient> {
-  let config = Config::load();
-  Ok(CaptchaClient::new(config.grpc_captcha_service_url.as_str()).await?)
-}
\ No newline at end of file
+/// 获取验证码图片数据,每次调用都会生成新的验证码
+pub async fn get_data(id: String) -> Result<GetDataResponse> {
+    let config = Config::load();
+    let traceparent = current_traceparent();
+    GrpcClient::new("captcha", &config.grpc.captcha_service_url)
+        .call("GetData", true, |channel| {
+            let mut client = CaptchaClient::from_channel(channel).with_traceparent(traceparent.clone());
+            let id = id.clone();
+            async move { client.get_data(id, API_KEY).await }
+        })
+        .await
+}
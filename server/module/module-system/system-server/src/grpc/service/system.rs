@@ -1,13 +1,27 @@
+use std::pin::Pin;
+
 use common::config::config::Config;
 use common::context::context::LoginUserContext;
 use common::database::mysql::get_database_instance;
 use common::state::app_state::AppState;
+use common::utils::jwt_utils::{self, AuthError};
 use sea_orm::DatabaseConnection;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status};
 use system_grpc::system_client::system::system_service_server::{SystemService, SystemServiceServer};
-use system_grpc::system_client::system::{GetUserRequest, GetUserResponse, UserResponse, GetDepartmentRequest, GetDepartmentResponse, DepartmentResponse};
+use system_grpc::system_client::system::{GetUserRequest, GetUserResponse, UserResponse, GetDepartmentRequest, GetDepartmentResponse, DepartmentResponse,
+    ValidateTokenRequest, LoginUserResponse, DataPermission, CheckPermissionRequest, CheckPermissionResponse,
+    ListSubordinateDepartmentIdsRequest, ListSubordinateDepartmentIdsResponse, GetDictDataRequest, GetDictDataResponse, DictDataResponse,
+    ResolveAreaPathRequest, ResolveAreaPathResponse, AreaPathResponse, GetTenantRequest, TenantResponse,
+    WatchChangesRequest, UserChangeEvent, RoleChangeEvent};
+
+use crate::service::system_change::{self, ChangeNotice};
+use crate::service::{system_area, system_department, system_dict_data, system_tenant, system_tenant_package, system_user};
 
-use crate::service::{system_department, system_user};
+type ChangeStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
 
 #[derive(Debug)]
 pub struct SystemServiceImpl {
@@ -66,8 +80,240 @@ impl SystemService for SystemServiceImpl {
         };
         Ok(Response::new(GetDepartmentResponse { list: departments }))
     }
+
+    async fn validate_token(
+        &self,
+        request: Request<ValidateTokenRequest>,
+    ) -> Result<Response<LoginUserResponse>, Status> {
+        let req = request.into_inner();
+        let login_user = validate(&req.token).await?;
+        Ok(Response::new(login_user_to_response(login_user)))
+    }
+
+    async fn check_permission(
+        &self,
+        request: Request<CheckPermissionRequest>,
+    ) -> Result<Response<CheckPermissionResponse>, Status> {
+        let req = request.into_inner();
+        let login_user = validate(&req.token).await?;
+        let allowed = req.permissions.iter().any(|permission| login_user.permissions.contains(permission));
+        Ok(Response::new(CheckPermissionResponse { allowed }))
+    }
+
+    async fn list_subordinate_department_ids(
+        &self,
+        request: Request<ListSubordinateDepartmentIdsRequest>,
+    ) -> Result<Response<ListSubordinateDepartmentIdsResponse>, Status> {
+        let req = request.into_inner();
+        let ids = system_department::find_subordinate_ids(&self.state.db, req.department_id, req.include_self)
+            .await
+            .map_err(internal)?;
+        Ok(Response::new(ListSubordinateDepartmentIdsResponse { id: ids }))
+    }
+
+    async fn get_dict_data(
+        &self,
+        request: Request<GetDictDataRequest>,
+    ) -> Result<Response<GetDictDataResponse>, Status> {
+        let req = request.into_inner();
+        let list = system_dict_data::list_enabled_by_types(&self.state.db, &req.dict_type)
+            .await
+            .map_err(internal)?
+            .into_iter()
+            .map(|data| DictDataResponse {
+                dict_type: data.dict_type,
+                label: data.label,
+                value: data.value,
+                sort: data.sort,
+                color_type: data.color_type,
+                css_class: data.css_class,
+            })
+            .collect();
+        Ok(Response::new(GetDictDataResponse { list }))
+    }
+
+    async fn resolve_area_path(
+        &self,
+        request: Request<ResolveAreaPathRequest>,
+    ) -> Result<Response<ResolveAreaPathResponse>, Status> {
+        let req = request.into_inner();
+        let mut list = Vec::with_capacity(req.id.len());
+        for id in req.id {
+            // 不存在的区域不返回
+            if let Some(area) = system_area::get_path(id).map_err(internal)? {
+                list.push(AreaPathResponse {
+                    id: area.id,
+                    name: area.name,
+                    path: area.path,
+                });
+            }
+        }
+        Ok(Response::new(ResolveAreaPathResponse { list }))
+    }
+
+    async fn get_tenant(
+        &self,
+        request: Request<GetTenantRequest>,
+    ) -> Result<Response<TenantResponse>, Status> {
+        let req = request.into_inner();
+        let database = &self.state.db;
+        let tenant = system_tenant::find_by_id(database, req.id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found("租户不存在"))?;
+        let package = system_tenant_package::find_by_id(database, tenant.package_id)
+            .await
+            .map_err(internal)?;
+        let (package_name, package_status, package_menu_ids) = match package {
+            Some(package) => {
+                let menu_ids = serde_json::from_str::<Vec<i64>>(&package.menu_ids).unwrap_or_else(|e| {
+                    tracing::warn!("invalid menu ids of tenant package {}: {}", package.id, e);
+                    vec![]
+                });
+                (package.name, package.status as i32, menu_ids)
+            }
+            None => (String::new(), 0, vec![]),
+        };
+        Ok(Response::new(TenantResponse {
+            id: tenant.id,
+            name: tenant.name,
+            status: tenant.status as i32,
+            expire_time: tenant.expire_time.and_utc().timestamp_millis(),
+            account_count: tenant.account_count,
+            package_id: tenant.package_id,
+            package_name,
+            package_status,
+            package_menu_ids,
+        }))
+    }
+
+    type WatchUserChangesStream = ChangeStream<UserChangeEvent>;
+
+    async fn watch_user_changes(
+        &self,
+        request: Request<WatchChangesRequest>,
+    ) -> Result<Response<Self::WatchUserChangesStream>, Status> {
+        let req = request.into_inner();
+        let stream = watch(system_change::subscribe_user_changes(), req.tenant_id, |notice| UserChangeEvent {
+            id: notice.id,
+            tenant_id: notice.tenant_id,
+            change_type: notice.change_type,
+            occurred_at: notice.occurred_at,
+        });
+        Ok(Response::new(stream))
+    }
+
+    type WatchRoleChangesStream = ChangeStream<RoleChangeEvent>;
+
+    async fn watch_role_changes(
+        &self,
+        request: Request<WatchChangesRequest>,
+    ) -> Result<Response<Self::WatchRoleChangesStream>, Status> {
+        let req = request.into_inner();
+        let stream = watch(system_change::subscribe_role_changes(), req.tenant_id, |notice| RoleChangeEvent {
+            id: notice.id,
+            tenant_id: notice.tenant_id,
+            change_type: notice.change_type,
+            occurred_at: notice.occurred_at,
+        });
+        Ok(Response::new(stream))
+    }
+}
+
+/// 校验令牌,错误信息与http接口一致
+async fn validate(token: &str) -> Result<LoginUserContext, Status> {
+    match jwt_utils::validate_token(token).await {
+        Ok((_, login_user)) => Ok(login_user),
+        Err(AuthError::TokenExpired) => Err(Status::unauthenticated("认证已过期")),
+        Err(AuthError::InvalidTenant) => Err(Status::unauthenticated("无效租户")),
+        Err(AuthError::UserExpired) => Err(Status::unauthenticated("授权失效")),
+        Err(_) => Err(Status::unauthenticated("授权失败")),
+    }
+}
+
+fn internal(e: anyhow::Error) -> Status {
+    tracing::error!("grpc system service error: {}", e);
+    Status::internal("服务内部错误")
+}
+
+/// 变更通知转为grpc流,订阅方处理过慢丢失变更时以 DATA_LOSS 结束,订阅方需全量校准后重新订阅
+fn watch<T, F>(receiver: broadcast::Receiver<ChangeNotice>, tenant_id: Option<i64>, convert: F) -> ChangeStream<T>
+where
+    T: Send + 'static,
+    F: Fn(ChangeNotice) -> T + Send + 'static,
+{
+    let mut ended = false;
+    let stream = BroadcastStream::new(receiver)
+        .filter_map(move |notice| match notice {
+            Ok(notice) if tenant_id.map_or(true, |tenant_id| tenant_id == notice.tenant_id) => Some(Ok(convert(notice))),
+            Ok(_) => None,
+            Err(BroadcastStreamRecvError::Lagged(count)) => {
+                tracing::warn!("change watcher lagged, {} notices dropped", count);
+                Some(Err(Status::data_loss(format!("变更通知丢失{}条", count))))
+            }
+        })
+        // 返回错误后结束,不再发送后续的变更
+        .map_while(move |item| {
+            if ended {
+                return None;
+            }
+            ended = item.is_err();
+            Some(item)
+        });
+    Box::pin(stream)
+}
+
+fn login_user_to_response(login_user: LoginUserContext) -> LoginUserResponse {
+    LoginUserResponse {
+        device_type: login_user.device_type,
+        id: login_user.id,
+        nickname: login_user.nickname,
+        tenant_id: login_user.tenant_id,
+        department_id: login_user.department_id,
+        department_code: login_user.department_code,
+        role_id: login_user.role_id,
+        permissions: login_user.permissions,
+        data_permission: login_user.data_permission.map(|data_permission| DataPermission {
+            id: data_permission.id,
+            name: data_permission.name,
+            field: data_permission.field,
+            condition: data_permission.condition,
+            value: data_permission.value,
+            data_scope_department_ids: data_permission.data_scope_department_ids,
+        }),
+    }
 }
 
 pub fn create_system_service(state: AppState) -> SystemServiceServer<SystemServiceImpl> {
     SystemServiceServer::new(SystemServiceImpl::new(state))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notice(id: i64, tenant_id: i64) -> ChangeNotice {
+        ChangeNotice { id, tenant_id, change_type: 0, occurred_at: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_watch_filters_tenant() {
+        let (sender, receiver) = broadcast::channel(8);
+        let mut stream = watch(receiver, Some(2), |notice| notice.id);
+        sender.send(notice(1, 1)).unwrap();
+        sender.send(notice(2, 2)).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_watch_ends_with_data_loss_after_lag() {
+        let (sender, receiver) = broadcast::channel(1);
+        for id in 1..=3 {
+            sender.send(notice(id, 1)).unwrap();
+        }
+        let mut stream = watch(receiver, None, |notice| notice.id);
+        let status = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::DataLoss);
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,7 +1,6 @@
 use std::future::Future;
 use sea_orm::DatabaseConnection;
 use tracing::error;
-use common::database::redis::RedisManager;
 use common::database::redis_constants::REDIS_KEY_TENANTS_LIST;
 use common::state::app_state::AppState;
 use common::utils::jwt_utils::add_tenants;
@@ -25,7 +24,7 @@ pub async fn initialize_tenant_cache(db: &DatabaseConnection) {
             Vec::new()
         }
     };
-    add_tenants(tenant_id_list.as_slice());
+    add_tenants(tenant_id_list.as_slice()).await;
 }
 
 /// 初始化区域缓存
@@ -1,20 +1,32 @@
 use axum::handler::HandlerWithoutStateExt;
 use axum::http::Method;
 use common::config::config::Config;
+use common::config::system_config::{self, CONFIG_KEY_TENANT_EXPIRE_CRON};
+use common::utils::snowflake_generator::SnowflakeGenerator;
 use common::database::mysql::{get_database_instance, DATABASE_INSTANCE};
+use common::shutdown::shutdown::{self, ShutdownSignal};
 use common::task::task_manager::TaskManager;
+use common::task::outbox_relay_task::OutboxRelayTask;
+use common::task::key_rotation_task::{EncryptedColumn, KeyRotationTask};
 use once_cell::sync::Lazy;
 use task::tenant_expire_task::TenantExpireTask;
 use tonic::transport::Server;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use sea_orm::DatabaseConnection;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use uaparser::UserAgentParser;
 use common::middleware::logger;
+use common::monitor::health::{health_router, HealthChecker};
+use common::monitor::metrics::metrics_handler;
 use common::state::app_state::AppState;
+use common::utils::{grpc_tls, trace_utils};
+use common::middleware::grpc_identity::ServiceIdentityLayer;
 use crate::grpc::service::system::create_system_service;
 use crate::initializer::initialize;
 
@@ -34,7 +46,13 @@ async fn main() -> Result<(), anyhow::Error> {
     logger::init_tracing().await?;
     // logger::init_tracing_flexi().await?;
     let config = Config::load();
-    let database = get_database_instance(config.database_url).await;
+    let database = get_database_instance(config.database.url).await;
+    // 加载系统参数
+    system_config::init(database).await?;
+    // 加载grpc证书
+    grpc_tls::init();
+    // 申请雪花算法机器id
+    SnowflakeGenerator::init_global().await?;
 
     // let ua_parser = UserAgentParser::from_yaml("regexes.yaml").expect("Failed to load regexes.yaml");
 
@@ -47,52 +65,85 @@ async fn main() -> Result<(), anyhow::Error> {
 
     // 执行初始化
     initialize(state.clone()).await;
+    // 订阅用户、角色变更,推送给grpc订阅方
+    service::system_change::init();
+    // 订阅登录安全告警,通知租户管理员
+    service::system_notice::subscribe_security_alerts(database.clone());
 
     // 初始化任务管理器
-    let mut task_manager = TaskManager::new();
-    // 每天00:05执行
-    task_manager.add_task(TenantExpireTask::new(state.clone()), "0 5 0 * * *").await;
+    let mut task_manager = TaskManager::new(database.clone());
+    // 默认每天00:05执行
+    task_manager.add_task_with_config_key(TenantExpireTask::new(state.clone()), CONFIG_KEY_TENANT_EXPIRE_CRON, &config.system_server.tenant_expire_cron).await;
+    // 投递领域事件
+    task_manager.add_task(OutboxRelayTask::new(database.clone()), &config.event.relay_cron).await;
+    // 敏感字段使用当前密钥重新加密
+    task_manager.add_task(KeyRotationTask::new("SystemKeyRotationTask", database.clone(), vec![
+        EncryptedColumn { table: "system_user", column: "mobile", index_column: None },
+    ]), &config.encryption.rotation_cron).await;
+
+    // 健康检查和指标,就绪检查项为服务依赖
+    let health_checker = HealthChecker::new()
+        .with_database(database.clone())
+        .with_redis()
+        .with_grpc("captcha", &config.grpc.captcha_service_url);
 
     let app = route::api(state.clone()).await
+        .layer(axum::middleware::from_fn(metrics_handler)) // 请求指标,需在合并健康检查路由前添加
+        .merge(health_router(health_checker))
+        .merge(logger::log_level_router()) // 运行中修改日志级别
         .layer(cors)
         .layer(axum::middleware::from_fn(logger::panic_handler)) // 添加异常处理中间件
         ;
 
+    // 退出信号
+    let shutdown_signal = ShutdownSignal::listen();
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout);
+
     // HTTP
-    let addr = format!("0.0.0.0:{}", config.system_server_port);
+    let addr = format!("0.0.0.0:{}", config.system_server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     info!("Server running on {}", addr);
-    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>());
+    let server_future = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal.clone().wait_owned());
 
     // gRPC
-    let grpc_addr = SocketAddr::from_str(&format!("0.0.0.0:{}", config.system_server_grpc_port))?;
-    let grpc_server_future = Server::builder()
-        .add_service(create_system_service(state.clone()))
-        .serve(grpc_addr);
+    let grpc_addr = SocketAddr::from_str(&format!("0.0.0.0:{}", config.system_server.grpc_port))?;
+    let grpc_router = Server::builder()
+        .layer(ServiceIdentityLayer::from_config()) // 按客户端证书的服务身份限制可调用的rpc
+        .trace_fn(|request| trace_utils::grpc_server_span(request.uri().path(), request.headers())) // 链路追踪
+        .add_service(create_system_service(state.clone()));
+    // 启用TLS时每个连接使用最新的证书握手
+    let grpc_server_future: Pin<Box<dyn Future<Output = Result<(), tonic::transport::Error>> + Send>> = match grpc_tls::current() {
+        Some(tls) => Box::pin(grpc_router.serve_with_incoming_shutdown(grpc_tls::incoming(grpc_addr, tls).await?, shutdown_signal.clone().wait_owned())),
+        None => Box::pin(grpc_router.serve_with_shutdown(grpc_addr, shutdown_signal.clone().wait_owned())),
+    };
 
     info!("gRPC Server running on {}", grpc_addr);
 
-    tokio::select! {
-        result = server_future => {
-            if let Err(e) = result {
-                eprintln!("Web server unexpected exit: {}", e);
-            }
-            println!("Web server has stopped");
+    // 任一服务退出时通知另一个服务停止
+    let web_server = async {
+        if let Err(e) = server_future.await {
+            tracing::error!("Web server unexpected exit: {}", e);
         }
-        result = grpc_server_future => {
-            if let Err(e) = result {
-                eprintln!("gRPC server unexpected exit: {}", e);
-            }
-            println!("gRPC server has stopped");
-        }
-        _ = tokio::signal::ctrl_c() => {
-            println!("The program is closed when the exit signal is received");
-            task_manager.shutdown();
-            return Ok(());
+        info!("Web server has stopped");
+        shutdown_signal.trigger();
+    };
+    let grpc_server = async {
+        if let Err(e) = grpc_server_future.await {
+            tracing::error!("gRPC server unexpected exit: {}", e);
         }
+        info!("gRPC server has stopped");
+        shutdown_signal.trigger();
+    };
+
+    // 收到退出信号后停止接收新连接,在超时时间内等待处理中的请求完成
+    tokio::select! {
+        _ = async { tokio::join!(web_server, grpc_server) } => {}
+        _ = shutdown_signal.drain_deadline(shutdown_timeout) => {}
     }
 
-    task_manager.shutdown();
+    // 停止定时任务,写入未完成的日志,关闭连接
+    shutdown::cleanup(Some(&task_manager), shutdown_timeout).await;
 
     Ok(())
 }
\ No newline at end of file
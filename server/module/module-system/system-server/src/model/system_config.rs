@@ -0,0 +1,54 @@
+use chrono::NaiveDateTime;
+use sea_orm::Condition;
+use sea_orm::entity::prelude::*;
+use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "system_config")]
+pub struct Model {
+    
+    #[sea_orm(primary_key)]
+    pub id: i64, // id
+    
+    pub config_key: String, // 参数键
+    
+    pub config_value: String, // 参数值
+    
+    pub value_type: String, // 参数类型 string/integer/float/boolean/json
+    
+    pub name: String, // 参数名称
+    
+    pub remark: Option<String>, // 备注
+    
+    pub status: i8, // 状态（0正常 1停用）
+    
+    pub creator: Option<i64>, // 创建者id
+    
+    pub create_time: NaiveDateTime, // 创建时间
+    
+    pub updater: Option<i64>, // 更新者id
+    
+    pub update_time: NaiveDateTime, // 更新时间
+    
+    pub deleted: bool, // 是否删除
+    
+    pub tenant_id: i64, // 租户编号
+    
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<Entity> for Entity {
+    fn to() -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl ActiveFilterEntityTrait for Entity {
+    fn active_condition() -> Condition {
+        Condition::all().add(Column::Deleted.eq(false))
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,56 @@
+use chrono::NaiveDateTime;
+use sea_orm::Condition;
+use sea_orm::entity::prelude::*;
+use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "system_job")]
+pub struct Model {
+    
+    #[sea_orm(primary_key)]
+    pub id: i64, // id
+    
+    pub name: String, // 任务名称
+    
+    pub cron_expression: String, // cron表达式
+    
+    pub status: i8, // 状态（0正常 1暂停）
+    
+    pub misfire_policy: i8, // 错过执行策略（0补执行一次 1忽略 2全部补执行）
+    
+    pub last_fire_time: Option<NaiveDateTime>, // 上次计划执行时间
+    
+    pub next_fire_time: Option<NaiveDateTime>, // 下次计划执行时间
+    
+    pub remark: Option<String>, // 备注
+    
+    pub creator: Option<i64>, // 创建者id
+    
+    pub create_time: NaiveDateTime, // 创建时间
+    
+    pub updater: Option<i64>, // 更新者id
+    
+    pub update_time: NaiveDateTime, // 更新时间
+    
+    pub deleted: bool, // 是否删除
+    
+    pub tenant_id: i64, // 租户编号
+    
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<Entity> for Entity {
+    fn to() -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl ActiveFilterEntityTrait for Entity {
+    fn active_condition() -> Condition {
+        Condition::all().add(Column::Deleted.eq(false))
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "system_job_log")]
+pub struct Model {
+    
+    #[sea_orm(primary_key)]
+    pub id: i64, // id
+    
+    pub job_name: String, // 任务名称
+    
+    pub trigger_type: i8, // 触发方式（0计划 1手动 2错过补执行 3重试）
+    
+    pub attempt: i32, // 第几次执行
+    
+    pub status: i8, // 状态（0执行中 1成功 2失败）
+    
+    pub instance: String, // 执行实例
+    
+    pub start_time: NaiveDateTime, // 开始时间
+    
+    pub end_time: Option<NaiveDateTime>, // 结束时间
+    
+    pub duration: Option<i64>, // 耗时（毫秒）
+    
+    pub error: Option<String>, // 错误信息
+    
+    pub tenant_id: i64, // 租户编号
+    
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<Entity> for Entity {
+    fn to() -> RelationDef {
+        panic!("No relations defined")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
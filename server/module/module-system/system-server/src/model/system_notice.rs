@@ -18,6 +18,8 @@ pub struct Model {
     
     pub status: i8, // 公告状态（0正常 1关闭）
     
+    pub permission: Option<String>, // 可见权限,为空时租户内所有用户可见
+    
     pub creator: Option<i64>, // 创建者id
     
     pub create_time: NaiveDateTime, // 创建时间
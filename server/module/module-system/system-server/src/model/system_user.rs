@@ -2,6 +2,7 @@ use chrono::NaiveDateTime;
 use sea_orm::{Condition};
 use sea_orm::entity::prelude::*;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::database::encrypted_string::EncryptedString;
 use crate::model::{system_user_role, system_department};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -21,7 +22,7 @@ pub struct Model {
     
     pub email: Option<String>, // 用户邮箱
     
-    pub mobile: Option<String>, // 手机号码
+    pub mobile: Option<EncryptedString>, // 手机号码
     
     pub sex: Option<i8>, // 用户性别
     
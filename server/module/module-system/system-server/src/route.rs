@@ -1,9 +1,11 @@
 use crate::api::system_area::system_area_router;
 use crate::api::system_auth::{system_auth_need_router, system_auth_router};
+use crate::api::system_config::system_config_router;
 use crate::api::system_data_scope_rule::system_data_scope_rule_router;
 use crate::api::system_department::system_department_router;
 use crate::api::system_dict_data::system_dict_data_router;
 use crate::api::system_dict_type::system_dict_type_router;
+use crate::api::system_job::system_job_router;
 use crate::api::system_menu::system_menu_router;
 use crate::api::system_notice::system_notice_router;
 use crate::api::system_post::system_post_router;
@@ -21,6 +23,8 @@ use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use common::middleware::authorize::{authorize_handler, init_route_authorizes};
 use common::middleware::operation_logger::operation_logger_handler;
+use common::middleware::idempotency::idempotency_handler;
+use common::middleware::rate_limit::rate_limit_handler;
 use common::state::app_state::AppState;
 use common::utils::jwt_utils::AccessClaims;
 
@@ -34,10 +38,12 @@ use common::utils::jwt_utils::AccessClaims;
     ),
     tags(
         (name = "system_auth", description = "认证"),
+        (name = "system_config", description = "系统参数"),
         (name = "system_data_scope_rule", description = "数据权限规则"),
         (name = "system_department", description = "部门"),
         (name = "system_dict_data", description = "字典数据"),
         (name = "system_dict_type", description = "字典类型"),
+        (name = "system_job", description = "定时任务"),
         (name = "system_menu", description = "菜单权限"),
         (name = "system_notice", description = "通知公告"),
         (name = "system_post", description = "职位信息"),
@@ -73,18 +79,22 @@ pub async fn no_auth_router(state: AppState) -> OpenApiRouter {
         .nest("/system_auth", system_auth_router(state.clone()).await)
         .nest("/system_area", system_area_router().await)
         .nest("/system_tenant", system_tenant_no_auth_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
 }
 
 pub async fn auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         .nest("/system_auth", system_auth_need_router(state.clone()).await)
+        .nest("/system_config", system_config_router(state.clone()).await)
         .nest("/system_data_scope_rule", system_data_scope_rule_router(state.clone()).await)
         .nest("/system_department", system_department_router(state.clone()).await)
         .nest("/system_dict_data", system_dict_data_router(state.clone()).await)
         .nest("/system_dict_type", system_dict_type_router(state.clone()).await)
+        .nest("/system_job", system_job_router(state.clone()).await)
         .nest("/system_menu", system_menu_router(state.clone()).await)
         .nest("/system_notice", system_notice_router(state.clone()).await)
         .nest("/system_post", system_post_router(state.clone()).await)
@@ -96,8 +106,10 @@ pub async fn auth_router(state: AppState) -> OpenApiRouter {
         .nest("/system_user", system_user_router(state.clone()).await)
         .nest("/system_user_post", system_user_post_router(state.clone()).await)
         .nest("/system_user_role", system_user_role_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
@@ -14,25 +14,28 @@ use chrono::Utc;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use system_model::request::system_auth::{LoginAccountRequest, LoginRequest};
 use tokio::sync::OnceCell;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 use tracing_subscriber::filter::filter_fn;
 use common::base::logger::{LoginLogger, OperationLogger};
 use common::constants::common_status::{is_disable, is_enable};
+use common::constants::enum_constants::ROOT_TENANT_ID;
 use common::context::context::{DataPermission, LoginUserContext, RequestContext};
-use common::database::redis::RedisManager;
-use common::database::redis_constants::{REDIS_KEY_LOGGER_LOGIN_PREFIX, REDIS_KEY_LOGGER_OPERATION_PREFIX, REDIS_KEY_LOGIN_USER_PREFIX};
+use common::database::redis_pool::AsyncRedisManager;
+use common::database::redis_constants::REDIS_KEY_LOGIN_USER_PREFIX;
+use common::event::log_stream::{self, LogQueue};
 use common::utils::crypt_utils::verify_password;
 use common::utils::jwt_utils::{generate_token_pair, is_valid_tenant, AuthBody, AuthError};
-use system_model::response::system_auth::{HomeResponse, UserResponse};
+use system_model::response::system_auth::{CaptchaDataResponse, HomeResponse, UserResponse};
 use system_model::response::system_department::SystemDepartmentResponse;
 use crate::model::system_user::{Model as SystemUserModel};
 use crate::service::{self, system_data_scope_rule, system_department, system_role, system_tenant, system_tenant_package, system_user_role};
 
 use super::{system_menu, system_user};
+use common::shutdown::shutdown::spawn_tracked;
 
 pub async fn login(db: &DatabaseConnection, request_context: RequestContext, request: LoginRequest) -> Result<AuthBody> {
     let start = Instant::now();
-    info!("into login: {:?}", request);
+    info!("into login: {}", request.username);
     // 验证验证码
     let status = captcha::check_status(request.captcha_key).await?;
     if !status {
@@ -48,21 +51,42 @@ pub async fn login_account(db: &DatabaseConnection, request_context: RequestCont
     Ok(auth)
 }
 
+pub async fn captcha_data(id: String) -> Result<CaptchaDataResponse> {
+    let data = captcha::get_data(id).await?;
+    // 验证码服务出错时只返回message,不返回key
+    if data.captcha_key.is_empty() {
+        return Err(anyhow!("获取验证码失败: {}", data.message));
+    }
+    Ok(CaptchaDataResponse {
+        id: data.id,
+        captcha_key: data.captcha_key,
+        master_image_base64: data.master_image_base64,
+        thumb_image_base64: data.thumb_image_base64,
+        master_width: data.master_width,
+        master_height: data.master_height,
+        thumb_width: data.thumb_width,
+        thumb_height: data.thumb_height,
+        thumb_size: data.thumb_size,
+        display_x: data.display_x,
+        display_y: data.display_y,
+    })
+}
+
 async fn _login(db: &DatabaseConnection, request_context: RequestContext, username: String, password: String) -> Result<AuthBody> {
     let start = Instant::now();
 
     // 查询用户
-    let user_result = service::system_user::get_by_username(db, username).await?;
+    let user_result = service::system_user::get_by_username(db, username.clone()).await?;
     let user: SystemUserModel = match user_result {
         Some(user) => {
             if is_enable(user.status) {
                 user
             } else {
-                return Err(anyhow!("用户已被禁用"));
+                return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
             }
         }
         None => {
-            return Err(anyhow!("用户不存在"));
+            return Err(login_failed(&username, None, &request_context, "用户不存在"));
         }
     };
     let duration_select = start.elapsed();
@@ -71,11 +95,11 @@ async fn _login(db: &DatabaseConnection, request_context: RequestContext, userna
     match role {
         Some(role) => {
             if is_disable(role.status) {
-                return Err(anyhow!("用户已被禁用"));
+                return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
             }
         }
         None => {
-            return Err(anyhow!("用户已被禁用"));
+            return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
         }
     }
     // 校验用户部门状态
@@ -83,11 +107,11 @@ async fn _login(db: &DatabaseConnection, request_context: RequestContext, userna
     match department {
         Some(department) => {
             if is_disable(department.status) {
-                return Err(anyhow!("用户已被禁用"));
+                return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
             }
         }
         None => {
-            return Err(anyhow!("用户已被禁用"));
+            return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
         }
     }
     // 校验用户租户状态
@@ -95,43 +119,36 @@ async fn _login(db: &DatabaseConnection, request_context: RequestContext, userna
     match tenant {
         Some(tenant) => {
             if is_disable(tenant.status) {
-                return Err(anyhow!("用户已被禁用"));
+                return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
             } else {
                 // 检查用户租户过期时间
                 let now = Utc::now().naive_utc();
                 if tenant.expire_time < now {
-                    return Err(anyhow!("用户已过期"));
+                    return Err(login_failed(&username, Some(&user), &request_context, "用户已过期"));
                 }
                 // 校验用户租户套餐状态
                 let tenant_package = system_tenant_package::find_by_id(&db, user.department_id).await?;
                 match tenant_package {
                     Some(tenant_package) => {
                         if is_disable(tenant_package.status) {
-                            return Err(anyhow!("用户已被禁用"));
+                            return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
                         }
                     }
                     None => {
-                        return Err(anyhow!("用户已被禁用"));
+                        return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
                     }
                 }
             }
         }
         None => {
-            return Err(anyhow!("用户已被禁用"));
+            return Err(login_failed(&username, Some(&user), &request_context, "用户已被禁用"));
         }
     }
 
     // 比较密码
-    let is_match = match verify_password(password, user.clone().password) {
-        Ok(is_match) => {
-            is_match
-        }
-        Err(_) => {
-            return Err(anyhow!("账号或密码不正确"));
-        }
-    };
+    let is_match = verify_password(password, user.clone().password).unwrap_or(false);
     if !is_match {
-        return Err(anyhow!("账号或密码不正确"));
+        return Err(login_failed(&username, Some(&user), &request_context, "账号或密码不正确"));
     }
     let duration_match = start.elapsed();
 
@@ -142,7 +159,7 @@ async fn _login(db: &DatabaseConnection, request_context: RequestContext, userna
             return Err(anyhow!("服务端异常"));
         }
     };
-    info!("auth: {:?}", auth);
+    // info!("auth: {:?}", auth);
     let duration_auth = start.elapsed();
     // 更新登录用户最近登录IP和时间
     // info!("request context: {:?}", request_context);
@@ -151,9 +168,9 @@ async fn _login(db: &DatabaseConnection, request_context: RequestContext, userna
     // add_login_logger_redis(request_context.clone(), user.clone(), &auth).await?;
     // 保存登录用户信息缓存
     cache_login_user(db, request_context.clone(), user.clone()).await?;
-    invoke_after_login(db, user.clone(), request_context.clone(), &auth);
+    invoke_after_login(db, user.clone(), request_context.clone());
     let duration_after = start.elapsed();
-    println!("duration, select: {:?}, match: {:?}, auth: {:?}, after: {:?}", duration_select, duration_match, duration_auth, duration_after);
+    debug!(select = ?duration_select, matched = ?duration_match, auth = ?duration_auth, after = ?duration_after, "login duration");
     Ok(auth)
 }
 
@@ -169,7 +186,7 @@ pub async fn refresh_token(_db: &DatabaseConnection, refresh_token: String) -> R
 
 pub async fn logout(_db: &DatabaseConnection, login_user: LoginUserContext) -> Result<()> {
     // 删除用户缓存
-    RedisManager::delete::<_>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, login_user.device_type, login_user.id))?;
+    AsyncRedisManager::delete::<_>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, login_user.device_type, login_user.id)).await?;
     Ok(())
 }
 
@@ -210,15 +227,14 @@ pub async fn offline_user(_db: &DatabaseConnection, user_id: i64) -> Result<()>
     for device in DeviceType::iter() {
         // 获取字符串形式
         let device_str: &'static str = device.into();
-        RedisManager::delete::<_>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, device_str, user_id))?;
+        AsyncRedisManager::delete::<_>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, device_str, user_id)).await?;
     }
     Ok(())
 }
 
-fn invoke_after_login(db: &DatabaseConnection, user: SystemUserModel, request_context: RequestContext, auth: &AuthBody) {
+fn invoke_after_login(db: &DatabaseConnection, user: SystemUserModel, request_context: RequestContext) {
     let db_clone = db.clone();
-    let auth_clone = auth.clone();
-    tokio::spawn(async move {
+    spawn_tracked(async move {
         // 保存登录用户信息缓存
         // if let Err(e) = cache_login_user(&db_clone, request_context.clone(), user.clone()).await {
         //     error!("cache login user error: {}", e.to_string());
@@ -229,12 +245,26 @@ fn invoke_after_login(db: &DatabaseConnection, user: SystemUserModel, request_co
             error!("update login error: {}", e.to_string());
         }
         // 记录登录日志
-        if let Err(e) = add_login_logger_redis(request_context.clone(), user.clone(), &auth_clone).await {
+        if let Err(e) = add_login_logger_redis(request_context.clone(), user.clone(), true, "登录成功").await {
             error!("add login logger error: {}", e.to_string());
         };
     });
 }
 
+/// 记录失败的登录并返回错误,账号不存在时按提交的账号记录在平台租户下,用于检测暴力破解
+fn login_failed(username: &str, user: Option<&SystemUserModel>, request_context: &RequestContext, result: &'static str) -> anyhow::Error {
+    let login_logger = match user {
+        Some(user) => login_logger(request_context.clone(), user.clone(), false, result),
+        None => unknown_login_logger(request_context.clone(), username.to_string(), result),
+    };
+    spawn_tracked(async move {
+        if let Err(e) = publish_login_logger(login_logger).await {
+            error!("add login logger error: {}", e.to_string());
+        };
+    });
+    anyhow!(result)
+}
+
 async fn cache_login_user(db: &DatabaseConnection, request_context: RequestContext, user: SystemUserModel) -> Result<()> {
     // 查询部门信息
     let _department_result = system_department::find_by_id(&db, user.department_id).await?;
@@ -273,43 +303,65 @@ async fn cache_login_user(db: &DatabaseConnection, request_context: RequestConte
         data_permission,
     };
     info!("login user {:?}", login_user);
-    RedisManager::set::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, request_context.device_type, user.id), serde_json::to_string(&login_user)?)?;
+    AsyncRedisManager::set::<_, String>(format!("{}{}:{}", REDIS_KEY_LOGIN_USER_PREFIX, request_context.device_type, user.id), serde_json::to_string(&login_user)?).await?;
     Ok(())
 }
 
-async fn add_login_logger_redis(request_context: RequestContext, user: SystemUserModel, auth: &AuthBody) -> Result<()> {
-    let result = serde_json::to_string(&auth)?;
-    let user_id = user.id;
-    let username = user.username;
-    let user_nickname = user.nickname;
-    let tenant_id = user.tenant_id;
-    let department_code = user.department_code;
-    let department_id = user.department_id;
+async fn add_login_logger_redis(request_context: RequestContext, user: SystemUserModel, success: bool, result: &str) -> Result<()> {
+    publish_login_logger(login_logger(request_context, user, success, result)).await
+}
+
+fn login_logger(request_context: RequestContext, user: SystemUserModel, success: bool, result: &str) -> LoginLogger {
+    LoginLogger {
+        id: None,
+        trace_id: Some(request_context.trace_id).filter(|id| !id.is_empty()),
+        user_id: Some(user.id),
+        user_type: None,
+        username: user.username,
+        result: result.to_string(),
+        success: Some(success),
+        user_ip: request_context.ip,
+        user_agent: request_context.user_agent,
+        department_code: Some(user.department_code),
+        department_id: Some(user.department_id),
+        operator: Some(user.id),
+        operator_nickname: Some(user.nickname),
+        operate_time: Some(Utc::now().timestamp()),
+        deleted: Some(false),
+        tenant_id: Some(user.tenant_id)
+    }
+}
 
-    let mut login_logger = LoginLogger {
+/// 账号不存在的登录日志,没有用户信息
+fn unknown_login_logger(request_context: RequestContext, username: String, result: &str) -> LoginLogger {
+    LoginLogger {
         id: None,
-        trace_id: None,
-        user_id: Some(user_id),
+        trace_id: Some(request_context.trace_id).filter(|id| !id.is_empty()),
+        user_id: None,
         user_type: None,
         username,
-        result,
+        result: result.to_string(),
+        success: Some(false),
         user_ip: request_context.ip,
         user_agent: request_context.user_agent,
-        department_code: Some(department_code),
-        department_id: Some(department_id),
-        operator: Some(user_id),
-        operator_nickname: Some(user_nickname),
+        department_code: None,
+        department_id: None,
+        operator: None,
+        operator_nickname: None,
         operate_time: Some(Utc::now().timestamp()),
         deleted: Some(false),
-        tenant_id: Some(tenant_id)
-    };
+        tenant_id: Some(ROOT_TENANT_ID)
+    }
+}
+
+async fn publish_login_logger(mut login_logger: LoginLogger) -> Result<()> {
     // 生成id
-    let generator = SnowflakeGenerator::new_with_machine_id(1000).unwrap();
+    let generator = SnowflakeGenerator::global();
     match generator.generate() {
         Ok(id) => login_logger.id = Some(id),
         Err(e) => login_logger.id = None
     }
     info!("login logger: {:?}", login_logger);
-    RedisManager::push_list::<_, String>(REDIS_KEY_LOGGER_LOGIN_PREFIX, serde_json::to_string(&login_logger)?)?;
+    log_stream::publish(LogQueue::Login, &login_logger).await?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use common::database::redis_constants::{REDIS_CHANNEL_ROLE_CHANGED, REDIS_CHANNEL_USER_CHANGED};
+use common::database::redis_pool::AsyncRedisManager;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use system_grpc::system_client::system::ChangeType;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+/// 断开后重新订阅的间隔
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(5);
+/// 本副本未被grpc订阅方取走的变更数量,超过后慢的订阅方会丢失变更
+const CHANGE_BUFFER_SIZE: usize = 1024;
+
+/// 用户、角色变更通知,通过redis广播到所有副本,再由各副本推送给自己的grpc订阅方
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChangeNotice {
+    pub id: i64, // 用户id或角色id
+    pub tenant_id: i64, // 租户id
+    pub change_type: i32, // 变更类型,见 ChangeType
+    pub occurred_at: i64, // 发生时间(毫秒时间戳)
+}
+
+static USER_CHANGES: Lazy<broadcast::Sender<ChangeNotice>> = Lazy::new(|| broadcast::channel(CHANGE_BUFFER_SIZE).0);
+static ROLE_CHANGES: Lazy<broadcast::Sender<ChangeNotice>> = Lazy::new(|| broadcast::channel(CHANGE_BUFFER_SIZE).0);
+
+/// 订阅变更通知,服务启动时调用一次
+pub fn init() {
+    listen(REDIS_CHANNEL_USER_CHANGED, &USER_CHANGES);
+    listen(REDIS_CHANNEL_ROLE_CHANGED, &ROLE_CHANGES);
+}
+
+fn listen(channel: &'static str, sender: &'static broadcast::Sender<ChangeNotice>) {
+    // 断开后重新订阅
+    tokio::spawn(async move {
+        loop {
+            let result = AsyncRedisManager::subscribe(channel, |message| {
+                match serde_json::from_str::<ChangeNotice>(&message) {
+                    // 没有订阅方时发送失败,忽略
+                    Ok(notice) => { let _ = sender.send(notice); }
+                    Err(e) => warn!("invalid change notice on {}: {}", channel, e),
+                }
+            }).await;
+            if let Err(e) = result {
+                error!("subscribe {} error: {}", channel, e);
+            }
+            tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+        }
+    });
+    info!("listen change notice {}", channel);
+}
+
+/// 接收用户变更
+pub fn subscribe_user_changes() -> broadcast::Receiver<ChangeNotice> {
+    USER_CHANGES.subscribe()
+}
+
+/// 接收角色变更
+pub fn subscribe_role_changes() -> broadcast::Receiver<ChangeNotice> {
+    ROLE_CHANGES.subscribe()
+}
+
+/// 通知用户变更,在业务数据提交后调用,通知失败不影响业务
+pub async fn notify_user_changed(id: i64, tenant_id: i64, change_type: ChangeType) {
+    notify(REDIS_CHANNEL_USER_CHANGED, id, tenant_id, change_type).await
+}
+
+/// 通知角色变更,在业务数据提交后调用,通知失败不影响业务
+pub async fn notify_role_changed(id: i64, tenant_id: i64, change_type: ChangeType) {
+    notify(REDIS_CHANNEL_ROLE_CHANGED, id, tenant_id, change_type).await
+}
+
+async fn notify(channel: &str, id: i64, tenant_id: i64, change_type: ChangeType) {
+    let notice = ChangeNotice {
+        id,
+        tenant_id,
+        change_type: change_type as i32,
+        occurred_at: Utc::now().timestamp_millis(),
+    };
+    let message = match serde_json::to_string(&notice) {
+        Ok(message) => message,
+        Err(e) => {
+            error!("serialize change notice error: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = AsyncRedisManager::publish(channel, message).await {
+        error!("publish {} error: {}", channel, e);
+    }
+}
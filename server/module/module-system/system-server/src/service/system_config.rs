@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use common::config::system_config::{self, ConfigValueType};
+use common::constants::enum_constants::{ROOT_TENANT_ID, STATUS_DISABLE, STATUS_ENABLE};
+use common::interceptor::orm::simple_support::SimpleSupport;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder};
+use crate::model::system_config::{Model as SystemConfigModel, ActiveModel as SystemConfigActiveModel, Entity as SystemConfigEntity, Column};
+use system_model::request::system_config::{CreateSystemConfigRequest, UpdateSystemConfigRequest, PaginatedKeywordRequest};
+use system_model::response::system_config::{SystemConfigResponse, SystemConfigValueResponse};
+use crate::convert::system_config::{create_request_to_model, update_request_to_model, model_to_response};
+use anyhow::{Result, anyhow};
+use sea_orm::ActiveValue::Set;
+use common::base::page::PaginatedResponse;
+use common::context::context::LoginUserContext;
+use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+
+/// 新增参数,根租户新增的为全局参数,其他租户只能覆盖已有的全局参数
+pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemConfigRequest) -> Result<i64> {
+    let key = request.config_key.trim().to_string();
+    if key.is_empty() {
+        return Err(anyhow!("参数键不能为空"));
+    }
+    if find_by_key(db, login_user.tenant_id, &key).await?.is_some() {
+        return Err(anyhow!("参数键已存在"));
+    }
+    let mut value_type = ConfigValueType::from_str(&request.value_type)?;
+    if login_user.tenant_id != ROOT_TENANT_ID {
+        let global = find_by_key(db, ROOT_TENANT_ID, &key).await?
+            .ok_or_else(|| anyhow!("全局参数不存在"))?;
+        // 租户参数类型与全局参数保持一致
+        value_type = ConfigValueType::from_str(&global.value_type)?;
+    }
+    value_type.validate(&request.config_value)?;
+
+    let mut system_config = create_request_to_model(&request);
+    system_config.config_key = Set(key.clone());
+    system_config.value_type = Set(value_type.as_str().to_string());
+    system_config.creator = Set(Some(login_user.id));
+    system_config.updater = Set(Some(login_user.id));
+    system_config.tenant_id = Set(login_user.tenant_id);
+    let system_config = system_config.insert(db).await?;
+    system_config::notify_changed(&key).await;
+    Ok(system_config.id)
+}
+
+pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, request: UpdateSystemConfigRequest) -> Result<()> {
+    let system_config = SystemConfigEntity::find_active_by_id(request.id)
+        .filter(Column::TenantId.eq(login_user.tenant_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))?;
+    if let Some(config_value) = &request.config_value {
+        ConfigValueType::from_str(&system_config.value_type)?.validate(config_value)?;
+    }
+
+    let key = system_config.config_key.clone();
+    let mut system_config = update_request_to_model(&request, system_config);
+    system_config.updater = Set(Some(login_user.id));
+    system_config.update(db).await?;
+    system_config::notify_changed(&key).await;
+    Ok(())
+}
+
+pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    let existing = find_by_id(db, login_user.tenant_id, id).await?;
+    let system_config = SystemConfigActiveModel {
+        id: Set(id),
+        updater: Set(Some(login_user.id)),
+        deleted: Set(true),
+        ..Default::default()
+    };
+    system_config.update(db).await?;
+    system_config::notify_changed(&existing.config_key).await;
+    Ok(())
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<Option<SystemConfigResponse>> {
+    let condition = Condition::all()
+            .add(Column::Id.eq(id))
+            .add(Column::TenantId.eq(login_user.tenant_id));
+
+    let system_config = SystemConfigEntity::find_active_with_condition(condition)
+        .one(db).await?;
+    Ok(system_config.map(model_to_response))
+}
+
+/// 获取当前租户生效的参数值
+pub async fn get_value(login_user: LoginUserContext, key: String) -> Result<SystemConfigValueResponse> {
+    let config_value = system_config::get_raw(login_user.tenant_id, &key);
+    Ok(SystemConfigValueResponse { config_key: key, config_value })
+}
+
+pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedKeywordRequest) -> Result<PaginatedResponse<SystemConfigResponse>> {
+    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));
+    let mut query = SystemConfigEntity::find_active_with_condition(condition);
+
+    if let Some(keyword) = &params.base.keyword {
+        if !keyword.is_empty() {
+            query = query.filter(
+                Condition::any()
+                    .add(Column::ConfigKey.like(format!("%{}%", keyword)))
+                    .add(Column::Name.like(format!("%{}%", keyword))),
+            );
+        }
+    }
+
+    let paginator = query
+        .support_filter(params.base.filter_field, params.base.filter_operator, params.base.filter_value)
+        .support_order(params.base.sort_field, params.base.sort, Some(vec![(Column::ConfigKey, Order::Asc)]))
+        .paginate(db, params.base.size);
+
+    let total = paginator.num_items().await?;
+    let total_pages = (total + params.base.size - 1) / params.base.size; // 向上取整
+    let list = paginator
+        .fetch_page(params.base.page - 1) // SeaORM 页码从 0 开始，所以减 1
+        .await?
+        .into_iter()
+        .map(model_to_response)
+        .collect();
+
+    Ok(PaginatedResponse {
+        list,
+        total_pages,
+        page: params.base.page,
+        size: params.base.size,
+        total,
+    })
+}
+
+pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<SystemConfigResponse>> {
+    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));
+    let list = SystemConfigEntity::find_active_with_condition(condition)
+        .order_by_asc(Column::ConfigKey)
+        .all(db).await?;
+    Ok(list.into_iter().map(model_to_response).collect())
+}
+
+pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    update_status(db, login_user, id, STATUS_ENABLE).await
+}
+
+pub async fn disable(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    update_status(db, login_user, id, STATUS_DISABLE).await
+}
+
+async fn update_status(db: &DatabaseConnection, login_user: LoginUserContext, id: i64, status: i8) -> Result<()> {
+    let existing = find_by_id(db, login_user.tenant_id, id).await?;
+    let system_config = SystemConfigActiveModel {
+        id: Set(id),
+        updater: Set(Some(login_user.id)),
+        status: Set(status),
+        ..Default::default()
+    };
+    system_config.update(db).await?;
+    system_config::notify_changed(&existing.config_key).await;
+    Ok(())
+}
+
+async fn find_by_id(db: &DatabaseConnection, tenant_id: i64, id: i64) -> Result<SystemConfigModel> {
+    SystemConfigEntity::find_active_by_id(id)
+        .filter(Column::TenantId.eq(tenant_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))
+}
+
+async fn find_by_key(db: &DatabaseConnection, tenant_id: i64, key: &str) -> Result<Option<SystemConfigModel>> {
+    let condition = Condition::all()
+        .add(Column::ConfigKey.eq(key))
+        .add(Column::TenantId.eq(tenant_id));
+    Ok(SystemConfigEntity::find_active_with_condition(condition).one(db).await?)
+}
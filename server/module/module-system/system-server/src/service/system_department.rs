@@ -1,3 +1,4 @@
+use std::time::Duration;
 use common::constants::enum_constants::{DEPARTMENT_ROOT_CODE, DEPARTMENT_ROOT_ID, STATUS_DISABLE, STATUS_ENABLE};
 use common::utils::string_utils::get_next_code;
 use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait};
@@ -10,10 +11,15 @@ use anyhow::{anyhow, Context, Result};
 use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
+use common::cache::cache_aside::{self, CacheOptions};
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 
 use super::system_user;
 
+/// 部门基础信息缓存,供其他服务通过grpc查询
+const CACHE_DEPARTMENT_BASE_PREFIX: &str = "system:department:base:";
+const CACHE_DEPARTMENT_BASE_TTL: Duration = Duration::from_secs(600);
+
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemDepartmentRequest) -> Result<i64> {
     // 查询父级编码
     let parent_department = SystemDepartmentEntity::find_active_by_id(request.parent_id.clone())
@@ -78,6 +84,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
     let mut system_department = update_request_to_model(&request, system_department);
     system_department.updater = Set(Some(login_user.id));
     system_department.update(db).await?;
+    cache_aside::invalidate(&format!("{}{}", CACHE_DEPARTMENT_BASE_PREFIX, request.id)).await;
     Ok(())
 }
 
@@ -89,6 +96,7 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_department.update(db).await?;
+    cache_aside::invalidate(&format!("{}{}", CACHE_DEPARTMENT_BASE_PREFIX, id)).await;
     Ok(())
 }
 
@@ -168,8 +176,33 @@ pub async fn find_by_id(db: &DatabaseConnection, id: i64) -> Result<Option<Syste
 }
 
 pub async fn find_by_ids(db: &DatabaseConnection, ids: Vec<i64>) -> Result<Vec<SystemDepartmentBaseResponse>> {
-    let list = SystemDepartmentEntity::find_active()
-        .filter(Column::Id.is_in(ids))
+    let options = CacheOptions::new(CACHE_DEPARTMENT_BASE_TTL);
+    let mut departments = cache_aside::get_many_or_load(CACHE_DEPARTMENT_BASE_PREFIX, &ids, &options, |missing| async move {
+        let list = SystemDepartmentEntity::find_active()
+            .filter(Column::Id.is_in(missing))
+            .all(db).await?;
+        Ok(list.into_iter().map(|department| (department.id, model_to_base_response(department))).collect())
+    }).await?;
+    Ok(ids.iter().filter_map(|id| departments.remove(id)).collect())
+}
+
+/// 部门及所有下级部门id,下级部门编码以上级编码加"-"开头
+pub async fn find_subordinate_ids(db: &DatabaseConnection, id: i64, include_self: bool) -> Result<Vec<i64>> {
+    let department = match find_by_id(db, id).await? {
+        Some(department) => department,
+        None => return Ok(vec![]),
+    };
+    let mut condition = Condition::any().add(Column::Code.starts_with(format!("{}-", department.code)));
+    if include_self {
+        condition = condition.add(Column::Id.eq(id));
+    }
+    let ids = SystemDepartmentEntity::find_active()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::TenantId.eq(department.tenant_id))
+        .filter(condition)
+        .order_by_asc(Column::Code)
+        .into_tuple::<i64>()
         .all(db).await?;
-    Ok(list.into_iter().map(model_to_base_response).collect())
-}
\ No newline at end of file
+    Ok(ids)
+}
@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use common::constants::enum_constants::{STATUS_DISABLE, STATUS_ENABLE};
 use common::interceptor::orm::simple_support::SimpleSupport;
@@ -13,14 +14,21 @@ use anyhow::{Result, anyhow};
 use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
+use common::cache::cache_aside::{self, CacheOptions};
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
 
+/// 字典数据缓存标签,字典变更时清除
+const CACHE_DICT_TAG: &str = "system_dict";
+const CACHE_DICT_DATA_LIST: &str = "system:dict_data:list";
+const CACHE_DICT_TTL: Duration = Duration::from_secs(3600);
+
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemDictDataRequest) -> Result<i64> {
     let mut system_dict_data = create_request_to_model(&request);
     system_dict_data.creator = Set(Some(login_user.id));
     system_dict_data.updater = Set(Some(login_user.id));
     
     let system_dict_data = system_dict_data.insert(db).await?;
+    cache_aside::invalidate_tag(CACHE_DICT_TAG).await;
     Ok(system_dict_data.id)
 }
 
@@ -33,6 +41,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
     let mut system_dict_data = update_request_to_model(&request, system_dict_data);
     system_dict_data.updater = Set(Some(login_user.id));
     system_dict_data.update(db).await?;
+    cache_aside::invalidate_tag(CACHE_DICT_TAG).await;
     Ok(())
 }
 
@@ -44,6 +53,7 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_dict_data.update(db).await?;
+    cache_aside::invalidate_tag(CACHE_DICT_TAG).await;
     Ok(())
 }
 
@@ -113,9 +123,27 @@ pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext
 }
 
 pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<SystemDictDataResponse>> {
-    let list = SystemDictDataEntity::find_active()
-        .all(db).await?;
-    Ok(list.into_iter().map(model_to_response).collect())
+    list_all(db).await
+}
+
+/// 按字典类型获取启用的字典数据,供其他服务通过grpc查询
+pub async fn list_enabled_by_types(db: &DatabaseConnection, dict_types: &[String]) -> Result<Vec<SystemDictDataResponse>> {
+    let mut list: Vec<SystemDictDataResponse> = list_all(db).await?
+        .into_iter()
+        .filter(|data| data.status == STATUS_ENABLE && dict_types.contains(&data.dict_type))
+        .collect();
+    list.sort_by(|a, b| a.dict_type.cmp(&b.dict_type).then(a.sort.cmp(&b.sort)));
+    Ok(list)
+}
+
+async fn list_all(db: &DatabaseConnection) -> Result<Vec<SystemDictDataResponse>> {
+    let options = CacheOptions::new(CACHE_DICT_TTL).with_tag(CACHE_DICT_TAG);
+    let list = cache_aside::get_or_load(CACHE_DICT_DATA_LIST, &options, || async {
+        let list = SystemDictDataEntity::find_active()
+            .all(db).await?;
+        Ok(Some(list.into_iter().map(model_to_response).collect::<Vec<SystemDictDataResponse>>()))
+    }).await?;
+    Ok(list.unwrap_or_default())
 }
 
 pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
@@ -126,6 +154,7 @@ pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_dict_data.update(db).await?;
+    cache_aside::invalidate_tag(CACHE_DICT_TAG).await;
     Ok(())
 }
 
@@ -137,5 +166,6 @@ pub async fn disable(db: &DatabaseConnection, login_user: LoginUserContext, id:
         ..Default::default()
     };
     system_dict_data.update(db).await?;
+    cache_aside::invalidate_tag(CACHE_DICT_TAG).await;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,158 @@
+use common::constants::enum_constants::{JOB_MISFIRE_FIRE_ALL, JOB_MISFIRE_FIRE_ONCE, STATUS_DISABLE, STATUS_ENABLE};
+use common::interceptor::orm::simple_support::SimpleSupport;
+use common::task::task_manager;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder};
+use crate::model::system_job::{Model as SystemJobModel, ActiveModel as SystemJobActiveModel, Entity as SystemJobEntity, Column};
+use crate::model::system_job_log::{Entity as SystemJobLogEntity, Column as LogColumn};
+use system_model::request::system_job::{UpdateSystemJobRequest, PaginatedKeywordRequest, PaginatedJobLogRequest};
+use system_model::response::system_job::{SystemJobResponse, SystemJobLogResponse};
+use crate::convert::system_job::{update_request_to_model, model_to_response, log_model_to_response};
+use anyhow::{Result, anyhow};
+use sea_orm::ActiveValue::Set;
+use common::base::page::PaginatedResponse;
+use common::context::context::LoginUserContext;
+use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+
+/// 任务由各服务启动时注册,这里只能修改错过执行策略和备注
+pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, request: UpdateSystemJobRequest) -> Result<()> {
+    if let Some(misfire_policy) = request.misfire_policy {
+        if !(JOB_MISFIRE_FIRE_ONCE..=JOB_MISFIRE_FIRE_ALL).contains(&misfire_policy) {
+            return Err(anyhow!("错过执行策略不正确"));
+        }
+    }
+    let system_job = find_by_id(db, login_user.tenant_id, request.id).await?;
+    let mut system_job = update_request_to_model(&request, system_job);
+    system_job.updater = Set(Some(login_user.id));
+    system_job.update(db).await?;
+    Ok(())
+}
+
+pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<Option<SystemJobResponse>> {
+    let condition = Condition::all()
+            .add(Column::Id.eq(id))
+            .add(Column::TenantId.eq(login_user.tenant_id));
+
+    let system_job = SystemJobEntity::find_active_with_condition(condition)
+        .one(db).await?;
+    Ok(system_job.map(model_to_response))
+}
+
+pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedKeywordRequest) -> Result<PaginatedResponse<SystemJobResponse>> {
+    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));
+    let mut query = SystemJobEntity::find_active_with_condition(condition);
+
+    if let Some(keyword) = &params.keyword {
+        if !keyword.is_empty() {
+            query = query.filter(Column::Name.like(format!("%{}%", keyword)));
+        }
+    }
+
+    let paginator = query
+        .support_filter(params.base.filter_field, params.base.filter_operator, params.base.filter_value)
+        .support_order(params.base.sort_field, params.base.sort, Some(vec![(Column::Name, Order::Asc)]))
+        .paginate(db, params.base.size);
+
+    let total = paginator.num_items().await?;
+    let total_pages = (total + params.base.size - 1) / params.base.size; // 向上取整
+    let list = paginator
+        .fetch_page(params.base.page - 1) // SeaORM 页码从 0 开始，所以减 1
+        .await?
+        .into_iter()
+        .map(model_to_response)
+        .collect();
+
+    Ok(PaginatedResponse {
+        list,
+        total_pages,
+        page: params.base.page,
+        size: params.base.size,
+        total,
+    })
+}
+
+pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<SystemJobResponse>> {
+    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));
+    let list = SystemJobEntity::find_active_with_condition(condition)
+        .order_by_asc(Column::Name)
+        .all(db).await?;
+    Ok(list.into_iter().map(model_to_response).collect())
+}
+
+/// 暂停后计划执行和错过的执行都会跳过,仍然可以手动执行
+pub async fn pause(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    update_status(db, login_user, id, STATUS_DISABLE).await
+}
+
+/// 恢复后从下次计划时间开始执行,暂停期间的执行不补
+pub async fn resume(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    let system_job = find_by_id(db, login_user.tenant_id, id).await?;
+    let system_job = SystemJobActiveModel {
+        id: Set(id),
+        updater: Set(Some(login_user.id)),
+        status: Set(STATUS_ENABLE),
+        last_fire_time: Set(Some(chrono::Local::now().naive_local()).max(system_job.last_fire_time)),
+        ..Default::default()
+    };
+    system_job.update(db).await?;
+    Ok(())
+}
+
+/// 立即执行一次,由注册了该任务的服务中的一个副本执行
+pub async fn trigger(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    let system_job = find_by_id(db, login_user.tenant_id, id).await?;
+    task_manager::trigger(&system_job.name).await
+}
+
+pub async fn get_log_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedJobLogRequest) -> Result<PaginatedResponse<SystemJobLogResponse>> {
+    let mut condition = Condition::all().add(LogColumn::TenantId.eq(login_user.tenant_id));
+    if let Some(job_name) = &params.job_name {
+        if !job_name.is_empty() {
+            condition = condition.add(LogColumn::JobName.eq(job_name.clone()));
+        }
+    }
+    if let Some(status) = params.status {
+        condition = condition.add(LogColumn::Status.eq(status));
+    }
+
+    let paginator = SystemJobLogEntity::find()
+        .filter(condition)
+        .order_by_desc(LogColumn::Id)
+        .paginate(db, params.base.size);
+
+    let total = paginator.num_items().await?;
+    let total_pages = (total + params.base.size - 1) / params.base.size; // 向上取整
+    let list = paginator
+        .fetch_page(params.base.page - 1) // SeaORM 页码从 0 开始，所以减 1
+        .await?
+        .into_iter()
+        .map(log_model_to_response)
+        .collect();
+
+    Ok(PaginatedResponse {
+        list,
+        total_pages,
+        page: params.base.page,
+        size: params.base.size,
+        total,
+    })
+}
+
+async fn update_status(db: &DatabaseConnection, login_user: LoginUserContext, id: i64, status: i8) -> Result<()> {
+    find_by_id(db, login_user.tenant_id, id).await?;
+    let system_job = SystemJobActiveModel {
+        id: Set(id),
+        updater: Set(Some(login_user.id)),
+        status: Set(status),
+        ..Default::default()
+    };
+    system_job.update(db).await?;
+    Ok(())
+}
+
+async fn find_by_id(db: &DatabaseConnection, tenant_id: i64, id: i64) -> Result<SystemJobModel> {
+    SystemJobEntity::find_active_by_id(id)
+        .filter(Column::TenantId.eq(tenant_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))
+}
@@ -8,6 +8,12 @@ use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use chrono::{Local, TimeZone};
+use common::constants::enum_constants::{security_event_name, NOTICE_STATUS_NORMAL, NOTICE_TYPE_NOTIFICATION, PERMISSION_AUDIT_SECURITY, SECURITY_LEVEL_HIGH, SECURITY_LEVEL_MEDIUM};
+use common::database::redis_constants::REDIS_KEY_EVENT_HANDLED_PREFIX;
+use common::database::redis_pool::AsyncRedisManager;
+use common::event::domain_event::{EventEnvelope, SecurityAlertEvent};
+use common::event::event_bus;
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemNoticeRequest) -> Result<i64> {
     let mut system_notice = create_request_to_model(&request);
@@ -20,7 +26,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
 
 pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, request: UpdateSystemNoticeRequest) -> Result<()> {
     let system_notice = SystemNoticeEntity::find_active_by_id(request.id)
-        .filter(Column::TenantId.eq(login_user.tenant_id))
+        .filter(visible_condition(&login_user))
         .one(db)
         .await?
         .ok_or_else(|| anyhow!("记录未找到"))?;
@@ -32,6 +38,11 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
 }
 
 pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    SystemNoticeEntity::find_active_by_id(id)
+        .filter(visible_condition(&login_user))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))?;
     let system_notice = SystemNoticeActiveModel {
         id: Set(id),
         tenant_id: Set(login_user.tenant_id),
@@ -44,9 +55,8 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
 }
 
 pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<Option<SystemNoticeResponse>> {
-    let condition = Condition::all()
-            .add(Column::Id.eq(id))
-            .add(Column::TenantId.eq(login_user.tenant_id));
+    let condition = visible_condition(&login_user)
+            .add(Column::Id.eq(id));
             
     let system_notice = SystemNoticeEntity::find_active_with_condition(condition)
         .one(db).await?;
@@ -54,7 +64,7 @@ pub async fn get_by_id(db: &DatabaseConnection, login_user: LoginUserContext, id
 }
 
 pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext, params: PaginatedKeywordRequest) -> Result<PaginatedResponse<SystemNoticeResponse>> {
-    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));let paginator = SystemNoticeEntity::find_active_with_condition(condition)
+    let condition = visible_condition(&login_user);let paginator = SystemNoticeEntity::find_active_with_condition(condition)
         .order_by_desc(Column::UpdateTime)
         .paginate(db, params.base.size);
 
@@ -77,7 +87,66 @@ pub async fn get_paginated(db: &DatabaseConnection, login_user: LoginUserContext
 }
 
 pub async fn list(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<SystemNoticeResponse>> {
-    let condition = Condition::all().add(Column::TenantId.eq(login_user.tenant_id));let list = SystemNoticeEntity::find_active_with_condition(condition)
+    let condition = visible_condition(&login_user);let list = SystemNoticeEntity::find_active_with_condition(condition)
         .all(db).await?;
     Ok(list.into_iter().map(model_to_response).collect())
-}
\ No newline at end of file
+}
+
+/// 当前用户可见的通知: 本租户内未限制权限,或拥有通知要求的权限
+fn visible_condition(login_user: &LoginUserContext) -> Condition {
+    Condition::all()
+        .add(Column::TenantId.eq(login_user.tenant_id))
+        .add(Condition::any()
+            .add(Column::Permission.is_null())
+            .add(Column::Permission.is_in(login_user.permissions.clone())))
+}
+/// 登录安全告警的消费组
+const SECURITY_ALERT_GROUP: &str = "system-server:notice";
+/// 已处理事件的去重记录保留时间(秒)
+const HANDLED_EVENT_TTL: u64 = 7 * 24 * 3600;
+
+/// 订阅logger-server的登录安全告警,作为通知发送给拥有安全分析权限的用户
+pub fn subscribe_security_alerts(db: DatabaseConnection) {
+    event_bus::subscribe::<SecurityAlertEvent, _, _>(SECURITY_ALERT_GROUP, move |envelope| {
+        let db = db.clone();
+        async move { create_security_alert(&db, envelope).await }
+    });
+}
+
+async fn create_security_alert(db: &DatabaseConnection, envelope: EventEnvelope<SecurityAlertEvent>) -> Result<()> {
+    // 事件至少投递一次,按事件id去重
+    let handled_key = format!("{}{}:{}", REDIS_KEY_EVENT_HANDLED_PREFIX, SECURITY_ALERT_GROUP, envelope.event_id);
+    if AsyncRedisManager::exists(&handled_key).await? {
+        return Ok(());
+    }
+
+    let alert = envelope.payload;
+    let mut content = String::from("以下登录存在安全风险,请管理员核实,必要时重置密码或禁用账号:\n");
+    for item in &alert.items {
+        let time = Local.timestamp_opt(item.operate_time, 0).single()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let level = match item.level {
+            SECURITY_LEVEL_HIGH => "高",
+            SECURITY_LEVEL_MEDIUM => "中",
+            _ => "低",
+        };
+        content.push_str(&format!("[{}] {} {} {}: {}\n", level, time, item.username, security_event_name(&item.event_type), item.detail));
+    }
+    if alert.total > alert.items.len() {
+        content.push_str(&format!("另有{}个事件,请在安全概览中查看\n", alert.total - alert.items.len()));
+    }
+
+    let system_notice = SystemNoticeActiveModel {
+        title: Set(format!("安全告警: 发现{}个异常登录", alert.total)),
+        content: Set(content),
+        r#type: Set(NOTICE_TYPE_NOTIFICATION),
+        status: Set(NOTICE_STATUS_NORMAL),
+        permission: Set(Some(PERMISSION_AUDIT_SECURITY.to_string())),
+        tenant_id: Set(envelope.tenant_id),
+        ..Default::default()
+    };
+    system_notice.insert(db).await?;
+    AsyncRedisManager::set_ex(&handled_key, "1", HANDLED_EVENT_TTL).await?;
+    Ok(())
+}
@@ -13,8 +13,9 @@ use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use system_grpc::system_client::system::ChangeType;
 
-use super::system_user_role;
+use super::{system_change, system_user_role};
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemRoleRequest) -> Result<i64> {
     let mut system_role = create_request_to_model(&request);
@@ -22,6 +23,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     system_role.updater = Set(Some(login_user.id));
     system_role.tenant_id = Set(login_user.tenant_id);
     let system_role = system_role.insert(db).await?;
+    system_change::notify_role_changed(system_role.id, login_user.tenant_id, ChangeType::Created).await;
     Ok(system_role.id)
 }
 
@@ -35,6 +37,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
     let mut system_role = update_request_to_model(&request, system_role);
     system_role.updater = Set(Some(login_user.id));
     system_role.update(db).await?;
+    system_change::notify_role_changed(request.id, login_user.tenant_id, ChangeType::Updated).await;
     Ok(())
 }
 
@@ -49,6 +52,7 @@ pub async fn update_rule(db: &DatabaseConnection, login_user: LoginUserContext,
     system_role.update(db).await?;
     // 修改数据权限规则后需退出角色下所有用户，使其重新登录
     system_user_role::offline_role_user(&db, request.id).await?;
+    system_change::notify_role_changed(request.id, login_user.tenant_id, ChangeType::Permission).await;
     Ok(())
 }
 
@@ -60,6 +64,7 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_role.update(db).await?;
+    system_change::notify_role_changed(id, login_user.tenant_id, ChangeType::Deleted).await;
     Ok(())
 }
 
@@ -117,6 +122,7 @@ pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_role.update(db).await?;
+    system_change::notify_role_changed(id, login_user.tenant_id, ChangeType::Enabled).await;
     Ok(())
 }
 
@@ -130,6 +136,7 @@ pub async fn disable(db: &DatabaseConnection, login_user: LoginUserContext, id:
     system_role.update(db).await?;
     // 下线角色用户
     system_user_role::offline_role_user(&db, id).await?;
+    system_change::notify_role_changed(id, login_user.tenant_id, ChangeType::Disabled).await;
     Ok(())
 }
 
@@ -12,9 +12,10 @@ use tracing::info;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use system_grpc::system_client::system::ChangeType;
 use crate::service;
 
-use super::system_user_role;
+use super::{system_change, system_user_role};
 
 pub async fn update(
     db: &DatabaseConnection,
@@ -95,6 +96,7 @@ pub async fn update(
 
     // 退出相关角色所有用户,使用户重新登录
     system_user_role::offline_role_user(&db, request.role_id).await?;
+    system_change::notify_role_changed(request.role_id, login_user.tenant_id, ChangeType::Permission).await;
 
     Ok(())
 }
@@ -16,6 +16,8 @@ use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::event::domain_event::TenantExpiredEvent;
+use common::event::outbox;
 use tracing::{error, info};
 
 use super::system_department::create_tenant_root;
@@ -179,13 +181,30 @@ pub async fn check_expire_tenant(db: &DatabaseConnection) -> Result<Vec<i64>> {
     // 获取当前时间
     let now = Utc::now().naive_utc();
 
+    let txn = db.begin().await?;
+    // 本次新过期的租户,发送租户过期事件
+    // 在事务中加锁查询,多个副本同时执行时后执行的等待前一个提交,不会重复发送事件
+    let expired_ids = SystemTenantEntity::find()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::ExpireTime.lt(now))
+        .filter(Column::Status.ne(STATUS_DISABLE))
+        .filter(Column::Deleted.eq(false))
+        .lock_exclusive()
+        .into_tuple::<i64>()
+        .all(&txn)
+        .await?;
     // 更新 expire_time 小于当前时间且未删除的租户，设置 status 为 禁用
     let update_result = SystemTenantEntity::update_many()
         .col_expr(Column::Status, Expr::value(STATUS_DISABLE))
         .filter(Column::ExpireTime.lt(now))
         .filter(Column::Deleted.eq(false))
-        .exec(db)
+        .exec(&txn)
         .await?;
+    for tenant_id in expired_ids {
+        outbox::publish(&txn, tenant_id, &TenantExpiredEvent { tenant_id }).await?;
+    }
+    txn.commit().await?;
 
     // 查询过期租户 ID
     let affected_ids = SystemTenantEntity::find()
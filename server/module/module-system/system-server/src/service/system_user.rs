@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use common::constants::enum_constants::{ROLE_ID_TENANT_ADMIN, STATUS_DISABLE, STATUS_ENABLE};
 use common::interceptor::orm::simple_support::SimpleSupport;
@@ -17,9 +18,16 @@ use chrono::Utc;
 use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
+use common::cache::cache_aside::{self, CacheOptions};
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use common::database::encrypted_string::EncryptedString;
+use system_grpc::system_client::system::ChangeType;
 
-use super::{system_auth, system_department, system_user_post, system_user_role};
+use super::{system_auth, system_change, system_department, system_user_post, system_user_role};
+
+/// 用户基础信息缓存,供其他服务通过grpc查询
+const CACHE_USER_BASE_PREFIX: &str = "system:user:base:";
+const CACHE_USER_BASE_TTL: Duration = Duration::from_secs(600);
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemUserRequest) -> Result<i64> {
     // 查询账号是否存在
@@ -34,7 +42,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     // 开启事务
     let txn = db.begin().await?;
     // 保存用户
-    let mut system_user = create_request_to_model(&request);
+    let mut system_user = create_request_to_model(&request)?;
     system_user.password = Set(crypt_password);
     system_user.department_code = Set(department.code);
     system_user.creator = Set(Some(login_user.id));
@@ -47,6 +55,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     system_user_post::save(db, &txn, login_user, system_user.id, request.post_ids).await?;
     // 提交事务
     txn.commit().await.with_context(|| "Failed to commit transaction")?;
+    system_change::notify_user_changed(system_user.id, login_user.tenant_id, ChangeType::Created).await;
     Ok(system_user.id)
 }
 
@@ -65,7 +74,7 @@ pub async fn create_tenant_admin(db: &DatabaseConnection, txn: &DatabaseTransact
         username: Set(username),
         password: Set(crypt_password),
         nickname: Set(nickname),
-        mobile: Set(mobile),
+        mobile: Set(mobile.as_deref().map(EncryptedString::encrypt).transpose()?),
         status: Set(STATUS_ENABLE),
         department_code: Set(department_code),
         department_id: Set(department_id),
@@ -103,7 +112,7 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
     // 保存用户岗位对应关系
     system_user_post::save(db, &txn, login_user.clone(), system_user.id, request.post_ids.clone());
     // 更新用户
-    let mut system_user = update_request_to_model(&request, system_user);
+    let mut system_user = update_request_to_model(&request, system_user)?;
     if let Some(department_code) = department_code { 
         system_user.department_code = Set(department_code.clone());
     }
@@ -112,6 +121,8 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
 
     // 提交事务
     txn.commit().await.with_context(|| "Failed to commit transaction")?;
+    cache_aside::invalidate(&format!("{}{}", CACHE_USER_BASE_PREFIX, request.id)).await;
+    system_change::notify_user_changed(request.id, login_user.tenant_id, ChangeType::Updated).await;
     Ok(())
 }
 
@@ -123,6 +134,8 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_user.update(db).await?;
+    cache_aside::invalidate(&format!("{}{}", CACHE_USER_BASE_PREFIX, id)).await;
+    system_change::notify_user_changed(id, login_user.tenant_id, ChangeType::Deleted).await;
     Ok(())
 }
 
@@ -192,6 +205,7 @@ pub async fn enable(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_user.update(db).await?;
+    system_change::notify_user_changed(id, login_user.tenant_id, ChangeType::Enabled).await;
     Ok(())
 }
 
@@ -205,6 +219,7 @@ pub async fn disable(db: &DatabaseConnection, login_user: LoginUserContext, id:
     system_user.update(db).await?;
     // 下线用户
     system_auth::offline_user(&db, id);
+    system_change::notify_user_changed(id, login_user.tenant_id, ChangeType::Disabled).await;
     Ok(())
 }
 
@@ -275,10 +290,14 @@ pub async fn find_by_id(db: &DatabaseConnection, id: i64) -> Result<Option<Syste
 }
 
 pub async fn find_by_ids(db: &DatabaseConnection, ids: Vec<i64>) -> Result<Vec<SystemUserBaseResponse>> {
-    let list = SystemUserEntity::find_active()
-        .filter(Column::Id.is_in(ids))
-        .all(db).await?;
-    Ok(list.into_iter().map(model_to_base_response).collect())
+    let options = CacheOptions::new(CACHE_USER_BASE_TTL);
+    let mut users = cache_aside::get_many_or_load(CACHE_USER_BASE_PREFIX, &ids, &options, |missing| async move {
+        let list = SystemUserEntity::find_active()
+            .filter(Column::Id.is_in(missing))
+            .all(db).await?;
+        Ok(list.into_iter().map(|user| (user.id, model_to_base_response(user))).collect())
+    }).await?;
+    Ok(ids.iter().filter_map(|id| users.remove(id)).collect())
 }
 
 pub async fn list_department_user(db: &DatabaseConnection, login_user: LoginUserContext) -> Result<Vec<SystemUserBaseResponse>> {
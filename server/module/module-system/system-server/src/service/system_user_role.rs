@@ -9,8 +9,9 @@ use sea_orm::ActiveValue::Set;
 use common::base::page::PaginatedResponse;
 use common::context::context::LoginUserContext;
 use common::interceptor::orm::active_filter::ActiveFilterEntityTrait;
+use system_grpc::system_client::system::ChangeType;
 
-use super::{system_auth, system_role};
+use super::{system_auth, system_change, system_role};
 
 pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, request: CreateSystemUserRoleRequest) -> Result<i64> {
     let mut system_user_role = create_request_to_model(&request);
@@ -18,6 +19,7 @@ pub async fn create(db: &DatabaseConnection, login_user: LoginUserContext, reque
     system_user_role.updater = Set(Some(login_user.id));
     system_user_role.tenant_id = Set(login_user.tenant_id);
     let system_user_role = system_user_role.insert(db).await?;
+    system_change::notify_user_changed(system_user_role.user_id, login_user.tenant_id, ChangeType::Permission).await;
     Ok(system_user_role.id)
 }
 
@@ -28,9 +30,15 @@ pub async fn update(db: &DatabaseConnection, login_user: LoginUserContext, reque
         .await?
         .ok_or_else(|| anyhow!("记录未找到"))?;
 
+    let old_user_id = system_user_role.user_id;
     let mut system_user_role = update_request_to_model(&request, system_user_role);
     system_user_role.updater = Set(Some(login_user.id));
-    system_user_role.update(db).await?;
+    let system_user_role = system_user_role.update(db).await?;
+    // 用户变更时原用户和新用户的权限都发生变化
+    system_change::notify_user_changed(system_user_role.user_id, login_user.tenant_id, ChangeType::Permission).await;
+    if old_user_id != system_user_role.user_id {
+        system_change::notify_user_changed(old_user_id, login_user.tenant_id, ChangeType::Permission).await;
+    }
     Ok(())
 }
 
@@ -75,6 +83,11 @@ pub async fn save(db: &DatabaseConnection, txn: &DatabaseTransaction, login_user
 }
 
 pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i64) -> Result<()> {
+    let existing = SystemUserRoleEntity::find_active_by_id(id)
+        .filter(Column::TenantId.eq(login_user.tenant_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("记录未找到"))?;
     let system_user_role = SystemUserRoleActiveModel {
         id: Set(id),
         tenant_id: Set(login_user.tenant_id),
@@ -83,6 +96,7 @@ pub async fn delete(db: &DatabaseConnection, login_user: LoginUserContext, id: i
         ..Default::default()
     };
     system_user_role.update(db).await?;
+    system_change::notify_user_changed(existing.user_id, login_user.tenant_id, ChangeType::Permission).await;
     Ok(())
 }
 
@@ -1,7 +1,6 @@
 use std::error::Error;
-use utoipa::gen::serde_json;
-use common::{state::app_state::AppState, task::task_manager::{ErrorAction, Task}};
-use crate::service::{self, system_tenant};
+use common::{state::app_state::AppState, task::task_manager::{async_trait, ErrorAction, Task}};
+use crate::service::system_tenant;
 
 // 租户过期任务
 pub struct TenantExpireTask {
@@ -15,24 +14,13 @@ impl TenantExpireTask {
     }
 }
 
+#[async_trait]
 impl Task for TenantExpireTask {
-    fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn execute(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         tracing::info!("execute task {}: {:?}", self.name, chrono::Local::now());
-
-        let db = self.state.db.clone();
-        let task_name = self.name.clone();
-        
-        tokio::spawn(async move {
-            // 检查并禁用租户
-            match system_tenant::check_expire_tenant(&db).await {
-                Ok(affected_ids) => {
-                    tracing::info!("{} task result: {:?}", task_name, affected_ids);
-                }
-                Err(e) => {
-                    tracing::error!("{} task failed: {:?}", task_name, e);
-                }
-            }
-        });
+        // 检查并禁用租户
+        let affected_ids = system_tenant::check_expire_tenant(&self.state.db).await?;
+        tracing::info!("{} task result: {:?}", self.name, affected_ids);
         Ok(())
     }
 
@@ -40,4 +28,4 @@ impl Task for TenantExpireTask {
         tracing::error!("execute task {} error: {}", self.name, error);
         ErrorAction::Continue
     }
-}
\ No newline at end of file
+}
@@ -20,7 +20,7 @@ use macros::{require_authorize, ExtendFields};
 use once_cell::sync::Lazy;
 use ctor;
 use dashmap::DashMap;
-use system_common::service::system::get_user_name;
+use common::formatter::extend_field::{self, ExtendValues};
 use serde_with::{serde_as, DisplayFromStr, SerializeAs};
 use common::formatter::string_date_time::StringDateTime;
 // use serde_with::{serde_as, chrono::NaiveDateTime as ChronoNaiveDateTime, formats::Strftime};
@@ -230,15 +230,18 @@ mod common_local {
 #[serde_as]
 #[derive(Deserialize, ExtendFields, Clone)]
 struct User {
-    #[extend_fields(invocation = "system_common::service::system::get_user_names_batch")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     id: i64,
-    #[extend_fields(invocation = "system_common::service::system::get_user_names_batch")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     user_id: i64,
-    #[extend_fields(invocation = "system_common::service::system::get_user_names_batch")]
+    #[extend_fields(fill_type = "user", invocation = "system_common::service::system::get_user_names")]
     creator: Option<i64>,
     value: String,
     #[serde_as(as = "common::formatter::string_date_time::StringDateTime")]
     date: NaiveDateTime,
+    #[serde(skip)]
+    #[extend_fields(values)]
+    extend: ExtendValues,
 }
 
 #[tokio::main]
@@ -308,13 +311,15 @@ async fn main() -> Result<(), anyhow::Error> {
 
     // common::formatter::string_date_time::StringDateTime::serialize_as(source, serializer)
 
-    let user = User {
+    let mut user = User {
         id: 1,
         user_id: 2,
         creator: Some(3),
         value: "user".to_string(),
-        date: Local::now().naive_utc()
+        date: Local::now().naive_utc(),
+        extend: ExtendValues::new(),
     };
+    extend_field::enrich_one(&mut user).await;
     let json = serde_json::to_string_pretty(&user).unwrap();
     // let json = task::spawn_blocking(move || {
     //     serde_json::to_string_pretty(&user)
@@ -326,6 +331,7 @@ async fn main() -> Result<(), anyhow::Error> {
     for index in 0..10 {
         users.push(user.clone());
     }
+    extend_field::enrich(&mut users).await;
     let json = serde_json::to_string_pretty(&users).unwrap();
     println!("\nUsers JSON:\n{}", json);
 
@@ -1,171 +1,94 @@
-// use serde::Serialize;
-// use macros::ExtendFields;
-// use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use common::formatter::extend_field::{self, ExtendFields, ExtendKeys, ExtendNames, ExtendValues};
+use macros::ExtendFields;
+use serde_json::{json, Value};
 
-// pub mod handler {
-//     pub fn get_user_name<T: ToString>(value: &T, fill_type: &str) -> String {
-//         format!("user: {} (type: {})", value.to_string(), fill_type)
-//     }
-// }
+static USER_CALLS: AtomicUsize = AtomicUsize::new(0);
 
-// #[derive(ExtendFields)]
-// struct MyStruct {
-//     #[extend_fields(field = "user_name", fill_type = "user", invocation = "handler::get_user_name")]
-//     name: String,
-//     value: i32,
-// }
+/// 获取用户名称,id为0的用户不存在
+async fn user_names(ids: Vec<i64>) -> anyhow::Result<HashMap<i64, String>> {
+    USER_CALLS.fetch_add(1, Ordering::SeqCst);
+    Ok(ids.into_iter().filter(|id| *id != 0).map(|id| (id, format!("user{}", id))).collect())
+}
 
-// #[derive(ExtendFields)]
-// struct UserStruct {
-//     #[extend_fields]
-//     user_id: String,
-//     score: i32,
-// }
+async fn department_names(ids: Vec<i64>) -> anyhow::Result<HashMap<i64, String>> {
+    Ok(ids.into_iter().map(|id| (id, format!("department{}", id))).collect())
+}
 
-// #[derive(ExtendFields)]
-// struct ExtraStruct {
-//     #[extend_fields(field = "custom_name", fill_type = "custom", invocation = "handler::get_user_name")]
-//     name: String,
-//     data: i32,
-// }
+#[derive(ExtendFields)]
+struct Order {
+    #[extend_fields(fill_type = "test_derive_user", invocation = "user_names")]
+    id: i64,
+    #[extend_fields(fill_type = "test_derive_user", invocation = "user_names")]
+    user_id: i64,
+    #[extend_fields(fill_type = "test_derive_user", invocation = "user_names")]
+    creator: Option<i64>,
+    #[extend_fields(field = "department", fill_type = "test_derive_department", invocation = "department_names")]
+    department_id: i64,
+    #[extend_fields(field = "code_name")]
+    code: String,
+    #[extend_fields(values)]
+    extend: ExtendValues,
+}
 
-// #[derive(ExtendFields)]
-// struct NoInvocationStruct {
-//     #[extend_fields(field = "raw_name", fill_type = "raw")]
-//     name: String,
-//     data: i32,
-// }
+fn order(id: i64, user_id: i64, creator: Option<i64>) -> Order {
+    Order { id, user_id, creator, department_id: 5, code: "A01".to_string(), extend: ExtendValues::new() }
+}
 
-// #[derive(ExtendFields)]
-// struct NumberStruct {
-//     #[extend_fields(field = "number_name", fill_type = "number", invocation = "handler::get_user_name")]
-//     id: i64,
-//     count: i32,
-// }
+#[test]
+fn test_resolvers_grouped_by_fill_type() {
+    let fill_types: Vec<&str> = Order::extend_resolvers().into_iter().map(|(fill_type, _)| fill_type).collect();
+    assert_eq!(fill_types, vec!["test_derive_department", "test_derive_user"]);
+}
 
-// #[derive(ExtendFields)]
-// struct ByteStruct {
-//     #[extend_fields(field = "byte_name", fill_type = "byte", invocation = "handler::get_user_name")]
-//     code: i8,
-//     flag: bool,
-// }
+#[test]
+fn test_serialize_before_enrich() {
+    let json: Value = serde_json::to_value(order(1, 2, Some(3))).unwrap();
+    // 未填充时扩展字段为null,没有获取方法的字段直接输出原值
+    assert_eq!(json, json!({
+        "id": 1,
+        "user_id": 2,
+        "creator": 3,
+        "department_id": 5,
+        "code": "A01",
+        "name": null,
+        "user_name": null,
+        "creator_name": null,
+        "department": null,
+        "code_name": "A01"
+    }));
+}
 
-// #[test]
-// fn test_custom_field() {
-//     let my_struct = MyStruct {
-//         name: String::from("example"),
-//         value: 42,
-//     };
-//     let json: Value = serde_json::to_value(&my_struct).unwrap();
-//     let expected = json!({
-//         "name": "example",
-//         "value": 42,
-//         "user_name": "user: example (type: user)"
-//     });
-//     assert_eq!(json, expected);
-// }
+#[test]
+fn test_fill() {
+    let mut order = order(1, 2, None);
+    let mut keys = ExtendKeys::default();
+    order.extend_keys(&mut keys);
+    let keys = format!("{:?}", keys);
+    assert!(keys.contains("test_derive_user"));
+    assert!(keys.contains("test_derive_department"));
 
-// #[test]
-// fn test_default_field_with_id() {
-//     let user_struct = UserStruct {
-//         user_id: String::from("user123"),
-//         score: 100,
-//     };
-//     let json: Value = serde_json::to_value(&user_struct).unwrap();
-//     let expected = json!({
-//         "user_id": "user123",
-//         "score": 100,
-//         "user_name": "user123"
-//     });
-//     assert_eq!(json, expected);
-// }
+    let names = ExtendNames::default();
+    order.extend_fill(&names);
+    assert!(order.extend.is_empty());
+}
 
-// #[test]
-// fn test_custom_field_different_type() {
-//     let extra_struct = ExtraStruct {
-//         name: String::from("test"),
-//         data: 50,
-//     };
-//     let json: Value = serde_json::to_value(&extra_struct).unwrap();
-//     let expected = json!({
-//         "name": "test",
-//         "data": 50,
-//         "custom_name": "user: test (type: custom)"
-//     });
-//     assert_eq!(json, expected);
-// }
+#[tokio::test]
+async fn test_enrich() {
+    let mut orders = vec![order(1, 2, Some(3)), order(2, 0, None)];
+    extend_field::enrich(&mut orders).await;
+    // 同一填充类型的所有字段合并为一次调用
+    assert_eq!(USER_CALLS.load(Ordering::SeqCst), 1);
 
-// #[test]
-// fn test_empty_string() {
-//     let user_struct = UserStruct {
-//         user_id: String::from(""),
-//         score: 0,
-//     };
-//     let json: Value = serde_json::to_value(&user_struct).unwrap();
-//     let expected = json!({
-//         "user_id": "",
-//         "score": 0,
-//         "user_name": ""
-//     });
-//     assert_eq!(json, expected);
-// }
-
-// #[test]
-// fn test_short_string() {
-//     let my_struct = MyStruct {
-//         name: String::from("abc"),
-//         value: 10,
-//     };
-//     let json: Value = serde_json::to_value(&my_struct).unwrap();
-//     let expected = json!({
-//         "name": "abc",
-//         "value": 10,
-//         "user_name": "user: abc (type: user)"
-//     });
-//     assert_eq!(json, expected);
-// }
-
-// #[test]
-// fn test_no_invocation() {
-//     let no_invocation_struct = NoInvocationStruct {
-//         name: String::from("direct"),
-//         data: 60,
-//     };
-//     let json: Value = serde_json::to_value(&no_invocation_struct).unwrap();
-//     let expected = json!({
-//         "name": "direct",
-//         "data": 60,
-//         "raw_name": "direct"
-//     });
-//     assert_eq!(json, expected);
-// }
-
-// #[test]
-// fn test_number_field() {
-//     let number_struct = NumberStruct {
-//         id: 1234567890,
-//         count: 10,
-//     };
-//     let json: Value = serde_json::to_value(&number_struct).unwrap();
-//     let expected = json!({
-//         "id": 1234567890,
-//         "count": 10,
-//         "number_name": "user: 1234567890 (type: number)"
-//     });
-//     assert_eq!(json, expected);
-// }
-
-// #[test]
-// fn test_byte_field() {
-//     let byte_struct = ByteStruct {
-//         code: 42,
-//         flag: true,
-//     };
-//     let json: Value = serde_json::to_value(&byte_struct).unwrap();
-//     let expected = json!({
-//         "code": 42,
-//         "flag": true,
-//         "byte_name": "user: 42 (type: byte)"
-//     });
-//     assert_eq!(json, expected);
-// }
\ No newline at end of file
+    let json: Value = serde_json::to_value(&orders).unwrap();
+    assert_eq!(json[0]["name"], "user1");
+    assert_eq!(json[0]["user_name"], "user2");
+    assert_eq!(json[0]["creator_name"], "user3");
+    assert_eq!(json[0]["department"], "department5");
+    assert_eq!(json[1]["name"], "user2");
+    // 不存在的id和空值不填充
+    assert_eq!(json[1]["user_name"], Value::Null);
+    assert_eq!(json[1]["creator_name"], Value::Null);
+    assert!(json[0].get("extend").is_none());
+}
@@ -1 +1,2 @@
-mod extend_fields;
\ No newline at end of file
+#[cfg(test)]
+mod extend_fields;
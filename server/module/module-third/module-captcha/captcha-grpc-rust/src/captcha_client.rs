@@ -10,14 +10,25 @@ pub mod gocaptcha {
 
 pub struct CaptchaClient {
     client: GoCaptchaServiceClient<Channel>,
+    traceparent: Option<String>, // 链路追踪,随请求传给服务端
 }
 
 impl CaptchaClient {
     /// 创建一个新的 CaptchaClient 实例
     pub async fn new(server_addr: &str) -> Result<Self> {
         let channel = Channel::from_shared(server_addr.to_string())?.connect().await?;
-        let client = GoCaptchaServiceClient::new(channel);
-        Ok(CaptchaClient { client })
+        Ok(Self::from_channel(channel))
+    }
+
+    /// 使用已建立的连接创建,用于TLS等需要自定义连接的场景
+    pub fn from_channel(channel: Channel) -> Self {
+        CaptchaClient { client: GoCaptchaServiceClient::new(channel), traceparent: None }
+    }
+
+    /// 设置链路追踪traceparent
+    pub fn with_traceparent(mut self, traceparent: Option<String>) -> Self {
+        self.traceparent = traceparent;
+        self
     }
 
     /// 调用 CheckStatus 方法
@@ -28,8 +39,23 @@ impl CaptchaClient {
         // Add x-api-key to metadata
         let metadata = request.metadata_mut();
         metadata.insert("x-api-key", MetadataValue::from_str(api_key)?);
+        if let Some(traceparent) = &self.traceparent {
+            metadata.insert("traceparent", MetadataValue::from_str(traceparent)?);
+        }
         let response = self.client.check_status(request).await?;
-        println!("response: {:?}", response);
+        tracing::debug!("response: {:?}", response);
         Ok(response.into_inner().data.eq("ok"))
     }
+
+    /// 调用 GetData 方法,获取验证码图片数据
+    pub async fn get_data(&mut self, id: String, api_key: &str) -> Result<GetDataResponse> {
+        let mut request = tonic::Request::new(GetDataRequest { id });
+        let metadata = request.metadata_mut();
+        metadata.insert("x-api-key", MetadataValue::from_str(api_key)?);
+        if let Some(traceparent) = &self.traceparent {
+            metadata.insert("traceparent", MetadataValue::from_str(traceparent)?);
+        }
+        let response = self.client.get_data(request).await?;
+        Ok(response.into_inner())
+    }
 }
\ No newline at end of file
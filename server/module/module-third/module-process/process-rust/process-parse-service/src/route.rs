@@ -1,7 +1,7 @@
 
 // use crate::{api::system_file::system_file_router, AppState};
 use axum::Router;
-use common::{middleware::{authorize::{authorize_handler, init_route_authorizes}, operation_logger::operation_logger_handler, request_context::request_context_handler}, state::app_state::AppState, utils::jwt_utils::AccessClaims};
+use common::{middleware::{authorize::{authorize_handler, init_route_authorizes}, idempotency::idempotency_handler, rate_limit::rate_limit_handler, operation_logger::operation_logger_handler, request_context::request_context_handler}, state::app_state::AppState, utils::jwt_utils::AccessClaims};
 use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 
@@ -35,8 +35,10 @@ pub async fn api(state: AppState) -> Router {
 pub async fn auth_router(state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
         // .nest("/system_file", system_file_router(state.clone()).await)
+        .layer(axum::middleware::from_fn(idempotency_handler))
         .layer(axum::middleware::from_fn(authorize_handler))
         .layer(axum::middleware::from_fn(operation_logger_handler))
+        .layer(axum::middleware::from_fn(rate_limit_handler))
         .layer(axum::middleware::from_fn_with_state(state.clone(), request_context_handler))
         .layer(axum::middleware::from_extractor::<AccessClaims>())
 }
@@ -14,7 +14,7 @@ pub async fn parse_invoice<T>(pdf_data: Vec<u8>) -> Result<Option<InvoiceParseRe
   where
     T: for<'de> Deserialize<'de> + std::fmt::Debug + Clone, {
   let text = pdf_extract::extract_text_from_mem(&pdf_data).unwrap();
-  println!("pdf content, {}", text);
+  tracing::debug!("pdf content, {}", text);
 
   // 正则表达式提取关键字段
   let invoice_number_re = Regex::new(r"Invoice Number:?\s*(\w+)")?;